@@ -5,13 +5,15 @@ use aries_vcx::core::profile::profile::Profile;
 use aries_vcx::plugins::wallet::agency_client_wallet::ToBaseAgencyClientWallet;
 
 use crate::agent::agent_config::AgentConfig;
+use crate::agent::message_handler::AgentDispatcher;
 
 use crate::error::*;
 use crate::services::connection::ServiceConnections;
 use crate::services::{
     credential_definition::ServiceCredentialDefinitions, holder::ServiceCredentialsHolder,
-    issuer::ServiceCredentialsIssuer, mediated_connection::ServiceMediatedConnections, prover::ServiceProver,
-    revocation_registry::ServiceRevocationRegistries, schema::ServiceSchemas, verifier::ServiceVerifier,
+    issuer::ServiceCredentialsIssuer, mediated_connection::ServiceMediatedConnections, mediator::ServiceMediator,
+    prover::ServiceProver, revocation_registry::ServiceRevocationRegistries, schema::ServiceSchemas,
+    verifier::ServiceVerifier,
 };
 
 #[derive(Clone)]
@@ -27,6 +29,8 @@ pub struct Agent {
     pub(super) issuer: Arc<ServiceCredentialsIssuer>,
     pub(super) verifier: Arc<ServiceVerifier>,
     pub(super) prover: Arc<ServiceProver>,
+    pub(super) mediator: Arc<ServiceMediator>,
+    pub(super) dispatcher: Arc<AgentDispatcher>,
 }
 
 impl Agent {
@@ -97,4 +101,8 @@ impl Agent {
     pub fn prover(&self) -> Arc<ServiceProver> {
         self.prover.clone()
     }
+
+    pub fn mediator(&self) -> Arc<ServiceMediator> {
+        self.mediator.clone()
+    }
 }
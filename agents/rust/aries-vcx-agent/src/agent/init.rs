@@ -10,9 +10,10 @@ use aries_vcx::{
     },
     utils::provision::provision_cloud_agent,
 };
+use derive_builder::Builder;
 
 use crate::{
-    agent::{agent_config::AgentConfig, agent_struct::Agent},
+    agent::{agent_config::AgentConfig, agent_struct::Agent, message_handler::build_dispatcher},
     error::AgentResult,
     services::{
         connection::{ServiceConnections, ServiceEndpoint},
@@ -20,11 +21,13 @@ use crate::{
         holder::ServiceCredentialsHolder,
         issuer::ServiceCredentialsIssuer,
         mediated_connection::ServiceMediatedConnections,
-        prover::ServiceProver,
+        mediator::ServiceMediator,
+        prover::{CredentialSelection, ServiceProver},
         revocation_registry::ServiceRevocationRegistries,
         schema::ServiceSchemas,
         verifier::ServiceVerifier,
     },
+    storage::StorageKind,
 };
 
 pub struct AgencyInitConfig {
@@ -44,12 +47,21 @@ pub struct PoolInitConfig {
     pub pool_name: String,
 }
 
+/// Configuration of [`Agent::initialize`]. Use [`InitConfigBuilder`] to leave the optional parts
+/// (agency, storage and credential selection) at their defaults.
+#[derive(Builder)]
+#[builder(pattern = "owned")]
 pub struct InitConfig {
     pub enterprise_seed: String,
     pub pool_config: PoolInitConfig,
+    #[builder(default)]
     pub agency_config: Option<AgencyInitConfig>,
     pub wallet_config: WalletInitConfig,
     pub service_endpoint: ServiceEndpoint,
+    #[builder(default)]
+    pub storage: StorageKind,
+    #[builder(default)]
+    pub credential_selection: CredentialSelection,
 }
 
 impl Agent {
@@ -105,6 +117,7 @@ impl Agent {
                 Some(Arc::new(ServiceMediatedConnections::new(
                     Arc::clone(&profile),
                     config_agency_client.clone(),
+                    init_config.storage,
                 ))),
                 Some(config_agency_client),
             )
@@ -114,21 +127,58 @@ impl Agent {
 
         let connections = Arc::new(ServiceConnections::new(
             Arc::clone(&profile),
-            init_config.service_endpoint,
+            init_config.service_endpoint.clone(),
+            init_config.storage,
         ));
         let schemas = Arc::new(ServiceSchemas::new(
             Arc::clone(&profile),
             config_issuer.institution_did.clone(),
+            init_config.storage,
+        ));
+        let cred_defs = Arc::new(ServiceCredentialDefinitions::new(
+            Arc::clone(&profile),
+            init_config.storage,
         ));
-        let cred_defs = Arc::new(ServiceCredentialDefinitions::new(Arc::clone(&profile)));
         let rev_regs = Arc::new(ServiceRevocationRegistries::new(
             Arc::clone(&profile),
             config_issuer.institution_did.clone(),
+            init_config.storage,
+        ));
+        let issuer = Arc::new(ServiceCredentialsIssuer::new(
+            Arc::clone(&profile),
+            connections.clone(),
+            init_config.storage,
+        ));
+        let holder = Arc::new(ServiceCredentialsHolder::new(
+            Arc::clone(&profile),
+            connections.clone(),
+            init_config.storage,
+        ));
+        let verifier = Arc::new(ServiceVerifier::new(
+            Arc::clone(&profile),
+            connections.clone(),
+            init_config.storage,
+        ));
+        let prover = Arc::new(ServiceProver::new(
+            Arc::clone(&profile),
+            connections.clone(),
+            init_config.storage,
+            init_config.credential_selection,
+        ));
+
+        let mediator = Arc::new(ServiceMediator::new(
+            Arc::clone(&profile),
+            init_config.service_endpoint,
+            connections.clone(),
         ));
-        let issuer = Arc::new(ServiceCredentialsIssuer::new(Arc::clone(&profile), connections.clone()));
-        let holder = Arc::new(ServiceCredentialsHolder::new(Arc::clone(&profile), connections.clone()));
-        let verifier = Arc::new(ServiceVerifier::new(Arc::clone(&profile), connections.clone()));
-        let prover = Arc::new(ServiceProver::new(Arc::clone(&profile), connections.clone()));
+        let dispatcher = Arc::new(build_dispatcher(
+            connections.clone(),
+            issuer.clone(),
+            holder.clone(),
+            verifier.clone(),
+            prover.clone(),
+            mediator.clone(),
+        )?);
 
         Ok(Self {
             profile,
@@ -141,6 +191,8 @@ impl Agent {
             holder,
             verifier,
             prover,
+            mediator,
+            dispatcher,
             config: AgentConfig {
                 config_wallet,
                 config_issuer,
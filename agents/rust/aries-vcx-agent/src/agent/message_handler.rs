@@ -0,0 +1,458 @@
+use std::sync::{Arc, Mutex};
+
+use aries_vcx::core::profile::profile::Profile;
+use aries_vcx::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use aries_vcx::handlers::compat::to_legacy_message;
+use aries_vcx::handlers::dispatcher::{
+    build_unhandled_message_problem_report, DispatchOutcome, FamilyHandler, MessageDispatcher, ProtocolFamily,
+};
+use aries_vcx::messages::a2a::A2AMessage;
+use aries_vcx::messages2::msg_fields::protocols::present_proof::{v2::PresentProofV2, PresentProof};
+use aries_vcx::messages2::AriesMessage;
+use aries_vcx::plugins::wallet::base_wallet::BaseWallet;
+use aries_vcx::protocols::AriesSendClosure;
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::agent::agent_struct::Agent;
+use crate::error::*;
+use crate::services::{
+    connection::ServiceConnections, holder::ServiceCredentialsHolder, issuer::ServiceCredentialsIssuer,
+    mediator::ServiceMediator, prover::ServiceProver, verifier::ServiceVerifier,
+};
+
+#[derive(Deserialize)]
+struct UnpackedMessage {
+    message: String,
+    recipient_verkey: String,
+}
+
+/// Context the agent dispatches an inbound message with.
+pub(crate) struct MessageContext {
+    connection_id: String,
+    /// Packed reply to answer the inbound transport with, set when the message asked for it on the
+    /// return route.
+    reply: Mutex<Option<Vec<u8>>>,
+}
+
+pub(crate) type AgentDispatcher = MessageDispatcher<MessageContext>;
+
+/// Builds the dispatcher passing every protocol the agent takes part in to the service running it.
+/// The services keep their state in their own storage, so their handlers are registered per
+/// protocol family rather than per thread.
+pub(crate) fn build_dispatcher(
+    connections: Arc<ServiceConnections>,
+    issuer: Arc<ServiceCredentialsIssuer>,
+    holder: Arc<ServiceCredentialsHolder>,
+    verifier: Arc<ServiceVerifier>,
+    prover: Arc<ServiceProver>,
+    mediator: Arc<ServiceMediator>,
+) -> AgentResult<AgentDispatcher> {
+    let dispatcher = AgentDispatcher::new();
+    let connection_handler = Arc::new(ConnectionHandler { connections });
+    dispatcher.register_family(ProtocolFamily::Connections, connection_handler.clone())?;
+    dispatcher.register_family(ProtocolFamily::Notification, connection_handler)?;
+    dispatcher.register_family(
+        ProtocolFamily::IssueCredential,
+        Arc::new(IssuanceHandler { issuer, holder }),
+    )?;
+    dispatcher.register_family(
+        ProtocolFamily::PresentProof,
+        Arc::new(PresentationHandler { verifier, prover }),
+    )?;
+    let mediator_handler = Arc::new(MediatorHandler { mediator });
+    dispatcher.register_family(ProtocolFamily::CoordinateMediation, mediator_handler.clone())?;
+    dispatcher.register_family(ProtocolFamily::Pickup, mediator_handler)?;
+    Ok(dispatcher)
+}
+
+impl Agent {
+    /// Unpacks an inbound envelope with the agent's wallet, finds the connection it was
+    /// addressed to by the recipient verkey and dispatches the message to the service
+    /// responsible for its protocol. Returns the packed reply when the message asked for it on
+    /// the return route, so that the inbound transport can answer with it.
+    pub async fn receive_message(&self, payload: Vec<u8>) -> AgentResult<Option<Vec<u8>>> {
+        let (message, recipient_verkey) = unpack_message(&self.profile.inject_wallet(), &payload).await?;
+        // Forward messages are addressed to one of our routing keys rather than to a connection
+        if let AriesMessage::Routing(_) = message {
+            if let A2AMessage::Forward(forward) = to_legacy_message(&message)? {
+                self.mediator.receive_forward(forward).await?;
+                return Ok(None);
+            }
+        }
+        let connection_id = self.find_connection_id(&recipient_verkey).await?;
+        self.handle_message(&connection_id, message).await
+    }
+
+    /// Dispatches a message received on the connection. Messages of protocols the agent does not
+    /// take part in are answered with a problem-report and fail with [`AgentErrorKind::UnhandledMessage`].
+    pub async fn handle_message(&self, connection_id: &str, message: AriesMessage) -> AgentResult<Option<Vec<u8>>> {
+        trace!(
+            "Agent::handle_message >>> connection_id: {}, message: {:?}",
+            connection_id,
+            message
+        );
+        let context = MessageContext {
+            connection_id: connection_id.to_string(),
+            reply: Mutex::new(None),
+        };
+        let unhandled = || {
+            AgentError::from_msg(
+                AgentErrorKind::UnhandledMessage,
+                &format!("No handler for message received on connection {}", connection_id),
+            )
+        };
+        let send_message = self.send_closure(connection_id);
+        match self
+            .dispatcher
+            .dispatch(&self.profile, &context, message, send_message)
+            .await
+        {
+            Ok(DispatchOutcome::Handled) => context
+                .reply
+                .into_inner()
+                .map_err(|_| AgentError::from_kind(AgentErrorKind::LockError)),
+            Ok(DispatchOutcome::ProblemReported) => Err(unhandled()),
+            Err(err) if err.kind() == AriesVcxErrorKind::ActionNotSupported => Err(unhandled()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn send_closure(&self, connection_id: &str) -> AriesSendClosure {
+        let connections = self.connections.clone();
+        let connection_id = connection_id.to_string();
+        Box::new(move |message: AriesMessage| {
+            Box::pin(async move {
+                connections.send_aries_message(&connection_id, &message).await?;
+                Ok(())
+            })
+        })
+    }
+
+    async fn find_connection_id(&self, recipient_verkey: &str) -> AgentResult<String> {
+        self.connections
+            .get_by_our_vk(recipient_verkey)
+            .await?
+            .pop()
+            .ok_or_else(|| {
+                AgentError::from_msg(
+                    AgentErrorKind::NotFound,
+                    &format!("No connection found for recipient verkey {}", recipient_verkey),
+                )
+            })
+    }
+}
+
+/// Answers a message of a dispatched protocol which the agent's role in it does not handle with a
+/// problem-report, as the dispatcher does for the protocols without a handler.
+async fn reject_unhandled_message(message: &AriesMessage, send_message: AriesSendClosure) -> VcxResult<()> {
+    let problem_report = build_unhandled_message_problem_report(message);
+    send_message(AriesMessage::ReportProblem(problem_report)).await?;
+    Err(AriesVcxError::from_msg(
+        AriesVcxErrorKind::ActionNotSupported,
+        format!("No handler for message: {:?}", message),
+    ))
+}
+
+struct ConnectionHandler {
+    connections: Arc<ServiceConnections>,
+}
+
+#[async_trait]
+impl FamilyHandler<MessageContext> for ConnectionHandler {
+    async fn handle_message(
+        &self,
+        _profile: &Arc<dyn Profile>,
+        context: &MessageContext,
+        message: AriesMessage,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<()> {
+        let connection_id = &context.connection_id;
+        match to_legacy_message(&message)? {
+            A2AMessage::ConnectionRequest(request) => {
+                self.connections.accept_request(connection_id, request).await?;
+                self.connections.send_response(connection_id).await?;
+            }
+            A2AMessage::ConnectionResponse(response) => {
+                self.connections.accept_response(connection_id, response).await?;
+                self.connections.send_ack(connection_id).await?;
+            }
+            A2AMessage::Ack(ack) => self.connections.process_ack(connection_id, ack).await?,
+            _ => return reject_unhandled_message(&message, send_message).await,
+        }
+        Ok(())
+    }
+}
+
+struct IssuanceHandler {
+    issuer: Arc<ServiceCredentialsIssuer>,
+    holder: Arc<ServiceCredentialsHolder>,
+}
+
+#[async_trait]
+impl FamilyHandler<MessageContext> for IssuanceHandler {
+    async fn handle_message(
+        &self,
+        _profile: &Arc<dyn Profile>,
+        context: &MessageContext,
+        message: AriesMessage,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<()> {
+        let connection_id = &context.connection_id;
+        match to_legacy_message(&message)? {
+            A2AMessage::CredentialProposal(proposal) => {
+                self.issuer.accept_proposal(connection_id, &proposal).await?;
+            }
+            A2AMessage::CredentialOffer(offer) => {
+                self.holder.create_from_offer(connection_id, offer).await?;
+            }
+            A2AMessage::CredentialRequest(request) => {
+                self.issuer
+                    .process_credential_request(&request.get_thread_id(), request)
+                    .await?
+            }
+            A2AMessage::Credential(credential) => {
+                self.holder
+                    .process_credential(&credential.get_thread_id(), credential)
+                    .await?;
+            }
+            A2AMessage::CredentialAck(ack) => self.issuer.process_credential_ack(&ack.get_thread_id(), ack).await?,
+            _ => return reject_unhandled_message(&message, send_message).await,
+        }
+        Ok(())
+    }
+}
+
+struct PresentationHandler {
+    verifier: Arc<ServiceVerifier>,
+    prover: Arc<ServiceProver>,
+}
+
+#[async_trait]
+impl FamilyHandler<MessageContext> for PresentationHandler {
+    async fn handle_message(
+        &self,
+        _profile: &Arc<dyn Profile>,
+        context: &MessageContext,
+        message: AriesMessage,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<()> {
+        if let AriesMessage::PresentProof(PresentProof::V2(message)) = message {
+            return self.handle_message_v2(context, message, send_message).await;
+        }
+        match to_legacy_message(&message)? {
+            A2AMessage::PresentationRequest(request) => {
+                self.prover.create_from_request(&context.connection_id, request).await?;
+            }
+            A2AMessage::Presentation(presentation) => {
+                self.verifier
+                    .verify_presentation(&presentation.get_thread_id(), presentation)
+                    .await?
+            }
+            A2AMessage::PresentationAck(ack) => {
+                self.prover.process_presentation_ack(&ack.get_thread_id(), ack).await?;
+            }
+            _ => return reject_unhandled_message(&message, send_message).await,
+        }
+        Ok(())
+    }
+}
+
+impl PresentationHandler {
+    async fn handle_message_v2(
+        &self,
+        context: &MessageContext,
+        message: PresentProofV2,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<()> {
+        match message {
+            PresentProofV2::RequestPresentation(request) => {
+                self.prover
+                    .create_from_request_v2(&context.connection_id, request)
+                    .await?;
+            }
+            PresentProofV2::Presentation(presentation) => {
+                let thread_id = presentation.decorators.thread.thid.clone();
+                self.verifier.verify_presentation_v2(&thread_id, presentation).await?
+            }
+            PresentProofV2::Ack(ack) => {
+                let thread_id = ack.decorators.thread.thid.clone();
+                self.prover.process_presentation_ack_v2(&thread_id, ack).await?;
+            }
+            message => {
+                let message = AriesMessage::from(PresentProof::V2(message));
+                return reject_unhandled_message(&message, send_message).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Handles the mediation and pickup requests of the connections we mediate for. Replies asked for
+/// on the return route are left in the context for the inbound transport.
+struct MediatorHandler {
+    mediator: Arc<ServiceMediator>,
+}
+
+#[async_trait]
+impl FamilyHandler<MessageContext> for MediatorHandler {
+    async fn handle_message(
+        &self,
+        _profile: &Arc<dyn Profile>,
+        context: &MessageContext,
+        message: AriesMessage,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<()> {
+        let connection_id = &context.connection_id;
+        let reply = match message {
+            AriesMessage::CoordinateMediation(message) => {
+                self.mediator
+                    .handle_coordinate_mediation(connection_id, message)
+                    .await?
+            }
+            AriesMessage::Pickup(message) => self.mediator.handle_pickup(connection_id, message).await?,
+            message => return reject_unhandled_message(&message, send_message).await,
+        };
+        *context.reply.lock()? = reply;
+        Ok(())
+    }
+}
+
+/// Unpacks an inbound envelope, returning the message and the verkey it was addressed to.
+pub(crate) async fn unpack_message(
+    wallet: &Arc<dyn BaseWallet>,
+    payload: &[u8],
+) -> AgentResult<(AriesMessage, String)> {
+    let unpacked = wallet.unpack_message(payload).await?;
+    let UnpackedMessage {
+        message,
+        recipient_verkey,
+    } = serde_json::from_slice(&unpacked)?;
+    Ok((serde_json::from_str(&message)?, recipient_verkey))
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use aries_vcx::core::profile::vdrtools_profile::VdrtoolsProfile;
+    use aries_vcx::global::settings::{DEFAULT_WALLET_KEY, WALLET_KDF_RAW};
+    use aries_vcx::indy::wallet::{close_wallet, create_and_open_wallet, delete_wallet, WalletConfig};
+    use aries_vcx::messages2::msg_fields::protocols::basic_message::{
+        BasicMessage, BasicMessageContent, BasicMessageDecorators,
+    };
+    use aries_vcx::utils::uuid::uuid;
+    use aries_vcx::vdrtools::INVALID_POOL_HANDLE;
+
+    use crate::services::prover::CredentialSelection;
+    use crate::storage::StorageKind;
+
+    use super::*;
+
+    const SERVICE_ENDPOINT: &str = "http://dummy.org";
+
+    fn recording_send_message(sent: Arc<Mutex<Vec<AriesMessage>>>) -> AriesSendClosure {
+        Box::new(move |message: AriesMessage| {
+            sent.lock().unwrap().push(message);
+            Box::pin(async { VcxResult::Ok(()) })
+        })
+    }
+
+    fn context() -> MessageContext {
+        MessageContext {
+            connection_id: "connection_id".to_string(),
+            reply: Mutex::new(None),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_rejects_messages_the_services_do_not_handle() {
+        let config_wallet = WalletConfig {
+            wallet_name: format!("test_agent_dispatcher_{}", uuid()),
+            wallet_key: DEFAULT_WALLET_KEY.into(),
+            wallet_key_derivation: WALLET_KDF_RAW.into(),
+            wallet_type: None,
+            storage_config: None,
+            storage_credentials: None,
+            rekey: None,
+            rekey_derivation_method: None,
+        };
+        let wallet_handle = create_and_open_wallet(&config_wallet).await.unwrap();
+        let profile: Arc<dyn Profile> = Arc::new(VdrtoolsProfile::new(wallet_handle, INVALID_POOL_HANDLE));
+        let connections = Arc::new(ServiceConnections::new(
+            Arc::clone(&profile),
+            SERVICE_ENDPOINT.to_string(),
+            StorageKind::InMemory,
+        ));
+        let dispatcher = build_dispatcher(
+            connections.clone(),
+            Arc::new(ServiceCredentialsIssuer::new(
+                Arc::clone(&profile),
+                connections.clone(),
+                StorageKind::InMemory,
+            )),
+            Arc::new(ServiceCredentialsHolder::new(
+                Arc::clone(&profile),
+                connections.clone(),
+                StorageKind::InMemory,
+            )),
+            Arc::new(ServiceVerifier::new(
+                Arc::clone(&profile),
+                connections.clone(),
+                StorageKind::InMemory,
+            )),
+            Arc::new(ServiceProver::new(
+                Arc::clone(&profile),
+                connections.clone(),
+                StorageKind::InMemory,
+                CredentialSelection::default(),
+            )),
+            Arc::new(ServiceMediator::new(
+                Arc::clone(&profile),
+                SERVICE_ENDPOINT.to_string(),
+                connections,
+            )),
+        )
+        .unwrap();
+
+        // No service takes part in the basic message protocol
+        let sent = Arc::new(Mutex::new(vec![]));
+        let message = BasicMessage::with_decorators(
+            uuid(),
+            BasicMessageContent::new("hello".to_string()),
+            BasicMessageDecorators::default(),
+        );
+        let outcome = dispatcher
+            .dispatch(
+                &profile,
+                &context(),
+                AriesMessage::BasicMessage(message),
+                recording_send_message(sent.clone()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome, DispatchOutcome::ProblemReported);
+        assert!(matches!(sent.lock().unwrap()[..], [AriesMessage::ReportProblem(_)]));
+
+        // The connection service does not handle invitations received over a connection
+        let sent = Arc::new(Mutex::new(vec![]));
+        let invitation: AriesMessage = serde_json::from_str(
+            &json!({
+                "@type": "https://didcomm.org/connections/1.0/invitation",
+                "@id": uuid(),
+                "label": "inviter",
+                "recipientKeys": ["recipient_key"],
+                "serviceEndpoint": SERVICE_ENDPOINT
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let err = dispatcher
+            .dispatch(&profile, &context(), invitation, recording_send_message(sent.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::ActionNotSupported);
+        assert!(matches!(sent.lock().unwrap()[..], [AriesMessage::ReportProblem(_)]));
+
+        close_wallet(wallet_handle).await.unwrap();
+        delete_wallet(&config_wallet).await.unwrap();
+    }
+}
@@ -1,7 +1,8 @@
 mod agent_config;
 mod agent_struct;
 mod init;
+pub(crate) mod message_handler;
 
 pub use agent_config::AgentConfig;
 pub use agent_struct::Agent;
-pub use init::{AgencyInitConfig, InitConfig, PoolInitConfig, WalletInitConfig};
+pub use init::{AgencyInitConfig, InitConfig, InitConfigBuilder, PoolInitConfig, WalletInitConfig};
@@ -16,6 +16,18 @@ impl From<AriesVcxError> for AgentError {
     }
 }
 
+impl From<AgentError> for AriesVcxError {
+    fn from(err: AgentError) -> AriesVcxError {
+        let kind = match err.kind {
+            AgentErrorKind::UnhandledMessage => AriesVcxErrorKind::ActionNotSupported,
+            AgentErrorKind::SerializationError => AriesVcxErrorKind::SerializationError,
+            AgentErrorKind::InvalidArguments => AriesVcxErrorKind::InvalidInput,
+            _ => AriesVcxErrorKind::UnknownError,
+        };
+        AriesVcxError::from_msg(kind, format!("Agent error: {}", err.message))
+    }
+}
+
 impl From<serde_json::Error> for AgentError {
     fn from(serde_err: serde_json::Error) -> AgentError {
         let kind = AgentErrorKind::SerializationError;
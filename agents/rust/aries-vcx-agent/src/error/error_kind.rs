@@ -16,4 +16,8 @@ pub enum AgentErrorKind {
     CredDefAlreadyCreated,
     #[error("Mediated connections not configured")]
     MediatedConnectionServiceUnavailable,
+    #[error("No handler found for message")]
+    UnhandledMessage,
+    #[error("Inbound transport error")]
+    InboundTransportError,
 }
@@ -0,0 +1,250 @@
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{SocketAddr, TcpListener};
+
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode, Uri};
+
+use crate::error::*;
+use crate::Agent;
+
+const DIDCOMM_ENVELOPE_CONTENT_TYPE: &str = "application/didcomm-envelope-enc";
+
+impl Agent {
+    /// Serves the agent's service endpoint over HTTP. Every envelope POSTed to the endpoint
+    /// path is passed to [`Agent::receive_message`], and the reply it returns for messages asking
+    /// for a return route is sent back as the response body. Runs until the server fails.
+    ///
+    /// The listener does not terminate TLS, so an `https` service endpoint is rejected; put a
+    /// TLS terminating proxy in front of an `http` endpoint instead.
+    pub async fn run_http_listener(&self) -> AgentResult<()> {
+        let (addr, path) = listen_address(&self.connections().service_endpoint())?;
+
+        let listener = TcpListener::bind(addr).map_err(|err| {
+            AgentError::from_msg(
+                AgentErrorKind::InboundTransportError,
+                &format!("Failed to bind HTTP listener to {}: {}", addr, err),
+            )
+        })?;
+
+        let agent = self.clone();
+        serve_http(listener, path, move |payload| {
+            let agent = agent.clone();
+            async move { agent.receive_message(payload).await }
+        })
+        .await
+    }
+}
+
+/// Address to bind and path to serve for the `http` service endpoint.
+fn listen_address(service_endpoint: &str) -> AgentResult<(SocketAddr, String)> {
+    let endpoint: Uri = service_endpoint.parse().map_err(|err| {
+        AgentError::from_msg(
+            AgentErrorKind::InvalidArguments,
+            &format!("Invalid service endpoint {}: {}", service_endpoint, err),
+        )
+    })?;
+    if endpoint.scheme_str() != Some("http") {
+        return Err(AgentError::from_msg(
+            AgentErrorKind::InvalidArguments,
+            &format!(
+                "HTTP listener only serves http service endpoints, got {}",
+                service_endpoint
+            ),
+        ));
+    }
+    let addr = SocketAddr::from(([0, 0, 0, 0], endpoint.port_u16().unwrap_or(80)));
+    Ok((addr, endpoint.path().to_string()))
+}
+
+/// Serves POST requests to `path` on the listener, passing every request body to `on_message`.
+/// The reply returned by `on_message`, if any, is the body of the response.
+async fn serve_http<F, Fut>(listener: TcpListener, path: String, on_message: F) -> AgentResult<()>
+where
+    F: Fn(Vec<u8>) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = AgentResult<Option<Vec<u8>>>> + Send + 'static,
+{
+    info!("Starting HTTP listener on {:?}, path {}", listener.local_addr(), path);
+    let make_service = make_service_fn(move |_| {
+        let on_message = on_message.clone();
+        let path = path.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                handle_request(on_message.clone(), path.clone(), req)
+            }))
+        }
+    });
+
+    Server::from_tcp(listener)
+        .map_err(|err| {
+            AgentError::from_msg(
+                AgentErrorKind::InboundTransportError,
+                &format!("Failed to start HTTP listener: {}", err),
+            )
+        })?
+        .serve(make_service)
+        .await
+        .map_err(|err| {
+            AgentError::from_msg(
+                AgentErrorKind::InboundTransportError,
+                &format!("HTTP listener failed: {}", err),
+            )
+        })
+}
+
+fn empty_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+async fn handle_request<F, Fut>(on_message: F, path: String, req: Request<Body>) -> Result<Response<Body>, Infallible>
+where
+    F: Fn(Vec<u8>) -> Fut,
+    Fut: Future<Output = AgentResult<Option<Vec<u8>>>>,
+{
+    if req.method() != Method::POST || req.uri().path() != path {
+        return Ok(empty_response(StatusCode::NOT_FOUND));
+    }
+    let payload = match hyper::body::to_bytes(req.into_body()).await {
+        Ok(payload) => payload,
+        Err(err) => {
+            warn!("Failed to read inbound message body: {}", err);
+            return Ok(empty_response(StatusCode::BAD_REQUEST));
+        }
+    };
+    match on_message(payload.to_vec()).await {
+        Ok(Some(reply)) => {
+            let mut response = Response::new(Body::from(reply));
+            response
+                .headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static(DIDCOMM_ENVELOPE_CONTENT_TYPE));
+            Ok(response)
+        }
+        Ok(None) => Ok(empty_response(StatusCode::ACCEPTED)),
+        Err(err) => {
+            error!("Failed to process inbound message: {}", err.message);
+            Ok(empty_response(StatusCode::BAD_REQUEST))
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::sync::Arc;
+
+    use aries_vcx::core::profile::profile::Profile;
+    use aries_vcx::core::profile::vdrtools_profile::VdrtoolsProfile;
+    use aries_vcx::global::settings::{DEFAULT_WALLET_KEY, WALLET_KDF_RAW};
+    use aries_vcx::handlers::compat::from_legacy_message;
+    use aries_vcx::indy::wallet::{close_wallet, create_and_open_wallet, delete_wallet, WalletConfig};
+    use aries_vcx::messages::a2a::A2AMessage;
+    use aries_vcx::messages::concepts::ack::Ack;
+    use aries_vcx::utils::uuid::uuid;
+    use aries_vcx::vdrtools::INVALID_POOL_HANDLE;
+    use hyper::Client;
+    use tokio::sync::mpsc;
+
+    use crate::agent::message_handler::unpack_message;
+
+    use super::*;
+
+    #[test]
+    fn test_listen_address_of_service_endpoint() {
+        let (addr, path) = listen_address("http://localhost:8080/didcomm").unwrap();
+        assert_eq!(addr, SocketAddr::from(([0, 0, 0, 0], 8080)));
+        assert_eq!(path, "/didcomm");
+
+        let (addr, _) = listen_address("http://localhost/didcomm").unwrap();
+        assert_eq!(addr.port(), 80);
+
+        let err = listen_address("https://localhost/didcomm").unwrap_err();
+        assert_eq!(err.kind, AgentErrorKind::InvalidArguments);
+        let err = listen_address("localhost:8080").unwrap_err();
+        assert_eq!(err.kind, AgentErrorKind::InvalidArguments);
+    }
+
+    #[tokio::test]
+    async fn test_http_listener_receives_packed_message() {
+        let config_wallet = WalletConfig {
+            wallet_name: format!("test_http_listener_{}", uuid()),
+            wallet_key: DEFAULT_WALLET_KEY.into(),
+            wallet_key_derivation: WALLET_KDF_RAW.into(),
+            wallet_type: None,
+            storage_config: None,
+            storage_credentials: None,
+            rekey: None,
+            rekey_derivation_method: None,
+        };
+        let wallet_handle = create_and_open_wallet(&config_wallet).await.unwrap();
+        let profile: Arc<dyn Profile> = Arc::new(VdrtoolsProfile::new(wallet_handle, INVALID_POOL_HANDLE));
+        let wallet = profile.inject_wallet();
+        let (_, verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let listener_wallet = Arc::clone(&wallet);
+        tokio::spawn(serve_http(listener, "/didcomm".to_string(), move |payload| {
+            let wallet = Arc::clone(&listener_wallet);
+            let sender = sender.clone();
+            async move {
+                sender.send(unpack_message(&wallet, &payload).await?).unwrap();
+                Ok(None)
+            }
+        }));
+
+        let ack = Ack::create().set_thread_id("thread_id");
+        let ack = A2AMessage::Ack(ack);
+        let message = serde_json::to_vec(&ack).unwrap();
+        let packed = wallet
+            .pack_message(None, &json!([verkey]).to_string(), &message)
+            .await
+            .unwrap();
+        let request = Request::post(format!("http://{}/didcomm", addr))
+            .body(Body::from(packed))
+            .unwrap();
+        let response = Client::new().request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let (received, recipient_verkey) = receiver.recv().await.unwrap();
+        assert_eq!(recipient_verkey, verkey);
+        assert_eq!(received, from_legacy_message(&ack).unwrap());
+
+        let request = Request::post(format!("http://{}/other", addr))
+            .body(Body::empty())
+            .unwrap();
+        let response = Client::new().request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        close_wallet(wallet_handle).await.unwrap();
+        delete_wallet(&config_wallet).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http_listener_responds_with_returned_reply() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_http(
+            listener,
+            "/didcomm".to_string(),
+            |payload: Vec<u8>| async move { Ok((payload == b"ping").then(|| b"pong".to_vec())) },
+        ));
+
+        let request = Request::post(format!("http://{}/didcomm", addr))
+            .body(Body::from("ping"))
+            .unwrap();
+        let response = Client::new().request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], DIDCOMM_ENVELOPE_CONTENT_TYPE);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body.as_ref(), b"pong");
+
+        let request = Request::post(format!("http://{}/didcomm", addr))
+            .body(Body::from("other"))
+            .unwrap();
+        let response = Client::new().request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+    }
+}
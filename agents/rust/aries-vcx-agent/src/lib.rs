@@ -10,8 +10,12 @@ extern crate uuid;
 mod agent;
 mod error;
 mod http_client;
+#[cfg(feature = "http_listener")]
+mod http_listener;
 mod services;
 mod storage;
 
 pub use agent::*;
 pub use error::*;
+pub use services::prover::CredentialSelection;
+pub use storage::StorageKind;
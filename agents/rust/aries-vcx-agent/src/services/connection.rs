@@ -1,15 +1,16 @@
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::error::*;
 use crate::http_client::HttpClient;
-use crate::storage::object_cache::ObjectCache;
-use crate::storage::Storage;
+use crate::storage::{new_storage, Storage, StorageKind, StorageRecord};
 use aries_vcx::core::profile::profile::Profile;
 use aries_vcx::messages::a2a::A2AMessage;
 use aries_vcx::messages::concepts::ack::Ack;
 use aries_vcx::messages::protocols::connection::invite::Invitation;
 use aries_vcx::messages::protocols::connection::request::Request;
 use aries_vcx::messages::protocols::connection::response::SignedResponse;
+use aries_vcx::messages2::AriesMessage;
 use aries_vcx::protocols::connection::pairwise_info::PairwiseInfo;
 use aries_vcx::protocols::connection::{Connection, GenericConnection, State, ThinState};
 
@@ -18,15 +19,15 @@ pub type ServiceEndpoint = String;
 pub struct ServiceConnections {
     profile: Arc<dyn Profile>,
     service_endpoint: ServiceEndpoint,
-    connections: Arc<ObjectCache<GenericConnection>>,
+    connections: Arc<dyn Storage<GenericConnection>>,
 }
 
 impl ServiceConnections {
-    pub fn new(profile: Arc<dyn Profile>, service_endpoint: ServiceEndpoint) -> Self {
+    pub fn new(profile: Arc<dyn Profile>, service_endpoint: ServiceEndpoint, storage: StorageKind) -> Self {
         Self {
+            connections: new_storage(storage, &profile, "connections"),
             profile,
             service_endpoint,
-            connections: Arc::new(ObjectCache::new("connections")),
         }
     }
 
@@ -37,7 +38,7 @@ impl ServiceConnections {
         let invite = inviter.get_invitation().clone();
         let thread_id = inviter.thread_id().to_owned();
 
-        self.connections.insert(&thread_id, inviter.into())?;
+        self.connections.insert(&thread_id, inviter.into()).await?;
 
         Ok(invite)
     }
@@ -50,11 +51,11 @@ impl ServiceConnections {
 
         let thread_id = invitee.thread_id().to_owned();
 
-        self.connections.insert(&thread_id, invitee.into())
+        self.connections.insert(&thread_id, invitee.into()).await
     }
 
     pub async fn send_request(&self, thread_id: &str) -> AgentResult<()> {
-        let invitee: Connection<_, _> = self.connections.get(thread_id)?.try_into()?;
+        let invitee: Connection<_, _> = self.connections.get(thread_id).await?.try_into()?;
         let invitee = invitee
             .send_request(
                 &self.profile.inject_wallet(),
@@ -64,12 +65,12 @@ impl ServiceConnections {
             )
             .await?;
 
-        self.connections.insert(thread_id, invitee.into())?;
+        self.connections.insert(thread_id, invitee.into()).await?;
         Ok(())
     }
 
     pub async fn accept_request(&self, thread_id: &str, request: Request) -> AgentResult<()> {
-        let inviter = self.connections.get(thread_id)?;
+        let inviter = self.connections.get(thread_id).await?;
 
         let inviter = match inviter.state() {
             ThinState::Inviter(State::Initial) => Connection::try_from(inviter)
@@ -95,72 +96,227 @@ impl ServiceConnections {
             )
             .await?;
 
-        self.connections.insert(thread_id, inviter.into())?;
+        self.connections.insert(thread_id, inviter.into()).await?;
 
         Ok(())
     }
 
     pub async fn send_response(&self, thread_id: &str) -> AgentResult<()> {
-        let inviter: Connection<_, _> = self.connections.get(thread_id)?.try_into()?;
+        let inviter: Connection<_, _> = self.connections.get(thread_id).await?.try_into()?;
         let inviter = inviter
             .send_response(&self.profile.inject_wallet(), &HttpClient)
             .await?;
 
-        self.connections.insert(thread_id, inviter.into())?;
+        self.connections.insert(thread_id, inviter.into()).await?;
 
         Ok(())
     }
 
     pub async fn accept_response(&self, thread_id: &str, response: SignedResponse) -> AgentResult<()> {
-        let invitee: Connection<_, _> = self.connections.get(thread_id)?.try_into()?;
+        let invitee: Connection<_, _> = self.connections.get(thread_id).await?.try_into()?;
         let invitee = invitee
             .handle_response(&self.profile.inject_wallet(), response, &HttpClient)
             .await?;
 
-        self.connections.insert(thread_id, invitee.into())?;
+        self.connections.insert(thread_id, invitee.into()).await?;
 
         Ok(())
     }
 
     pub async fn send_ack(&self, thread_id: &str) -> AgentResult<()> {
-        let invitee: Connection<_, _> = self.connections.get(thread_id)?.try_into()?;
+        let invitee: Connection<_, _> = self.connections.get(thread_id).await?.try_into()?;
         let invitee = invitee.send_ack(&self.profile.inject_wallet(), &HttpClient).await?;
 
-        self.connections.insert(thread_id, invitee.into())?;
+        self.connections.insert(thread_id, invitee.into()).await?;
 
         Ok(())
     }
 
     pub async fn process_ack(&self, thread_id: &str, ack: Ack) -> AgentResult<()> {
-        let inviter: Connection<_, _> = self.connections.get(thread_id)?.try_into()?;
+        let inviter: Connection<_, _> = self.connections.get(thread_id).await?.try_into()?;
         let inviter = inviter.acknowledge_connection(&A2AMessage::Ack(ack))?;
 
-        self.connections.insert(thread_id, inviter.into())?;
+        self.connections.insert(thread_id, inviter.into()).await?;
 
         Ok(())
     }
 
-    pub fn get_state(&self, thread_id: &str) -> AgentResult<ThinState> {
-        Ok(self.connections.get(thread_id)?.state())
+    pub async fn send_message(&self, thread_id: &str, message: &A2AMessage) -> AgentResult<()> {
+        let connection = self.connections.get(thread_id).await?;
+        connection
+            .send_message(&self.profile.inject_wallet(), message, &HttpClient)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn send_aries_message(&self, thread_id: &str, message: &AriesMessage) -> AgentResult<()> {
+        let message = A2AMessage::Generic(serde_json::to_value(message)?);
+        self.send_message(thread_id, &message).await
+    }
+
+    /// Packs a message for the counterparty of the connection without sending it, so that it can be
+    /// returned on the route an inbound message arrived on.
+    pub async fn pack_aries_message(&self, thread_id: &str, message: &AriesMessage) -> AgentResult<Vec<u8>> {
+        let connection = self.connections.get(thread_id).await?;
+        let receiver_keys = json!([connection.remote_vk()?]).to_string();
+        Ok(self
+            .profile
+            .inject_wallet()
+            .pack_message(
+                Some(&connection.pairwise_info().pw_vk),
+                &receiver_keys,
+                &serde_json::to_vec(message)?,
+            )
+            .await?)
     }
 
-    pub(in crate::services) fn get_by_id(&self, thread_id: &str) -> AgentResult<GenericConnection> {
-        self.connections.get(thread_id)
+    pub async fn get_state(&self, thread_id: &str) -> AgentResult<ThinState> {
+        Ok(self.connections.get(thread_id).await?.state())
     }
 
-    pub fn get_by_their_vk(&self, their_vk: &str) -> AgentResult<Vec<String>> {
-        let their_vk = their_vk.to_string();
-        let f = |(id, m): (&String, &Mutex<GenericConnection>)| -> Option<String> {
-            let connection = m.lock().unwrap();
-            match connection.remote_vk() {
-                Ok(remote_vk) if remote_vk == their_vk => Some(id.to_string()),
-                _ => None,
-            }
+    pub(in crate::services) async fn get_by_id(&self, thread_id: &str) -> AgentResult<GenericConnection> {
+        self.connections.get(thread_id).await
+    }
+
+    pub async fn get_by_their_vk(&self, their_vk: &str) -> AgentResult<Vec<String>> {
+        let tags = HashMap::from([("their_vk".to_string(), their_vk.to_string())]);
+        self.connections.find_by(&tags).await
+    }
+
+    pub async fn get_by_our_vk(&self, our_vk: &str) -> AgentResult<Vec<String>> {
+        let tags = HashMap::from([("our_vk".to_string(), our_vk.to_string())]);
+        self.connections.find_by(&tags).await
+    }
+
+    pub async fn exists_by_id(&self, thread_id: &str) -> bool {
+        self.connections.contains_key(thread_id).await
+    }
+
+    pub fn service_endpoint(&self) -> ServiceEndpoint {
+        self.service_endpoint.clone()
+    }
+}
+
+impl StorageRecord for GenericConnection {
+    fn tags(&self) -> HashMap<String, String> {
+        let mut tags = HashMap::new();
+        if let Some(thread_id) = self.thread_id() {
+            tags.insert("thread_id".to_string(), thread_id.to_string());
+        }
+        tags.insert("state".to_string(), format!("{:?}", self.state()));
+        tags.insert("our_vk".to_string(), self.pairwise_info().pw_vk.clone());
+        if let Ok(their_vk) = self.remote_vk() {
+            tags.insert("their_vk".to_string(), their_vk);
+        }
+        tags
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use aries_vcx::agency_client::testing::mocking::{disable_agency_mocks, enable_agency_mocks};
+    use aries_vcx::core::profile::vdrtools_profile::VdrtoolsProfile;
+    use aries_vcx::global::settings::{DEFAULT_WALLET_KEY, WALLET_KDF_RAW};
+    use aries_vcx::indy::wallet::{close_wallet, create_and_open_wallet, delete_wallet, WalletConfig};
+    use aries_vcx::utils::uuid::uuid;
+    use aries_vcx::vdrtools::{WalletHandle, INVALID_POOL_HANDLE};
+
+    use super::*;
+
+    const SERVICE_ENDPOINT: &str = "http://dummy.org";
+
+    async fn create_profile() -> (Arc<dyn Profile>, WalletHandle, WalletConfig) {
+        let config_wallet = WalletConfig {
+            wallet_name: format!("test_agent_storage_{}", uuid()),
+            wallet_key: DEFAULT_WALLET_KEY.into(),
+            wallet_key_derivation: WALLET_KDF_RAW.into(),
+            wallet_type: None,
+            storage_config: None,
+            storage_credentials: None,
+            rekey: None,
+            rekey_derivation_method: None,
         };
-        self.connections.find_by(f)
+        let wallet_handle = create_and_open_wallet(&config_wallet).await.unwrap();
+        let profile = Arc::new(VdrtoolsProfile::new(wallet_handle, INVALID_POOL_HANDLE));
+        (profile, wallet_handle, config_wallet)
+    }
+
+    async fn delete_profile(wallet_handle: WalletHandle, config_wallet: WalletConfig) {
+        close_wallet(wallet_handle).await.unwrap();
+        delete_wallet(&config_wallet).await.unwrap();
+    }
+
+    fn start_service(profile: &Arc<dyn Profile>) -> ServiceConnections {
+        ServiceConnections::new(Arc::clone(profile), SERVICE_ENDPOINT.to_string(), StorageKind::Wallet)
     }
 
-    pub fn exists_by_id(&self, thread_id: &str) -> bool {
-        self.connections.contains_key(thread_id)
+    #[tokio::test]
+    async fn test_connection_resumes_after_restart() {
+        enable_agency_mocks();
+        let (inviter_profile, inviter_handle, inviter_wallet) = create_profile().await;
+        let (invitee_profile, invitee_handle, invitee_wallet) = create_profile().await;
+
+        let inviter = start_service(&inviter_profile);
+        let invitee = start_service(&invitee_profile);
+        let invite = inviter.create_invitation(None).await.unwrap();
+        let thread_id = invitee.receive_invitation(invite).await.unwrap();
+        invitee.send_request(&thread_id).await.unwrap();
+
+        drop(inviter);
+        drop(invitee);
+        let inviter = start_service(&inviter_profile);
+        let invitee = start_service(&invitee_profile);
+
+        assert!(matches!(
+            inviter.get_state(&thread_id).await.unwrap(),
+            ThinState::Inviter(State::Invited)
+        ));
+        assert!(matches!(
+            invitee.get_state(&thread_id).await.unwrap(),
+            ThinState::Invitee(State::Requested)
+        ));
+
+        let invitee_pw_info = invitee.get_by_id(&thread_id).await.unwrap().pairwise_info().clone();
+        let request = Request::create()
+            .set_did(invitee_pw_info.pw_did.clone())
+            .set_service_endpoint(SERVICE_ENDPOINT.to_string())
+            .set_keys(vec![invitee_pw_info.pw_vk.clone()], vec![])
+            .set_thread_id(&thread_id);
+        inviter.accept_request(&thread_id, request).await.unwrap();
+        inviter.send_response(&thread_id).await.unwrap();
+
+        drop(inviter);
+        let inviter = start_service(&inviter_profile);
+
+        assert!(matches!(
+            inviter.get_state(&thread_id).await.unwrap(),
+            ThinState::Inviter(State::Responded)
+        ));
+        assert_eq!(
+            inviter.get_by_their_vk(&invitee_pw_info.pw_vk).await.unwrap(),
+            vec![thread_id.clone()]
+        );
+        assert_eq!(
+            invitee.get_by_our_vk(&invitee_pw_info.pw_vk).await.unwrap(),
+            vec![thread_id.clone()]
+        );
+        assert!(inviter.get_by_their_vk("unknown").await.unwrap().is_empty());
+
+        inviter
+            .process_ack(&thread_id, Ack::create().set_thread_id(&thread_id))
+            .await
+            .unwrap();
+
+        drop(inviter);
+        let inviter = start_service(&inviter_profile);
+
+        assert!(matches!(
+            inviter.get_state(&thread_id).await.unwrap(),
+            ThinState::Inviter(State::Completed)
+        ));
+
+        delete_profile(inviter_handle, inviter_wallet).await;
+        delete_profile(invitee_handle, invitee_wallet).await;
+        disable_agency_mocks();
     }
 }
@@ -1,8 +1,8 @@
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::error::*;
-use crate::storage::object_cache::ObjectCache;
-use crate::storage::Storage;
+use crate::storage::{new_storage, Storage, StorageKind, StorageRecord};
 use aries_vcx::{
     common::primitives::credential_definition::{CredentialDef, CredentialDefConfig},
     core::profile::profile::Profile,
@@ -10,43 +10,45 @@ use aries_vcx::{
 
 pub struct ServiceCredentialDefinitions {
     profile: Arc<dyn Profile>,
-    cred_defs: ObjectCache<CredentialDef>,
+    cred_defs: Arc<dyn Storage<CredentialDef>>,
 }
 
 impl ServiceCredentialDefinitions {
-    pub fn new(profile: Arc<dyn Profile>) -> Self {
+    pub fn new(profile: Arc<dyn Profile>, storage: StorageKind) -> Self {
         Self {
+            cred_defs: new_storage(storage, &profile, "cred-defs"),
             profile,
-            cred_defs: ObjectCache::new("cred-defs"),
         }
     }
 
     pub async fn create_cred_def(&self, config: CredentialDefConfig) -> AgentResult<String> {
         let cd = CredentialDef::create(&self.profile, "".to_string(), config, true).await?;
-        self.cred_defs.insert(&cd.get_cred_def_id(), cd)
+        self.cred_defs.insert(&cd.get_cred_def_id(), cd).await
     }
 
     pub async fn publish_cred_def(&self, thread_id: &str) -> AgentResult<()> {
-        let cred_def = self.cred_defs.get(thread_id)?;
+        let cred_def = self.cred_defs.get(thread_id).await?;
         let cred_def = cred_def.publish_cred_def(&self.profile).await?;
-        self.cred_defs.insert(thread_id, cred_def)?;
+        self.cred_defs.insert(thread_id, cred_def).await?;
         Ok(())
     }
 
-    pub fn cred_def_json(&self, thread_id: &str) -> AgentResult<String> {
-        self.cred_defs.get(thread_id)?.get_data_json().map_err(|err| err.into())
+    pub async fn cred_def_json(&self, thread_id: &str) -> AgentResult<String> {
+        self.cred_defs
+            .get(thread_id)
+            .await?
+            .get_data_json()
+            .map_err(|err| err.into())
     }
 
-    pub fn find_by_schema_id(&self, schema_id: &str) -> AgentResult<Vec<String>> {
-        let schema_id = schema_id.to_string();
-        let f = |(id, m): (&String, &Mutex<CredentialDef>)| -> Option<String> {
-            let cred_def = m.lock().unwrap();
-            if cred_def.get_schema_id() == schema_id {
-                Some(id.clone())
-            } else {
-                None
-            }
-        };
-        self.cred_defs.find_by(f)
+    pub async fn find_by_schema_id(&self, schema_id: &str) -> AgentResult<Vec<String>> {
+        let tags = HashMap::from([("schema_id".to_string(), schema_id.to_string())]);
+        self.cred_defs.find_by(&tags).await
+    }
+}
+
+impl StorageRecord for CredentialDef {
+    fn tags(&self) -> HashMap<String, String> {
+        HashMap::from([("schema_id".to_string(), self.get_schema_id())])
     }
 }
@@ -1,10 +1,10 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::error::*;
 use crate::http_client::HttpClient;
 use crate::services::connection::ServiceConnections;
-use crate::storage::object_cache::ObjectCache;
-use crate::storage::Storage;
+use crate::storage::{new_storage, Storage, StorageKind, StorageRecord};
 use aries_vcx::core::profile::profile::Profile;
 use aries_vcx::handlers::issuance::holder::Holder;
 use aries_vcx::messages::a2a::A2AMessage;
@@ -13,8 +13,9 @@ use aries_vcx::messages::protocols::issuance::credential_offer::CredentialOffer;
 use aries_vcx::messages::protocols::issuance::credential_proposal::CredentialProposalData;
 use aries_vcx::protocols::issuance::holder::state_machine::HolderState;
 use aries_vcx::protocols::SendClosure;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct HolderWrapper {
     holder: Holder,
     connection_id: String,
@@ -29,28 +30,41 @@ impl HolderWrapper {
     }
 }
 
+impl StorageRecord for HolderWrapper {
+    fn tags(&self) -> HashMap<String, String> {
+        let mut tags = HashMap::from([
+            ("state".to_string(), format!("{:?}", self.holder.get_state())),
+            ("connection_id".to_string(), self.connection_id.clone()),
+        ]);
+        if let Ok(thread_id) = self.holder.get_thread_id() {
+            tags.insert("thread_id".to_string(), thread_id);
+        }
+        tags
+    }
+}
+
 pub struct ServiceCredentialsHolder {
     profile: Arc<dyn Profile>,
-    creds_holder: ObjectCache<HolderWrapper>,
+    creds_holder: Arc<dyn Storage<HolderWrapper>>,
     service_connections: Arc<ServiceConnections>,
 }
 
 impl ServiceCredentialsHolder {
-    pub fn new(profile: Arc<dyn Profile>, service_connections: Arc<ServiceConnections>) -> Self {
+    pub fn new(profile: Arc<dyn Profile>, service_connections: Arc<ServiceConnections>, storage: StorageKind) -> Self {
         Self {
+            creds_holder: new_storage(storage, &profile, "creds-holder"),
             profile,
             service_connections,
-            creds_holder: ObjectCache::new("creds-holder"),
         }
     }
 
-    fn get_holder(&self, thread_id: &str) -> AgentResult<Holder> {
-        let HolderWrapper { holder, .. } = self.creds_holder.get(thread_id)?;
+    async fn get_holder(&self, thread_id: &str) -> AgentResult<Holder> {
+        let HolderWrapper { holder, .. } = self.creds_holder.get(thread_id).await?;
         Ok(holder)
     }
 
-    pub fn get_connection_id(&self, thread_id: &str) -> AgentResult<String> {
-        let HolderWrapper { connection_id, .. } = self.creds_holder.get(thread_id)?;
+    pub async fn get_connection_id(&self, thread_id: &str) -> AgentResult<String> {
+        let HolderWrapper { connection_id, .. } = self.creds_holder.get(thread_id).await?;
         Ok(connection_id)
     }
 
@@ -59,7 +73,7 @@ impl ServiceCredentialsHolder {
         connection_id: &str,
         proposal_data: CredentialProposalData,
     ) -> AgentResult<String> {
-        let connection = self.service_connections.get_by_id(connection_id)?;
+        let connection = self.service_connections.get_by_id(connection_id).await?;
         let wallet = self.profile.inject_wallet();
 
         let send_closure: SendClosure = Box::new(|msg: A2AMessage| {
@@ -71,13 +85,15 @@ impl ServiceCredentialsHolder {
 
         self.creds_holder
             .insert(&holder.get_thread_id()?, HolderWrapper::new(holder, connection_id))
+            .await
     }
 
-    pub fn create_from_offer(&self, connection_id: &str, offer: CredentialOffer) -> AgentResult<String> {
-        self.service_connections.get_by_id(connection_id)?;
+    pub async fn create_from_offer(&self, connection_id: &str, offer: CredentialOffer) -> AgentResult<String> {
+        self.service_connections.get_by_id(connection_id).await?;
         let holder = Holder::create_from_offer("", offer)?;
         self.creds_holder
             .insert(&holder.get_thread_id()?, HolderWrapper::new(holder, connection_id))
+            .await
     }
 
     pub async fn send_credential_request(
@@ -86,12 +102,12 @@ impl ServiceCredentialsHolder {
         connection_id: Option<&str>,
     ) -> AgentResult<String> {
         let (mut holder, connection_id) = match (thread_id, connection_id) {
-            (Some(id), Some(connection_id)) => (self.get_holder(id)?, connection_id.to_string()),
-            (Some(id), None) => (self.get_holder(id)?, self.get_connection_id(id)?),
+            (Some(id), Some(connection_id)) => (self.get_holder(id).await?, connection_id.to_string()),
+            (Some(id), None) => (self.get_holder(id).await?, self.get_connection_id(id).await?),
             (None, Some(connection_id)) => (Holder::create("")?, connection_id.to_string()),
             (None, None) => return Err(AgentError::from_kind(AgentErrorKind::InvalidArguments)),
         };
-        let connection = self.service_connections.get_by_id(&connection_id)?;
+        let connection = self.service_connections.get_by_id(&connection_id).await?;
         let wallet = self.profile.inject_wallet();
         let pw_did = connection.pairwise_info().pw_did.to_string();
 
@@ -102,12 +118,13 @@ impl ServiceCredentialsHolder {
         holder.send_request(&self.profile, pw_did, send_closure).await?;
         self.creds_holder
             .insert(&holder.get_thread_id()?, HolderWrapper::new(holder, &connection_id))
+            .await
     }
 
     pub async fn process_credential(&self, thread_id: &str, credential: Credential) -> AgentResult<String> {
-        let mut holder = self.get_holder(thread_id)?;
-        let connection_id = self.get_connection_id(thread_id)?;
-        let connection = self.service_connections.get_by_id(&connection_id)?;
+        let mut holder = self.get_holder(thread_id).await?;
+        let connection_id = self.get_connection_id(thread_id).await?;
+        let connection = self.service_connections.get_by_id(&connection_id).await?;
         let wallet = self.profile.inject_wallet();
 
         let send_closure: SendClosure = Box::new(|msg: A2AMessage| {
@@ -119,34 +136,43 @@ impl ServiceCredentialsHolder {
             .await?;
         self.creds_holder
             .insert(&holder.get_thread_id()?, HolderWrapper::new(holder, &connection_id))
+            .await
     }
 
-    pub fn get_state(&self, thread_id: &str) -> AgentResult<HolderState> {
-        Ok(self.get_holder(thread_id)?.get_state())
+    pub async fn get_state(&self, thread_id: &str) -> AgentResult<HolderState> {
+        Ok(self.get_holder(thread_id).await?.get_state())
     }
 
     pub async fn is_revokable(&self, thread_id: &str) -> AgentResult<bool> {
-        self.get_holder(thread_id)?
+        self.get_holder(thread_id)
+            .await?
             .is_revokable(&self.profile)
             .await
             .map_err(|err| err.into())
     }
 
     pub async fn get_rev_reg_id(&self, thread_id: &str) -> AgentResult<String> {
-        self.get_holder(thread_id)?.get_rev_reg_id().map_err(|err| err.into())
+        self.get_holder(thread_id)
+            .await?
+            .get_rev_reg_id()
+            .map_err(|err| err.into())
     }
 
     pub async fn get_tails_hash(&self, thread_id: &str) -> AgentResult<String> {
-        self.get_holder(thread_id)?.get_tails_hash().map_err(|err| err.into())
+        self.get_holder(thread_id)
+            .await?
+            .get_tails_hash()
+            .map_err(|err| err.into())
     }
 
     pub async fn get_tails_location(&self, thread_id: &str) -> AgentResult<String> {
-        self.get_holder(thread_id)?
+        self.get_holder(thread_id)
+            .await?
             .get_tails_location()
             .map_err(|err| err.into())
     }
 
-    pub fn exists_by_id(&self, thread_id: &str) -> bool {
-        self.creds_holder.contains_key(thread_id)
+    pub async fn exists_by_id(&self, thread_id: &str) -> bool {
+        self.creds_holder.contains_key(thread_id).await
     }
 }
@@ -1,10 +1,10 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::error::*;
 use crate::http_client::HttpClient;
 use crate::services::connection::ServiceConnections;
-use crate::storage::object_cache::ObjectCache;
-use crate::storage::Storage;
+use crate::storage::{new_storage, Storage, StorageKind, StorageRecord};
 use aries_vcx::core::profile::profile::Profile;
 use aries_vcx::handlers::issuance::issuer::Issuer;
 use aries_vcx::messages::a2a::A2AMessage;
@@ -14,8 +14,9 @@ use aries_vcx::messages::protocols::issuance::credential_proposal::CredentialPro
 use aries_vcx::messages::protocols::issuance::credential_request::CredentialRequest;
 use aries_vcx::protocols::issuance::issuer::state_machine::IssuerState;
 use aries_vcx::protocols::SendClosure;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct IssuerWrapper {
     issuer: Issuer,
     connection_id: String,
@@ -30,28 +31,41 @@ impl IssuerWrapper {
     }
 }
 
+impl StorageRecord for IssuerWrapper {
+    fn tags(&self) -> HashMap<String, String> {
+        let mut tags = HashMap::from([
+            ("state".to_string(), format!("{:?}", self.issuer.get_state())),
+            ("connection_id".to_string(), self.connection_id.clone()),
+        ]);
+        if let Ok(thread_id) = self.issuer.get_thread_id() {
+            tags.insert("thread_id".to_string(), thread_id);
+        }
+        tags
+    }
+}
+
 pub struct ServiceCredentialsIssuer {
     profile: Arc<dyn Profile>,
-    creds_issuer: ObjectCache<IssuerWrapper>,
+    creds_issuer: Arc<dyn Storage<IssuerWrapper>>,
     service_connections: Arc<ServiceConnections>,
 }
 
 impl ServiceCredentialsIssuer {
-    pub fn new(profile: Arc<dyn Profile>, service_connections: Arc<ServiceConnections>) -> Self {
+    pub fn new(profile: Arc<dyn Profile>, service_connections: Arc<ServiceConnections>, storage: StorageKind) -> Self {
         Self {
+            creds_issuer: new_storage(storage, &profile, "creds-issuer"),
             profile,
             service_connections,
-            creds_issuer: ObjectCache::new("creds-issuer"),
         }
     }
 
-    fn get_issuer(&self, thread_id: &str) -> AgentResult<Issuer> {
-        let IssuerWrapper { issuer, .. } = self.creds_issuer.get(thread_id)?;
+    async fn get_issuer(&self, thread_id: &str) -> AgentResult<Issuer> {
+        let IssuerWrapper { issuer, .. } = self.creds_issuer.get(thread_id).await?;
         Ok(issuer)
     }
 
-    pub fn get_connection_id(&self, thread_id: &str) -> AgentResult<String> {
-        let IssuerWrapper { connection_id, .. } = self.creds_issuer.get(thread_id)?;
+    pub async fn get_connection_id(&self, thread_id: &str) -> AgentResult<String> {
+        let IssuerWrapper { connection_id, .. } = self.creds_issuer.get(thread_id).await?;
         Ok(connection_id)
     }
 
@@ -59,6 +73,7 @@ impl ServiceCredentialsIssuer {
         let issuer = Issuer::create_from_proposal("", proposal)?;
         self.creds_issuer
             .insert(&issuer.get_thread_id()?, IssuerWrapper::new(issuer, connection_id))
+            .await
     }
 
     pub async fn send_credential_offer(
@@ -68,12 +83,12 @@ impl ServiceCredentialsIssuer {
         offer_info: OfferInfo,
     ) -> AgentResult<String> {
         let (mut issuer, connection_id) = match (thread_id, connection_id) {
-            (Some(id), Some(connection_id)) => (self.get_issuer(id)?, connection_id.to_string()),
-            (Some(id), None) => (self.get_issuer(id)?, self.get_connection_id(id)?),
+            (Some(id), Some(connection_id)) => (self.get_issuer(id).await?, connection_id.to_string()),
+            (Some(id), None) => (self.get_issuer(id).await?, self.get_connection_id(id).await?),
             (None, Some(connection_id)) => (Issuer::create("")?, connection_id.to_string()),
             (None, None) => return Err(AgentError::from_kind(AgentErrorKind::InvalidArguments)),
         };
-        let connection = self.service_connections.get_by_id(&connection_id)?;
+        let connection = self.service_connections.get_by_id(&connection_id).await?;
         issuer
             .build_credential_offer_msg(&self.profile, offer_info, None)
             .await?;
@@ -87,27 +102,30 @@ impl ServiceCredentialsIssuer {
         issuer.send_credential_offer(send_closure).await?;
         self.creds_issuer
             .insert(&issuer.get_thread_id()?, IssuerWrapper::new(issuer, &connection_id))
+            .await
     }
 
-    pub fn process_credential_request(&self, thread_id: &str, request: CredentialRequest) -> AgentResult<()> {
+    pub async fn process_credential_request(&self, thread_id: &str, request: CredentialRequest) -> AgentResult<()> {
         let IssuerWrapper {
             mut issuer,
             connection_id,
-        } = self.creds_issuer.get(thread_id)?;
+        } = self.creds_issuer.get(thread_id).await?;
         issuer.process_credential_request(request)?;
         self.creds_issuer
-            .insert(&issuer.get_thread_id()?, IssuerWrapper::new(issuer, &connection_id))?;
+            .insert(&issuer.get_thread_id()?, IssuerWrapper::new(issuer, &connection_id))
+            .await?;
         Ok(())
     }
 
-    pub fn process_credential_ack(&self, thread_id: &str, ack: CredentialAck) -> AgentResult<()> {
+    pub async fn process_credential_ack(&self, thread_id: &str, ack: CredentialAck) -> AgentResult<()> {
         let IssuerWrapper {
             mut issuer,
             connection_id,
-        } = self.creds_issuer.get(thread_id)?;
+        } = self.creds_issuer.get(thread_id).await?;
         issuer.process_credential_ack(ack)?;
         self.creds_issuer
-            .insert(&issuer.get_thread_id()?, IssuerWrapper::new(issuer, &connection_id))?;
+            .insert(&issuer.get_thread_id()?, IssuerWrapper::new(issuer, &connection_id))
+            .await?;
         Ok(())
     }
 
@@ -115,8 +133,8 @@ impl ServiceCredentialsIssuer {
         let IssuerWrapper {
             mut issuer,
             connection_id,
-        } = self.creds_issuer.get(thread_id)?;
-        let connection = self.service_connections.get_by_id(&connection_id)?;
+        } = self.creds_issuer.get(thread_id).await?;
+        let connection = self.service_connections.get_by_id(&connection_id).await?;
 
         let wallet = self.profile.inject_wallet();
 
@@ -126,30 +144,31 @@ impl ServiceCredentialsIssuer {
 
         issuer.send_credential(&self.profile, send_closure).await?;
         self.creds_issuer
-            .insert(&issuer.get_thread_id()?, IssuerWrapper::new(issuer, &connection_id))?;
+            .insert(&issuer.get_thread_id()?, IssuerWrapper::new(issuer, &connection_id))
+            .await?;
         Ok(())
     }
 
-    pub fn get_state(&self, thread_id: &str) -> AgentResult<IssuerState> {
-        Ok(self.get_issuer(thread_id)?.get_state())
+    pub async fn get_state(&self, thread_id: &str) -> AgentResult<IssuerState> {
+        Ok(self.get_issuer(thread_id).await?.get_state())
     }
 
-    pub fn get_rev_reg_id(&self, thread_id: &str) -> AgentResult<String> {
-        let issuer = self.get_issuer(thread_id)?;
+    pub async fn get_rev_reg_id(&self, thread_id: &str) -> AgentResult<String> {
+        let issuer = self.get_issuer(thread_id).await?;
         issuer.get_rev_reg_id().map_err(|err| err.into())
     }
 
-    pub fn get_rev_id(&self, thread_id: &str) -> AgentResult<String> {
-        let issuer = self.get_issuer(thread_id)?;
+    pub async fn get_rev_id(&self, thread_id: &str) -> AgentResult<String> {
+        let issuer = self.get_issuer(thread_id).await?;
         issuer.get_rev_id().map_err(|err| err.into())
     }
 
-    pub fn get_proposal(&self, thread_id: &str) -> AgentResult<CredentialProposal> {
-        let issuer = self.get_issuer(thread_id)?;
+    pub async fn get_proposal(&self, thread_id: &str) -> AgentResult<CredentialProposal> {
+        let issuer = self.get_issuer(thread_id).await?;
         issuer.get_proposal().map_err(|err| err.into())
     }
 
-    pub fn exists_by_id(&self, thread_id: &str) -> bool {
-        self.creds_issuer.contains_key(thread_id)
+    pub async fn exists_by_id(&self, thread_id: &str) -> bool {
+        self.creds_issuer.contains_key(thread_id).await
     }
 }
@@ -1,8 +1,8 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::error::*;
-use crate::storage::object_cache::ObjectCache;
-use crate::storage::Storage;
+use crate::storage::{new_storage, Storage, StorageKind, StorageRecord};
 use aries_vcx::common::ledger::transactions::into_did_doc;
 use aries_vcx::core::profile::profile::Profile;
 use aries_vcx::messages::protocols::connection::invite::Invitation;
@@ -20,15 +20,15 @@ use aries_vcx::{
 pub struct ServiceMediatedConnections {
     profile: Arc<dyn Profile>,
     config_agency_client: AgencyClientConfig,
-    mediated_connections: Arc<ObjectCache<MediatedConnection>>,
+    mediated_connections: Arc<dyn Storage<MediatedConnection>>,
 }
 
 impl ServiceMediatedConnections {
-    pub fn new(profile: Arc<dyn Profile>, config_agency_client: AgencyClientConfig) -> Self {
+    pub fn new(profile: Arc<dyn Profile>, config_agency_client: AgencyClientConfig, storage: StorageKind) -> Self {
         Self {
+            mediated_connections: new_storage(storage, &profile, "mediated-connections"),
             profile,
             config_agency_client,
-            mediated_connections: Arc::new(ObjectCache::new("mediated-connections")),
         }
     }
 
@@ -54,7 +54,8 @@ impl ServiceMediatedConnections {
             .ok_or_else(|| AgentError::from_kind(AgentErrorKind::InviteDetails))?
             .clone();
         self.mediated_connections
-            .insert(&connection.get_thread_id(), connection)?;
+            .insert(&connection.get_thread_id(), connection)
+            .await?;
         Ok(invite)
     }
 
@@ -65,57 +66,67 @@ impl ServiceMediatedConnections {
                 .await?;
         self.mediated_connections
             .insert(&connection.get_thread_id(), connection)
+            .await
     }
 
     pub async fn send_request(&self, thread_id: &str) -> AgentResult<()> {
-        let mut connection = self.mediated_connections.get(thread_id)?;
+        let mut connection = self.mediated_connections.get(thread_id).await?;
         connection.connect(&self.profile, &self.agency_client()?, None).await?;
         connection
             .find_message_and_update_state(&self.profile, &self.agency_client()?)
             .await?;
-        self.mediated_connections.insert(thread_id, connection)?;
+        self.mediated_connections.insert(thread_id, connection).await?;
         Ok(())
     }
 
     pub async fn accept_request(&self, thread_id: &str, request: Request) -> AgentResult<()> {
-        let mut connection = self.mediated_connections.get(thread_id)?;
+        let mut connection = self.mediated_connections.get(thread_id).await?;
         connection
             .process_request(&self.profile, &self.agency_client()?, request)
             .await?;
         connection.send_response(&self.profile).await?;
-        self.mediated_connections.insert(thread_id, connection)?;
+        self.mediated_connections.insert(thread_id, connection).await?;
         Ok(())
     }
 
     pub async fn send_ping(&self, thread_id: &str) -> AgentResult<()> {
-        let mut connection = self.mediated_connections.get(thread_id)?;
+        let mut connection = self.mediated_connections.get(thread_id).await?;
         connection.send_ping(&self.profile, None).await?;
-        self.mediated_connections.insert(thread_id, connection)?;
+        self.mediated_connections.insert(thread_id, connection).await?;
         Ok(())
     }
 
-    pub fn get_state(&self, thread_id: &str) -> AgentResult<ConnectionState> {
-        Ok(self.mediated_connections.get(thread_id)?.get_state())
+    pub async fn get_state(&self, thread_id: &str) -> AgentResult<ConnectionState> {
+        Ok(self.mediated_connections.get(thread_id).await?.get_state())
     }
 
     pub async fn update_state(&self, thread_id: &str) -> AgentResult<ConnectionState> {
-        let mut connection = self.mediated_connections.get(thread_id)?;
+        let mut connection = self.mediated_connections.get(thread_id).await?;
         connection
             .find_message_and_update_state(&self.profile, &self.agency_client()?)
             .await?;
-        self.mediated_connections.insert(thread_id, connection)?;
-        Ok(self.mediated_connections.get(thread_id)?.get_state())
+        self.mediated_connections.insert(thread_id, connection).await?;
+        Ok(self.mediated_connections.get(thread_id).await?.get_state())
     }
 
-    pub fn exists_by_id(&self, thread_id: &str) -> bool {
-        self.mediated_connections.contains_key(thread_id)
+    pub async fn exists_by_id(&self, thread_id: &str) -> bool {
+        self.mediated_connections.contains_key(thread_id).await
+    }
+}
+
+impl StorageRecord for MediatedConnection {
+    fn tags(&self) -> HashMap<String, String> {
+        HashMap::from([
+            ("thread_id".to_string(), self.get_thread_id()),
+            ("state".to_string(), format!("{:?}", self.get_state())),
+        ])
     }
 }
 
 macro_rules! get_messages (($msg_type:ty, $a2a_msg:ident, $name:ident) => (
     impl ServiceMediatedConnections {
         pub async fn $name(&self, thread_id: &str) -> AgentResult<Vec<$msg_type>> {
-            let connection = self.mediated_connections.get(thread_id)?;
+            let connection = self.mediated_connections.get(thread_id).await?;
             let agency_client = self.agency_client()?;
             let mut messages = Vec::<$msg_type>::new();
             for (uid, message) in connection.get_messages_noauth(&agency_client).await?.into_iter() {
@@ -0,0 +1,837 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use aries_vcx::core::profile::profile::Profile;
+use aries_vcx::messages::protocols::routing::forward::Forward;
+use aries_vcx::messages2::decorators::attachment::{Attachment, AttachmentData, AttachmentType};
+use aries_vcx::messages2::decorators::thread::Thread;
+use aries_vcx::messages2::decorators::transport::{ReturnRoute, Transport};
+use aries_vcx::messages2::msg_fields::protocols::coordinate_mediation::keylist::{
+    Keylist, KeylistContent, KeylistItem, KeylistPagination,
+};
+use aries_vcx::messages2::msg_fields::protocols::coordinate_mediation::keylist_query::KeylistQuery;
+use aries_vcx::messages2::msg_fields::protocols::coordinate_mediation::keylist_update::{
+    KeylistUpdate, KeylistUpdateAction,
+};
+use aries_vcx::messages2::msg_fields::protocols::coordinate_mediation::keylist_update_response::{
+    KeylistUpdateResponse, KeylistUpdateResponseContent, KeylistUpdateResponseItem, KeylistUpdateResult,
+};
+use aries_vcx::messages2::msg_fields::protocols::coordinate_mediation::mediate_grant::{
+    MediateGrant, MediateGrantContent,
+};
+use aries_vcx::messages2::msg_fields::protocols::coordinate_mediation::mediate_request::MediateRequest;
+use aries_vcx::messages2::msg_fields::protocols::coordinate_mediation::{
+    CoordinateMediation, CoordinateMediationDecorators,
+};
+use aries_vcx::messages2::msg_fields::protocols::pickup::delivery::{Delivery, DeliveryContent};
+use aries_vcx::messages2::msg_fields::protocols::pickup::delivery_request::DeliveryRequest;
+use aries_vcx::messages2::msg_fields::protocols::pickup::messages_received::MessagesReceived;
+use aries_vcx::messages2::msg_fields::protocols::pickup::status::{Status, StatusContent};
+use aries_vcx::messages2::msg_fields::protocols::pickup::{Pickup, PickupDecorators};
+use aries_vcx::messages2::AriesMessage;
+use aries_vcx::utils::uuid::uuid;
+use serde::{Deserialize, Serialize};
+
+use crate::error::*;
+use crate::storage::wallet_storage::WalletStorage;
+use crate::storage::{Storage, StorageRecord};
+
+use super::connection::{ServiceConnections, ServiceEndpoint};
+
+/// Mediation granted to a connection, along with the routing key senders must forward through.
+#[derive(Clone, Serialize, Deserialize)]
+struct MediationRecord {
+    routing_key: String,
+}
+
+impl StorageRecord for MediationRecord {
+    fn tags(&self) -> HashMap<String, String> {
+        HashMap::from([("routing_key".to_string(), self.routing_key.clone())])
+    }
+}
+
+/// Entry of the routing table, stored under the recipient key it routes for.
+#[derive(Clone, Serialize, Deserialize)]
+struct RouteRecord {
+    connection_id: String,
+}
+
+impl StorageRecord for RouteRecord {
+    fn tags(&self) -> HashMap<String, String> {
+        HashMap::from([("connection_id".to_string(), self.connection_id.clone())])
+    }
+}
+
+/// Forwarded envelope waiting to be picked up by the recipient. Messages are delivered in the
+/// order of their sequence number, as many of them may be received within the same second.
+#[derive(Clone, Serialize, Deserialize)]
+struct QueuedMessage {
+    recipient_key: String,
+    connection_id: String,
+    message: String,
+    received_at: u64,
+    #[serde(default)]
+    sequence: u64,
+}
+
+impl StorageRecord for QueuedMessage {
+    fn tags(&self) -> HashMap<String, String> {
+        HashMap::from([
+            ("recipient_key".to_string(), self.recipient_key.clone()),
+            ("connection_id".to_string(), self.connection_id.clone()),
+        ])
+    }
+}
+
+/// Relays messages for the agents connected to us. Mediation is granted to every
+/// connection asking for it through coordinate-mediation 1.0, forwarded messages are
+/// queued per recipient key and served through message pickup 2.0.
+///
+/// The routing table and the queues always live in the wallet, so that no message is
+/// lost when the agent restarts. Replies are returned to the caller of the handlers
+/// when the request asked for them through `~transport.return_route`, and sent to the
+/// connection's endpoint otherwise.
+pub struct ServiceMediator {
+    profile: Arc<dyn Profile>,
+    service_endpoint: ServiceEndpoint,
+    service_connections: Arc<ServiceConnections>,
+    mediations: Arc<dyn Storage<MediationRecord>>,
+    routes: Arc<dyn Storage<RouteRecord>>,
+    queue: Arc<dyn Storage<QueuedMessage>>,
+    last_sequence: AtomicU64,
+    sequence_loaded: AtomicBool,
+}
+
+impl ServiceMediator {
+    pub fn new(
+        profile: Arc<dyn Profile>,
+        service_endpoint: ServiceEndpoint,
+        service_connections: Arc<ServiceConnections>,
+    ) -> Self {
+        let wallet = profile.inject_wallet();
+        Self {
+            mediations: Arc::new(WalletStorage::new(Arc::clone(&wallet), "mediations")),
+            routes: Arc::new(WalletStorage::new(Arc::clone(&wallet), "mediator-routes")),
+            queue: Arc::new(WalletStorage::new(wallet, "mediator-queue")),
+            profile,
+            service_endpoint,
+            service_connections,
+            last_sequence: AtomicU64::new(0),
+            sequence_loaded: AtomicBool::new(false),
+        }
+    }
+
+    /// Handles a coordinate mediation request, returning the packed reply if the request asked
+    /// for it on the return route.
+    pub async fn handle_coordinate_mediation(
+        &self,
+        connection_id: &str,
+        message: CoordinateMediation,
+    ) -> AgentResult<Option<Vec<u8>>> {
+        let (return_route, reply) = match message {
+            CoordinateMediation::MediateRequest(request) => (
+                wants_return_route(&request.decorators.transport),
+                self.grant_mediation(connection_id, request).await?,
+            ),
+            CoordinateMediation::KeylistUpdate(update) => (
+                wants_return_route(&update.decorators.transport),
+                self.update_keylist(connection_id, update).await?,
+            ),
+            CoordinateMediation::KeylistQuery(query) => (
+                wants_return_route(&query.decorators.transport),
+                self.keylist(connection_id, query).await?,
+            ),
+            message => {
+                return Err(AgentError::from_msg(
+                    AgentErrorKind::UnhandledMessage,
+                    &format!("Mediator cannot handle message: {:?}", message),
+                ))
+            }
+        };
+        self.reply(connection_id, reply, return_route).await
+    }
+
+    /// Handles a message pickup request, returning the packed reply if the request asked for it
+    /// on the return route.
+    pub async fn handle_pickup(&self, connection_id: &str, message: Pickup) -> AgentResult<Option<Vec<u8>>> {
+        let (return_route, reply) = match message {
+            Pickup::StatusRequest(request) => (
+                wants_return_route(&request.decorators.transport),
+                self.status(connection_id, request.content.recipient_key, &request.id)
+                    .await?,
+            ),
+            Pickup::DeliveryRequest(request) => (
+                wants_return_route(&request.decorators.transport),
+                self.deliver(connection_id, request).await?,
+            ),
+            Pickup::MessagesReceived(received) => (
+                wants_return_route(&received.decorators.transport),
+                self.remove_received(connection_id, received).await?,
+            ),
+            Pickup::LiveDeliveryChange(change) => (
+                wants_return_route(&change.decorators.transport),
+                // Live delivery is not supported; keep queueing and report it as off.
+                self.status(connection_id, None, &change.id).await?,
+            ),
+            message => {
+                return Err(AgentError::from_msg(
+                    AgentErrorKind::UnhandledMessage,
+                    &format!("Mediator cannot handle message: {:?}", message),
+                ))
+            }
+        };
+        self.reply(connection_id, reply, return_route).await
+    }
+
+    /// Queues the envelope carried by a `forward` message for the connection owning its recipient key.
+    pub async fn receive_forward(&self, forward: Forward) -> AgentResult<()> {
+        let route = self.routes.get(&forward.to).await.map_err(|_| {
+            AgentError::from_msg(
+                AgentErrorKind::NotFound,
+                &format!("No route registered for recipient key {}", forward.to),
+            )
+        })?;
+        let queued = QueuedMessage {
+            recipient_key: forward.to,
+            connection_id: route.connection_id,
+            message: serde_json::to_string(&forward.msg)?,
+            received_at: unix_timestamp(),
+            sequence: self.next_sequence().await?,
+        };
+        self.queue.insert(&uuid(), queued).await?;
+        Ok(())
+    }
+
+    /// Number of messages queued for the keys registered by the connection.
+    pub async fn queued_message_count(&self, connection_id: &str) -> AgentResult<usize> {
+        Ok(self.queued_messages(connection_id, None).await?.len())
+    }
+
+    async fn reply(
+        &self,
+        connection_id: &str,
+        reply: AriesMessage,
+        return_route: bool,
+    ) -> AgentResult<Option<Vec<u8>>> {
+        if return_route {
+            let packed = self
+                .service_connections
+                .pack_aries_message(connection_id, &reply)
+                .await?;
+            return Ok(Some(packed));
+        }
+        self.service_connections
+            .send_aries_message(connection_id, &reply)
+            .await?;
+        Ok(None)
+    }
+
+    /// Sequence number of the next queued message. The counter continues after the messages still
+    /// queued when the agent was started.
+    async fn next_sequence(&self) -> AgentResult<u64> {
+        if !self.sequence_loaded.load(Ordering::SeqCst) {
+            let last = self
+                .queue
+                .find_records_by(&HashMap::new())
+                .await?
+                .into_iter()
+                .map(|(_, message)| message.sequence)
+                .max()
+                .unwrap_or_default();
+            self.last_sequence.fetch_max(last, Ordering::SeqCst);
+            self.sequence_loaded.store(true, Ordering::SeqCst);
+        }
+        Ok(self.last_sequence.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    async fn grant_mediation(&self, connection_id: &str, request: MediateRequest) -> AgentResult<AriesMessage> {
+        let routing_key = match self.mediations.get(connection_id).await {
+            Ok(mediation) => mediation.routing_key,
+            Err(_) => {
+                let (_, routing_key) = self.profile.inject_wallet().create_and_store_my_did(None, None).await?;
+                let mediation = MediationRecord {
+                    routing_key: routing_key.clone(),
+                };
+                self.mediations.insert(connection_id, mediation).await?;
+                routing_key
+            }
+        };
+
+        let content = MediateGrantContent::new(self.service_endpoint.clone(), vec![routing_key]);
+        let grant = MediateGrant::with_decorators(uuid(), content, reply_decorators(&request.id));
+        Ok(grant.into())
+    }
+
+    async fn update_keylist(&self, connection_id: &str, update: KeylistUpdate) -> AgentResult<AriesMessage> {
+        let granted = self.mediations.contains_key(connection_id).await;
+
+        let mut updated = Vec::new();
+        for item in update.content.updates {
+            let result = if granted {
+                self.apply_update(connection_id, &item.recipient_key, item.action).await
+            } else {
+                KeylistUpdateResult::ClientError
+            };
+            updated.push(KeylistUpdateResponseItem::new(item.recipient_key, item.action, result));
+        }
+
+        let content = KeylistUpdateResponseContent::new(updated);
+        let response = KeylistUpdateResponse::with_decorators(uuid(), content, reply_decorators(&update.id));
+        Ok(response.into())
+    }
+
+    async fn apply_update(
+        &self,
+        connection_id: &str,
+        recipient_key: &str,
+        action: KeylistUpdateAction,
+    ) -> KeylistUpdateResult {
+        let route = self.routes.get(recipient_key).await.ok();
+        let owned = matches!(&route, Some(route) if route.connection_id == connection_id);
+        let res = match (action, route) {
+            (_, Some(_)) if !owned => return KeylistUpdateResult::ClientError,
+            (KeylistUpdateAction::Add, Some(_)) | (KeylistUpdateAction::Remove, None) => {
+                return KeylistUpdateResult::NoChange
+            }
+            (KeylistUpdateAction::Add, None) => {
+                let route = RouteRecord {
+                    connection_id: connection_id.to_string(),
+                };
+                self.routes.insert(recipient_key, route).await.map(|_| ())
+            }
+            (KeylistUpdateAction::Remove, Some(_)) => self.routes.remove(recipient_key).await,
+        };
+        match res {
+            Ok(()) => KeylistUpdateResult::Success,
+            Err(err) => {
+                error!("Failed to update route for key {}: {}", recipient_key, err.message);
+                KeylistUpdateResult::ServerError
+            }
+        }
+    }
+
+    async fn keylist(&self, connection_id: &str, query: KeylistQuery) -> AgentResult<AriesMessage> {
+        let tags = HashMap::from([("connection_id".to_string(), connection_id.to_string())]);
+        let mut keys = self.routes.find_by(&tags).await?;
+        keys.sort();
+
+        let total = keys.len();
+        let (offset, limit) = match &query.content.paginate {
+            Some(paginate) => (paginate.offset as usize, paginate.limit as usize),
+            None => (0, total),
+        };
+        let page: Vec<_> = keys
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(KeylistItem::new)
+            .collect();
+
+        let mut content = KeylistContent::new(Vec::new());
+        if query.content.paginate.is_some() {
+            let remaining = total.saturating_sub(offset + page.len());
+            content.pagination = Some(KeylistPagination::new(
+                page.len() as u32,
+                offset as u32,
+                remaining as u32,
+            ));
+        }
+        content.keys = page;
+
+        let keylist = Keylist::with_decorators(uuid(), content, reply_decorators(&query.id));
+        Ok(keylist.into())
+    }
+
+    async fn status(
+        &self,
+        connection_id: &str,
+        recipient_key: Option<String>,
+        thread_id: &str,
+    ) -> AgentResult<AriesMessage> {
+        let queued = self.queued_messages(connection_id, recipient_key.as_deref()).await?;
+
+        let mut content = StatusContent::new(queued.len() as u32);
+        content.longest_waited_seconds = queued
+            .iter()
+            .map(|(_, message)| message.received_at)
+            .min()
+            .map(|received_at| unix_timestamp().saturating_sub(received_at));
+        content.total_bytes = Some(queued.iter().map(|(_, message)| message.message.len() as u64).sum());
+        content.live_delivery = Some(false);
+        content.recipient_key = recipient_key;
+
+        let status = Status::with_decorators(uuid(), content, pickup_reply_decorators(thread_id));
+        Ok(status.into())
+    }
+
+    async fn deliver(&self, connection_id: &str, request: DeliveryRequest) -> AgentResult<AriesMessage> {
+        let queued = self
+            .queued_messages(connection_id, request.content.recipient_key.as_deref())
+            .await?;
+        if queued.is_empty() {
+            return self
+                .status(connection_id, request.content.recipient_key, &request.id)
+                .await;
+        }
+
+        let attachments = queued
+            .into_iter()
+            .take(request.content.limit as usize)
+            .map(|(id, message)| {
+                let mut attachment = Attachment::new(AttachmentData::new(AttachmentType::Base64(
+                    message.message.into_bytes(),
+                )));
+                attachment.id = Some(id);
+                attachment
+            })
+            .collect();
+
+        let mut content = DeliveryContent::new(attachments);
+        content.recipient_key = request.content.recipient_key;
+        let delivery = Delivery::with_decorators(uuid(), content, pickup_reply_decorators(&request.id));
+        Ok(delivery.into())
+    }
+
+    async fn remove_received(&self, connection_id: &str, received: MessagesReceived) -> AgentResult<AriesMessage> {
+        for id in received.content.message_id_list {
+            match self.queue.get(&id).await {
+                Ok(message) if message.connection_id == connection_id => self.queue.remove(&id).await?,
+                _ => warn!("Connection {} acknowledged unknown message {}", connection_id, id),
+            }
+        }
+        self.status(connection_id, None, &received.id).await
+    }
+
+    /// Messages queued for the connection, in the order they were received.
+    async fn queued_messages(
+        &self,
+        connection_id: &str,
+        recipient_key: Option<&str>,
+    ) -> AgentResult<Vec<(String, QueuedMessage)>> {
+        let mut tags = HashMap::from([("connection_id".to_string(), connection_id.to_string())]);
+        if let Some(recipient_key) = recipient_key {
+            tags.insert("recipient_key".to_string(), recipient_key.to_string());
+        }
+        let mut messages = self.queue.find_records_by(&tags).await?;
+        messages.sort_by_key(|(_, message)| message.sequence);
+        Ok(messages)
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn wants_return_route(transport: &Option<Transport>) -> bool {
+    matches!(transport, Some(transport) if transport.return_route != ReturnRoute::None)
+}
+
+fn reply_decorators(thread_id: &str) -> CoordinateMediationDecorators {
+    let mut decorators = CoordinateMediationDecorators::default();
+    decorators.thread = Some(Thread::new(thread_id.to_string()));
+    decorators
+}
+
+fn pickup_reply_decorators(thread_id: &str) -> PickupDecorators {
+    let mut decorators = PickupDecorators::default();
+    decorators.thread = Some(Thread::new(thread_id.to_string()));
+    decorators
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use aries_vcx::agency_client::testing::mocking::{disable_agency_mocks, enable_agency_mocks};
+    use aries_vcx::core::profile::vdrtools_profile::VdrtoolsProfile;
+    use aries_vcx::global::settings::{DEFAULT_WALLET_KEY, WALLET_KDF_RAW};
+    use aries_vcx::indy::wallet::{close_wallet, create_and_open_wallet, delete_wallet, WalletConfig};
+    use aries_vcx::messages::concepts::ack::Ack;
+    use aries_vcx::messages::protocols::connection::request::Request;
+    use aries_vcx::messages2::msg_fields::protocols::coordinate_mediation::keylist_query::{
+        KeylistQueryContent, KeylistQueryPaginate,
+    };
+    use aries_vcx::messages2::msg_fields::protocols::coordinate_mediation::keylist_update::{
+        KeylistUpdateContent, KeylistUpdateItem,
+    };
+    use aries_vcx::messages2::msg_fields::protocols::coordinate_mediation::mediate_request::MediateRequestContent;
+    use aries_vcx::messages2::msg_fields::protocols::pickup::delivery_request::DeliveryRequestContent;
+    use aries_vcx::messages2::msg_fields::protocols::pickup::messages_received::MessagesReceivedContent;
+    use aries_vcx::messages2::msg_fields::protocols::pickup::status_request::{StatusRequest, StatusRequestContent};
+    use aries_vcx::vdrtools::{WalletHandle, INVALID_POOL_HANDLE};
+    use serde_json::Value;
+
+    use crate::storage::StorageKind;
+
+    use super::*;
+
+    const SERVICE_ENDPOINT: &str = "http://dummy.org";
+
+    async fn create_profile() -> (Arc<dyn Profile>, WalletHandle, WalletConfig) {
+        let config_wallet = WalletConfig {
+            wallet_name: format!("test_agent_mediator_{}", uuid()),
+            wallet_key: DEFAULT_WALLET_KEY.into(),
+            wallet_key_derivation: WALLET_KDF_RAW.into(),
+            wallet_type: None,
+            storage_config: None,
+            storage_credentials: None,
+            rekey: None,
+            rekey_derivation_method: None,
+        };
+        let wallet_handle = create_and_open_wallet(&config_wallet).await.unwrap();
+        let profile = Arc::new(VdrtoolsProfile::new(wallet_handle, INVALID_POOL_HANDLE));
+        (profile, wallet_handle, config_wallet)
+    }
+
+    async fn delete_profile(wallet_handle: WalletHandle, config_wallet: WalletConfig) {
+        close_wallet(wallet_handle).await.unwrap();
+        delete_wallet(&config_wallet).await.unwrap();
+    }
+
+    async fn establish_connection(mediator: &ServiceConnections, recipient: &ServiceConnections) -> String {
+        let invite = mediator.create_invitation(None).await.unwrap();
+        let thread_id = recipient.receive_invitation(invite).await.unwrap();
+        recipient.send_request(&thread_id).await.unwrap();
+
+        let recipient_pw_info = recipient.get_by_id(&thread_id).await.unwrap().pairwise_info().clone();
+        let request = Request::create()
+            .set_did(recipient_pw_info.pw_did.clone())
+            .set_service_endpoint(SERVICE_ENDPOINT.to_string())
+            .set_keys(vec![recipient_pw_info.pw_vk], vec![])
+            .set_thread_id(&thread_id);
+        mediator.accept_request(&thread_id, request).await.unwrap();
+        mediator.send_response(&thread_id).await.unwrap();
+        mediator
+            .process_ack(&thread_id, Ack::create().set_thread_id(&thread_id))
+            .await
+            .unwrap();
+        thread_id
+    }
+
+    fn return_route_decorators() -> CoordinateMediationDecorators {
+        let mut decorators = CoordinateMediationDecorators::default();
+        decorators.transport = Some(Transport::new(ReturnRoute::All));
+        decorators
+    }
+
+    fn pickup_return_route_decorators() -> PickupDecorators {
+        let mut decorators = PickupDecorators::default();
+        decorators.transport = Some(Transport::new(ReturnRoute::All));
+        decorators
+    }
+
+    async fn unpack_reply(profile: &Arc<dyn Profile>, reply: Option<Vec<u8>>) -> AriesMessage {
+        let unpacked = profile
+            .inject_wallet()
+            .unpack_message(&reply.expect("Expected a reply on the return route"))
+            .await
+            .unwrap();
+        let unpacked: Value = serde_json::from_slice(&unpacked).unwrap();
+        serde_json::from_str(unpacked["message"].as_str().unwrap()).unwrap()
+    }
+
+    async fn update_keys(
+        mediator: &ServiceMediator,
+        recipient_profile: &Arc<dyn Profile>,
+        connection_id: &str,
+        keys: &[&str],
+    ) -> Vec<KeylistUpdateResult> {
+        let items = keys
+            .iter()
+            .map(|key| KeylistUpdateItem::new(key.to_string(), KeylistUpdateAction::Add))
+            .collect();
+        let update =
+            KeylistUpdate::with_decorators(uuid(), KeylistUpdateContent::new(items), return_route_decorators());
+        let reply = mediator
+            .handle_coordinate_mediation(connection_id, update.into())
+            .await
+            .unwrap();
+        match unpack_reply(recipient_profile, reply).await {
+            AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdateResponse(response)) => {
+                response.content.updated.into_iter().map(|item| item.result).collect()
+            }
+            message => panic!("Expected a keylist update response, received {:?}", message),
+        }
+    }
+
+    async fn grant_mediation(mediator: &ServiceMediator, recipient_profile: &Arc<dyn Profile>, connection_id: &str) {
+        let request =
+            MediateRequest::with_decorators(uuid(), MediateRequestContent::default(), return_route_decorators());
+        let reply = mediator
+            .handle_coordinate_mediation(connection_id, request.clone().into())
+            .await
+            .unwrap();
+        match unpack_reply(recipient_profile, reply).await {
+            AriesMessage::CoordinateMediation(CoordinateMediation::MediateGrant(grant)) => {
+                assert_eq!(grant.decorators.thread.unwrap().thid, request.id);
+            }
+            message => panic!("Expected a mediation grant, received {:?}", message),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mediator_queues_forwarded_messages_across_restarts() {
+        enable_agency_mocks();
+        let (mediator_profile, mediator_handle, mediator_wallet) = create_profile().await;
+        let (recipient_profile, recipient_handle, recipient_wallet) = create_profile().await;
+
+        let mediator_connections = Arc::new(ServiceConnections::new(
+            Arc::clone(&mediator_profile),
+            SERVICE_ENDPOINT.to_string(),
+            StorageKind::Wallet,
+        ));
+        let recipient_connections = ServiceConnections::new(
+            Arc::clone(&recipient_profile),
+            SERVICE_ENDPOINT.to_string(),
+            StorageKind::InMemory,
+        );
+        let connection_id = establish_connection(&mediator_connections, &recipient_connections).await;
+
+        let start_mediator = || {
+            ServiceMediator::new(
+                Arc::clone(&mediator_profile),
+                SERVICE_ENDPOINT.to_string(),
+                mediator_connections.clone(),
+            )
+        };
+        let mediator = start_mediator();
+
+        let update = KeylistUpdate::with_decorators(
+            uuid(),
+            KeylistUpdateContent::new(vec![KeylistUpdateItem::new(
+                "key_1".to_string(),
+                KeylistUpdateAction::Add,
+            )]),
+            CoordinateMediationDecorators::default(),
+        );
+        // Keys cannot be registered before mediation is granted
+        mediator
+            .handle_coordinate_mediation(&connection_id, update.clone().into())
+            .await
+            .unwrap();
+        assert!(mediator
+            .receive_forward(Forward::new("key_1".to_string(), b"{}".to_vec()).unwrap())
+            .await
+            .is_err());
+
+        let request = MediateRequest::with_decorators(
+            uuid(),
+            MediateRequestContent::default(),
+            CoordinateMediationDecorators::default(),
+        );
+        mediator
+            .handle_coordinate_mediation(&connection_id, request.into())
+            .await
+            .unwrap();
+        mediator
+            .handle_coordinate_mediation(&connection_id, update.into())
+            .await
+            .unwrap();
+        mediator
+            .receive_forward(Forward::new("key_1".to_string(), b"{\"ciphertext\":\"abc\"}".to_vec()).unwrap())
+            .await
+            .unwrap();
+
+        drop(mediator);
+        let mediator = start_mediator();
+        assert_eq!(mediator.queued_message_count(&connection_id).await.unwrap(), 1);
+
+        // Messages queued after the restart are ordered after the ones queued before it
+        mediator
+            .receive_forward(Forward::new("key_1".to_string(), b"{\"ciphertext\":\"def\"}".to_vec()).unwrap())
+            .await
+            .unwrap();
+        let sequences: Vec<_> = mediator
+            .queued_messages(&connection_id, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(_, message)| message.sequence)
+            .collect();
+        assert_eq!(sequences, vec![1, 2]);
+
+        delete_profile(mediator_handle, mediator_wallet).await;
+        delete_profile(recipient_handle, recipient_wallet).await;
+        disable_agency_mocks();
+    }
+
+    #[tokio::test]
+    async fn test_mediator_delivers_queued_messages_in_order_on_return_route() {
+        enable_agency_mocks();
+        let (mediator_profile, mediator_handle, mediator_wallet) = create_profile().await;
+        let (recipient_profile, recipient_handle, recipient_wallet) = create_profile().await;
+
+        let mediator_connections = Arc::new(ServiceConnections::new(
+            Arc::clone(&mediator_profile),
+            SERVICE_ENDPOINT.to_string(),
+            StorageKind::InMemory,
+        ));
+        let recipient_connections = ServiceConnections::new(
+            Arc::clone(&recipient_profile),
+            SERVICE_ENDPOINT.to_string(),
+            StorageKind::InMemory,
+        );
+        let connection_id = establish_connection(&mediator_connections, &recipient_connections).await;
+        let mediator = ServiceMediator::new(
+            Arc::clone(&mediator_profile),
+            SERVICE_ENDPOINT.to_string(),
+            mediator_connections,
+        );
+
+        grant_mediation(&mediator, &recipient_profile, &connection_id).await;
+        assert_eq!(
+            update_keys(&mediator, &recipient_profile, &connection_id, &["key_1", "key_2"]).await,
+            vec![KeylistUpdateResult::Success, KeylistUpdateResult::Success]
+        );
+
+        // All messages are received within the same second, the queue keeps their order anyway
+        let messages: Vec<_> = (0..3)
+            .map(|i| json!({ "ciphertext": format!("message_{}", i) }).to_string())
+            .collect();
+        for message in &messages {
+            mediator
+                .receive_forward(Forward::new("key_1".to_string(), message.clone().into_bytes()).unwrap())
+                .await
+                .unwrap();
+        }
+        mediator
+            .receive_forward(Forward::new("key_2".to_string(), b"{\"ciphertext\":\"other\"}".to_vec()).unwrap())
+            .await
+            .unwrap();
+
+        let mut content = StatusRequestContent::default();
+        content.recipient_key = Some("key_2".to_string());
+        let request = StatusRequest::with_decorators(uuid(), content, pickup_return_route_decorators());
+        let reply = mediator.handle_pickup(&connection_id, request.into()).await.unwrap();
+        match unpack_reply(&recipient_profile, reply).await {
+            AriesMessage::Pickup(Pickup::Status(status)) => {
+                assert_eq!(status.content.message_count, 1);
+                assert_eq!(status.content.recipient_key, Some("key_2".to_string()));
+            }
+            message => panic!("Expected a status, received {:?}", message),
+        }
+
+        let mut content = DeliveryRequestContent::new(10);
+        content.recipient_key = Some("key_1".to_string());
+        let request = DeliveryRequest::with_decorators(uuid(), content, pickup_return_route_decorators());
+        let reply = mediator.handle_pickup(&connection_id, request.into()).await.unwrap();
+        let delivered = match unpack_reply(&recipient_profile, reply).await {
+            AriesMessage::Pickup(Pickup::Delivery(delivery)) => delivery.content.attach,
+            message => panic!("Expected a delivery, received {:?}", message),
+        };
+        let contents: Vec<_> = delivered
+            .iter()
+            .map(|attachment| attachment.data.content.clone())
+            .collect();
+        let expected: Vec<_> = messages
+            .iter()
+            .map(|message| AttachmentType::Base64(message.clone().into_bytes()))
+            .collect();
+        assert_eq!(contents, expected);
+
+        let received_ids = delivered
+            .into_iter()
+            .take(2)
+            .map(|attachment| attachment.id.unwrap())
+            .collect();
+        let received = MessagesReceived::with_decorators(
+            uuid(),
+            MessagesReceivedContent::new(received_ids),
+            pickup_return_route_decorators(),
+        );
+        let reply = mediator.handle_pickup(&connection_id, received.into()).await.unwrap();
+        match unpack_reply(&recipient_profile, reply).await {
+            AriesMessage::Pickup(Pickup::Status(status)) => assert_eq!(status.content.message_count, 2),
+            message => panic!("Expected a status, received {:?}", message),
+        }
+
+        // Without a return route the reply goes to the connection's endpoint
+        let request =
+            StatusRequest::with_decorators(uuid(), StatusRequestContent::default(), PickupDecorators::default());
+        assert!(mediator
+            .handle_pickup(&connection_id, request.into())
+            .await
+            .unwrap()
+            .is_none());
+
+        delete_profile(mediator_handle, mediator_wallet).await;
+        delete_profile(recipient_handle, recipient_wallet).await;
+        disable_agency_mocks();
+    }
+
+    #[tokio::test]
+    async fn test_mediator_keylist_is_owned_and_paginated() {
+        enable_agency_mocks();
+        let (mediator_profile, mediator_handle, mediator_wallet) = create_profile().await;
+        let (alice_profile, alice_handle, alice_wallet) = create_profile().await;
+        let (bob_profile, bob_handle, bob_wallet) = create_profile().await;
+
+        let mediator_connections = Arc::new(ServiceConnections::new(
+            Arc::clone(&mediator_profile),
+            SERVICE_ENDPOINT.to_string(),
+            StorageKind::InMemory,
+        ));
+        let alice_connections = ServiceConnections::new(
+            Arc::clone(&alice_profile),
+            SERVICE_ENDPOINT.to_string(),
+            StorageKind::InMemory,
+        );
+        let bob_connections = ServiceConnections::new(
+            Arc::clone(&bob_profile),
+            SERVICE_ENDPOINT.to_string(),
+            StorageKind::InMemory,
+        );
+        let alice_connection_id = establish_connection(&mediator_connections, &alice_connections).await;
+        let bob_connection_id = establish_connection(&mediator_connections, &bob_connections).await;
+        let mediator = ServiceMediator::new(
+            Arc::clone(&mediator_profile),
+            SERVICE_ENDPOINT.to_string(),
+            mediator_connections,
+        );
+
+        grant_mediation(&mediator, &alice_profile, &alice_connection_id).await;
+        grant_mediation(&mediator, &bob_profile, &bob_connection_id).await;
+        assert_eq!(
+            update_keys(
+                &mediator,
+                &alice_profile,
+                &alice_connection_id,
+                &["key_1", "key_2", "key_3"]
+            )
+            .await,
+            vec![KeylistUpdateResult::Success; 3]
+        );
+        assert_eq!(
+            update_keys(&mediator, &alice_profile, &alice_connection_id, &["key_1"]).await,
+            vec![KeylistUpdateResult::NoChange]
+        );
+        // Keys registered by another connection cannot be taken over
+        assert_eq!(
+            update_keys(&mediator, &bob_profile, &bob_connection_id, &["key_1", "key_4"]).await,
+            vec![KeylistUpdateResult::ClientError, KeylistUpdateResult::Success]
+        );
+
+        let mut content = KeylistQueryContent::default();
+        content.paginate = Some(KeylistQueryPaginate::new(1, 1));
+        let query = KeylistQuery::with_decorators(uuid(), content, return_route_decorators());
+        let reply = mediator
+            .handle_coordinate_mediation(&alice_connection_id, query.into())
+            .await
+            .unwrap();
+        match unpack_reply(&alice_profile, reply).await {
+            AriesMessage::CoordinateMediation(CoordinateMediation::Keylist(keylist)) => {
+                assert_eq!(keylist.content.keys, vec![KeylistItem::new("key_2".to_string())]);
+                assert_eq!(keylist.content.pagination, Some(KeylistPagination::new(1, 1, 1)));
+            }
+            message => panic!("Expected a keylist, received {:?}", message),
+        }
+
+        delete_profile(mediator_handle, mediator_wallet).await;
+        delete_profile(alice_handle, alice_wallet).await;
+        delete_profile(bob_handle, bob_wallet).await;
+        disable_agency_mocks();
+    }
+}
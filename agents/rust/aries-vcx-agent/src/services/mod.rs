@@ -3,6 +3,7 @@ pub(crate) mod credential_definition;
 pub(crate) mod holder;
 pub(crate) mod issuer;
 pub(crate) mod mediated_connection;
+pub(crate) mod mediator;
 pub(crate) mod prover;
 pub(crate) mod revocation_registry;
 pub(crate) mod schema;
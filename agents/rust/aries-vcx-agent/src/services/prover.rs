@@ -3,28 +3,33 @@ use std::sync::Arc;
 
 use crate::error::*;
 use crate::http_client::HttpClient;
-use crate::storage::object_cache::ObjectCache;
-use crate::storage::Storage;
+use crate::storage::{new_storage, Storage, StorageKind, StorageRecord};
+use aries_vcx::common::proofs::prover::credential_selection::{NewestFirst, PreferNonRevoked};
 use aries_vcx::core::profile::profile::Profile;
 use aries_vcx::handlers::proof_presentation::prover::Prover;
+use aries_vcx::handlers::proof_presentation::versioned_prover::VersionedProver;
 use aries_vcx::messages::a2a::A2AMessage;
 use aries_vcx::messages::protocols::proof_presentation::presentation_ack::PresentationAck;
 use aries_vcx::messages::protocols::proof_presentation::presentation_proposal::PresentationProposalData;
 use aries_vcx::messages::protocols::proof_presentation::presentation_request::PresentationRequest;
+use aries_vcx::messages2::msg_fields::protocols::present_proof::v2::ack::AckPresentationV2;
+use aries_vcx::messages2::msg_fields::protocols::present_proof::v2::request::RequestPresentationV2;
+use aries_vcx::messages2::AriesMessage;
 use aries_vcx::protocols::proof_presentation::prover::state_machine::ProverState;
 use aries_vcx::protocols::SendClosure;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use super::connection::ServiceConnections;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct ProverWrapper {
-    prover: Prover,
+    prover: VersionedProver,
     connection_id: String,
 }
 
 impl ProverWrapper {
-    pub fn new(prover: Prover, connection_id: &str) -> Self {
+    pub fn new(prover: VersionedProver, connection_id: &str) -> Self {
         Self {
             prover,
             connection_id: connection_id.to_string(),
@@ -32,55 +37,101 @@ impl ProverWrapper {
     }
 }
 
+impl StorageRecord for ProverWrapper {
+    fn tags(&self) -> HashMap<String, String> {
+        let mut tags = HashMap::from([
+            ("state".to_string(), format!("{:?}", self.prover.get_state())),
+            ("connection_id".to_string(), self.connection_id.clone()),
+        ]);
+        if let Ok(thread_id) = self.prover.get_thread_id() {
+            tags.insert("thread_id".to_string(), thread_id);
+        }
+        tags
+    }
+}
+
+/// Selects how the prover picks the credentials it presents.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum CredentialSelection {
+    /// Presents the most recently stored credentials.
+    #[default]
+    NewestFirst,
+    /// Presents credentials which are not revoked, as recorded on the ledger.
+    PreferNonRevoked,
+}
+
 pub struct ServiceProver {
     profile: Arc<dyn Profile>,
-    provers: ObjectCache<ProverWrapper>,
+    provers: Arc<dyn Storage<ProverWrapper>>,
     service_connections: Arc<ServiceConnections>,
+    credential_selection: CredentialSelection,
 }
 
 impl ServiceProver {
-    pub fn new(profile: Arc<dyn Profile>, service_connections: Arc<ServiceConnections>) -> Self {
+    pub fn new(
+        profile: Arc<dyn Profile>,
+        service_connections: Arc<ServiceConnections>,
+        storage: StorageKind,
+        credential_selection: CredentialSelection,
+    ) -> Self {
         Self {
+            provers: new_storage(storage, &profile, "provers"),
             profile,
             service_connections,
-            provers: ObjectCache::new("provers"),
+            credential_selection,
         }
     }
 
-    pub fn get_prover(&self, thread_id: &str) -> AgentResult<Prover> {
-        let ProverWrapper { prover, .. } = self.provers.get(thread_id)?;
+    pub async fn get_prover(&self, thread_id: &str) -> AgentResult<VersionedProver> {
+        let ProverWrapper { prover, .. } = self.provers.get(thread_id).await?;
         Ok(prover)
     }
 
-    pub fn get_connection_id(&self, thread_id: &str) -> AgentResult<String> {
-        let ProverWrapper { connection_id, .. } = self.provers.get(thread_id)?;
+    pub async fn get_connection_id(&self, thread_id: &str) -> AgentResult<String> {
+        let ProverWrapper { connection_id, .. } = self.provers.get(thread_id).await?;
         Ok(connection_id)
     }
 
-    async fn get_credentials_for_presentation(&self, prover: &Prover, tails_dir: Option<&str>) -> AgentResult<String> {
-        let credentials = prover.retrieve_credentials(&self.profile).await?;
-        let credentials: HashMap<String, Value> = serde_json::from_str(&credentials).unwrap();
-
-        let mut res_credentials = json!({});
-
-        for (key, val) in credentials["attrs"].as_object().unwrap().iter() {
-            let cred_array = val.as_array().unwrap();
-            if !cred_array.is_empty() {
-                let first_cred = &cred_array[0];
-                res_credentials["attrs"][key]["credential"] = first_cred.clone();
-                if let Some(tails_dir) = tails_dir {
-                    res_credentials["attrs"][key]["tails_file"] = Value::from(tails_dir);
-                }
+    async fn get_credentials_for_presentation(
+        &self,
+        prover: &VersionedProver,
+        tails_dir: Option<&str>,
+    ) -> AgentResult<String> {
+        let credentials = match self.credential_selection {
+            CredentialSelection::NewestFirst => {
+                prover
+                    .select_credentials(&self.profile, &NewestFirst, tails_dir)
+                    .await?
             }
-        }
-        Ok(res_credentials.to_string())
+            CredentialSelection::PreferNonRevoked => {
+                let strategy = PreferNonRevoked::new(Arc::clone(&self.profile).inject_ledger());
+                prover.select_credentials(&self.profile, &strategy, tails_dir).await?
+            }
+        };
+        Ok(credentials.to_json()?)
+    }
+
+    pub async fn create_from_request(&self, connection_id: &str, request: PresentationRequest) -> AgentResult<String> {
+        self.service_connections.get_by_id(connection_id).await?;
+        let prover = VersionedProver::create_from_request("", request)?;
+        self.provers
+            .insert(&prover.get_thread_id()?, ProverWrapper::new(prover, connection_id))
+            .await
     }
 
-    pub fn create_from_request(&self, connection_id: &str, request: PresentationRequest) -> AgentResult<String> {
-        self.service_connections.get_by_id(connection_id)?;
-        let prover = Prover::create_from_request("", request)?;
+    /// Records a request received with the present proof protocol 2.0, to be answered with
+    /// [`ServiceProver::send_proof_prentation`] as any other request.
+    pub async fn create_from_request_v2(
+        &self,
+        connection_id: &str,
+        request: RequestPresentationV2,
+    ) -> AgentResult<String> {
+        self.service_connections.get_by_id(connection_id).await?;
+        let request = serde_json::to_value(AriesMessage::from(request))?;
+        let prover = VersionedProver::create_from_request_v2(A2AMessage::Generic(request))?;
         self.provers
             .insert(&prover.get_thread_id()?, ProverWrapper::new(prover, connection_id))
+            .await
     }
 
     pub async fn send_proof_proposal(
@@ -88,7 +139,7 @@ impl ServiceProver {
         connection_id: &str,
         proposal: PresentationProposalData,
     ) -> AgentResult<String> {
-        let connection = self.service_connections.get_by_id(connection_id)?;
+        let connection = self.service_connections.get_by_id(connection_id).await?;
         let mut prover = Prover::create("")?;
 
         let wallet = self.profile.inject_wallet();
@@ -99,11 +150,15 @@ impl ServiceProver {
 
         prover.send_proposal(proposal, send_closure).await?;
         self.provers
-            .insert(&prover.get_thread_id()?, ProverWrapper::new(prover, connection_id))
+            .insert(
+                &prover.get_thread_id()?,
+                ProverWrapper::new(VersionedProver::V1(prover), connection_id),
+            )
+            .await
     }
 
-    pub fn is_secondary_proof_requested(&self, thread_id: &str) -> AgentResult<bool> {
-        let prover = self.get_prover(thread_id)?;
+    pub async fn is_secondary_proof_requested(&self, thread_id: &str) -> AgentResult<bool> {
+        let prover = self.get_prover(thread_id).await?;
         let attach = prover.get_proof_request_attachment()?;
         let attach: Value = serde_json::from_str(&attach)?;
         Ok(!attach["non_revoked"].is_null())
@@ -113,8 +168,8 @@ impl ServiceProver {
         let ProverWrapper {
             mut prover,
             connection_id,
-        } = self.provers.get(thread_id)?;
-        let connection = self.service_connections.get_by_id(&connection_id)?;
+        } = self.provers.get(thread_id).await?;
+        let connection = self.service_connections.get_by_id(&connection_id).await?;
         let credentials = self.get_credentials_for_presentation(&prover, tails_dir).await?;
         prover
             .generate_presentation(&self.profile, credentials, "{}".to_string())
@@ -128,26 +183,39 @@ impl ServiceProver {
 
         prover.send_presentation(send_closure).await?;
         self.provers
-            .insert(&prover.get_thread_id()?, ProverWrapper::new(prover, &connection_id))?;
+            .insert(&prover.get_thread_id()?, ProverWrapper::new(prover, &connection_id))
+            .await?;
         Ok(())
     }
 
-    pub fn process_presentation_ack(&self, thread_id: &str, ack: PresentationAck) -> AgentResult<String> {
+    pub async fn process_presentation_ack(&self, thread_id: &str, ack: PresentationAck) -> AgentResult<String> {
+        self.process_versioned_presentation_ack(thread_id, A2AMessage::PresentationAck(ack))
+            .await
+    }
+
+    pub async fn process_presentation_ack_v2(&self, thread_id: &str, ack: AckPresentationV2) -> AgentResult<String> {
+        let ack = serde_json::to_value(AriesMessage::from(ack))?;
+        self.process_versioned_presentation_ack(thread_id, A2AMessage::Generic(ack))
+            .await
+    }
+
+    async fn process_versioned_presentation_ack(&self, thread_id: &str, ack: A2AMessage) -> AgentResult<String> {
         let ProverWrapper {
             mut prover,
             connection_id,
-        } = self.provers.get(thread_id)?;
+        } = self.provers.get(thread_id).await?;
         prover.process_presentation_ack(ack)?;
         self.provers
             .insert(&prover.get_thread_id()?, ProverWrapper::new(prover, &connection_id))
+            .await
     }
 
-    pub fn get_state(&self, thread_id: &str) -> AgentResult<ProverState> {
-        let ProverWrapper { prover, .. } = self.provers.get(thread_id)?;
+    pub async fn get_state(&self, thread_id: &str) -> AgentResult<ProverState> {
+        let ProverWrapper { prover, .. } = self.provers.get(thread_id).await?;
         Ok(prover.get_state())
     }
 
-    pub fn exists_by_id(&self, thread_id: &str) -> bool {
-        self.provers.contains_key(thread_id)
+    pub async fn exists_by_id(&self, thread_id: &str) -> bool {
+        self.provers.contains_key(thread_id).await
     }
 }
@@ -1,46 +1,46 @@
+use std::collections::HashMap;
 use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use crate::error::*;
-use crate::storage::object_cache::ObjectCache;
-use crate::storage::Storage;
+use crate::storage::{new_storage, Storage, StorageKind, StorageRecord};
 use aries_vcx::common::primitives::revocation_registry::RevocationRegistry;
 use aries_vcx::core::profile::profile::Profile;
 
 pub struct ServiceRevocationRegistries {
     profile: Arc<dyn Profile>,
     issuer_did: String,
-    rev_regs: ObjectCache<RevocationRegistry>,
+    rev_regs: Arc<dyn Storage<RevocationRegistry>>,
 }
 
 impl ServiceRevocationRegistries {
-    pub fn new(profile: Arc<dyn Profile>, issuer_did: String) -> Self {
+    pub fn new(profile: Arc<dyn Profile>, issuer_did: String, storage: StorageKind) -> Self {
         Self {
+            rev_regs: new_storage(storage, &profile, "rev-regs"),
             profile,
             issuer_did,
-            rev_regs: ObjectCache::new("rev-regs"),
         }
     }
 
-    fn get_tails_hash(&self, thread_id: &str) -> AgentResult<String> {
-        let rev_reg = self.rev_regs.get(thread_id)?;
+    async fn get_tails_hash(&self, thread_id: &str) -> AgentResult<String> {
+        let rev_reg = self.rev_regs.get(thread_id).await?;
         Ok(rev_reg.get_rev_reg_def().value.tails_hash)
     }
 
-    pub fn get_tails_dir(&self, thread_id: &str) -> AgentResult<String> {
-        let rev_reg = self.rev_regs.get(thread_id)?;
+    pub async fn get_tails_dir(&self, thread_id: &str) -> AgentResult<String> {
+        let rev_reg = self.rev_regs.get(thread_id).await?;
         Ok(rev_reg.get_tails_dir())
     }
 
     pub async fn create_rev_reg(&self, cred_def_id: &str, max_creds: u32) -> AgentResult<String> {
         let rev_reg =
             RevocationRegistry::create(&self.profile, &self.issuer_did, cred_def_id, "/tmp", max_creds, 1).await?;
-        self.rev_regs.insert(&rev_reg.get_rev_reg_id(), rev_reg)
+        self.rev_regs.insert(&rev_reg.get_rev_reg_id(), rev_reg).await
     }
 
-    pub fn tails_file_path(&self, thread_id: &str) -> AgentResult<String> {
-        Ok(Path::new(&self.get_tails_dir(thread_id)?)
-            .join(self.get_tails_hash(thread_id)?)
+    pub async fn tails_file_path(&self, thread_id: &str) -> AgentResult<String> {
+        Ok(Path::new(&self.get_tails_dir(thread_id).await?)
+            .join(self.get_tails_hash(thread_id).await?)
             .to_str()
             .ok_or_else(|| {
                 AgentError::from_msg(
@@ -52,36 +52,34 @@ impl ServiceRevocationRegistries {
     }
 
     pub async fn publish_rev_reg(&self, thread_id: &str, tails_url: &str) -> AgentResult<()> {
-        let mut rev_reg = self.rev_regs.get(thread_id)?;
+        let mut rev_reg = self.rev_regs.get(thread_id).await?;
         rev_reg.publish_revocation_primitives(&self.profile, tails_url).await?;
-        self.rev_regs.insert(thread_id, rev_reg)?;
+        self.rev_regs.insert(thread_id, rev_reg).await?;
         Ok(())
     }
 
     pub async fn revoke_credential_locally(&self, id: &str, cred_rev_id: &str) -> AgentResult<()> {
-        let rev_reg = self.rev_regs.get(id)?;
+        let rev_reg = self.rev_regs.get(id).await?;
         rev_reg.revoke_credential_local(&self.profile, cred_rev_id).await?;
         Ok(())
     }
 
     pub async fn publish_local_revocations(&self, id: &str) -> AgentResult<()> {
-        let rev_reg = self.rev_regs.get(id)?;
+        let rev_reg = self.rev_regs.get(id).await?;
         rev_reg
             .publish_local_revocations(&self.profile, &self.issuer_did)
             .await?;
         Ok(())
     }
 
-    pub fn find_by_cred_def_id(&self, cred_def_id: &str) -> AgentResult<Vec<String>> {
-        let cred_def_id = cred_def_id.to_string();
-        let f = |(id, m): (&String, &Mutex<RevocationRegistry>)| -> Option<String> {
-            let rev_reg = m.lock().unwrap();
-            if rev_reg.get_cred_def_id() == cred_def_id {
-                Some(id.clone())
-            } else {
-                None
-            }
-        };
-        self.rev_regs.find_by(f)
+    pub async fn find_by_cred_def_id(&self, cred_def_id: &str) -> AgentResult<Vec<String>> {
+        let tags = HashMap::from([("cred_def_id".to_string(), cred_def_id.to_string())]);
+        self.rev_regs.find_by(&tags).await
+    }
+}
+
+impl StorageRecord for RevocationRegistry {
+    fn tags(&self) -> HashMap<String, String> {
+        HashMap::from([("cred_def_id".to_string(), self.get_cred_def_id())])
     }
 }
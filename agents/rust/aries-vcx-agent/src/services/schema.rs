@@ -1,35 +1,35 @@
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::error::*;
-use crate::storage::object_cache::ObjectCache;
-use crate::storage::Storage;
+use crate::storage::{new_storage, Storage, StorageKind, StorageRecord};
 use aries_vcx::common::primitives::credential_schema::Schema;
 use aries_vcx::core::profile::profile::Profile;
 
 pub struct ServiceSchemas {
     profile: Arc<dyn Profile>,
     issuer_did: String,
-    schemas: ObjectCache<Schema>,
+    schemas: Arc<dyn Storage<Schema>>,
 }
 
 impl ServiceSchemas {
-    pub fn new(profile: Arc<dyn Profile>, issuer_did: String) -> Self {
+    pub fn new(profile: Arc<dyn Profile>, issuer_did: String, storage: StorageKind) -> Self {
         Self {
+            schemas: new_storage(storage, &profile, "schemas"),
             profile,
             issuer_did,
-            schemas: ObjectCache::new("schemas"),
         }
     }
 
     pub async fn create_schema(&self, name: &str, version: &str, attributes: &Vec<String>) -> AgentResult<String> {
         let schema = Schema::create(&self.profile, "", &self.issuer_did, name, version, attributes).await?;
-        self.schemas.insert(&schema.get_schema_id(), schema)
+        self.schemas.insert(&schema.get_schema_id(), schema).await
     }
 
     pub async fn publish_schema(&self, thread_id: &str) -> AgentResult<()> {
-        let schema = self.schemas.get(thread_id)?;
+        let schema = self.schemas.get(thread_id).await?;
         let schema = schema.publish(&self.profile, None).await?;
-        self.schemas.insert(thread_id, schema)?;
+        self.schemas.insert(thread_id, schema).await?;
         Ok(())
     }
 
@@ -38,21 +38,24 @@ impl ServiceSchemas {
         Ok(ledger.get_schema(thread_id, None).await?)
     }
 
-    pub fn find_by_name_and_version(&self, name: &str, version: &str) -> AgentResult<Vec<String>> {
-        let name = name.to_string();
-        let version = version.to_string();
-        let f = |(id, m): (&String, &Mutex<Schema>)| -> Option<String> {
-            let schema = m.lock().unwrap();
-            if schema.name == name && schema.version == version {
-                Some(id.to_string())
-            } else {
-                None
-            }
-        };
-        self.schemas.find_by(f)
+    pub async fn find_by_name_and_version(&self, name: &str, version: &str) -> AgentResult<Vec<String>> {
+        let tags = HashMap::from([
+            ("name".to_string(), name.to_string()),
+            ("version".to_string(), version.to_string()),
+        ]);
+        self.schemas.find_by(&tags).await
     }
 
-    pub fn get_by_id(&self, thread_id: &str) -> AgentResult<Schema> {
-        self.schemas.get(thread_id)
+    pub async fn get_by_id(&self, thread_id: &str) -> AgentResult<Schema> {
+        self.schemas.get(thread_id).await
+    }
+}
+
+impl StorageRecord for Schema {
+    fn tags(&self) -> HashMap<String, String> {
+        HashMap::from([
+            ("name".to_string(), self.name.clone()),
+            ("version".to_string(), self.version.clone()),
+        ])
     }
 }
@@ -1,30 +1,34 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::error::*;
 use crate::http_client::HttpClient;
-use crate::storage::object_cache::ObjectCache;
-use crate::storage::Storage;
+use crate::storage::{new_storage, Storage, StorageKind, StorageRecord};
 use aries_vcx::common::proofs::proof_request::PresentationRequestData;
 use aries_vcx::core::profile::profile::Profile;
 use aries_vcx::handlers::proof_presentation::verifier::Verifier;
+use aries_vcx::handlers::proof_presentation::versioned_verifier::{PresentProofVersion, VersionedVerifier};
 use aries_vcx::messages::a2a::A2AMessage;
 use aries_vcx::messages::protocols::proof_presentation::presentation::Presentation;
 use aries_vcx::messages::protocols::proof_presentation::presentation_proposal::PresentationProposal;
 use aries_vcx::messages::status::Status;
+use aries_vcx::messages2::msg_fields::protocols::present_proof::v2::present::PresentationV2;
+use aries_vcx::messages2::AriesMessage;
 use aries_vcx::protocols::proof_presentation::verifier::state_machine::VerifierState;
 use aries_vcx::protocols::proof_presentation::verifier::verification_status::PresentationVerificationStatus;
 use aries_vcx::protocols::SendClosure;
+use serde::{Deserialize, Serialize};
 
 use super::connection::ServiceConnections;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct VerifierWrapper {
-    verifier: Verifier,
+    verifier: VersionedVerifier,
     connection_id: String,
 }
 
 impl VerifierWrapper {
-    pub fn new(verifier: Verifier, connection_id: &str) -> Self {
+    pub fn new(verifier: VersionedVerifier, connection_id: &str) -> Self {
         Self {
             verifier,
             connection_id: connection_id.to_string(),
@@ -32,18 +36,31 @@ impl VerifierWrapper {
     }
 }
 
+impl StorageRecord for VerifierWrapper {
+    fn tags(&self) -> HashMap<String, String> {
+        let mut tags = HashMap::from([
+            ("state".to_string(), format!("{:?}", self.verifier.get_state())),
+            ("connection_id".to_string(), self.connection_id.clone()),
+        ]);
+        if let Ok(thread_id) = self.verifier.get_thread_id() {
+            tags.insert("thread_id".to_string(), thread_id);
+        }
+        tags
+    }
+}
+
 pub struct ServiceVerifier {
     profile: Arc<dyn Profile>,
-    verifiers: ObjectCache<VerifierWrapper>,
+    verifiers: Arc<dyn Storage<VerifierWrapper>>,
     service_connections: Arc<ServiceConnections>,
 }
 
 impl ServiceVerifier {
-    pub fn new(profile: Arc<dyn Profile>, service_connections: Arc<ServiceConnections>) -> Self {
+    pub fn new(profile: Arc<dyn Profile>, service_connections: Arc<ServiceConnections>, storage: StorageKind) -> Self {
         Self {
+            verifiers: new_storage(storage, &profile, "verifiers"),
             profile,
             service_connections,
-            verifiers: ObjectCache::new("verifiers"),
         }
     }
 
@@ -53,13 +70,33 @@ impl ServiceVerifier {
         request: PresentationRequestData,
         proposal: Option<PresentationProposal>,
     ) -> AgentResult<String> {
-        let connection = self.service_connections.get_by_id(connection_id)?;
-        let mut verifier = if let Some(proposal) = proposal {
+        let verifier = if let Some(proposal) = proposal {
             Verifier::create_from_proposal("", &proposal)?
         } else {
             Verifier::create_from_request("".to_string(), &request)?
         };
+        self.send_versioned_proof_request(connection_id, VersionedVerifier::V1(verifier))
+            .await
+    }
+
+    /// Requests a presentation with the present proof protocol 2.0.
+    pub async fn send_proof_request_v2(
+        &self,
+        connection_id: &str,
+        request: PresentationRequestData,
+    ) -> AgentResult<String> {
+        let verifier =
+            VersionedVerifier::create_from_request(&self.profile, "".to_string(), &request, PresentProofVersion::V2_0)
+                .await?;
+        self.send_versioned_proof_request(connection_id, verifier).await
+    }
 
+    async fn send_versioned_proof_request(
+        &self,
+        connection_id: &str,
+        mut verifier: VersionedVerifier,
+    ) -> AgentResult<String> {
+        let connection = self.service_connections.get_by_id(connection_id).await?;
         let wallet = self.profile.inject_wallet();
 
         let send_closure: SendClosure = Box::new(|msg: A2AMessage| {
@@ -67,23 +104,36 @@ impl ServiceVerifier {
         });
 
         verifier.send_presentation_request(send_closure).await?;
-        self.verifiers.insert(
-            &verifier.get_thread_id()?,
-            VerifierWrapper::new(verifier, connection_id),
-        )
+        self.verifiers
+            .insert(
+                &verifier.get_thread_id()?,
+                VerifierWrapper::new(verifier, connection_id),
+            )
+            .await
     }
 
-    pub fn get_presentation_status(&self, thread_id: &str) -> AgentResult<PresentationVerificationStatus> {
-        let VerifierWrapper { verifier, .. } = self.verifiers.get(thread_id)?;
+    pub async fn get_presentation_status(&self, thread_id: &str) -> AgentResult<PresentationVerificationStatus> {
+        let VerifierWrapper { verifier, .. } = self.verifiers.get(thread_id).await?;
         Ok(verifier.get_verification_status())
     }
 
     pub async fn verify_presentation(&self, thread_id: &str, presentation: Presentation) -> AgentResult<()> {
+        self.verify_versioned_presentation(thread_id, A2AMessage::Presentation(presentation))
+            .await
+    }
+
+    pub async fn verify_presentation_v2(&self, thread_id: &str, presentation: PresentationV2) -> AgentResult<()> {
+        let presentation = serde_json::to_value(AriesMessage::from(presentation))?;
+        self.verify_versioned_presentation(thread_id, A2AMessage::Generic(presentation))
+            .await
+    }
+
+    async fn verify_versioned_presentation(&self, thread_id: &str, presentation: A2AMessage) -> AgentResult<()> {
         let VerifierWrapper {
             mut verifier,
             connection_id,
-        } = self.verifiers.get(thread_id)?;
-        let connection = self.service_connections.get_by_id(&connection_id)?;
+        } = self.verifiers.get(thread_id).await?;
+        let connection = self.service_connections.get_by_id(&connection_id).await?;
         let wallet = self.profile.inject_wallet();
 
         let send_closure: SendClosure = Box::new(|msg: A2AMessage| {
@@ -94,16 +144,17 @@ impl ServiceVerifier {
             .verify_presentation(&self.profile, presentation, send_closure)
             .await?;
         self.verifiers
-            .insert(thread_id, VerifierWrapper::new(verifier, &connection_id))?;
+            .insert(thread_id, VerifierWrapper::new(verifier, &connection_id))
+            .await?;
         Ok(())
     }
 
-    pub fn get_state(&self, thread_id: &str) -> AgentResult<VerifierState> {
-        let VerifierWrapper { verifier, .. } = self.verifiers.get(thread_id)?;
+    pub async fn get_state(&self, thread_id: &str) -> AgentResult<VerifierState> {
+        let VerifierWrapper { verifier, .. } = self.verifiers.get(thread_id).await?;
         Ok(verifier.get_state())
     }
 
-    pub fn exists_by_id(&self, thread_id: &str) -> bool {
-        self.verifiers.contains_key(thread_id)
+    pub async fn exists_by_id(&self, thread_id: &str) -> bool {
+        self.verifiers.contains_key(thread_id).await
     }
 }
@@ -1,13 +1,54 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aries_vcx::core::profile::profile::Profile;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::storage::object_cache::ObjectCache;
+use crate::storage::wallet_storage::WalletStorage;
 use crate::AgentResult;
 
 pub(crate) mod object_cache;
+pub(crate) mod wallet_storage;
+
+#[async_trait]
+pub trait Storage<T>: Send + Sync {
+    async fn get(&self, id: &str) -> AgentResult<T>;
+    async fn insert(&self, id: &str, obj: T) -> AgentResult<String>;
+    async fn contains_key(&self, id: &str) -> bool;
+    async fn remove(&self, id: &str) -> AgentResult<()>;
+    /// Ids of the objects whose [`StorageRecord::tags`] contain every given tag.
+    async fn find_by(&self, tags: &HashMap<String, String>) -> AgentResult<Vec<String>>;
+    /// Ids and objects whose [`StorageRecord::tags`] contain every given tag, fetched in a single query.
+    async fn find_records_by(&self, tags: &HashMap<String, String>) -> AgentResult<Vec<(String, T)>>;
+}
+
+/// Tags attached to an object when it is persisted as a wallet record, so that records
+/// can be looked up without deserializing them.
+pub trait StorageRecord {
+    fn tags(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+}
+
+/// Selects where services keep their objects.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum StorageKind {
+    /// Objects live in process memory and are lost when the agent stops.
+    #[default]
+    InMemory,
+    /// Objects are serialized into the profile's wallet and survive restarts.
+    Wallet,
+}
 
-pub trait Storage<T> {
-    type Value;
-    fn get(&self, id: &str) -> AgentResult<T>;
-    fn insert(&self, id: &str, obj: T) -> AgentResult<String>;
-    fn contains_key(&self, id: &str) -> bool;
-    fn find_by<F>(&self, closure: F) -> AgentResult<Vec<String>>
-    where
-        F: FnMut((&String, &Self::Value)) -> Option<String>;
+pub(crate) fn new_storage<T>(kind: StorageKind, profile: &Arc<dyn Profile>, name: &str) -> Arc<dyn Storage<T>>
+where
+    T: Clone + Serialize + DeserializeOwned + StorageRecord + Send + Sync + 'static,
+{
+    match kind {
+        StorageKind::InMemory => Arc::new(ObjectCache::new(name)),
+        StorageKind::Wallet => Arc::new(WalletStorage::new(profile.inject_wallet(), name)),
+    }
 }
@@ -2,9 +2,11 @@ use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
+use async_trait::async_trait;
+
 use crate::error::*;
 
-use super::Storage;
+use super::{Storage, StorageRecord};
 
 pub struct ObjectCache<T>
 where
@@ -58,13 +60,12 @@ where
     }
 }
 
+#[async_trait]
 impl<T> Storage<T> for ObjectCache<T>
 where
-    T: Clone,
+    T: Clone + StorageRecord + Send + Sync,
 {
-    type Value = Mutex<T>;
-
-    fn get(&self, id: &str) -> AgentResult<T> {
+    async fn get(&self, id: &str) -> AgentResult<T> {
         let store = self._lock_store_read()?;
         match store.get(id) {
             Some(m) => match m.lock() {
@@ -81,7 +82,7 @@ where
         }
     }
 
-    fn insert(&self, id: &str, obj: T) -> AgentResult<String> {
+    async fn insert(&self, id: &str, obj: T) -> AgentResult<String> {
         let mut store = self._lock_store_write()?;
 
         match store.insert(id.to_string(), Mutex::new(obj)) {
@@ -90,7 +91,7 @@ where
         }
     }
 
-    fn contains_key(&self, id: &str) -> bool {
+    async fn contains_key(&self, id: &str) -> bool {
         let store = match self._lock_store_read() {
             Ok(g) => g,
             Err(_) => return false,
@@ -98,11 +99,39 @@ where
         store.contains_key(id)
     }
 
-    fn find_by<F>(&self, closure: F) -> AgentResult<Vec<String>>
-    where
-        F: FnMut((&String, &Self::Value)) -> Option<String>,
-    {
+    async fn remove(&self, id: &str) -> AgentResult<()> {
+        let mut store = self._lock_store_write()?;
+        match store.remove(id) {
+            Some(_) => Ok(()),
+            None => Err(AgentError::from_msg(
+                AgentErrorKind::NotFound,
+                &format!("[ObjectCache: {}] Object not found for id: {}", self.cache_name, id),
+            )),
+        }
+    }
+
+    async fn find_by(&self, tags: &HashMap<String, String>) -> AgentResult<Vec<String>> {
+        Ok(self
+            .find_records_by(tags)
+            .await?
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect())
+    }
+
+    async fn find_records_by(&self, tags: &HashMap<String, String>) -> AgentResult<Vec<(String, T)>> {
         let store = self._lock_store_read()?;
-        Ok(store.iter().filter_map(closure).collect())
+        Ok(store
+            .iter()
+            .filter_map(|(id, m)| match m.lock() {
+                Ok(obj) => {
+                    let obj_tags = obj.tags();
+                    tags.iter()
+                        .all(|(name, value)| obj_tags.get(name) == Some(value))
+                        .then(|| (id.to_string(), (*obj.deref()).clone()))
+                }
+                _ => None,
+            })
+            .collect())
     }
 }
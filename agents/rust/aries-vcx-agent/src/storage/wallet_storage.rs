@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use aries_vcx::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use aries_vcx::plugins::wallet::base_wallet::BaseWallet;
+use aries_vcx::utils::async_fn_iterator::AsyncFnIterator;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::*;
+
+use super::{Storage, StorageRecord};
+
+const RECORD_TYPE_PREFIX: &str = "aries-vcx-agent";
+
+/// Persists objects as typed records in a wallet. Every object is stored as its JSON
+/// serialization under a record type derived from the storage name, tagged with
+/// the values returned by [`StorageRecord::tags`].
+pub struct WalletStorage<T> {
+    wallet: Arc<dyn BaseWallet>,
+    record_type: String,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> WalletStorage<T> {
+    pub fn new(wallet: Arc<dyn BaseWallet>, storage_name: &str) -> Self {
+        Self {
+            wallet,
+            record_type: format!("{}:{}", RECORD_TYPE_PREFIX, storage_name),
+            _marker: PhantomData,
+        }
+    }
+
+    fn map_wallet_error(&self, id: &str, err: AriesVcxError) -> AgentError {
+        match err.kind() {
+            AriesVcxErrorKind::WalletRecordNotFound => AgentError::from_msg(
+                AgentErrorKind::NotFound,
+                &format!("[WalletStorage: {}] Object not found for id: {}", self.record_type, id),
+            ),
+            _ => err.into(),
+        }
+    }
+}
+
+impl<T> WalletStorage<T>
+where
+    T: DeserializeOwned,
+{
+    fn parse_record(&self, record: &str) -> AgentResult<(String, T)> {
+        let record: Value = serde_json::from_str(record)?;
+        let id = record["id"].as_str().ok_or_else(|| {
+            AgentError::from_msg(
+                AgentErrorKind::SerializationError,
+                &format!("[WalletStorage: {}] Record is missing id: {}", self.record_type, record),
+            )
+        })?;
+        let value = record["value"].as_str().ok_or_else(|| {
+            AgentError::from_msg(
+                AgentErrorKind::SerializationError,
+                &format!(
+                    "[WalletStorage: {}] Record is missing value: {}",
+                    self.record_type, record
+                ),
+            )
+        })?;
+        Ok((id.to_string(), serde_json::from_str(value)?))
+    }
+}
+
+impl<T> WalletStorage<T> {
+    async fn search_records(
+        &self,
+        tags: &HashMap<String, String>,
+        retrieve_value: bool,
+    ) -> AgentResult<Box<dyn AsyncFnIterator<Item = VcxResult<String>>>> {
+        let query = serde_json::to_string(tags)?;
+        let options = json!({
+            "retrieveRecords": true,
+            "retrieveTotalCount": false,
+            "retrieveType": false,
+            "retrieveValue": retrieve_value,
+            "retrieveTags": false
+        })
+        .to_string();
+        Ok(self
+            .wallet
+            .iterate_wallet_records(&self.record_type, &query, &options)
+            .await?)
+    }
+}
+
+#[async_trait]
+impl<T> Storage<T> for WalletStorage<T>
+where
+    T: Serialize + DeserializeOwned + StorageRecord + Send + Sync,
+{
+    async fn get(&self, id: &str) -> AgentResult<T> {
+        let options = json!({
+            "retrieveType": false,
+            "retrieveValue": true,
+            "retrieveTags": false
+        })
+        .to_string();
+        let record = self
+            .wallet
+            .get_wallet_record(&self.record_type, id, &options)
+            .await
+            .map_err(|err| self.map_wallet_error(id, err))?;
+        let (_, obj) = self.parse_record(&record)?;
+        Ok(obj)
+    }
+
+    async fn insert(&self, id: &str, obj: T) -> AgentResult<String> {
+        let value = serde_json::to_string(&obj)?;
+        let tags = serde_json::to_string(&obj.tags())?;
+        if self.contains_key(id).await {
+            self.wallet
+                .update_wallet_record_value(&self.record_type, id, &value)
+                .await?;
+            self.wallet
+                .update_wallet_record_tags(&self.record_type, id, &tags)
+                .await?;
+        } else {
+            self.wallet
+                .add_wallet_record(&self.record_type, id, &value, Some(&tags))
+                .await?;
+        }
+        Ok(id.to_string())
+    }
+
+    async fn contains_key(&self, id: &str) -> bool {
+        let options = json!({
+            "retrieveType": false,
+            "retrieveValue": false,
+            "retrieveTags": false
+        })
+        .to_string();
+        self.wallet
+            .get_wallet_record(&self.record_type, id, &options)
+            .await
+            .is_ok()
+    }
+
+    async fn remove(&self, id: &str) -> AgentResult<()> {
+        self.wallet
+            .delete_wallet_record(&self.record_type, id)
+            .await
+            .map_err(|err| self.map_wallet_error(id, err))
+    }
+
+    async fn find_by(&self, tags: &HashMap<String, String>) -> AgentResult<Vec<String>> {
+        let mut records = self.search_records(tags, false).await?;
+
+        let mut ids = Vec::new();
+        while let Some(record) = records.next().await {
+            let record: Value = serde_json::from_str(&record?)?;
+            let id = record["id"].as_str().ok_or_else(|| {
+                AgentError::from_msg(
+                    AgentErrorKind::SerializationError,
+                    &format!("[WalletStorage: {}] Record is missing id: {}", self.record_type, record),
+                )
+            })?;
+            ids.push(id.to_string());
+        }
+        Ok(ids)
+    }
+
+    async fn find_records_by(&self, tags: &HashMap<String, String>) -> AgentResult<Vec<(String, T)>> {
+        let mut records = self.search_records(tags, true).await?;
+
+        let mut found = Vec::new();
+        while let Some(record) = records.next().await {
+            found.push(self.parse_record(&record?)?);
+        }
+        Ok(found)
+    }
+}
@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+use messages::diddoc::w3c::{
+    diddoc::W3cDidDoc,
+    verification_method::{PublicKeyField, VerificationMethod, VerificationMethodKind, VerificationMethodType},
+};
+
+use super::DidResolver;
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+pub(crate) const DID_KEY_PREFIX: &str = "did:key:";
+const ED25519_MULTIBASE_CODEC: [u8; 2] = [0xed, 0x01];
+
+/// Resolves [did:key](https://w3c-ccg.github.io/did-method-key/) DIDs. Only Ed25519 keys are supported.
+pub struct DidKeyResolver;
+
+#[async_trait]
+impl DidResolver for DidKeyResolver {
+    async fn resolve(&self, did: &str) -> VcxResult<W3cDidDoc> {
+        let fingerprint = did
+            .strip_prefix(DID_KEY_PREFIX)
+            .ok_or_else(|| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidDid, format!("Not a did:key: {}", did)))?;
+        let public_key_base58 = ed25519_fingerprint_to_public_key(fingerprint, did)?;
+        Ok(ed25519_did_doc(did, fingerprint, public_key_base58))
+    }
+}
+
+/// Builds the DID document of a DID consisting of a single Ed25519 key, as done by did:key
+/// and did:peer with numalgo 0. The key is referenced by its multibase `fingerprint`.
+pub(crate) fn ed25519_did_doc(did: &str, fingerprint: &str, public_key_base58: String) -> W3cDidDoc {
+    let key_id = format!("{}#{}", did, fingerprint);
+    let mut did_doc = W3cDidDoc::new(did.to_string());
+    did_doc.verification_method.push(VerificationMethod::new(
+        key_id.clone(),
+        VerificationMethodType::Ed25519VerificationKey2018,
+        did.to_string(),
+        PublicKeyField::Base58 { public_key_base58 },
+    ));
+    did_doc.authentication.push(VerificationMethodKind::Resolvable(key_id));
+    did_doc
+}
+
+/// Decodes a multibase (base58btc) encoded, multicodec prefixed Ed25519 key into a base58 verkey.
+pub(crate) fn ed25519_fingerprint_to_public_key(fingerprint: &str, did: &str) -> VcxResult<String> {
+    let stripped = if let Some(stripped) = fingerprint.strip_prefix('z') {
+        stripped
+    } else {
+        Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidDid,
+            format!("z prefix is missing: {}", did),
+        ))?
+    };
+    let decoded_value = bs58::decode(stripped).into_vec().map_err(|_| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidDid,
+            format!("Could not decode base58: {} as portion of {}", stripped, did),
+        )
+    })?;
+    if let Some(public_key_bytes) = decoded_value.strip_prefix(&ED25519_MULTIBASE_CODEC) {
+        Ok(bs58::encode(public_key_bytes).into_string())
+    } else {
+        Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidDid,
+            format!("Only Ed25519-based did:keys are currently supported, got key: {}", did),
+        ))
+    }
+}
+
+pub(crate) fn ed25519_public_key_to_did_key(public_key_base58: &str) -> VcxResult<String> {
+    let public_key_bytes = bs58::decode(public_key_base58).into_vec().map_err(|_| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidDid,
+            format!("Could not base58 decode a did:key fingerprint: {}", public_key_base58),
+        )
+    })?;
+    let mut did_key_bytes = ED25519_MULTIBASE_CODEC.to_vec();
+    did_key_bytes.extend_from_slice(&public_key_bytes);
+    let did_key_bytes_bs58 = bs58::encode(&did_key_bytes).into_string();
+    let did_key = format!("{DID_KEY_PREFIX}z{did_key_bytes_bs58}");
+    Ok(did_key)
+}
+
+/// Replaces did:key encoded keys by the base58 verkeys they encode, leaving other keys untouched.
+pub(crate) fn normalize_keys_as_naked(keys_list: Vec<String>) -> VcxResult<Vec<String>> {
+    let mut result = Vec::new();
+    for key in keys_list {
+        if let Some(fingerprint) = key.strip_prefix(DID_KEY_PREFIX) {
+            // did:key recipient keys can also reference the verification method of the DID document
+            let fingerprint = fingerprint.split('#').next().unwrap_or(fingerprint);
+            result.push(ed25519_fingerprint_to_public_key(fingerprint, &key)?);
+        } else {
+            result.push(key);
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve_did_key() {
+        let did = "did:key:z6MkwHgArrRJq3tTdhQZKVAa1sdFgSAs5P5N1C4RJcD11Ycv";
+        let did_doc = DidKeyResolver.resolve(did).await.unwrap();
+
+        assert_eq!(did_doc.id, did);
+        assert_eq!(
+            did_doc.verification_method[0].id,
+            "did:key:z6MkwHgArrRJq3tTdhQZKVAa1sdFgSAs5P5N1C4RJcD11Ycv#z6MkwHgArrRJq3tTdhQZKVAa1sdFgSAs5P5N1C4RJcD11Ycv"
+        );
+        assert_eq!(
+            did_doc.verification_method[0].public_key_base58().unwrap(),
+            "HqR8GcAsVWPzXCZrdvCjAn5Frru1fVq1KB9VULEz6KqY"
+        );
+        assert_eq!(did_doc.authentication[0].id(), did_doc.verification_method[0].id);
+        assert!(did_doc.service.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_did_key_rejects_invalid_keys() {
+        let err = DidKeyResolver.resolve("did:key:zInvalid").await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidDid);
+
+        let err = DidKeyResolver
+            .resolve("did:sov:2wJPyULfLLnYTEFYzByfUR")
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidDid);
+    }
+}
@@ -0,0 +1,258 @@
+//! Resolution of DIDs into W3C DID documents.
+//!
+//! Resolvers implement [`DidResolver`] for one or more DID methods and get registered
+//! into a [`DidResolverRegistry`], which dispatches every DID to the resolver of its method.
+
+pub mod key;
+pub mod peer;
+pub mod sov;
+
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use async_trait::async_trait;
+use messages::diddoc::{
+    aries::{diddoc::AriesDidDoc, service::AriesService},
+    w3c::{diddoc::W3cDidDoc, service::DidDocService},
+};
+
+use self::{
+    key::{normalize_keys_as_naked, DidKeyResolver, DID_KEY_PREFIX},
+    peer::DidPeerResolver,
+    sov::DidSovResolver,
+};
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    plugins::ledger::base_ledger::BaseLedger,
+};
+
+/// Method assumed for unqualified DIDs, which are legacy Indy DIDs.
+pub const UNQUALIFIED_DID_METHOD: &str = "sov";
+
+#[async_trait]
+pub trait DidResolver: Send + Sync {
+    /// Resolves a DID of a method the resolver got registered for into its DID document.
+    async fn resolve(&self, did: &str) -> VcxResult<W3cDidDoc>;
+}
+
+/// Dispatches DIDs to the [`DidResolver`] registered for their method.
+#[derive(Default, Clone)]
+pub struct DidResolverRegistry {
+    resolvers: HashMap<String, Arc<dyn DidResolver>>,
+}
+
+impl fmt::Debug for DidResolverRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DidResolverRegistry")
+            .field("methods", &self.resolvers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl DidResolverRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry able to resolve did:sov (and unqualified DIDs) through `ledger`,
+    /// did:key and did:peer. Profiles build one such registry, available through
+    /// [`crate::core::profile::profile::Profile::inject_did_resolver`].
+    pub fn with_default_resolvers(ledger: Arc<dyn BaseLedger>) -> Self {
+        Self::new()
+            .register_resolver("sov", Arc::new(DidSovResolver::new(ledger)))
+            .register_resolver("key", Arc::new(DidKeyResolver))
+            .register_resolver("peer", Arc::new(DidPeerResolver))
+    }
+
+    /// Registers `resolver` for DIDs of `method`, replacing the resolver previously registered for it.
+    pub fn register_resolver(mut self, method: &str, resolver: Arc<dyn DidResolver>) -> Self {
+        self.resolvers.insert(method.to_string(), resolver);
+        self
+    }
+
+    pub async fn resolve(&self, did: &str) -> VcxResult<W3cDidDoc> {
+        let method = did_method(did).unwrap_or(UNQUALIFIED_DID_METHOD);
+        let resolver = self.resolvers.get(method).ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::ActionNotSupported,
+                format!("No resolver registered for DID method \"{}\", DID: {}", method, did),
+            )
+        })?;
+        resolver.resolve(did).await
+    }
+
+    /// Resolves `did` and returns the first DIDComm service of its DID document, with recipient
+    /// keys referencing verification methods replaced by the base58 keys they reference.
+    pub async fn resolve_service(&self, did: &str) -> VcxResult<AriesService> {
+        let did_doc = self.resolve(did).await?;
+        didcomm_service(&did_doc)
+    }
+
+    /// Resolves `did` into an [`AriesDidDoc`] built from its first DIDComm service, with all keys
+    /// given as base58 verkeys, so that peers identified by fully qualified DIDs can be messaged
+    /// just like peers which shared their [`AriesDidDoc`].
+    pub async fn resolve_aries_did_doc(&self, did: &str) -> VcxResult<AriesDidDoc> {
+        let service = self.resolve_service(did).await?;
+
+        let mut did_doc = AriesDidDoc::default();
+        did_doc.set_id(did.to_string());
+        did_doc.set_service_endpoint(service.service_endpoint);
+        did_doc.set_recipient_keys(normalize_keys_as_naked(service.recipient_keys)?);
+        did_doc.set_routing_keys(normalize_keys_as_naked(service.routing_keys)?);
+        Ok(did_doc)
+    }
+}
+
+/// Returns the method of a qualified DID, e.g. `sov` for `did:sov:123`, or `None` for unqualified DIDs.
+pub fn did_method(did: &str) -> Option<&str> {
+    let mut parts = did.splitn(3, ':');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some("did"), Some(method), Some(_)) => Some(method),
+        _ => None,
+    }
+}
+
+/// Strips the `did:<method>:` prefix off a DID, leaving unqualified DIDs as they are.
+pub(crate) fn method_specific_id(did: &str) -> &str {
+    match did_method(did) {
+        Some(method) => &did[("did:".len() + method.len() + 1)..],
+        None => did,
+    }
+}
+
+fn didcomm_service(did_doc: &W3cDidDoc) -> VcxResult<AriesService> {
+    let service = did_doc
+        .service
+        .iter()
+        .min_by_key(|service| service.priority)
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidDid,
+                format!("DID document of {} does not contain any service", did_doc.id),
+            )
+        })?;
+    // DIDComm v2 services do not list recipient keys, the authentication keys are used instead.
+    let recipient_keys = if service.recipient_keys.is_empty() {
+        did_doc
+            .authentication
+            .iter()
+            .map(|authentication| authentication.id().to_string())
+            .collect()
+    } else {
+        service.recipient_keys.clone()
+    };
+    let recipient_keys = recipient_keys
+        .iter()
+        .map(|key| dereference_recipient_key(did_doc, service, key))
+        .collect::<VcxResult<Vec<_>>>()?;
+
+    Ok(AriesService {
+        id: service.id.clone(),
+        type_: service.type_.clone(),
+        priority: service.priority,
+        recipient_keys,
+        routing_keys: service.routing_keys.clone(),
+        service_endpoint: service.service_endpoint.clone(),
+    })
+}
+
+fn dereference_recipient_key(did_doc: &W3cDidDoc, service: &DidDocService, key: &str) -> VcxResult<String> {
+    if !key.contains('#') || key.starts_with(DID_KEY_PREFIX) {
+        return Ok(key.to_string());
+    }
+    did_doc
+        .dereference_key(key)
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidDid,
+                format!(
+                    "Recipient key {} of service {} is not present in the DID document of {}",
+                    key, service.id, did_doc.id
+                ),
+            )
+        })?
+        .public_key_base58()
+        .map_err(AriesVcxError::from)
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use super::*;
+
+    struct MockResolver;
+
+    #[async_trait]
+    impl DidResolver for MockResolver {
+        async fn resolve(&self, did: &str) -> VcxResult<W3cDidDoc> {
+            Ok(W3cDidDoc::new(did.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_did_method() {
+        assert_eq!(did_method("did:sov:2wJPyULfLLnYTEFYzByfUR"), Some("sov"));
+        assert_eq!(
+            did_method("did:peer:0z6MkpTHR8VNsBxYAAWHut2Geadd9jSwuBV8xRoAnwWsdvktH"),
+            Some("peer")
+        );
+        assert_eq!(did_method("2wJPyULfLLnYTEFYzByfUR"), None);
+        assert_eq!(did_method("did:sov"), None);
+        assert_eq!(
+            method_specific_id("did:sov:2wJPyULfLLnYTEFYzByfUR"),
+            "2wJPyULfLLnYTEFYzByfUR"
+        );
+        assert_eq!(method_specific_id("2wJPyULfLLnYTEFYzByfUR"), "2wJPyULfLLnYTEFYzByfUR");
+    }
+
+    #[tokio::test]
+    async fn test_registry_dispatches_on_method() {
+        let registry = DidResolverRegistry::new().register_resolver("example", Arc::new(MockResolver));
+
+        let did_doc = registry.resolve("did:example:123").await.unwrap();
+        assert_eq!(did_doc.id, "did:example:123");
+
+        let err = registry.resolve("did:unknown:123").await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::ActionNotSupported);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_service_dereferences_recipient_keys() {
+        struct PeerWithServiceResolver;
+
+        #[async_trait]
+        impl DidResolver for PeerWithServiceResolver {
+            async fn resolve(&self, did: &str) -> VcxResult<W3cDidDoc> {
+                let mut did_doc = DidPeerResolver.resolve(did).await?;
+                did_doc.service.push(DidDocService {
+                    id: format!("{}#didcomm", did),
+                    type_: "did-communication".to_string(),
+                    service_endpoint: "http://example.org/agent".to_string(),
+                    priority: 0,
+                    recipient_keys: vec![did_doc.verification_method[0].id.clone()],
+                    routing_keys: vec![],
+                });
+                Ok(did_doc)
+            }
+        }
+
+        let registry = DidResolverRegistry::new()
+            .register_resolver("key", Arc::new(DidKeyResolver))
+            .register_resolver("peer", Arc::new(PeerWithServiceResolver));
+
+        let service = registry
+            .resolve_service("did:peer:0z6MkwHgArrRJq3tTdhQZKVAa1sdFgSAs5P5N1C4RJcD11Ycv")
+            .await
+            .unwrap();
+        assert_eq!(service.service_endpoint, "http://example.org/agent");
+        assert_eq!(
+            service.recipient_keys,
+            vec!["HqR8GcAsVWPzXCZrdvCjAn5Frru1fVq1KB9VULEz6KqY".to_string()]
+        );
+
+        let err = registry
+            .resolve_service("did:key:z6MkwHgArrRJq3tTdhQZKVAa1sdFgSAs5P5N1C4RJcD11Ycv")
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidDid);
+    }
+}
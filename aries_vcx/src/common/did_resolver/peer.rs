@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use messages::diddoc::{peer::resolve_peer_did, w3c::diddoc::W3cDidDoc};
+
+use super::DidResolver;
+use crate::errors::error::VcxResult;
+
+/// Resolves [did:peer](https://identity.foundation/peer-did-method-spec/) DIDs of numalgo 0 and 2,
+/// which are expanded into DID documents without any network access.
+pub struct DidPeerResolver;
+
+#[async_trait]
+impl DidResolver for DidPeerResolver {
+    async fn resolve(&self, did: &str) -> VcxResult<W3cDidDoc> {
+        Ok(resolve_peer_did(did)?)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use messages::diddoc::{
+        aries::diddoc::test_utils::{_key_1, _routing_keys, _service_endpoint},
+        peer::generate_numalgo2,
+    };
+
+    use super::*;
+    use crate::{common::did_resolver::DidResolverRegistry, errors::error::AriesVcxErrorKind};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_resolve_did_peer_numalgo_0() {
+        let did = "did:peer:0z6MkwHgArrRJq3tTdhQZKVAa1sdFgSAs5P5N1C4RJcD11Ycv";
+        let did_doc = DidPeerResolver.resolve(did).await.unwrap();
+
+        assert_eq!(did_doc.id, did);
+        assert_eq!(
+            did_doc.verification_method[0].public_key_base58().unwrap(),
+            "HqR8GcAsVWPzXCZrdvCjAn5Frru1fVq1KB9VULEz6KqY"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_did_peer_numalgo_2_into_aries_did_doc() {
+        let did = generate_numalgo2(&_key_1(), &_service_endpoint(), &_routing_keys()).unwrap();
+        let registry = DidResolverRegistry::new().register_resolver("peer", Arc::new(DidPeerResolver));
+
+        let did_doc = registry.resolve_aries_did_doc(&did).await.unwrap();
+        assert_eq!(did_doc.id, did);
+        assert_eq!(did_doc.get_endpoint(), _service_endpoint());
+        assert_eq!(did_doc.recipient_keys().unwrap(), vec![_key_1()]);
+        assert_eq!(did_doc.routing_keys(), _routing_keys());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_did_peer_unsupported_numalgo() {
+        let err = DidPeerResolver
+            .resolve("did:peer:1zQmZMygzYqNwU6Uhmewx5Xepf2VLp5S4HLSwwgf2aiKZuwa")
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidDid);
+    }
+}
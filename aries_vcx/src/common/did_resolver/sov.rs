@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use messages::diddoc::{
+    aries::service::AriesService,
+    w3c::{
+        diddoc::W3cDidDoc,
+        service::{DidDocService, SERVICE_TYPE_DID_COMMUNICATION},
+        verification_method::{PublicKeyField, VerificationMethod, VerificationMethodKind, VerificationMethodType},
+    },
+};
+
+use super::{method_specific_id, DidResolver};
+use crate::{
+    common::{
+        keys::parse_verkey_from_nym_response,
+        ledger::{service_didsov::EndpointDidSov, transactions::get_data_from_response},
+    },
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    plugins::ledger::base_ledger::BaseLedger,
+};
+
+/// Resolves did:sov DIDs, as well as unqualified Indy DIDs, from the `NYM` and `ATTRIB`
+/// transactions of an Indy ledger.
+///
+/// The service is read from the `endpoint` attribute, falling back to the legacy `service`
+/// attribute holding a serialized [`AriesService`].
+pub struct DidSovResolver {
+    ledger: Arc<dyn BaseLedger>,
+}
+
+impl DidSovResolver {
+    pub fn new(ledger: Arc<dyn BaseLedger>) -> Self {
+        Self { ledger }
+    }
+
+    pub(crate) async fn get_legacy_service(&self, did_raw: &str) -> VcxResult<AriesService> {
+        let attr_resp = self.ledger.get_attr(did_raw, "service").await?;
+        let data = get_data_from_response(&attr_resp)?;
+        let ser_service = match data["service"].as_str() {
+            Some(ser_service) => ser_service.to_string(),
+            None => {
+                warn!("Failed converting service read from ledger {:?} to string, falling back to new single-serialized format", data["service"]);
+                data["service"].to_string()
+            }
+        };
+        serde_json::from_str(&ser_service).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::SerializationError,
+                format!("Failed to deserialize service read from the ledger: {:?}", err),
+            )
+        })
+    }
+}
+
+#[async_trait]
+impl DidResolver for DidSovResolver {
+    async fn resolve(&self, did: &str) -> VcxResult<W3cDidDoc> {
+        let did_raw = method_specific_id(did);
+        let did_qualified = format!("did:sov:{}", did_raw);
+        let mut did_doc = W3cDidDoc::new(did_qualified.clone());
+
+        let attr_resp = self.ledger.get_attr(did_raw, "endpoint").await?;
+        let data = get_data_from_response(&attr_resp)?;
+        if data["endpoint"].is_object() {
+            let endpoint: EndpointDidSov = serde_json::from_value(data["endpoint"].clone())?;
+            let verkey = parse_verkey_from_nym_response(&self.ledger.get_nym(did_raw).await?)?;
+            let key_id = format!("{}#1", did_qualified);
+
+            did_doc.verification_method.push(VerificationMethod::new(
+                key_id.clone(),
+                VerificationMethodType::Ed25519VerificationKey2018,
+                did_qualified.clone(),
+                PublicKeyField::Base58 {
+                    public_key_base58: verkey,
+                },
+            ));
+            did_doc
+                .authentication
+                .push(VerificationMethodKind::Resolvable(key_id.clone()));
+            did_doc.service.push(DidDocService {
+                id: format!("{}#did-communication", did_qualified),
+                type_: String::from(SERVICE_TYPE_DID_COMMUNICATION),
+                service_endpoint: endpoint.endpoint,
+                priority: 0,
+                recipient_keys: vec![key_id],
+                routing_keys: endpoint.routing_keys.unwrap_or_default(),
+            });
+        } else {
+            let service = self.get_legacy_service(did_raw).await?;
+            did_doc.service.push(DidDocService {
+                id: service.id,
+                type_: service.type_,
+                service_endpoint: service.service_endpoint,
+                priority: service.priority,
+                recipient_keys: service.recipient_keys,
+                routing_keys: service.routing_keys,
+            });
+        }
+        Ok(did_doc)
+    }
+}
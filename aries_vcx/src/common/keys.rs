@@ -44,7 +44,11 @@ pub async fn get_verkey_from_ledger(profile: &Arc<dyn Profile>, did: &str) -> Vc
     let ledger = Arc::clone(profile).inject_ledger();
 
     let nym_response: String = ledger.get_nym(did).await?;
-    let nym_json: Value = serde_json::from_str(&nym_response).map_err(|err| {
+    parse_verkey_from_nym_response(&nym_response)
+}
+
+pub(crate) fn parse_verkey_from_nym_response(nym_response: &str) -> VcxResult<String> {
+    let nym_json: Value = serde_json::from_str(nym_response).map_err(|err| {
         AriesVcxError::from_msg(
             AriesVcxErrorKind::SerializationError,
             format!("Cannot deserialize {:?} into Value, err: {:?}", nym_response, err),
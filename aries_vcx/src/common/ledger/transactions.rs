@@ -1,6 +1,7 @@
-use bs58;
 use std::{collections::HashMap, sync::Arc};
 
+use crate::common::did_resolver::key::normalize_keys_as_naked;
+use crate::common::did_resolver::sov::DidSovResolver;
 use crate::common::ledger::service_didsov::EndpointDidSov;
 use messages::diddoc::aries::diddoc::AriesDidDoc;
 use messages::diddoc::aries::service::AriesService;
@@ -10,7 +11,7 @@ use messages::protocols::out_of_band::service_oob::ServiceOob;
 use serde_json::Value;
 
 use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
-use crate::{common::keys::get_verkey_from_ledger, core::profile::profile::Profile, global::settings};
+use crate::{core::profile::profile::Profile, global::settings};
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -61,13 +62,15 @@ pub struct ReplyDataV1 {
     pub result: serde_json::Value,
 }
 
-const DID_KEY_PREFIX: &str = "did:key:";
-const ED25519_MULTIBASE_CODEC: [u8; 2] = [0xed, 0x01];
-
 pub async fn resolve_service(profile: &Arc<dyn Profile>, service: &ServiceOob) -> VcxResult<AriesService> {
     match service {
         ServiceOob::AriesService(service) => Ok(service.clone()),
-        ServiceOob::Did(did) => get_service(profile, did).await,
+        ServiceOob::Did(did) => {
+            Arc::clone(profile)
+                .inject_did_resolver()
+                .resolve_service(&did.to_string())
+                .await
+        }
     }
 }
 
@@ -118,7 +121,11 @@ pub async fn into_did_doc(profile: &Arc<dyn Profile>, invitation: &Invitation) -
                 error!("Is not did valid: {}", err);
                 Vec::new()
             });
-            (service.service_endpoint, recipient_keys, service.routing_keys)
+            let routing_keys = normalize_keys_as_naked(service.routing_keys).unwrap_or_else(|err| {
+                error!("Is not did valid: {}", err);
+                Vec::new()
+            });
+            (service.service_endpoint, recipient_keys, routing_keys)
         }
     };
     did_doc.set_service_endpoint(service_endpoint);
@@ -127,91 +134,17 @@ pub async fn into_did_doc(profile: &Arc<dyn Profile>, invitation: &Invitation) -
     Ok(did_doc)
 }
 
-fn _ed25519_public_key_to_did_key(public_key_base58: &str) -> VcxResult<String> {
-    let public_key_bytes = bs58::decode(public_key_base58).into_vec().map_err(|_| {
-        AriesVcxError::from_msg(
-            AriesVcxErrorKind::InvalidDid,
-            format!("Could not base58 decode a did:key fingerprint: {}", public_key_base58),
-        )
-    })?;
-    let mut did_key_bytes = ED25519_MULTIBASE_CODEC.to_vec();
-    did_key_bytes.extend_from_slice(&public_key_bytes);
-    let did_key_bytes_bs58 = bs58::encode(&did_key_bytes).into_string();
-    let did_key = format!("{DID_KEY_PREFIX}z{did_key_bytes_bs58}");
-    Ok(did_key)
-}
-
-fn normalize_keys_as_naked(keys_list: Vec<String>) -> VcxResult<Vec<String>> {
-    let mut result = Vec::new();
-    for key in keys_list {
-        if let Some(stripped_didkey) = key.strip_prefix(DID_KEY_PREFIX) {
-            let stripped = if let Some(stripped) = stripped_didkey.strip_prefix('z') {
-                stripped
-            } else {
-                Err(AriesVcxError::from_msg(
-                    AriesVcxErrorKind::InvalidDid,
-                    format!("z prefix is missing: {}", key),
-                ))?
-            };
-            let decoded_value = bs58::decode(stripped).into_vec().map_err(|_| {
-                AriesVcxError::from_msg(
-                    AriesVcxErrorKind::InvalidDid,
-                    format!("Could not decode base58: {} as portion of {}", stripped, key),
-                )
-            })?;
-            let verkey = if let Some(public_key_bytes) = decoded_value.strip_prefix(&ED25519_MULTIBASE_CODEC) {
-                Ok(bs58::encode(public_key_bytes).into_string())
-            } else {
-                Err(AriesVcxError::from_msg(
-                    AriesVcxErrorKind::InvalidDid,
-                    format!("Only Ed25519-based did:keys are currently supported, got key: {}", key),
-                ))
-            }?;
-            result.push(verkey);
-        } else {
-            result.push(key);
-        }
-    }
-    Ok(result)
-}
-
 pub async fn get_service(profile: &Arc<dyn Profile>, did: &Did) -> VcxResult<AriesService> {
-    let did_raw = did.to_string();
-    let did_raw = match did_raw.rsplit_once(':') {
-        None => did_raw,
-        Some((_, value)) => value.to_string(),
-    };
-    let ledger = Arc::clone(profile).inject_ledger();
-    let attr_resp = ledger.get_attr(&did_raw, "endpoint").await?;
-    let data = get_data_from_response(&attr_resp)?;
-    if data["endpoint"].is_object() {
-        let endpoint: EndpointDidSov = serde_json::from_value(data["endpoint"].clone())?;
-        let recipient_keys = vec![get_verkey_from_ledger(profile, &did_raw).await?];
-        return Ok(AriesService::create()
-            .set_recipient_keys(recipient_keys)
-            .set_service_endpoint(endpoint.endpoint)
-            .set_routing_keys(endpoint.routing_keys.unwrap_or_default()));
-    }
-    parse_legacy_endpoint_attrib(profile, &did_raw).await
+    Arc::clone(profile)
+        .inject_did_resolver()
+        .resolve_service(&did.to_string())
+        .await
 }
 
 pub async fn parse_legacy_endpoint_attrib(profile: &Arc<dyn Profile>, did_raw: &str) -> VcxResult<AriesService> {
-    let ledger = Arc::clone(profile).inject_ledger();
-    let attr_resp = ledger.get_attr(did_raw, "service").await?;
-    let data = get_data_from_response(&attr_resp)?;
-    let ser_service = match data["service"].as_str() {
-        Some(ser_service) => ser_service.to_string(),
-        None => {
-            warn!("Failed converting service read from ledger {:?} to string, falling back to new single-serialized format", data["service"]);
-            data["service"].to_string()
-        }
-    };
-    serde_json::from_str(&ser_service).map_err(|err| {
-        AriesVcxError::from_msg(
-            AriesVcxErrorKind::SerializationError,
-            format!("Failed to deserialize service read from the ledger: {:?}", err),
-        )
-    })
+    DidSovResolver::new(Arc::clone(profile).inject_ledger())
+        .get_legacy_service(did_raw)
+        .await
 }
 
 pub async fn write_endpoint_legacy(profile: &Arc<dyn Profile>, did: &str, service: &AriesService) -> VcxResult<String> {
@@ -277,7 +210,7 @@ fn parse_response(response: &str) -> VcxResult<Response> {
     })
 }
 
-fn get_data_from_response(resp: &str) -> VcxResult<serde_json::Value> {
+pub(crate) fn get_data_from_response(resp: &str) -> VcxResult<serde_json::Value> {
     let resp: serde_json::Value = serde_json::from_str(resp)
         .map_err(|err| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidLedgerResponse, format!("{:?}", err)))?;
     serde_json::from_str(resp["result"]["data"].as_str().unwrap_or("{}"))
@@ -296,6 +229,7 @@ mod test {
     use messages::protocols::out_of_band::invitation::OutOfBandInvitation;
 
     use super::*;
+    use crate::common::did_resolver::key::ed25519_public_key_to_did_key;
 
     #[tokio::test]
     async fn test_did_doc_from_invitation_works() {
@@ -340,6 +274,28 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_did_doc_from_invitation_with_peer_did_service_works() {
+        let peer_did =
+            messages::diddoc::peer::generate_numalgo2(&_key_1(), &_service_endpoint(), &_routing_keys()).unwrap();
+
+        let mut did_doc = AriesDidDoc::default();
+        did_doc.set_id(MessageId::id().0);
+        did_doc.set_service_endpoint(_service_endpoint());
+        did_doc.set_recipient_keys(vec![_key_1()]);
+        did_doc.set_routing_keys(_routing_keys());
+
+        let mut invitation = OutOfBandInvitation::default();
+        invitation.services.push(ServiceOob::Did(Did::new(&peer_did).unwrap()));
+
+        assert_eq!(
+            did_doc,
+            into_did_doc(&mock_profile(), &Invitation::OutOfBand(invitation))
+                .await
+                .unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_did_key_to_did_raw() {
         let recipient_keys = vec![_key_1()];
@@ -377,7 +333,7 @@ mod test {
     async fn test_public_key_to_did_naked_with_previously_known_keys_suggested() {
         let did_pub_with_key = "did:key:z6MkwHgArrRJq3tTdhQZKVAa1sdFgSAs5P5N1C4RJcD11Ycv".to_string();
         let did_pub = "HqR8GcAsVWPzXCZrdvCjAn5Frru1fVq1KB9VULEz6KqY".to_string();
-        let did_raw = _ed25519_public_key_to_did_key(&did_pub).unwrap();
+        let did_raw = ed25519_public_key_to_did_key(&did_pub).unwrap();
         let recipient_keys = vec![did_raw];
         let expected_output = vec![did_pub_with_key];
         assert_eq!(recipient_keys, expected_output);
@@ -387,7 +343,7 @@ mod test {
     async fn test_public_key_to_did_naked_with_previously_known_keys_rfc_0360() {
         let did_pub_with_key_rfc_0360 = "did:key:z6MkmjY8GnV5i9YTDtPETC2uUAW6ejw3nk5mXF5yci5ab7th".to_string();
         let did_pub_rfc_0360 = "8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K".to_string();
-        let did_raw = _ed25519_public_key_to_did_key(&did_pub_rfc_0360).unwrap();
+        let did_raw = ed25519_public_key_to_did_key(&did_pub_rfc_0360).unwrap();
         let recipient_keys = vec![did_raw];
         let expected_output = vec![did_pub_with_key_rfc_0360];
         assert_eq!(recipient_keys, expected_output);
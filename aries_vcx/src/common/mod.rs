@@ -1,5 +1,6 @@
 pub mod anoncreds;
 pub mod credentials;
+pub mod did_resolver;
 pub mod keys;
 pub mod ledger;
 pub mod primitives;
@@ -0,0 +1,755 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::Value;
+
+use crate::common::primitives::revocation_registry_delta::RevocationRegistryDelta;
+use crate::common::proofs::proof_request_internal::NonRevokedInterval;
+use crate::errors::error::prelude::*;
+use crate::plugins::ledger::base_ledger::BaseLedger;
+use crate::plugins::wallet::base_wallet::BaseWallet;
+
+const CREDENTIAL_STORED_RECORD_TYPE: &str = "aries-vcx-credential-stored";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CredentialInfo {
+    pub referent: String,
+    #[serde(default)]
+    pub attrs: HashMap<String, String>,
+    pub schema_id: String,
+    pub cred_def_id: String,
+    pub rev_reg_id: Option<String>,
+    pub cred_rev_id: Option<String>,
+}
+
+impl CredentialInfo {
+    /// Unqualified DID of the issuer of the credential, taken from its credential definition id.
+    pub fn issuer_did(&self) -> &str {
+        let cred_def_prefix = self.cred_def_id.split(":3:").next().unwrap_or_default();
+        cred_def_prefix.rsplit(':').next().unwrap_or_default()
+    }
+}
+
+/// A stored credential which can be used for a referent of a presentation request.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CredentialCandidate {
+    pub cred_info: CredentialInfo,
+    pub interval: Option<NonRevokedInterval>,
+    /// When the credential was stored in the wallet, in milliseconds since the unix epoch, if it
+    /// was recorded with [`record_credential_stored`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stored_at: Option<i64>,
+}
+
+/// Credentials found in the wallet for each referent of a presentation request, in the format
+/// returned by [`Prover::retrieve_credentials`](crate::handlers::proof_presentation::prover::Prover::retrieve_credentials).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct RetrievedCredentials {
+    #[serde(default)]
+    pub attrs: HashMap<String, Vec<CredentialCandidate>>,
+}
+
+impl RetrievedCredentials {
+    pub fn from_json(credentials: &str) -> VcxResult<Self> {
+        serde_json::from_str(credentials).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!("Cannot deserialize retrieved credentials: {}", err),
+            )
+        })
+    }
+
+    /// Sets when every candidate was stored in the wallet, looking up each credential once.
+    pub async fn load_storage_times(&mut self, wallet: &Arc<dyn BaseWallet>) -> VcxResult<()> {
+        let mut stored_at = HashMap::new();
+        for candidate in self.attrs.values_mut().flatten() {
+            let referent = &candidate.cred_info.referent;
+            if !stored_at.contains_key(referent) {
+                stored_at.insert(referent.clone(), credential_stored_at(wallet, referent).await?);
+            }
+            candidate.stored_at = stored_at[referent];
+        }
+        Ok(())
+    }
+}
+
+/// Records that the credential `cred_id` was just stored in the wallet, for [`NewestFirst`] to
+/// order credentials by the time they were stored.
+pub async fn record_credential_stored(wallet: &Arc<dyn BaseWallet>, cred_id: &str) -> VcxResult<()> {
+    let stored_at = Utc::now().timestamp_millis().to_string();
+    wallet
+        .add_wallet_record(CREDENTIAL_STORED_RECORD_TYPE, cred_id, &stored_at, None)
+        .await
+}
+
+/// Removes the storage time recorded for the credential `cred_id`, if any.
+pub async fn remove_credential_stored(wallet: &Arc<dyn BaseWallet>, cred_id: &str) -> VcxResult<()> {
+    match wallet
+        .delete_wallet_record(CREDENTIAL_STORED_RECORD_TYPE, cred_id)
+        .await
+    {
+        Err(err) if err.kind() == AriesVcxErrorKind::WalletRecordNotFound => Ok(()),
+        result => result,
+    }
+}
+
+async fn credential_stored_at(wallet: &Arc<dyn BaseWallet>, cred_id: &str) -> VcxResult<Option<i64>> {
+    match wallet
+        .get_wallet_record(CREDENTIAL_STORED_RECORD_TYPE, cred_id, "{}")
+        .await
+    {
+        Ok(record) => {
+            let record: Value = serde_json::from_str(&record)?;
+            Ok(record["value"].as_str().and_then(|value| value.parse().ok()))
+        }
+        Err(err) if err.kind() == AriesVcxErrorKind::WalletRecordNotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SelectedCredential {
+    pub credential: CredentialCandidate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tails_file: Option<String>,
+}
+
+/// Credentials chosen for the referents of a presentation request, in the format expected by
+/// [`Prover::generate_presentation`](crate::handlers::proof_presentation::prover::Prover::generate_presentation).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct SelectedCredentials {
+    #[serde(default)]
+    pub attrs: HashMap<String, SelectedCredential>,
+}
+
+impl SelectedCredentials {
+    pub fn to_json(&self) -> VcxResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+/// Decides which of the stored credentials is used for a referent of a presentation request.
+#[async_trait]
+pub trait CredentialSelectionStrategy: Send + Sync {
+    /// Orders the candidates for `referent` from the most to the least preferred. The order of
+    /// candidates the strategy has no preference between must be kept, so strategies can be
+    /// combined with [`CompositeStrategy`].
+    async fn rank(
+        &self,
+        referent: &str,
+        candidates: Vec<CredentialCandidate>,
+        retrieved: &RetrievedCredentials,
+    ) -> VcxResult<Vec<CredentialCandidate>>;
+}
+
+/// Selects the most preferred candidate for every referent which has any. Referents without
+/// candidates are left out, to be self attested or left unanswered by the caller. The tails file
+/// of revocable credentials is expected in `tails_dir`.
+pub async fn select_credentials(
+    strategy: &dyn CredentialSelectionStrategy,
+    retrieved: &RetrievedCredentials,
+    tails_dir: Option<&str>,
+) -> VcxResult<SelectedCredentials> {
+    let mut selected = SelectedCredentials::default();
+    for (referent, candidates) in retrieved.attrs.iter() {
+        let ranked = strategy.rank(referent, candidates.clone(), retrieved).await?;
+        if let Some(credential) = ranked.into_iter().next() {
+            let tails_file = credential
+                .cred_info
+                .rev_reg_id
+                .as_ref()
+                .and(tails_dir)
+                .map(String::from);
+            selected
+                .attrs
+                .insert(referent.to_string(), SelectedCredential { credential, tails_file });
+        }
+    }
+    Ok(selected)
+}
+
+/// Prefers the most recently stored credentials, by their [`CredentialCandidate::stored_at`] time.
+/// Credentials stored without recording the time come last.
+#[derive(Debug, Clone, Default)]
+pub struct NewestFirst;
+
+#[async_trait]
+impl CredentialSelectionStrategy for NewestFirst {
+    async fn rank(
+        &self,
+        _referent: &str,
+        mut candidates: Vec<CredentialCandidate>,
+        _retrieved: &RetrievedCredentials,
+    ) -> VcxResult<Vec<CredentialCandidate>> {
+        candidates.sort_by_key(|candidate| Reverse(candidate.stored_at));
+        Ok(candidates)
+    }
+}
+
+/// Prefers credentials issued by the given issuer.
+#[derive(Debug, Clone)]
+pub struct PreferIssuer {
+    issuer_did: String,
+}
+
+impl PreferIssuer {
+    pub fn new(issuer_did: &str) -> Self {
+        Self {
+            issuer_did: issuer_did.rsplit(':').next().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialSelectionStrategy for PreferIssuer {
+    async fn rank(
+        &self,
+        _referent: &str,
+        mut candidates: Vec<CredentialCandidate>,
+        _retrieved: &RetrievedCredentials,
+    ) -> VcxResult<Vec<CredentialCandidate>> {
+        candidates.sort_by_key(|candidate| candidate.cred_info.issuer_did() != self.issuer_did);
+        Ok(candidates)
+    }
+}
+
+/// Prefers credentials which were not revoked by the end of the requested non-revocation
+/// interval, as recorded on the ledger. Credentials which are not revocable count as not revoked.
+/// The revocation registry delta is fetched once per registry and interval end, and reused for
+/// every credential of the registry.
+pub struct PreferNonRevoked {
+    ledger: Arc<dyn BaseLedger>,
+    revoked: Mutex<HashMap<(String, Option<u64>), Arc<HashSet<String>>>>,
+}
+
+impl PreferNonRevoked {
+    pub fn new(ledger: Arc<dyn BaseLedger>) -> Self {
+        Self {
+            ledger,
+            revoked: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn is_revoked(&self, candidate: &CredentialCandidate) -> VcxResult<bool> {
+        let (rev_reg_id, cred_rev_id) = match (&candidate.cred_info.rev_reg_id, &candidate.cred_info.cred_rev_id) {
+            (Some(rev_reg_id), Some(cred_rev_id)) => (rev_reg_id, cred_rev_id),
+            _ => return Ok(false),
+        };
+        let to = candidate.interval.as_ref().and_then(|interval| interval.to);
+        Ok(self.revoked_in_registry(rev_reg_id, to).await?.contains(cred_rev_id))
+    }
+
+    async fn revoked_in_registry(&self, rev_reg_id: &str, to: Option<u64>) -> VcxResult<Arc<HashSet<String>>> {
+        let key = (rev_reg_id.to_string(), to);
+        let cached = self.revoked.lock()?.get(&key).cloned();
+        if let Some(revoked) = cached {
+            return Ok(revoked);
+        }
+        let (_, rev_reg_delta_json, _) = self.ledger.get_rev_reg_delta_json(rev_reg_id, None, to).await?;
+        let rev_reg_delta: RevocationRegistryDelta = serde_json::from_str(&rev_reg_delta_json).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::SerializationError,
+                format!("Failed to deserialize rev_reg_delta_json from ledger, err: {}", err),
+            )
+        })?;
+        let revoked = Arc::new(
+            rev_reg_delta
+                .revoked()
+                .iter()
+                .map(u32::to_string)
+                .collect::<HashSet<_>>(),
+        );
+        self.revoked.lock()?.insert(key, Arc::clone(&revoked));
+        Ok(revoked)
+    }
+}
+
+#[async_trait]
+impl CredentialSelectionStrategy for PreferNonRevoked {
+    async fn rank(
+        &self,
+        _referent: &str,
+        candidates: Vec<CredentialCandidate>,
+        _retrieved: &RetrievedCredentials,
+    ) -> VcxResult<Vec<CredentialCandidate>> {
+        let mut ranked = Vec::with_capacity(candidates.len());
+        for candidate in candidates {
+            ranked.push((self.is_revoked(&candidate).await?, candidate));
+        }
+        ranked.sort_by_key(|(revoked, _)| *revoked);
+        Ok(ranked.into_iter().map(|(_, candidate)| candidate).collect())
+    }
+}
+
+/// Prefers the credentials of a smallest set of credentials answering every referent of the
+/// presentation request which has candidates, so the presentation is derived from, and discloses
+/// the identifiers of, as few credentials as possible.
+///
+/// The set is searched once for the retrieved credentials and reused while ranking the candidates
+/// of each of their referents.
+#[derive(Debug, Default)]
+pub struct MinimalDisclosure {
+    cover: Mutex<Option<(Vec<Vec<String>>, Arc<HashSet<String>>)>>,
+}
+
+impl MinimalDisclosure {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cover of the retrieved credentials, searching for it only if it was last
+    /// computed for different credentials.
+    fn minimal_cover(&self, retrieved: &RetrievedCredentials) -> VcxResult<Arc<HashSet<String>>> {
+        let referents = Self::referent_cred_ids(retrieved);
+        if let Some((cached_referents, cover)) = self.cover.lock()?.as_ref() {
+            if *cached_referents == referents {
+                return Ok(Arc::clone(cover));
+            }
+        }
+        let cover = Arc::new(Self::search_cover(&referents));
+        *self.cover.lock()? = Some((referents, Arc::clone(&cover)));
+        Ok(cover)
+    }
+
+    /// Lists the ids of the candidates of every referent with candidates.
+    fn referent_cred_ids(retrieved: &RetrievedCredentials) -> Vec<Vec<String>> {
+        let mut referents: Vec<(&String, Vec<String>)> = retrieved
+            .attrs
+            .iter()
+            .filter(|(_, candidates)| !candidates.is_empty())
+            .map(|(referent, candidates)| {
+                let mut cred_ids: Vec<String> = candidates
+                    .iter()
+                    .map(|candidate| candidate.cred_info.referent.clone())
+                    .collect();
+                cred_ids.sort_unstable();
+                cred_ids.dedup();
+                (referent, cred_ids)
+            })
+            .collect();
+        // a fixed order of referents makes every referent agree on the same cover
+        referents.sort_by_key(|(referent, _)| *referent);
+        referents.into_iter().map(|(_, cred_ids)| cred_ids).collect()
+    }
+
+    /// Finds a smallest set of credentials covering the referents. The search branches on the
+    /// candidates of the uncovered referent with the fewest candidates and drops branches which
+    /// cannot improve on the smallest set found so far.
+    fn search_cover(referents: &[Vec<String>]) -> HashSet<String> {
+        let referents: Vec<Vec<&str>> = referents
+            .iter()
+            .map(|cred_ids| cred_ids.iter().map(String::as_str).collect())
+            .collect();
+        let mut smallest = None;
+        Self::cover(&referents, &mut Vec::new(), &mut smallest);
+        smallest.unwrap_or_default().into_iter().map(String::from).collect()
+    }
+
+    fn cover<'a>(referents: &[Vec<&'a str>], chosen: &mut Vec<&'a str>, smallest: &mut Option<Vec<&'a str>>) {
+        if matches!(smallest, Some(best) if chosen.len() >= best.len()) {
+            return;
+        }
+        let uncovered = referents
+            .iter()
+            .filter(|cred_ids| !cred_ids.iter().any(|cred_id| chosen.contains(cred_id)))
+            .min_by_key(|cred_ids| cred_ids.len());
+        match uncovered {
+            None => *smallest = Some(chosen.clone()),
+            Some(cred_ids) => {
+                for &cred_id in cred_ids {
+                    chosen.push(cred_id);
+                    Self::cover(referents, chosen, smallest);
+                    chosen.pop();
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialSelectionStrategy for MinimalDisclosure {
+    async fn rank(
+        &self,
+        _referent: &str,
+        mut candidates: Vec<CredentialCandidate>,
+        retrieved: &RetrievedCredentials,
+    ) -> VcxResult<Vec<CredentialCandidate>> {
+        let cover = self.minimal_cover(retrieved)?;
+        candidates.sort_by_key(|candidate| !cover.contains(&candidate.cred_info.referent));
+        Ok(candidates)
+    }
+}
+
+/// Combines strategies in order of precedence: candidates are ordered by the first strategy, and
+/// candidates it has no preference between by the following ones.
+#[derive(Default)]
+pub struct CompositeStrategy {
+    strategies: Vec<Box<dyn CredentialSelectionStrategy>>,
+}
+
+impl CompositeStrategy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn then(mut self, strategy: impl CredentialSelectionStrategy + 'static) -> Self {
+        self.strategies.push(Box::new(strategy));
+        self
+    }
+}
+
+#[async_trait]
+impl CredentialSelectionStrategy for CompositeStrategy {
+    async fn rank(
+        &self,
+        referent: &str,
+        mut candidates: Vec<CredentialCandidate>,
+        retrieved: &RetrievedCredentials,
+    ) -> VcxResult<Vec<CredentialCandidate>> {
+        // strategies keep the order of candidates they have no preference between, so applying
+        // the strategy of highest precedence last leaves the candidates ordered by precedence
+        for strategy in self.strategies.iter().rev() {
+            candidates = strategy.rank(referent, candidates, retrieved).await?;
+        }
+        Ok(candidates)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use crate::common::primitives::revocation_registry::RevocationRegistryDefinition;
+    use crate::common::proofs::proof_request::ProofRequestData;
+    use crate::common::proofs::prover::prover_internal::credential_def_identifiers;
+    use crate::common::test_utils::indy_handles_to_profile;
+    use crate::indy::utils::test_setup::with_wallet;
+    use crate::plugins::ledger::in_memory_ledger::InMemoryLedger;
+    use crate::utils::devsetup::SetupEmpty;
+
+    use super::*;
+
+    const ISSUER_DID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+    const OTHER_ISSUER_DID: &str = "2hoqvcwupRTUNkXn6ArYzs";
+
+    fn _rev_reg_id(issuer_did: &str) -> String {
+        format!("{}:4:{}:3:CL:1281:tag:CL_ACCUM:tag", issuer_did, issuer_did)
+    }
+
+    fn _candidate(referent: &str, issuer_did: &str, cred_rev_id: Option<&str>) -> CredentialCandidate {
+        CredentialCandidate {
+            cred_info: CredentialInfo {
+                referent: referent.to_string(),
+                attrs: HashMap::from([("name".to_string(), "alice".to_string())]),
+                schema_id: format!("{}:2:degree:1.0", issuer_did),
+                cred_def_id: format!("{}:3:CL:1281:tag", issuer_did),
+                rev_reg_id: cred_rev_id.map(|_| _rev_reg_id(issuer_did)),
+                cred_rev_id: cred_rev_id.map(String::from),
+            },
+            interval: None,
+            stored_at: None,
+        }
+    }
+
+    fn _stored(mut candidate: CredentialCandidate, stored_at: i64) -> CredentialCandidate {
+        candidate.stored_at = Some(stored_at);
+        candidate
+    }
+
+    fn _retrieved(attrs: Vec<(&str, Vec<CredentialCandidate>)>) -> RetrievedCredentials {
+        RetrievedCredentials {
+            attrs: attrs
+                .into_iter()
+                .map(|(referent, candidates)| (referent.to_string(), candidates))
+                .collect(),
+        }
+    }
+
+    async fn _selected_referent(
+        strategy: &dyn CredentialSelectionStrategy,
+        retrieved: &RetrievedCredentials,
+        referent: &str,
+    ) -> String {
+        let selected = select_credentials(strategy, retrieved, None).await.unwrap();
+        selected.attrs[referent].credential.cred_info.referent.clone()
+    }
+
+    #[test]
+    fn test_retrieved_credentials_from_json() {
+        let retrieved = RetrievedCredentials::from_json(
+            &json!({
+                "attrs": {
+                    "attribute_0": [{
+                        "cred_info": {
+                            "referent": "cred_1",
+                            "attrs": {"name": "alice"},
+                            "schema_id": format!("{}:2:degree:1.0", ISSUER_DID),
+                            "cred_def_id": format!("{}:3:CL:1281:tag", ISSUER_DID),
+                            "rev_reg_id": null,
+                            "cred_rev_id": null
+                        },
+                        "interval": null
+                    }],
+                    "attribute_1": []
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            retrieved.attrs["attribute_0"],
+            vec![_candidate("cred_1", ISSUER_DID, None)]
+        );
+        assert!(retrieved.attrs["attribute_1"].is_empty());
+        assert_eq!(
+            RetrievedCredentials::from_json(r#"{"attrs": []}"#).unwrap_err().kind(),
+            AriesVcxErrorKind::InvalidJson
+        );
+    }
+
+    #[test]
+    fn test_issuer_did_of_qualified_cred_def_id() {
+        let mut candidate = _candidate("cred_1", ISSUER_DID, None);
+        assert_eq!(candidate.cred_info.issuer_did(), ISSUER_DID);
+        candidate.cred_info.cred_def_id = format!("creddef:sov:did:sov:{}:3:CL:1281:tag", ISSUER_DID);
+        assert_eq!(candidate.cred_info.issuer_did(), ISSUER_DID);
+    }
+
+    #[tokio::test]
+    async fn test_select_credentials_in_generate_presentation_format() {
+        let retrieved = _retrieved(vec![
+            (
+                "attribute_0",
+                vec![
+                    _stored(_candidate("cred_1", ISSUER_DID, None), 1),
+                    _stored(_candidate("cred_2", ISSUER_DID, Some("1")), 2),
+                ],
+            ),
+            ("attribute_1", vec![]),
+        ]);
+
+        let selected = select_credentials(&NewestFirst, &retrieved, Some("/tmp/tails"))
+            .await
+            .unwrap();
+        assert!(!selected.attrs.contains_key("attribute_1"));
+        assert_eq!(selected.attrs["attribute_0"].tails_file.as_deref(), Some("/tmp/tails"));
+
+        let proof_request: ProofRequestData = serde_json::from_value(json!({
+            "nonce": "123432421212",
+            "name": "proof_req_1",
+            "version": "1.0",
+            "requested_attributes": {"attribute_0": {"name": "name"}, "attribute_1": {"name": "age"}}
+        }))
+        .unwrap();
+        let identifiers = credential_def_identifiers(&selected.to_json().unwrap(), &proof_request).unwrap();
+        assert_eq!(identifiers.len(), 1);
+        assert_eq!(identifiers[0].referent, "cred_2");
+        assert_eq!(identifiers[0].cred_rev_id.as_deref(), Some("1"));
+        assert_eq!(identifiers[0].tails_file.as_deref(), Some("/tmp/tails"));
+    }
+
+    #[tokio::test]
+    async fn test_newest_first() {
+        let retrieved = _retrieved(vec![(
+            "attribute_0",
+            vec![
+                _candidate("cred_1", ISSUER_DID, None),
+                _stored(_candidate("cred_2", ISSUER_DID, None), 300),
+                _stored(_candidate("cred_3", ISSUER_DID, None), 200),
+            ],
+        )]);
+        let ranked = NewestFirst
+            .rank("attribute_0", retrieved.attrs["attribute_0"].clone(), &retrieved)
+            .await
+            .unwrap();
+        let ranked: Vec<&str> = ranked
+            .iter()
+            .map(|candidate| candidate.cred_info.referent.as_str())
+            .collect();
+        assert_eq!(ranked, vec!["cred_2", "cred_3", "cred_1"]);
+    }
+
+    #[tokio::test]
+    async fn test_load_storage_times() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let wallet = indy_handles_to_profile(wallet_handle, 0).inject_wallet();
+            record_credential_stored(&wallet, "cred_2").await.unwrap();
+
+            let mut retrieved = _retrieved(vec![
+                (
+                    "attribute_0",
+                    vec![
+                        _candidate("cred_1", ISSUER_DID, None),
+                        _candidate("cred_2", ISSUER_DID, None),
+                    ],
+                ),
+                ("attribute_1", vec![_candidate("cred_2", ISSUER_DID, None)]),
+            ]);
+            retrieved.load_storage_times(&wallet).await.unwrap();
+            assert!(retrieved.attrs["attribute_0"][0].stored_at.is_none());
+            assert!(retrieved.attrs["attribute_0"][1].stored_at.is_some());
+            assert_eq!(
+                retrieved.attrs["attribute_1"][0].stored_at,
+                retrieved.attrs["attribute_0"][1].stored_at
+            );
+            assert_eq!(
+                _selected_referent(&NewestFirst, &retrieved, "attribute_0").await,
+                "cred_2"
+            );
+
+            remove_credential_stored(&wallet, "cred_2").await.unwrap();
+            remove_credential_stored(&wallet, "cred_2").await.unwrap();
+            retrieved.load_storage_times(&wallet).await.unwrap();
+            assert!(retrieved.attrs["attribute_1"][0].stored_at.is_none());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_prefer_issuer() {
+        let retrieved = _retrieved(vec![(
+            "attribute_0",
+            vec![
+                _candidate("cred_1", OTHER_ISSUER_DID, None),
+                _candidate("cred_2", ISSUER_DID, None),
+                _candidate("cred_3", ISSUER_DID, None),
+            ],
+        )]);
+        let strategy = PreferIssuer::new(&format!("did:sov:{}", ISSUER_DID));
+        assert_eq!(_selected_referent(&strategy, &retrieved, "attribute_0").await, "cred_2");
+    }
+
+    #[tokio::test]
+    async fn test_minimal_disclosure() {
+        let retrieved = _retrieved(vec![
+            (
+                "attribute_0",
+                vec![
+                    _candidate("cred_1", ISSUER_DID, None),
+                    _candidate("cred_2", ISSUER_DID, None),
+                ],
+            ),
+            ("attribute_1", vec![_candidate("cred_2", ISSUER_DID, None)]),
+        ]);
+        assert_eq!(
+            _selected_referent(&MinimalDisclosure::new(), &retrieved, "attribute_0").await,
+            "cred_2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_minimal_disclosure_finds_smallest_set_of_credentials() {
+        // cred_wide answers the most referents, but cred_a and cred_b answer all of them together
+        let wide = || _candidate("cred_wide", ISSUER_DID, None);
+        let a = || _candidate("cred_a", ISSUER_DID, None);
+        let b = || _candidate("cred_b", ISSUER_DID, None);
+        let retrieved = _retrieved(vec![
+            ("attribute_0", vec![wide(), a()]),
+            ("attribute_1", vec![wide(), a()]),
+            ("attribute_2", vec![a()]),
+            ("attribute_3", vec![wide(), b()]),
+            ("attribute_4", vec![wide(), b()]),
+            ("attribute_5", vec![b()]),
+            ("attribute_6", vec![]),
+        ]);
+
+        let strategy = MinimalDisclosure::new();
+        let selected = select_credentials(&strategy, &retrieved, None).await.unwrap();
+        let mut used: Vec<&str> = selected
+            .attrs
+            .values()
+            .map(|selected| selected.credential.cred_info.referent.as_str())
+            .collect();
+        used.sort_unstable();
+        used.dedup();
+        assert_eq!(used, vec!["cred_a", "cred_b"]);
+        assert_eq!(selected.attrs.len(), 6);
+
+        // the cover found for these credentials is reused, also for another selection
+        let cover = strategy.minimal_cover(&retrieved).unwrap();
+        assert_eq!(*cover, HashSet::from(["cred_a".to_string(), "cred_b".to_string()]));
+        let selected_again = select_credentials(&strategy, &retrieved, None).await.unwrap();
+        assert_eq!(selected_again, selected);
+        assert!(Arc::ptr_eq(&strategy.minimal_cover(&retrieved).unwrap(), &cover));
+
+        // a cover is searched again for different credentials
+        let other = _retrieved(vec![("attribute_0", vec![wide()])]);
+        let other_cover = strategy.minimal_cover(&other).unwrap();
+        assert_eq!(*other_cover, HashSet::from(["cred_wide".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_composite_strategy_precedence() {
+        let retrieved = _retrieved(vec![(
+            "attribute_0",
+            vec![
+                _stored(_candidate("cred_1", ISSUER_DID, None), 100),
+                _stored(_candidate("cred_2", ISSUER_DID, None), 200),
+                _stored(_candidate("cred_3", OTHER_ISSUER_DID, None), 300),
+            ],
+        )]);
+
+        let strategy = CompositeStrategy::new()
+            .then(PreferIssuer::new(ISSUER_DID))
+            .then(NewestFirst);
+        assert_eq!(_selected_referent(&strategy, &retrieved, "attribute_0").await, "cred_2");
+
+        let strategy = CompositeStrategy::new()
+            .then(NewestFirst)
+            .then(PreferIssuer::new(ISSUER_DID));
+        assert_eq!(_selected_referent(&strategy, &retrieved, "attribute_0").await, "cred_3");
+    }
+
+    #[tokio::test]
+    async fn test_prefer_non_revoked() {
+        let ledger = Arc::new(InMemoryLedger::new());
+        let rev_reg_def = RevocationRegistryDefinition {
+            id: _rev_reg_id(ISSUER_DID),
+            ..RevocationRegistryDefinition::default()
+        };
+        ledger.publish_rev_reg_def(&rev_reg_def, ISSUER_DID).await.unwrap();
+        let delta = json!({"ver": "1.0", "value": {"accum": "accum", "issued": [1, 2], "revoked": [1]}});
+        ledger
+            .publish_rev_reg_delta(&_rev_reg_id(ISSUER_DID), &delta.to_string(), ISSUER_DID)
+            .await
+            .unwrap();
+
+        let retrieved = _retrieved(vec![
+            (
+                "attribute_0",
+                vec![
+                    _candidate("cred_1", ISSUER_DID, Some("1")),
+                    _candidate("cred_2", ISSUER_DID, Some("2")),
+                ],
+            ),
+            (
+                "attribute_1",
+                vec![
+                    _candidate("cred_1", ISSUER_DID, Some("1")),
+                    _candidate("cred_3", OTHER_ISSUER_DID, None),
+                ],
+            ),
+        ]);
+
+        let strategy = PreferNonRevoked::new(ledger.clone());
+        let selected = select_credentials(&strategy, &retrieved, None).await.unwrap();
+        assert_eq!(selected.attrs["attribute_0"].credential.cred_info.referent, "cred_2");
+        assert_eq!(selected.attrs["attribute_1"].credential.cred_info.referent, "cred_3");
+
+        // the revocation registry is fetched once, later revocations are not seen by the strategy
+        let delta = json!({"ver": "1.0", "value": {"accum": "accum", "issued": [], "revoked": [2]}});
+        ledger
+            .publish_rev_reg_delta(&_rev_reg_id(ISSUER_DID), &delta.to_string(), ISSUER_DID)
+            .await
+            .unwrap();
+        let selected = select_credentials(&strategy, &retrieved, None).await.unwrap();
+        assert_eq!(selected.attrs["attribute_0"].credential.cred_info.referent, "cred_2");
+
+        let strategy = PreferNonRevoked::new(ledger);
+        let selected = select_credentials(&strategy, &retrieved, None).await.unwrap();
+        assert_eq!(selected.attrs["attribute_0"].credential.cred_info.referent, "cred_1");
+    }
+}
@@ -1,2 +1,3 @@
+pub mod verification_report;
 pub mod verifier;
 mod verifier_internal;
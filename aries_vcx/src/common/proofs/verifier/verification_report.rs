@@ -0,0 +1,774 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use crate::common::proofs::proof_request::ProofRequestData;
+use crate::common::proofs::proof_request_internal::{NonRevokedInterval, Restrictions};
+use crate::common::proofs::verifier::verifier_internal::{get_credential_info, CredInfoVerifier};
+use crate::errors::error::prelude::*;
+use crate::utils::openssl::encode;
+
+/// Outcome of a single check performed while verifying a presentation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "result")]
+pub enum CheckResult {
+    Passed,
+    Failed { reason: String },
+    Skipped { reason: String },
+}
+
+impl CheckResult {
+    fn failed(reason: impl Into<String>) -> Self {
+        Self::Failed { reason: reason.into() }
+    }
+
+    fn skipped(reason: impl Into<String>) -> Self {
+        Self::Skipped { reason: reason.into() }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed { .. })
+    }
+}
+
+/// Identifiers of a credential a presentation was derived from, as listed in the presentation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CredentialIdentifier {
+    pub schema_id: String,
+    pub cred_def_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rev_reg_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+}
+
+impl From<CredInfoVerifier> for CredentialIdentifier {
+    fn from(info: CredInfoVerifier) -> Self {
+        Self {
+            schema_id: info.schema_id,
+            cred_def_id: info.cred_def_id,
+            rev_reg_id: info.rev_reg_id,
+            timestamp: info.timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RevealedValue {
+    pub raw: String,
+    pub encoded: String,
+    pub encoding: CheckResult,
+}
+
+/// How a requested attribute was disclosed in the presentation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "disclosure", rename_all = "snake_case")]
+pub enum AttributeDisclosure {
+    Revealed {
+        sub_proof_index: u64,
+        values: HashMap<String, RevealedValue>,
+    },
+    Unrevealed {
+        sub_proof_index: u64,
+    },
+    SelfAttested {
+        value: String,
+    },
+    Missing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NonRevocationReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<NonRevokedInterval>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    pub check: CheckResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AttributeReport {
+    #[serde(flatten)]
+    pub disclosure: AttributeDisclosure,
+    pub restrictions: CheckResult,
+    pub non_revocation: NonRevocationReport,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PredicateReport {
+    pub name: String,
+    pub p_type: String,
+    pub p_value: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_proof_index: Option<u64>,
+    pub restrictions: CheckResult,
+    pub non_revocation: NonRevocationReport,
+    /// Predicates are proven without revealing the attribute value, so whether a predicate
+    /// holds is established by the cryptographic verification of the presentation.
+    pub satisfied: CheckResult,
+}
+
+/// Detailed result of the verification of a presentation, listing the checks performed
+/// for every referent of the presentation request.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VerificationReport {
+    pub identifiers: Vec<CredentialIdentifier>,
+    pub requested_attributes: HashMap<String, AttributeReport>,
+    pub requested_predicates: HashMap<String, PredicateReport>,
+    pub cryptographic: CheckResult,
+    /// Whether the presentation is valid: it passed the cryptographic verification and its revealed
+    /// values match their encodings. The other checks explain the outcome and do not change it.
+    pub valid: bool,
+}
+
+impl VerificationReport {
+    /// Builds a report from the presentation and the presentation request it answers. The
+    /// cryptographic verification is not part of it and must be recorded with
+    /// [`VerificationReport::set_cryptographic_result`].
+    pub fn build(proof_json: &str, proof_req_json: &str) -> VcxResult<Self> {
+        let proof: Value = serde_json::from_str(proof_json).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!("Cannot deserialize libndy proof: {}", err),
+            )
+        })?;
+        let proof_request: ProofRequestData = serde_json::from_str(proof_req_json).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!("Cannot deserialize proof request: {}", err),
+            )
+        })?;
+        let identifiers: Vec<CredentialIdentifier> = get_credential_info(proof_json)?
+            .into_iter()
+            .map(CredentialIdentifier::from)
+            .collect();
+        let requested_proof = &proof["requested_proof"];
+
+        let mut disclosures = HashMap::new();
+        for referent in proof_request.requested_attributes.keys() {
+            disclosures.insert(referent.clone(), attribute_disclosure(requested_proof, referent)?);
+        }
+        let revealed_values = revealed_values_by_credential(&proof_request, &disclosures);
+
+        let mut requested_attributes = HashMap::new();
+        for (referent, attr_info) in proof_request.requested_attributes.iter() {
+            let disclosure = disclosures.remove(referent).unwrap_or(AttributeDisclosure::Missing);
+            let identifier = match &disclosure {
+                AttributeDisclosure::Revealed { sub_proof_index, .. }
+                | AttributeDisclosure::Unrevealed { sub_proof_index } => identifiers.get(*sub_proof_index as usize),
+                _ => None,
+            };
+            let restrictions = match &disclosure {
+                AttributeDisclosure::SelfAttested { .. } if attr_info.restrictions.is_some() => {
+                    CheckResult::failed("Self attested attribute cannot satisfy restrictions")
+                }
+                AttributeDisclosure::SelfAttested { .. } => CheckResult::skipped("Attribute is self attested"),
+                AttributeDisclosure::Missing => CheckResult::failed("Attribute is missing from the presentation"),
+                AttributeDisclosure::Revealed { sub_proof_index, .. }
+                | AttributeDisclosure::Unrevealed { sub_proof_index } => check_restrictions(
+                    attr_info.restrictions.as_ref(),
+                    identifier,
+                    revealed_values.get(sub_proof_index),
+                ),
+            };
+            let interval = attr_info.non_revoked.as_ref().or(proof_request.non_revoked.as_ref());
+            requested_attributes.insert(
+                referent.clone(),
+                AttributeReport {
+                    restrictions,
+                    non_revocation: check_non_revocation(interval, identifier),
+                    disclosure,
+                },
+            );
+        }
+
+        let mut requested_predicates = HashMap::new();
+        for (referent, predicate_info) in proof_request.requested_predicates.iter() {
+            let sub_proof_index = requested_proof["predicates"][referent]["sub_proof_index"].as_u64();
+            let identifier = sub_proof_index.and_then(|index| identifiers.get(index as usize));
+            let restrictions = match sub_proof_index {
+                Some(index) => check_restrictions(
+                    predicate_info.restrictions.as_ref(),
+                    identifier,
+                    revealed_values.get(&index),
+                ),
+                None => CheckResult::failed("Predicate is missing from the presentation"),
+            };
+            let interval = predicate_info
+                .non_revoked
+                .as_ref()
+                .or(proof_request.non_revoked.as_ref());
+            requested_predicates.insert(
+                referent.clone(),
+                PredicateReport {
+                    name: predicate_info.name.clone(),
+                    p_type: predicate_info.p_type.clone(),
+                    p_value: predicate_info.p_value,
+                    sub_proof_index,
+                    restrictions,
+                    non_revocation: check_non_revocation(interval, identifier),
+                    satisfied: CheckResult::skipped("Presentation was not verified cryptographically"),
+                },
+            );
+        }
+
+        let mut report = Self {
+            identifiers,
+            requested_attributes,
+            requested_predicates,
+            cryptographic: CheckResult::skipped("Presentation was not verified cryptographically"),
+            valid: false,
+        };
+        report.valid = report.is_valid();
+        Ok(report)
+    }
+
+    /// Builds a report carrying only the result of the cryptographic verification.
+    pub fn from_cryptographic_result(valid: bool) -> Self {
+        let mut report = Self {
+            identifiers: Vec::new(),
+            requested_attributes: HashMap::new(),
+            requested_predicates: HashMap::new(),
+            cryptographic: CheckResult::skipped("Presentation was not verified cryptographically"),
+            valid: false,
+        };
+        report.set_cryptographic_result(valid);
+        report
+    }
+
+    pub fn set_cryptographic_result(&mut self, valid: bool) {
+        self.cryptographic = match valid {
+            true => CheckResult::Passed,
+            false => CheckResult::failed("Cryptographic verification of the presentation failed"),
+        };
+        for predicate in self.requested_predicates.values_mut() {
+            predicate.satisfied = self.cryptographic.clone();
+        }
+        self.valid = self.is_valid();
+    }
+
+    fn is_valid(&self) -> bool {
+        let encodings_passed = self.requested_attributes.values().all(|attr| match &attr.disclosure {
+            AttributeDisclosure::Revealed { values, .. } => values.values().all(|value| !value.encoding.is_failed()),
+            _ => true,
+        });
+        self.cryptographic == CheckResult::Passed && encodings_passed
+    }
+}
+
+/// Raw values revealed from each credential of the presentation, by sub proof index and then by
+/// attribute name, as named in the presentation request.
+fn revealed_values_by_credential(
+    proof_request: &ProofRequestData,
+    disclosures: &HashMap<String, AttributeDisclosure>,
+) -> HashMap<u64, HashMap<String, String>> {
+    let mut revealed_values: HashMap<u64, HashMap<String, String>> = HashMap::new();
+    for (referent, disclosure) in disclosures {
+        let (sub_proof_index, values) = match disclosure {
+            AttributeDisclosure::Revealed {
+                sub_proof_index,
+                values,
+            } => (sub_proof_index, values),
+            _ => continue,
+        };
+        // Values of single attributes are keyed by referent, values of attribute groups by name
+        let name = proof_request
+            .requested_attributes
+            .get(referent)
+            .and_then(|attr_info| attr_info.name.as_ref());
+        let credential_values = revealed_values.entry(*sub_proof_index).or_default();
+        for (value_name, value) in values {
+            let name = name.unwrap_or(value_name);
+            credential_values.insert(normalize_attr_name(name), value.raw.clone());
+        }
+    }
+    revealed_values
+}
+
+fn attribute_disclosure(requested_proof: &Value, referent: &str) -> VcxResult<AttributeDisclosure> {
+    if let Some(revealed) = requested_proof["revealed_attrs"].get(referent) {
+        let mut values = HashMap::new();
+        values.insert(referent.to_string(), revealed_value(referent, revealed)?);
+        return Ok(AttributeDisclosure::Revealed {
+            sub_proof_index: sub_proof_index(referent, revealed)?,
+            values,
+        });
+    }
+    if let Some(group) = requested_proof["revealed_attr_groups"].get(referent) {
+        let mut values = HashMap::new();
+        if let Some(group_values) = group["values"].as_object() {
+            for (name, value) in group_values {
+                values.insert(name.clone(), revealed_value(referent, value)?);
+            }
+        }
+        return Ok(AttributeDisclosure::Revealed {
+            sub_proof_index: sub_proof_index(referent, group)?,
+            values,
+        });
+    }
+    if let Some(unrevealed) = requested_proof["unrevealed_attrs"].get(referent) {
+        return Ok(AttributeDisclosure::Unrevealed {
+            sub_proof_index: sub_proof_index(referent, unrevealed)?,
+        });
+    }
+    if let Some(value) = requested_proof["self_attested_attrs"][referent].as_str() {
+        return Ok(AttributeDisclosure::SelfAttested {
+            value: value.to_string(),
+        });
+    }
+    Ok(AttributeDisclosure::Missing)
+}
+
+fn sub_proof_index(referent: &str, info: &Value) -> VcxResult<u64> {
+    info["sub_proof_index"].as_u64().ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidProof,
+            format!("Cannot get sub proof index for \"{}\" attribute", referent),
+        )
+    })
+}
+
+fn revealed_value(referent: &str, info: &Value) -> VcxResult<RevealedValue> {
+    let raw = info["raw"].as_str().ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidProof,
+            format!("Cannot get raw value for \"{}\" attribute", referent),
+        )
+    })?;
+    let encoded = info["encoded"].as_str().ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidProof,
+            format!("Cannot get encoded value for \"{}\" attribute", referent),
+        )
+    })?;
+    let expected_encoded = encode(raw)?;
+    let encoding = if expected_encoded == encoded {
+        CheckResult::Passed
+    } else {
+        CheckResult::failed(format!(
+            "Encoded values are different. Expected: {}. From Proof: {}",
+            expected_encoded, encoded
+        ))
+    };
+    Ok(RevealedValue {
+        raw: raw.to_string(),
+        encoded: encoded.to_string(),
+        encoding,
+    })
+}
+
+fn check_non_revocation(
+    interval: Option<&NonRevokedInterval>,
+    identifier: Option<&CredentialIdentifier>,
+) -> NonRevocationReport {
+    let timestamp = identifier.and_then(|identifier| identifier.timestamp);
+    let check = match (interval, identifier) {
+        (None, _) => CheckResult::skipped("Non-revocation was not requested"),
+        (Some(_), None) => CheckResult::skipped("Attribute is not disclosed from a credential"),
+        (Some(_), Some(identifier)) if identifier.rev_reg_id.is_none() => {
+            CheckResult::skipped("Credential is not revocable")
+        }
+        (Some(interval), Some(_)) => match timestamp {
+            None => CheckResult::failed("Presentation does not prove non-revocation of a revocable credential"),
+            Some(timestamp) if interval.from.map_or(false, |from| timestamp < from) => CheckResult::failed(format!(
+                "Non-revocation timestamp {} precedes the requested interval",
+                timestamp
+            )),
+            Some(timestamp) if interval.to.map_or(false, |to| timestamp > to) => CheckResult::failed(format!(
+                "Non-revocation timestamp {} exceeds the requested interval",
+                timestamp
+            )),
+            Some(_) => CheckResult::Passed,
+        },
+    };
+    NonRevocationReport {
+        interval: interval.cloned(),
+        timestamp,
+        check,
+    }
+}
+
+fn check_restrictions(
+    restrictions: Option<&Restrictions>,
+    identifier: Option<&CredentialIdentifier>,
+    revealed_values: Option<&HashMap<String, String>>,
+) -> CheckResult {
+    let restrictions = match restrictions.map(serde_json::to_value) {
+        None => return CheckResult::skipped("No restrictions were requested"),
+        Some(Ok(restrictions)) => restrictions,
+        Some(Err(err)) => return CheckResult::failed(format!("Cannot serialize restrictions: {}", err)),
+    };
+    let identifier = match identifier {
+        Some(identifier) => identifier,
+        None => return CheckResult::failed("Presentation does not list the credential identifiers"),
+    };
+    let evaluator = RestrictionEvaluator {
+        identifier,
+        revealed_values,
+    };
+    match evaluator.evaluate(&restrictions) {
+        Some(true) => CheckResult::Passed,
+        Some(false) => CheckResult::failed(format!(
+            "Credential {} does not match restrictions {}",
+            identifier.cred_def_id, restrictions
+        )),
+        None => CheckResult::skipped(format!("Cannot evaluate restrictions {}", restrictions)),
+    }
+}
+
+/// Evaluates indy restriction queries against the identifiers of a credential and the values
+/// revealed from it. Evaluation yields `None` for queries which cannot be decided from the
+/// presentation alone, such as attribute values the credential was not asked to reveal.
+struct RestrictionEvaluator<'a> {
+    identifier: &'a CredentialIdentifier,
+    revealed_values: Option<&'a HashMap<String, String>>,
+}
+
+impl RestrictionEvaluator<'_> {
+    fn evaluate(&self, query: &Value) -> Option<bool> {
+        match query {
+            Value::Array(queries) if queries.is_empty() => Some(true),
+            Value::Array(queries) => any(queries.iter().map(|query| self.evaluate(query))),
+            Value::Object(query) => self.evaluate_object(query),
+            _ => None,
+        }
+    }
+
+    fn evaluate_object(&self, query: &Map<String, Value>) -> Option<bool> {
+        all(query.iter().map(|(key, value)| match key.as_str() {
+            "$or" => value
+                .as_array()
+                .and_then(|queries| any(queries.iter().map(|query| self.evaluate(query)))),
+            "$and" => value
+                .as_array()
+                .and_then(|queries| all(queries.iter().map(|query| self.evaluate(query)))),
+            "$not" => self.evaluate(value).map(|matched| !matched),
+            _ => {
+                let actual = self.field_value(key)?;
+                match value {
+                    Value::String(expected) => Some(*expected == actual),
+                    Value::Object(operator) => operator.get("$in").and_then(Value::as_array).map(|expected| {
+                        expected
+                            .iter()
+                            .any(|expected| expected.as_str() == Some(actual.as_str()))
+                    }),
+                    _ => None,
+                }
+            }
+        }))
+    }
+
+    fn field_value(&self, key: &str) -> Option<String> {
+        let schema_parts: Vec<&str> = self.identifier.schema_id.split(':').collect();
+        let cred_def_parts: Vec<&str> = self.identifier.cred_def_id.split(':').collect();
+        match key {
+            "schema_id" => Some(self.identifier.schema_id.clone()),
+            "cred_def_id" => Some(self.identifier.cred_def_id.clone()),
+            "rev_reg_id" => self.identifier.rev_reg_id.clone(),
+            "issuer_did" if cred_def_parts.len() == 5 => Some(cred_def_parts[0].to_string()),
+            "schema_issuer_did" if schema_parts.len() == 4 => Some(schema_parts[0].to_string()),
+            "schema_name" if schema_parts.len() == 4 => Some(schema_parts[2].to_string()),
+            "schema_version" if schema_parts.len() == 4 => Some(schema_parts[3].to_string()),
+            _ => {
+                let name = key.strip_prefix("attr::")?.strip_suffix("::value")?;
+                self.revealed_value(name)
+            }
+        }
+    }
+
+    fn revealed_value(&self, name: &str) -> Option<String> {
+        self.revealed_values?.get(&normalize_attr_name(name)).cloned()
+    }
+}
+
+fn normalize_attr_name(name: &str) -> String {
+    name.replace(' ', "").to_lowercase()
+}
+
+fn any(results: impl Iterator<Item = Option<bool>>) -> Option<bool> {
+    let mut undecided = false;
+    for result in results {
+        match result {
+            Some(true) => return Some(true),
+            Some(false) => {}
+            None => undecided = true,
+        }
+    }
+    if undecided {
+        None
+    } else {
+        Some(false)
+    }
+}
+
+fn all(results: impl Iterator<Item = Option<bool>>) -> Option<bool> {
+    let mut undecided = false;
+    for result in results {
+        match result {
+            Some(true) => {}
+            Some(false) => return Some(false),
+            None => undecided = true,
+        }
+    }
+    if undecided {
+        None
+    } else {
+        Some(true)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use super::*;
+
+    const SCHEMA_ID: &str = "V4SGRU86Z58d6TV7PBUe6f:2:degree:1.0";
+    const CRED_DEF_ID: &str = "V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag";
+    const REV_REG_ID: &str = "V4SGRU86Z58d6TV7PBUe6f:4:V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag:CL_ACCUM:tag";
+
+    fn _proof_request(requested_attributes: Value, requested_predicates: Value) -> String {
+        json!({
+            "nonce": "123432421212",
+            "name": "proof_req_1",
+            "version": "1.0",
+            "requested_attributes": requested_attributes,
+            "requested_predicates": requested_predicates,
+        })
+        .to_string()
+    }
+
+    fn _proof(requested_proof: Value, rev_reg_id: Option<&str>, timestamp: Option<u64>) -> String {
+        json!({
+            "proof": {},
+            "requested_proof": requested_proof,
+            "identifiers": [{
+                "schema_id": SCHEMA_ID,
+                "cred_def_id": CRED_DEF_ID,
+                "rev_reg_id": rev_reg_id,
+                "timestamp": timestamp
+            }]
+        })
+        .to_string()
+    }
+
+    fn _revealed_name(encoded: &str) -> Value {
+        json!({
+            "revealed_attrs": {
+                "name": {"sub_proof_index": 0, "raw": "Alice", "encoded": encoded}
+            },
+            "self_attested_attrs": {},
+            "unrevealed_attrs": {},
+            "predicates": {}
+        })
+    }
+
+    fn _alice_encoded() -> String {
+        encode("Alice").unwrap()
+    }
+
+    #[test]
+    fn test_report_for_valid_presentation() {
+        let proof_request = _proof_request(
+            json!({
+                "name": {"name": "name", "restrictions": [{"schema_name": "degree", "issuer_did": "V4SGRU86Z58d6TV7PBUe6f"}]}
+            }),
+            json!({
+                "age": {"name": "age", "p_type": ">=", "p_value": 18, "restrictions": {"cred_def_id": CRED_DEF_ID}}
+            }),
+        );
+        let mut requested_proof = _revealed_name(&_alice_encoded());
+        requested_proof["predicates"] = json!({"age": {"sub_proof_index": 0}});
+        let proof = _proof(requested_proof, None, None);
+
+        let mut report = VerificationReport::build(&proof, &proof_request).unwrap();
+        assert!(!report.valid);
+        report.set_cryptographic_result(true);
+        assert!(report.valid);
+
+        let name = &report.requested_attributes["name"];
+        assert_eq!(name.restrictions, CheckResult::Passed);
+        let AttributeDisclosure::Revealed {
+            sub_proof_index,
+            values,
+        } = &name.disclosure
+        else {
+            panic!("expected a revealed attribute, got {:?}", name.disclosure);
+        };
+        assert_eq!(*sub_proof_index, 0);
+        assert_eq!(values["name"].raw, "Alice");
+        assert_eq!(values["name"].encoding, CheckResult::Passed);
+        assert!(matches!(name.non_revocation.check, CheckResult::Skipped { .. }));
+
+        let age = &report.requested_predicates["age"];
+        assert_eq!(age.restrictions, CheckResult::Passed);
+        assert_eq!(age.satisfied, CheckResult::Passed);
+    }
+
+    #[test]
+    fn test_report_records_failed_encoding() {
+        let proof_request = _proof_request(json!({"name": {"name": "name"}}), json!({}));
+        let proof = _proof(_revealed_name("1234"), None, None);
+
+        let mut report = VerificationReport::build(&proof, &proof_request).unwrap();
+        report.set_cryptographic_result(true);
+
+        assert!(!report.valid);
+        let AttributeDisclosure::Revealed { values, .. } = &report.requested_attributes["name"].disclosure else {
+            panic!("expected a revealed attribute");
+        };
+        assert!(values["name"].encoding.is_failed());
+    }
+
+    #[test]
+    fn test_report_records_unmatched_restrictions() {
+        let proof_request = _proof_request(
+            json!({
+                "name": {"name": "name", "restrictions": {"$or": [{"schema_name": "passport"}, {"attr::name::value": "Bob"}]}}
+            }),
+            json!({}),
+        );
+        let proof = _proof(_revealed_name(&_alice_encoded()), None, None);
+
+        let mut report = VerificationReport::build(&proof, &proof_request).unwrap();
+        report.set_cryptographic_result(true);
+
+        assert!(report.valid);
+        assert!(report.requested_attributes["name"].restrictions.is_failed());
+    }
+
+    #[test]
+    fn test_report_resolves_attribute_values_within_the_credential() {
+        let proof_request = _proof_request(
+            json!({
+                "name": {"name": "name"},
+                "holder": {"name": "holder", "restrictions": {"attr::holder::value": "Bob"}},
+                "degree": {"name": "degree", "restrictions": {"attr::name::value": "Alice"}}
+            }),
+            json!({}),
+        );
+        let proof = json!({
+            "proof": {},
+            "requested_proof": {
+                "revealed_attrs": {
+                    "name": {"sub_proof_index": 0, "raw": "Alice", "encoded": _alice_encoded()},
+                    "holder": {"sub_proof_index": 1, "raw": "Bob", "encoded": encode("Bob").unwrap()},
+                    "degree": {"sub_proof_index": 1, "raw": "Maths", "encoded": encode("Maths").unwrap()}
+                },
+                "self_attested_attrs": {},
+                "unrevealed_attrs": {},
+                "predicates": {}
+            },
+            "identifiers": [
+                {"schema_id": SCHEMA_ID, "cred_def_id": CRED_DEF_ID, "rev_reg_id": null, "timestamp": null},
+                {"schema_id": SCHEMA_ID, "cred_def_id": CRED_DEF_ID, "rev_reg_id": null, "timestamp": null}
+            ]
+        })
+        .to_string();
+
+        let mut report = VerificationReport::build(&proof, &proof_request).unwrap();
+        report.set_cryptographic_result(true);
+
+        assert!(report.valid);
+        assert_eq!(report.requested_attributes["holder"].restrictions, CheckResult::Passed);
+        assert!(matches!(
+            report.requested_attributes["degree"].restrictions,
+            CheckResult::Skipped { .. }
+        ));
+    }
+
+    #[test]
+    fn test_report_skips_undecidable_restrictions() {
+        let proof_request = _proof_request(
+            json!({"name": {"name": "name", "restrictions": {"attr::age::marker": "1"}}}),
+            json!({}),
+        );
+        let proof = _proof(_revealed_name(&_alice_encoded()), None, None);
+
+        let mut report = VerificationReport::build(&proof, &proof_request).unwrap();
+        report.set_cryptographic_result(true);
+
+        assert!(report.valid);
+        assert!(matches!(
+            report.requested_attributes["name"].restrictions,
+            CheckResult::Skipped { .. }
+        ));
+    }
+
+    #[test]
+    fn test_report_checks_non_revocation_timestamp() {
+        let proof_request = _proof_request(
+            json!({"name": {"name": "name", "non_revoked": {"from": 100, "to": 200}}}),
+            json!({}),
+        );
+
+        let proof = _proof(_revealed_name(&_alice_encoded()), Some(REV_REG_ID), Some(150));
+        let report = VerificationReport::build(&proof, &proof_request).unwrap();
+        let non_revocation = &report.requested_attributes["name"].non_revocation;
+        assert_eq!(non_revocation.check, CheckResult::Passed);
+        assert_eq!(non_revocation.timestamp, Some(150));
+
+        let proof = _proof(_revealed_name(&_alice_encoded()), Some(REV_REG_ID), Some(250));
+        let report = VerificationReport::build(&proof, &proof_request).unwrap();
+        assert!(report.requested_attributes["name"].non_revocation.check.is_failed());
+
+        let proof = _proof(_revealed_name(&_alice_encoded()), Some(REV_REG_ID), None);
+        let report = VerificationReport::build(&proof, &proof_request).unwrap();
+        assert!(report.requested_attributes["name"].non_revocation.check.is_failed());
+    }
+
+    #[test]
+    fn test_report_for_self_attested_and_missing_attributes() {
+        let proof_request = _proof_request(
+            json!({
+                "address": {"name": "address"},
+                "restricted": {"name": "zip", "restrictions": {"schema_name": "degree"}},
+                "missing": {"name": "missing"}
+            }),
+            json!({}),
+        );
+        let requested_proof = json!({
+            "revealed_attrs": {},
+            "self_attested_attrs": {"address": "Main street", "restricted": "12345"},
+            "unrevealed_attrs": {},
+            "predicates": {}
+        });
+        let proof = _proof(requested_proof, None, None);
+
+        let mut report = VerificationReport::build(&proof, &proof_request).unwrap();
+        report.set_cryptographic_result(true);
+
+        assert!(report.valid);
+        let address = &report.requested_attributes["address"];
+        assert_eq!(
+            address.disclosure,
+            AttributeDisclosure::SelfAttested {
+                value: "Main street".to_string()
+            }
+        );
+        assert!(matches!(address.restrictions, CheckResult::Skipped { .. }));
+        assert!(report.requested_attributes["restricted"].restrictions.is_failed());
+        assert_eq!(
+            report.requested_attributes["missing"].disclosure,
+            AttributeDisclosure::Missing
+        );
+    }
+
+    #[test]
+    fn test_report_for_failed_cryptographic_verification() {
+        let report = VerificationReport::from_cryptographic_result(false);
+        assert!(!report.valid);
+        assert!(report.cryptographic.is_failed());
+    }
+
+    #[test]
+    fn test_report_ser_deser() {
+        let proof_request = _proof_request(json!({"name": {"name": "name"}}), json!({}));
+        let proof = _proof(_revealed_name(&_alice_encoded()), None, None);
+        let mut report = VerificationReport::build(&proof, &proof_request).unwrap();
+        report.set_cryptographic_result(true);
+
+        let serialized = serde_json::to_string(&report).unwrap();
+        let deserialized: VerificationReport = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(report, deserialized);
+    }
+}
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::common::proofs::verifier::verification_report::VerificationReport;
 use crate::common::proofs::verifier::verifier_internal::{
     build_cred_defs_json_verifier, build_rev_reg_defs_json, build_rev_reg_json, build_schemas_json_verifier,
     get_credential_info, validate_proof_revealed_attributes,
@@ -17,9 +18,33 @@ pub async fn validate_indy_proof(
         return mock_result;
     }
 
-    let anoncreds = Arc::clone(profile).inject_anoncreds();
     validate_proof_revealed_attributes(proof_json)?;
+    verify_proof_cryptography(profile, proof_json, proof_req_json).await
+}
+
+/// Verifies the presentation and reports the outcome of every check performed, per referent of
+/// the presentation request. Failed checks are recorded in the report rather than returned as errors.
+pub async fn verify_indy_proof(
+    profile: &Arc<dyn Profile>,
+    proof_json: &str,
+    proof_req_json: &str,
+) -> VcxResult<VerificationReport> {
+    if let Some(mock_result) = get_mock_result_for_validate_indy_proof() {
+        return mock_result.map(VerificationReport::from_cryptographic_result);
+    }
 
+    let mut report = VerificationReport::build(proof_json, proof_req_json)?;
+    let valid = verify_proof_cryptography(profile, proof_json, proof_req_json).await?;
+    report.set_cryptographic_result(valid);
+    Ok(report)
+}
+
+async fn verify_proof_cryptography(
+    profile: &Arc<dyn Profile>,
+    proof_json: &str,
+    proof_req_json: &str,
+) -> VcxResult<bool> {
+    let anoncreds = Arc::clone(profile).inject_anoncreds();
     let credential_data = get_credential_info(proof_json)?;
 
     let credential_defs_json = build_cred_defs_json_verifier(profile, &credential_data)
@@ -3,9 +3,11 @@ use std::sync::Arc;
 use base64;
 use time;
 
+use crate::common::did_resolver::key::ed25519_public_key_to_did_key;
 use crate::errors::error::prelude::*;
 use crate::{global::settings, plugins::wallet::base_wallet::BaseWallet};
 use messages::protocols::connection::response::{ConnectionData, ConnectionSignature, Response, SignedResponse};
+use messages2::decorators::attachment::{Attachment, AttachmentJws, AttachmentType, JwsHeader};
 
 async fn get_signature_data(wallet: &Arc<dyn BaseWallet>, data: String, key: &str) -> VcxResult<(Vec<u8>, Vec<u8>)> {
     let now: u64 = time::get_time().sec as u64;
@@ -95,6 +97,91 @@ pub async fn decode_signed_connection_response(
     })
 }
 
+pub(crate) fn attachment_payload(attachment: &Attachment) -> VcxResult<Vec<u8>> {
+    match &attachment.data.content {
+        AttachmentType::Base64(bytes) => Ok(bytes.clone()),
+        AttachmentType::Json(value) => Ok(value.to_string().into_bytes()),
+        AttachmentType::Links(_) => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Cannot sign an attachment that only references its content through links",
+        )),
+    }
+}
+
+/// Signs the content of the attachment with the provided key, storing the detached JWS
+/// in the attachment data.
+pub async fn sign_attachment(wallet: &Arc<dyn BaseWallet>, key: &str, attachment: &mut Attachment) -> VcxResult<()> {
+    let payload = attachment_payload(attachment)?;
+    let did_key = ed25519_public_key_to_did_key(key)?;
+    let public_key = bs58::decode(key).into_vec().map_err(|_| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidVerkey,
+            format!("Could not base58 decode verkey: {}", key),
+        )
+    })?;
+
+    let protected = json!({
+        "alg": "EdDSA",
+        "kid": did_key,
+        "jwk": {
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": base64::encode_config(&public_key, base64::URL_SAFE_NO_PAD),
+            "kid": did_key
+        }
+    })
+    .to_string();
+    let protected = base64::encode_config(protected.as_bytes(), base64::URL_SAFE_NO_PAD);
+    let signing_input = format!(
+        "{}.{}",
+        protected,
+        base64::encode_config(&payload, base64::URL_SAFE_NO_PAD)
+    );
+
+    let signature = wallet.sign(key, signing_input.as_bytes()).await?;
+    let signature = base64::encode_config(&signature, base64::URL_SAFE_NO_PAD);
+
+    let mut jws = AttachmentJws::new(protected, signature);
+    jws.header = Some(JwsHeader::new(did_key));
+    attachment.data.jws = Some(jws);
+
+    Ok(())
+}
+
+/// Verifies the detached JWS of the attachment against the provided key and
+/// returns the signed content.
+pub async fn verify_signed_attachment(
+    wallet: &Arc<dyn BaseWallet>,
+    key: &str,
+    attachment: &Attachment,
+) -> VcxResult<Vec<u8>> {
+    let jws = attachment.data.jws.as_ref().ok_or_else(|| {
+        AriesVcxError::from_msg(AriesVcxErrorKind::InvalidJson, "Attachment is expected to be signed")
+    })?;
+    let payload = attachment_payload(attachment)?;
+
+    let signature = base64::decode_config(jws.signature.as_bytes(), base64::URL_SAFE_NO_PAD).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("Cannot decode attachment signature: {:?}", err),
+        )
+    })?;
+    let signing_input = format!(
+        "{}.{}",
+        jws.protected,
+        base64::encode_config(&payload, base64::URL_SAFE_NO_PAD)
+    );
+
+    if !wallet.verify(key, signing_input.as_bytes(), &signature).await? {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("Attachment signature is invalid for key {}", key),
+        ));
+    }
+
+    Ok(payload)
+}
+
 pub async fn unpack_message_to_string(wallet: &Arc<dyn BaseWallet>, msg: &[u8]) -> VcxResult<String> {
     if settings::indy_mocks_enabled() {
         return Ok(String::new());
@@ -122,6 +209,7 @@ pub mod unit_tests {
     use crate::utils::devsetup::SetupEmpty;
     use messages::diddoc::aries::diddoc::test_utils::*;
     use messages::protocols::connection::response::test_utils::{_did, _response, _thread_id};
+    use messages2::decorators::attachment::AttachmentData;
 
     use super::*;
 
@@ -174,4 +262,29 @@ pub mod unit_tests {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn test_signed_attachment_roundtrip() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let profile = indy_handles_to_profile(wallet_handle, 0);
+            let wallet = profile.inject_wallet();
+            let trustee_key = create_trustee_key(&profile).await;
+            let content = AttachmentType::Base64(b"test_attachment_content".to_vec());
+            let mut attachment = Attachment::new(AttachmentData::new(content));
+
+            sign_attachment(&wallet, &trustee_key, &mut attachment).await.unwrap();
+
+            let payload = verify_signed_attachment(&wallet, &trustee_key, &attachment)
+                .await
+                .unwrap();
+            assert_eq!(payload, b"test_attachment_content".to_vec());
+
+            let (_, other_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            verify_signed_attachment(&wallet, &other_key, &attachment)
+                .await
+                .unwrap_err();
+        })
+        .await;
+    }
 }
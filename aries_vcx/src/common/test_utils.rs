@@ -1,7 +1,11 @@
-use std::sync::Arc;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
+use async_trait::async_trait;
+use messages::diddoc::aries::diddoc::AriesDidDoc;
+use messages2::AriesMessage;
 use vdrtools::{PoolHandle, WalletHandle};
 
 use crate::common::credentials::encoding::encode_attributes;
@@ -10,10 +14,18 @@ use crate::common::primitives::credential_definition::CredentialDefConfigBuilder
 use crate::common::primitives::revocation_registry::RevocationRegistry;
 use crate::core::profile::profile::Profile;
 use crate::core::profile::vdrtools_profile::VdrtoolsProfile;
+use crate::errors::error::VcxResult;
 use crate::global::settings;
+use crate::plugins::wallet::base_wallet::BaseWallet;
+use crate::protocols::connection::initiation_type::Invitee;
+use crate::protocols::connection::invitee::states::completed::Completed;
+use crate::protocols::connection::pairwise_info::PairwiseInfo;
+use crate::protocols::connection::Connection;
+use crate::transport::{DuplexTransport, Transport};
 use crate::utils::constants::{DEFAULT_SCHEMA_ATTRS, TAILS_DIR, TEST_TAILS_URL, TRUSTEE_SEED};
 use crate::utils::get_temp_dir_path;
 use crate::utils::mockdata::profile::mock_profile::MockProfile;
+use crate::utils::uuid::uuid;
 
 pub async fn create_schema(profile: &Arc<dyn Profile>, attr_list: &str, submitter_did: &str) -> (String, String) {
     let data = attr_list.to_string();
@@ -439,3 +451,80 @@ pub fn mock_profile() -> Arc<dyn Profile> {
 pub fn indy_handles_to_profile(wallet_handle: WalletHandle, pool_handle: PoolHandle) -> Arc<dyn Profile> {
     Arc::new(VdrtoolsProfile::new(wallet_handle, pool_handle))
 }
+
+/// Keeps every message sent through it and answers each one with the next queued reply, as a
+/// counterparty honoring `~transport.return_route` does.
+#[derive(Default)]
+pub struct CapturingTransport {
+    sent: Mutex<Vec<(Vec<u8>, String)>>,
+    replies: Mutex<VecDeque<Vec<u8>>>,
+}
+
+#[async_trait]
+impl Transport for CapturingTransport {
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &str) -> VcxResult<()> {
+        self.sent.lock()?.push((msg, service_endpoint.to_owned()));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DuplexTransport for CapturingTransport {
+    async fn receive_message(&self, _service_endpoint: &str) -> VcxResult<Option<Vec<u8>>> {
+        Ok(self.replies.lock()?.pop_front())
+    }
+}
+
+impl CapturingTransport {
+    pub fn queue_reply(&self, reply: Vec<u8>) {
+        self.replies.lock().unwrap().push_back(reply);
+    }
+
+    /// Returns the last message sent, still packed, along with the endpoint it was sent to.
+    pub fn take_packed(&self) -> (Vec<u8>, String) {
+        self.sent.lock().unwrap().pop().unwrap()
+    }
+
+    /// Returns the last message sent, unpacked with `wallet`.
+    pub async fn take_message(&self, wallet: &Arc<dyn BaseWallet>) -> AriesMessage {
+        let (packed, _) = self.take_packed();
+        let unpacked = wallet.unpack_message(&packed).await.unwrap();
+        let unpacked: serde_json::Value = serde_json::from_slice(&unpacked).unwrap();
+        serde_json::from_str(unpacked["message"].as_str().unwrap()).unwrap()
+    }
+}
+
+/// A [`Completed`] connection to a counterparty whose key is held in the same wallet, so the
+/// messages sent over it can be unpacked and replies packed on the counterparty's behalf.
+pub struct CompletedConnection {
+    pub connection: Connection<Invitee, Completed>,
+    pub their_vk: String,
+}
+
+impl CompletedConnection {
+    pub async fn new(wallet: &Arc<dyn BaseWallet>, service_endpoint: &str) -> Self {
+        let pairwise_info = PairwiseInfo::create(wallet).await.unwrap();
+        let (_, their_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+
+        let mut did_doc = AriesDidDoc::default();
+        did_doc.set_service_endpoint(service_endpoint.to_owned());
+        did_doc.set_recipient_keys(vec![their_vk.clone()]);
+
+        let state = Completed::new(did_doc.clone(), did_doc, uuid(), None);
+        let connection = Connection::from_parts("counterparty".to_owned(), pairwise_info, Invitee, state);
+        Self { connection, their_vk }
+    }
+
+    /// Packs `reply` as sent by the counterparty over the connection.
+    pub async fn pack_reply(&self, wallet: &Arc<dyn BaseWallet>, reply: &AriesMessage) -> Vec<u8> {
+        let receiver_keys = serde_json::json!([self.connection.pairwise_info().pw_vk]).to_string();
+        wallet
+            .pack_message(
+                Some(&self.their_vk),
+                &receiver_keys,
+                &serde_json::to_vec(reply).unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+}
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::common::did_resolver::DidResolverRegistry;
 use crate::errors::error::VcxResult;
 use crate::plugins::ledger::indy_vdr_ledger::LedgerPoolConfig;
 use crate::plugins::{
@@ -19,18 +20,27 @@ pub struct ModularLibsProfile {
     wallet: Arc<dyn BaseWallet>,
     ledger: Arc<dyn BaseLedger>,
     anoncreds: Arc<dyn BaseAnonCreds>,
+    did_resolver: Arc<DidResolverRegistry>,
 }
 
 impl ModularLibsProfile {
     pub fn new(wallet: Arc<dyn BaseWallet>, ledger_pool_config: LedgerPoolConfig) -> VcxResult<Self> {
         let ledger_pool = Arc::new(IndyVdrLedgerPool::new(ledger_pool_config)?);
-        let ledger = Arc::new(IndyVdrLedger::new(Arc::clone(&wallet), ledger_pool));
-        let anoncreds = Arc::new(IndyCredxAnonCreds::new(Arc::clone(&wallet)));
-        Ok(ModularLibsProfile {
+        let ledger: Arc<dyn BaseLedger> = Arc::new(IndyVdrLedger::new(Arc::clone(&wallet), ledger_pool));
+        Ok(ModularLibsProfile::new_with_ledger(wallet, ledger))
+    }
+
+    /// Creates a profile over any [BaseLedger] implementation, such as the
+    /// [InMemoryLedger](crate::plugins::ledger::in_memory_ledger::InMemoryLedger) for offline testing
+    pub fn new_with_ledger(wallet: Arc<dyn BaseWallet>, ledger: Arc<dyn BaseLedger>) -> Self {
+        let anoncreds = Arc::new(IndyCredxAnonCreds::new(Arc::clone(&wallet), Arc::clone(&ledger)));
+        let did_resolver = Arc::new(DidResolverRegistry::with_default_resolvers(Arc::clone(&ledger)));
+        ModularLibsProfile {
             wallet,
             ledger,
             anoncreds,
-        })
+            did_resolver,
+        }
     }
 }
 
@@ -46,4 +56,8 @@ impl Profile for ModularLibsProfile {
     fn inject_wallet(&self) -> Arc<dyn BaseWallet> {
         Arc::clone(&self.wallet)
     }
+
+    fn inject_did_resolver(self: Arc<Self>) -> Arc<DidResolverRegistry> {
+        Arc::clone(&self.did_resolver)
+    }
 }
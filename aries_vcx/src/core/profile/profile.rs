@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
-use crate::plugins::{
-    anoncreds::base_anoncreds::BaseAnonCreds, ledger::base_ledger::BaseLedger, wallet::base_wallet::BaseWallet,
+use crate::{
+    common::did_resolver::DidResolverRegistry,
+    plugins::{
+        anoncreds::base_anoncreds::BaseAnonCreds, ledger::base_ledger::BaseLedger, wallet::base_wallet::BaseWallet,
+    },
 };
 
 pub trait Profile: std::fmt::Debug + Send + Sync {
@@ -10,4 +13,7 @@ pub trait Profile: std::fmt::Debug + Send + Sync {
     fn inject_anoncreds(self: Arc<Self>) -> Arc<dyn BaseAnonCreds>;
 
     fn inject_wallet(&self) -> Arc<dyn BaseWallet>;
+
+    /// Returns the registry of the DID resolvers of the profile, resolving did:sov through its ledger.
+    fn inject_did_resolver(self: Arc<Self>) -> Arc<DidResolverRegistry>;
 }
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use vdrtools::{PoolHandle, WalletHandle};
 
+use crate::common::did_resolver::DidResolverRegistry;
 use crate::plugins::{
     anoncreds::{base_anoncreds::BaseAnonCreds, indy_anoncreds::IndySdkAnonCreds},
     ledger::{base_ledger::BaseLedger, indy_ledger::IndySdkLedger},
@@ -15,17 +16,20 @@ pub struct VdrtoolsProfile {
     wallet: Arc<dyn BaseWallet>,
     ledger: Arc<dyn BaseLedger>,
     anoncreds: Arc<dyn BaseAnonCreds>,
+    did_resolver: Arc<DidResolverRegistry>,
 }
 
 impl VdrtoolsProfile {
     pub fn new(indy_wallet_handle: WalletHandle, indy_pool_handle: PoolHandle) -> Self {
         let wallet = Arc::new(IndySdkWallet::new(indy_wallet_handle));
-        let ledger = Arc::new(IndySdkLedger::new(indy_wallet_handle, indy_pool_handle));
+        let ledger: Arc<dyn BaseLedger> = Arc::new(IndySdkLedger::new(indy_wallet_handle, indy_pool_handle));
         let anoncreds = Arc::new(IndySdkAnonCreds::new(indy_wallet_handle, indy_pool_handle));
+        let did_resolver = Arc::new(DidResolverRegistry::with_default_resolvers(Arc::clone(&ledger)));
         VdrtoolsProfile {
             wallet,
             ledger,
             anoncreds,
+            did_resolver,
         }
     }
 }
@@ -42,4 +46,8 @@ impl Profile for VdrtoolsProfile {
     fn inject_wallet(&self) -> Arc<dyn BaseWallet> {
         Arc::clone(&self.wallet)
     }
+
+    fn inject_did_resolver(self: Arc<Self>) -> Arc<DidResolverRegistry> {
+        Arc::clone(&self.did_resolver)
+    }
 }
@@ -96,6 +96,8 @@ pub enum AriesVcxErrorKind {
     NoPoolOpen,
     #[error("Message failed in post")]
     PostMessageFailed,
+    #[error("Timed out waiting for a message")]
+    Timeout,
 
     // Wallet
     #[error("Error Creating a wallet")]
@@ -0,0 +1,17 @@
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind};
+use aries_askar::{Error as AskarError, ErrorKind as AskarErrorKind};
+
+impl From<AskarError> for AriesVcxError {
+    fn from(err: AskarError) -> Self {
+        match err.kind() {
+            AskarErrorKind::Backend => AriesVcxError::from_msg(AriesVcxErrorKind::IOError, err),
+            AskarErrorKind::Busy => AriesVcxError::from_msg(AriesVcxErrorKind::IOError, err),
+            AskarErrorKind::Duplicate => AriesVcxError::from_msg(AriesVcxErrorKind::DuplicationWalletRecord, err),
+            AskarErrorKind::Encryption => AriesVcxError::from_msg(AriesVcxErrorKind::WalletAccessFailed, err),
+            AskarErrorKind::Input => AriesVcxError::from_msg(AriesVcxErrorKind::InvalidInput, err),
+            AskarErrorKind::NotFound => AriesVcxError::from_msg(AriesVcxErrorKind::WalletRecordNotFound, err),
+            AskarErrorKind::Unexpected => AriesVcxError::from_msg(AriesVcxErrorKind::UnknownError, err),
+            AskarErrorKind::Unsupported => AriesVcxError::from_msg(AriesVcxErrorKind::ActionNotSupported, err),
+        }
+    }
+}
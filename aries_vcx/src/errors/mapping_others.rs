@@ -21,3 +21,9 @@ impl<T> From<PoisonError<T>> for AriesVcxError {
         AriesVcxError::from_msg(AriesVcxErrorKind::InvalidState, err.to_string())
     }
 }
+
+impl From<openssl::error::ErrorStack> for AriesVcxError {
+    fn from(err: openssl::error::ErrorStack) -> Self {
+        AriesVcxError::from_msg(AriesVcxErrorKind::UnknownError, format!("OpenSSL error: {}", err))
+    }
+}
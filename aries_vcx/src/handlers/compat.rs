@@ -0,0 +1,121 @@
+//! Conversions between [`messages2::AriesMessage`] and the legacy [`messages::a2a::A2AMessage`],
+//! for code which still works with the legacy message types.
+
+use messages::a2a::A2AMessage;
+use messages2::AriesMessage;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::protocols::{AriesSendClosure, SendClosure};
+
+/// Converts a legacy message to an [`AriesMessage`].
+///
+/// As for any message parsed by `messages2`, a message of a newer minor version than the ones
+/// supported is read as the highest supported minor version of the same major version.
+pub fn from_legacy_message(message: &A2AMessage) -> VcxResult<AriesMessage> {
+    // `AriesMessage` borrows the `@type` while deserializing, which a `serde_json::Value` cannot lend.
+    let json = serde_json::to_string(message)?;
+    serde_json::from_str(&json).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!("Cannot convert legacy message to an aries message: {}", err),
+        )
+    })
+}
+
+/// Converts an [`AriesMessage`] to a legacy message. Messages of protocols the legacy types
+/// do not implement are converted to [`A2AMessage::Generic`].
+pub fn to_legacy_message(message: &AriesMessage) -> VcxResult<A2AMessage> {
+    let value = serde_json::to_value(message)?;
+    serde_json::from_value(value).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!("Cannot convert aries message to a legacy message: {}", err),
+        )
+    })
+}
+
+/// Wraps a closure sending [`AriesMessage`]s into one accepting the legacy messages produced by
+/// the state machines, converting every message before it is sent.
+pub fn to_legacy_send_closure(send_message: AriesSendClosure) -> SendClosure {
+    Box::new(move |message: A2AMessage| {
+        Box::pin(async move {
+            let message = from_legacy_message(&message)?;
+            send_message(message).await
+        })
+    })
+}
+
+/// Wraps a closure sending legacy messages into one accepting [`AriesMessage`]s, for dispatching
+/// messages over connections which still send the legacy types. Messages are passed on as
+/// [`A2AMessage::Generic`], since not all of them keep their fields through the legacy types.
+pub fn from_legacy_send_closure(send_message: SendClosure) -> AriesSendClosure {
+    Box::new(move |message: AriesMessage| {
+        Box::pin(async move {
+            let message = A2AMessage::Generic(serde_json::to_value(&message)?);
+            send_message(message).await
+        })
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use messages::{a2a::MessageId, protocols::trust_ping::ping::Ping};
+    use messages2::msg_fields::protocols::{coordinate_mediation::CoordinateMediation, trust_ping::TrustPing};
+
+    use super::*;
+
+    #[test]
+    fn test_legacy_message_round_trip() {
+        let ping = Ping::create(MessageId("ping_id".to_owned()))
+            .set_thread_id("thread_id")
+            .to_a2a_message();
+
+        let message = from_legacy_message(&ping).unwrap();
+        let AriesMessage::TrustPing(TrustPing::Ping(aries_ping)) = &message else {
+            panic!("expected a ping, got {:?}", message);
+        };
+        assert_eq!(aries_ping.id, "ping_id");
+        assert_eq!(aries_ping.decorators.thread.as_ref().unwrap().thid, "thread_id");
+
+        let A2AMessage::Ping(legacy_ping) = to_legacy_message(&message).unwrap() else {
+            panic!("expected a legacy ping");
+        };
+        assert_eq!(legacy_ping.id.0, "ping_id");
+        assert_eq!(legacy_ping.get_thread_id(), "thread_id");
+    }
+
+    #[test]
+    fn test_legacy_message_resolves_supported_minor_version() {
+        let request = A2AMessage::Generic(json!({
+            "@id": "request_id",
+            "@type": "https://didcomm.org/coordinate-mediation/1.3/mediate-request"
+        }));
+
+        let message = from_legacy_message(&request).unwrap();
+        assert!(matches!(
+            message,
+            AriesMessage::CoordinateMediation(CoordinateMediation::MediateRequest(_))
+        ));
+
+        let A2AMessage::Generic(value) = to_legacy_message(&message).unwrap() else {
+            panic!("expected a generic message");
+        };
+        assert_eq!(
+            value["@type"],
+            "https://didcomm.org/coordinate-mediation/1.0/mediate-request"
+        );
+    }
+
+    #[test]
+    fn test_legacy_message_of_unknown_protocol_is_rejected() {
+        let message = A2AMessage::Generic(json!({
+            "@id": "message_id",
+            "@type": "https://didcomm.org/unknown-protocol/1.0/message"
+        }));
+        assert_eq!(
+            from_legacy_message(&message).unwrap_err().kind(),
+            AriesVcxErrorKind::InvalidMessageFormat
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! [`MessageHandler`] implementations for the handlers whose state machines still work with the
+//! legacy message types. Inbound messages are converted before being passed to them.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use messages::a2a::A2AMessage;
+use messages2::AriesMessage;
+
+use super::MessageHandler;
+use crate::core::profile::profile::Profile;
+use crate::errors::error::prelude::*;
+use crate::handlers::compat::{to_legacy_message, to_legacy_send_closure};
+use crate::handlers::issuance::holder::Holder;
+use crate::handlers::issuance::issuer::Issuer;
+use crate::handlers::proof_presentation::prover::Prover;
+use crate::handlers::proof_presentation::verifier::Verifier;
+use crate::handlers::revocation_notification::receiver::RevocationNotificationReceiver;
+use crate::handlers::trust_ping::TrustPingSender;
+use crate::protocols::AriesSendClosure;
+
+#[async_trait]
+impl MessageHandler for Issuer {
+    async fn handle_message(
+        &mut self,
+        profile: &Arc<dyn Profile>,
+        message: AriesMessage,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<()> {
+        let message = to_legacy_message(&message)?;
+        self.step(profile, message.into(), Some(to_legacy_send_closure(send_message)))
+            .await
+    }
+}
+
+#[async_trait]
+impl MessageHandler for Holder {
+    async fn handle_message(
+        &mut self,
+        profile: &Arc<dyn Profile>,
+        message: AriesMessage,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<()> {
+        let message = to_legacy_message(&message)?;
+        self.step(profile, message.into(), Some(to_legacy_send_closure(send_message)))
+            .await
+    }
+}
+
+#[async_trait]
+impl MessageHandler for Prover {
+    async fn handle_message(
+        &mut self,
+        profile: &Arc<dyn Profile>,
+        message: AriesMessage,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<()> {
+        let message = to_legacy_message(&message)?;
+        self.step(profile, message.into(), Some(to_legacy_send_closure(send_message)))
+            .await
+    }
+}
+
+#[async_trait]
+impl MessageHandler for Verifier {
+    async fn handle_message(
+        &mut self,
+        profile: &Arc<dyn Profile>,
+        message: AriesMessage,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<()> {
+        let message = to_legacy_message(&message)?;
+        self.step(profile, message.into(), Some(to_legacy_send_closure(send_message)))
+            .await
+    }
+}
+
+#[async_trait]
+impl MessageHandler for TrustPingSender {
+    async fn handle_message(
+        &mut self,
+        _profile: &Arc<dyn Profile>,
+        message: AriesMessage,
+        _send_message: AriesSendClosure,
+    ) -> VcxResult<()> {
+        match to_legacy_message(&message)? {
+            A2AMessage::PingResponse(ping_response) => self.handle_ping_response(&ping_response),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessages,
+                "TrustPingSender can only handle ping responses",
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl MessageHandler for RevocationNotificationReceiver {
+    async fn handle_message(
+        &mut self,
+        _profile: &Arc<dyn Profile>,
+        message: AriesMessage,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<()> {
+        match to_legacy_message(&message)? {
+            A2AMessage::RevocationNotification(notification) => {
+                *self = self
+                    .clone()
+                    .handle_revocation_notification(notification, to_legacy_send_closure(send_message))
+                    .await?;
+                Ok(())
+            }
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessages,
+                "RevocationNotificationReceiver can only handle revocation notifications",
+            )),
+        }
+    }
+}
@@ -0,0 +1,566 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use messages2::{
+    decorators::thread::Thread,
+    msg_fields::protocols::{
+        connection::Connection,
+        coordinate_mediation::CoordinateMediation,
+        cred_issuance::{v1::CredentialIssuanceV1, v2::CredentialIssuanceV2, CredentialIssuance},
+        did_exchange::DidExchange,
+        discover_features::DiscoverFeatures,
+        out_of_band::OutOfBand,
+        pickup::Pickup,
+        present_proof::{v1::PresentProofV1, v2::PresentProofV2, PresentProof},
+        report_problem::{ProblemReport, ProblemReportContent, ProblemReportDecorators},
+        revocation::Revocation,
+        trust_ping::TrustPing,
+    },
+    AriesMessage,
+};
+use tokio::sync::Mutex;
+
+use crate::core::profile::profile::Profile;
+use crate::errors::error::prelude::*;
+use crate::protocols::AriesSendClosure;
+use crate::utils::uuid::uuid;
+
+#[cfg(feature = "legacy_messages")]
+mod legacy;
+
+/// Protocol family a message belongs to. Together with the thread id it identifies
+/// the handler responsible for the message.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ProtocolFamily {
+    Routing,
+    Connections,
+    TrustPing,
+    Notification,
+    ReportProblem,
+    IssueCredential,
+    RevocationNotification,
+    PresentProof,
+    DiscoverFeatures,
+    BasicMessage,
+    OutOfBand,
+    DidExchange,
+    CoordinateMediation,
+    Pickup,
+}
+
+impl ProtocolFamily {
+    pub fn from_message(message: &AriesMessage) -> Self {
+        match message {
+            AriesMessage::Routing(_) => Self::Routing,
+            AriesMessage::Connection(_) => Self::Connections,
+            AriesMessage::TrustPing(_) => Self::TrustPing,
+            AriesMessage::Notification(_) => Self::Notification,
+            AriesMessage::ReportProblem(_) => Self::ReportProblem,
+            AriesMessage::CredentialIssuance(_) => Self::IssueCredential,
+            AriesMessage::Revocation(_) => Self::RevocationNotification,
+            AriesMessage::PresentProof(_) => Self::PresentProof,
+            AriesMessage::DiscoverFeatures(_) => Self::DiscoverFeatures,
+            AriesMessage::BasicMessage(_) => Self::BasicMessage,
+            AriesMessage::OutOfBand(_) => Self::OutOfBand,
+            AriesMessage::DidExchange(_) => Self::DidExchange,
+            AriesMessage::CoordinateMediation(_) => Self::CoordinateMediation,
+            AriesMessage::Pickup(_) => Self::Pickup,
+        }
+    }
+
+    /// Acks and problem reports are adopted by other protocols for their own acknowledgements
+    /// and errors, so they can be handled by a handler of any family.
+    fn is_adoptable(self) -> bool {
+        matches!(self, Self::Notification | Self::ReportProblem)
+    }
+}
+
+/// Returns the id of the thread the message belongs to, if the message can be part of a thread.
+/// Messages which do not carry the `~thread` decorator start a thread identified by their own id.
+pub fn message_thread_id(message: &AriesMessage) -> Option<String> {
+    let (id, thread) = match message {
+        AriesMessage::Routing(_)
+        | AriesMessage::Connection(Connection::Invitation(_))
+        | AriesMessage::DiscoverFeatures(DiscoverFeatures::Query(_))
+        | AriesMessage::OutOfBand(OutOfBand::Invitation(_)) => return None,
+        AriesMessage::Connection(Connection::Request(msg)) => (&msg.id, msg.decorators.thread.as_ref()),
+        AriesMessage::Connection(Connection::Response(msg)) => (&msg.id, Some(&msg.decorators.thread)),
+        AriesMessage::Connection(Connection::ProblemReport(msg)) => (&msg.id, Some(&msg.decorators.thread)),
+        AriesMessage::Revocation(Revocation::Revoke(msg)) => (&msg.id, msg.decorators.thread.as_ref()),
+        AriesMessage::Revocation(Revocation::Ack(msg)) => (&msg.id, Some(&msg.decorators.thread)),
+        AriesMessage::CredentialIssuance(CredentialIssuance::V1(msg)) => match msg {
+            CredentialIssuanceV1::OfferCredential(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            CredentialIssuanceV1::ProposeCredential(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            CredentialIssuanceV1::RequestCredential(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            CredentialIssuanceV1::IssueCredential(msg) => (&msg.id, Some(&msg.decorators.thread)),
+            CredentialIssuanceV1::Ack(msg) => (&msg.id, Some(&msg.decorators.thread)),
+        },
+        AriesMessage::CredentialIssuance(CredentialIssuance::V2(msg)) => match msg {
+            CredentialIssuanceV2::OfferCredential(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            CredentialIssuanceV2::ProposeCredential(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            CredentialIssuanceV2::RequestCredential(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            CredentialIssuanceV2::IssueCredential(msg) => (&msg.id, Some(&msg.decorators.thread)),
+            CredentialIssuanceV2::Ack(msg) => (&msg.id, Some(&msg.decorators.thread)),
+            CredentialIssuanceV2::ProblemReport(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+        },
+        AriesMessage::ReportProblem(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+        AriesMessage::PresentProof(PresentProof::V1(msg)) => match msg {
+            PresentProofV1::ProposePresentation(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            PresentProofV1::RequestPresentation(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            PresentProofV1::Presentation(msg) => (&msg.id, Some(&msg.decorators.thread)),
+            PresentProofV1::Ack(msg) => (&msg.id, Some(&msg.decorators.thread)),
+        },
+        AriesMessage::PresentProof(PresentProof::V2(msg)) => match msg {
+            PresentProofV2::ProposePresentation(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            PresentProofV2::RequestPresentation(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            PresentProofV2::Presentation(msg) => (&msg.id, Some(&msg.decorators.thread)),
+            PresentProofV2::Ack(msg) => (&msg.id, Some(&msg.decorators.thread)),
+            PresentProofV2::ProblemReport(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+        },
+        AriesMessage::TrustPing(TrustPing::Ping(msg)) => (&msg.id, msg.decorators.thread.as_ref()),
+        AriesMessage::TrustPing(TrustPing::PingResponse(msg)) => (&msg.id, Some(&msg.decorators.thread)),
+        AriesMessage::DiscoverFeatures(DiscoverFeatures::Disclose(msg)) => (&msg.id, msg.decorators.thread.as_ref()),
+        AriesMessage::BasicMessage(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+        AriesMessage::OutOfBand(OutOfBand::HandshakeReuse(msg)) => (&msg.id, Some(&msg.decorators.thread)),
+        AriesMessage::OutOfBand(OutOfBand::HandshakeReuseAccepted(msg)) => (&msg.id, Some(&msg.decorators.thread)),
+        AriesMessage::Notification(msg) => (&msg.id, Some(&msg.decorators.thread)),
+        AriesMessage::DidExchange(DidExchange::Request(msg)) => (&msg.id, msg.decorators.thread.as_ref()),
+        AriesMessage::DidExchange(DidExchange::Response(msg)) => (&msg.id, Some(&msg.decorators.thread)),
+        AriesMessage::DidExchange(DidExchange::Complete(msg)) => (&msg.id, Some(&msg.decorators.thread)),
+        AriesMessage::DidExchange(DidExchange::ProblemReport(msg)) => (&msg.id, Some(&msg.decorators.thread)),
+        AriesMessage::CoordinateMediation(msg) => match msg {
+            CoordinateMediation::MediateRequest(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            CoordinateMediation::MediateGrant(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            CoordinateMediation::MediateDeny(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            CoordinateMediation::KeylistUpdate(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            CoordinateMediation::KeylistUpdateResponse(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            CoordinateMediation::KeylistQuery(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            CoordinateMediation::Keylist(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+        },
+        AriesMessage::Pickup(msg) => match msg {
+            Pickup::StatusRequest(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            Pickup::Status(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            Pickup::DeliveryRequest(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            Pickup::Delivery(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            Pickup::MessagesReceived(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+            Pickup::LiveDeliveryChange(msg) => (&msg.id, msg.decorators.thread.as_ref()),
+        },
+    };
+    Some(thread.map_or(id, |thread| &thread.thid).to_owned())
+}
+
+/// Builds the `problem-report` sent back when no handler accepts a message.
+pub fn build_unhandled_message_problem_report(message: &AriesMessage) -> ProblemReport {
+    let content = ProblemReportContent {
+        description: Some(format!(
+            "No handler registered for message of protocol family {:?}",
+            ProtocolFamily::from_message(message)
+        )),
+        ..Default::default()
+    };
+    let decorators = ProblemReportDecorators {
+        thread: message_thread_id(message).map(Thread::new),
+        ..Default::default()
+    };
+    ProblemReport::with_decorators(uuid(), content, decorators)
+}
+
+/// Protocol handler which can be driven by inbound messages.
+#[async_trait]
+pub trait MessageHandler: Send + Sync {
+    async fn handle_message(
+        &mut self,
+        profile: &Arc<dyn Profile>,
+        message: AriesMessage,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<()>;
+}
+
+pub type SharedMessageHandler = Arc<Mutex<dyn MessageHandler>>;
+
+/// Handler for the messages of a protocol family which no handler registered on a thread takes,
+/// because they start a new thread or because the consumer resolves their thread from its own
+/// storage. `C` is the context the consumer dispatches messages with, such as the connection
+/// they were received on.
+#[async_trait]
+pub trait FamilyHandler<C>: Send + Sync {
+    async fn handle_message(
+        &self,
+        profile: &Arc<dyn Profile>,
+        context: &C,
+        message: AriesMessage,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<()>;
+}
+
+/// Result of dispatching a message.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DispatchOutcome {
+    /// The message was passed to a registered handler.
+    Handled,
+    /// No handler was registered for the message and a problem-report was sent back.
+    ProblemReported,
+}
+
+/// Routes inbound messages to registered handlers by protocol family and thread id.
+///
+/// A message goes to the handler registered for its family and thread. Acks and problem reports
+/// go to the single handler registered on their thread, whatever its family; if handlers of several
+/// families are registered on the thread, dispatching fails rather than picking one of them. Messages
+/// no thread handler takes go to the family handler of their protocol family, and a problem-report
+/// is sent back for those which have none either.
+///
+/// Thread handlers are held in memory for as long as they are registered. Consumers which persist
+/// their protocol state, such as libvcx_core and aries-vcx-agent, register family handlers which
+/// resolve the thread from their own storage instead.
+///
+/// The issuance, presentation, trust ping and revocation notification state machines still run
+/// on the legacy message types; they can only be dispatched to with the `legacy_messages` feature,
+/// which converts messages on the way in and out. No protocol version is negotiated with the
+/// counterparty: messages are handled in the version `messages2` parsed them as.
+pub struct MessageDispatcher<C = ()> {
+    handlers: RwLock<HashMap<(ProtocolFamily, String), SharedMessageHandler>>,
+    family_handlers: RwLock<HashMap<ProtocolFamily, Arc<dyn FamilyHandler<C>>>>,
+}
+
+impl<C> Default for MessageDispatcher<C> {
+    fn default() -> Self {
+        Self {
+            handlers: RwLock::new(HashMap::new()),
+            family_handlers: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<C> MessageDispatcher<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, family: ProtocolFamily, thread_id: &str, handler: SharedMessageHandler) -> VcxResult<()> {
+        self.handlers.write()?.insert((family, thread_id.to_string()), handler);
+        Ok(())
+    }
+
+    pub fn unregister(&self, family: ProtocolFamily, thread_id: &str) -> VcxResult<Option<SharedMessageHandler>> {
+        Ok(self.handlers.write()?.remove(&(family, thread_id.to_string())))
+    }
+
+    /// Registers the handler of the family's messages which no thread handler takes, replacing
+    /// the one registered before.
+    pub fn register_family(&self, family: ProtocolFamily, handler: Arc<dyn FamilyHandler<C>>) -> VcxResult<()> {
+        self.family_handlers.write()?.insert(family, handler);
+        Ok(())
+    }
+
+    /// Finds the handler registered for the message's thread. Acks and problem reports
+    /// are accepted by a handler of any family on the thread, since protocols adopt them
+    /// for their own acknowledgements; they are ambiguous if handlers of several families
+    /// are registered on the thread.
+    pub fn find_handler(&self, message: &AriesMessage) -> VcxResult<Option<SharedMessageHandler>> {
+        let family = ProtocolFamily::from_message(message);
+        let Some(thread_id) = message_thread_id(message) else {
+            return Ok(None);
+        };
+        let handlers = self.handlers.read()?;
+        if let Some(handler) = handlers.get(&(family, thread_id.clone())) {
+            return Ok(Some(handler.clone()));
+        }
+        if !family.is_adoptable() {
+            return Ok(None);
+        }
+        let mut candidates = handlers.iter().filter(|((_, id), _)| *id == thread_id);
+        match (candidates.next(), candidates.next()) {
+            (Some((_, handler)), None) => Ok(Some(handler.clone())),
+            (None, _) => Ok(None),
+            (Some(_), Some(_)) => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!(
+                    "Handlers of several protocol families are registered on thread {}, cannot pick one for {:?} message",
+                    thread_id, family
+                ),
+            )),
+        }
+    }
+
+    /// Finds the family handler registered for the message's protocol family.
+    pub fn find_family_handler(&self, message: &AriesMessage) -> VcxResult<Option<Arc<dyn FamilyHandler<C>>>> {
+        let family = ProtocolFamily::from_message(message);
+        Ok(self.family_handlers.read()?.get(&family).cloned())
+    }
+
+    /// Passes the message to its thread handler, or else to its family handler along with
+    /// `context`. If there is none, a problem-report is sent back through `send_message` instead.
+    pub async fn dispatch(
+        &self,
+        profile: &Arc<dyn Profile>,
+        context: &C,
+        message: AriesMessage,
+        send_message: AriesSendClosure,
+    ) -> VcxResult<DispatchOutcome> {
+        trace!("MessageDispatcher::dispatch >>> message: {:?}", message);
+        if let Some(handler) = self.find_handler(&message)? {
+            handler
+                .lock()
+                .await
+                .handle_message(profile, message, send_message)
+                .await?;
+            return Ok(DispatchOutcome::Handled);
+        }
+        if let Some(handler) = self.find_family_handler(&message)? {
+            handler.handle_message(profile, context, message, send_message).await?;
+            return Ok(DispatchOutcome::Handled);
+        }
+        warn!(
+            "MessageDispatcher::dispatch >>> no handler found for message: {:?}",
+            message
+        );
+        let problem_report = build_unhandled_message_problem_report(&message);
+        send_message(AriesMessage::ReportProblem(problem_report)).await?;
+        Ok(DispatchOutcome::ProblemReported)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use messages2::msg_fields::protocols::notification::{Ack, AckContent, AckDecorators, AckStatus};
+    use messages2::msg_fields::protocols::trust_ping::{
+        ping_response::{PingResponse, PingResponseContent, PingResponseDecorators},
+        TrustPing,
+    };
+
+    use crate::common::test_utils::mock_profile;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _recording_send_message(sent: Arc<std::sync::Mutex<Vec<AriesMessage>>>) -> AriesSendClosure {
+        Box::new(move |message: AriesMessage| {
+            sent.lock().unwrap().push(message);
+            Box::pin(async { VcxResult::Ok(()) })
+        })
+    }
+
+    fn _noop_send_message() -> AriesSendClosure {
+        Box::new(|_: AriesMessage| Box::pin(async { VcxResult::Ok(()) }))
+    }
+
+    /// Handler recording the messages it receives, with the context of those it receives as a family handler.
+    #[derive(Default)]
+    struct RecordingHandler {
+        received: std::sync::Mutex<Vec<(Option<String>, AriesMessage)>>,
+    }
+
+    #[async_trait]
+    impl MessageHandler for RecordingHandler {
+        async fn handle_message(
+            &mut self,
+            _profile: &Arc<dyn Profile>,
+            message: AriesMessage,
+            _send_message: AriesSendClosure,
+        ) -> VcxResult<()> {
+            self.received.lock().unwrap().push((None, message));
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl FamilyHandler<String> for RecordingHandler {
+        async fn handle_message(
+            &self,
+            _profile: &Arc<dyn Profile>,
+            context: &String,
+            message: AriesMessage,
+            _send_message: AriesSendClosure,
+        ) -> VcxResult<()> {
+            self.received.lock().unwrap().push((Some(context.clone()), message));
+            Ok(())
+        }
+    }
+
+    fn _ack(thread_id: &str) -> AriesMessage {
+        let decorators = AckDecorators::new(Thread::new(thread_id.to_owned()));
+        AriesMessage::Notification(Ack::with_decorators(uuid(), AckContent::new(AckStatus::Ok), decorators))
+    }
+
+    fn _ping_response(thread_id: &str) -> AriesMessage {
+        let decorators = PingResponseDecorators::new(Thread::new(thread_id.to_owned()));
+        let ping_response = PingResponse::with_decorators(uuid(), PingResponseContent::default(), decorators);
+        AriesMessage::TrustPing(TrustPing::PingResponse(ping_response))
+    }
+
+    #[test]
+    fn test_message_thread_id() {
+        assert_eq!(message_thread_id(&_ping_response("thread_id")).unwrap(), "thread_id");
+
+        let report = ProblemReport::with_decorators(
+            "report_id".to_owned(),
+            ProblemReportContent::default(),
+            ProblemReportDecorators::default(),
+        );
+        let report = AriesMessage::ReportProblem(report);
+        assert_eq!(message_thread_id(&report).unwrap(), "report_id");
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "legacy_messages")]
+    async fn test_dispatch_routes_message_to_registered_handler() {
+        use messages::a2a::A2AMessage;
+
+        use crate::handlers::compat::from_legacy_message;
+        use crate::handlers::trust_ping::TrustPingSender;
+        use crate::protocols::trustping::build_ping_response;
+
+        let _setup = SetupMocks::init();
+        let profile = mock_profile();
+        let dispatcher: MessageDispatcher = MessageDispatcher::new();
+
+        let mut sender = TrustPingSender::build(true, None);
+        sender
+            .send_ping(Box::new(|_: A2AMessage| Box::pin(async { VcxResult::Ok(()) })))
+            .await
+            .unwrap();
+        let ping_response = build_ping_response(sender.get_ping());
+        let sender = Arc::new(Mutex::new(sender));
+        dispatcher
+            .register(
+                ProtocolFamily::TrustPing,
+                &sender.lock().await.get_thread_id(),
+                sender.clone(),
+            )
+            .unwrap();
+
+        let ping_response = from_legacy_message(&ping_response.to_a2a_message()).unwrap();
+        let outcome = dispatcher
+            .dispatch(&profile, &(), ping_response, _noop_send_message())
+            .await
+            .unwrap();
+
+        assert_eq!(outcome, DispatchOutcome::Handled);
+        assert!(sender.lock().await.response_received());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_sends_problem_report_for_unhandled_message() {
+        let _setup = SetupMocks::init();
+        let profile = mock_profile();
+        let dispatcher: MessageDispatcher = MessageDispatcher::new();
+        let sent = Arc::new(std::sync::Mutex::new(vec![]));
+
+        let outcome = dispatcher
+            .dispatch(
+                &profile,
+                &(),
+                _ping_response("unknown-thread"),
+                _recording_send_message(sent.clone()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(outcome, DispatchOutcome::ProblemReported);
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        match &sent[0] {
+            AriesMessage::ReportProblem(report) => {
+                assert_eq!(report.decorators.thread.as_ref().unwrap().thid, "unknown-thread")
+            }
+            message => panic!("Expected problem report, got {:?}", message),
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "legacy_messages")]
+    async fn test_unregistered_handler_is_not_used() {
+        use crate::handlers::trust_ping::TrustPingSender;
+
+        let _setup = SetupMocks::init();
+        let dispatcher: MessageDispatcher = MessageDispatcher::new();
+        let sender = TrustPingSender::build(true, None);
+        let thread_id = sender.get_thread_id();
+        dispatcher
+            .register(ProtocolFamily::TrustPing, &thread_id, Arc::new(Mutex::new(sender)))
+            .unwrap();
+        dispatcher.unregister(ProtocolFamily::TrustPing, &thread_id).unwrap();
+
+        assert!(dispatcher.find_handler(&_ping_response(&thread_id)).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_ack_is_adopted_by_single_handler_on_thread() {
+        let _setup = SetupMocks::init();
+        let profile = mock_profile();
+        let dispatcher: MessageDispatcher = MessageDispatcher::new();
+        let handler = Arc::new(Mutex::new(RecordingHandler::default()));
+        dispatcher
+            .register(ProtocolFamily::PresentProof, "thread_id", handler.clone())
+            .unwrap();
+
+        let outcome = dispatcher
+            .dispatch(&profile, &(), _ack("thread_id"), _noop_send_message())
+            .await
+            .unwrap();
+
+        assert_eq!(outcome, DispatchOutcome::Handled);
+        assert_eq!(handler.lock().await.received.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ack_is_ambiguous_with_handlers_of_several_families_on_thread() {
+        let _setup = SetupMocks::init();
+        let profile = mock_profile();
+        let dispatcher: MessageDispatcher = MessageDispatcher::new();
+        let first = Arc::new(Mutex::new(RecordingHandler::default()));
+        let second = Arc::new(Mutex::new(RecordingHandler::default()));
+        dispatcher
+            .register(ProtocolFamily::PresentProof, "thread_id", first.clone())
+            .unwrap();
+        dispatcher
+            .register(ProtocolFamily::IssueCredential, "thread_id", second.clone())
+            .unwrap();
+
+        let err = dispatcher
+            .dispatch(&profile, &(), _ack("thread_id"), _noop_send_message())
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidState);
+        assert!(first.lock().await.received.lock().unwrap().is_empty());
+        assert!(second.lock().await.received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_falls_back_to_family_handler() {
+        let _setup = SetupMocks::init();
+        let profile = mock_profile();
+        let dispatcher = MessageDispatcher::<String>::new();
+        let thread_handler = Arc::new(Mutex::new(RecordingHandler::default()));
+        let family_handler = Arc::new(RecordingHandler::default());
+        dispatcher
+            .register(ProtocolFamily::TrustPing, "known-thread", thread_handler.clone())
+            .unwrap();
+        dispatcher
+            .register_family(ProtocolFamily::TrustPing, family_handler.clone())
+            .unwrap();
+
+        for thread_id in ["known-thread", "unknown-thread"] {
+            let outcome = dispatcher
+                .dispatch(
+                    &profile,
+                    &"connection_id".to_string(),
+                    _ping_response(thread_id),
+                    _noop_send_message(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(outcome, DispatchOutcome::Handled);
+        }
+
+        assert_eq!(thread_handler.lock().await.received.lock().unwrap().len(), 1);
+        let received = family_handler.received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].0.as_deref(), Some("connection_id"));
+        assert_eq!(message_thread_id(&received[0].1).unwrap(), "unknown-thread");
+    }
+}
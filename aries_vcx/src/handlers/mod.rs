@@ -6,8 +6,11 @@ use crate::protocols::mediated_connection::inviter::state_machine::InviterState;
 use crate::protocols::proof_presentation::prover::state_machine::ProverState;
 use crate::protocols::proof_presentation::verifier::state_machine::VerifierState;
 
+#[cfg(feature = "legacy_messages")]
+pub mod compat;
 pub mod connection;
 pub mod discovery;
+pub mod dispatcher;
 pub mod issuance;
 pub mod out_of_band;
 pub mod proof_presentation;
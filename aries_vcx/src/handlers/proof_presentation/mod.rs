@@ -1,2 +1,21 @@
+use messages::a2a::A2AMessage;
+use messages2::AriesMessage;
+
+use crate::errors::error::prelude::*;
+
 pub mod prover;
 pub mod verifier;
+pub mod versioned_prover;
+pub mod versioned_verifier;
+
+/// Reads a message passed around as a legacy [`A2AMessage::Generic`] as an [`AriesMessage`].
+// `AriesMessage` borrows the `@type` while deserializing, which a `serde_json::Value` cannot lend.
+fn to_aries_message(message: &A2AMessage) -> VcxResult<AriesMessage> {
+    let json = serde_json::to_string(message)?;
+    serde_json::from_str(&json).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!("Cannot read message as an aries message: {}", err),
+        )
+    })
+}
@@ -4,13 +4,21 @@ use std::sync::Arc;
 
 use agency_client::agency_client::AgencyClient;
 
+use crate::common::proofs::proof_request::ProofRequestData;
+use crate::common::proofs::prover::credential_selection::{
+    select_credentials, CredentialSelectionStrategy, RetrievedCredentials, SelectedCredentials,
+};
 use crate::core::profile::profile::Profile;
 use crate::errors::error::prelude::*;
 use crate::handlers::connection::mediated_connection::MediatedConnection;
+use crate::protocols::connectionless::build_service_send_closure;
 use crate::protocols::proof_presentation::prover::messages::ProverMessages;
 use crate::protocols::proof_presentation::prover::state_machine::{ProverSM, ProverState};
 use crate::protocols::SendClosure;
+use crate::transport::{with_return_route, DuplexTransport, ReturnRouted};
+use crate::utils::encryption_envelope::EncryptionEnvelope;
 use messages::a2a::A2AMessage;
+use messages::concepts::service_decorator::ServiceDecorator;
 use messages::protocols::proof_presentation::presentation::Presentation;
 use messages::protocols::proof_presentation::presentation_ack::PresentationAck;
 use messages::protocols::proof_presentation::presentation_proposal::{PresentationPreview, PresentationProposalData};
@@ -76,6 +84,69 @@ impl Prover {
         Ok(())
     }
 
+    /// Selects a credential for every referent of the presentation request among the credentials
+    /// in the wallet, as preferred by the strategy. Candidates carry the time they were stored in
+    /// the wallet, if it was recorded.
+    pub async fn select_credentials(
+        &self,
+        profile: &Arc<dyn Profile>,
+        strategy: &dyn CredentialSelectionStrategy,
+        tails_dir: Option<&str>,
+    ) -> VcxResult<SelectedCredentials> {
+        trace!("Prover::select_credentials >>> tails_dir: {:?}", tails_dir);
+        let mut retrieved = RetrievedCredentials::from_json(&self.retrieve_credentials(profile).await?)?;
+        retrieved.load_storage_times(&profile.inject_wallet()).await?;
+        select_credentials(strategy, &retrieved, tails_dir).await
+    }
+
+    /// Selects credentials for the presentation request with the strategy and generates the
+    /// presentation from them. Fails if a referent is neither answered by a credential nor self
+    /// attested.
+    pub async fn generate_presentation_with_strategy(
+        &mut self,
+        profile: &Arc<dyn Profile>,
+        strategy: &dyn CredentialSelectionStrategy,
+        tails_dir: Option<&str>,
+        self_attested_attrs: String,
+    ) -> VcxResult<()> {
+        trace!(
+            "Prover::generate_presentation_with_strategy >>> tails_dir: {:?}, self_attested_attrs: {:?}",
+            tails_dir,
+            self_attested_attrs
+        );
+        let selected = self.select_credentials(profile, strategy, tails_dir).await?;
+        let self_attested: HashMap<String, String> = serde_json::from_str(&self_attested_attrs).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!("Cannot deserialize self attested attributes: {}", err),
+            )
+        })?;
+        let presentation_request: ProofRequestData =
+            serde_json::from_str(&self.presentation_request_data()?).map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidJson,
+                    format!("Cannot deserialize proof request: {}", err),
+                )
+            })?;
+        let unanswered = presentation_request
+            .requested_attributes
+            .keys()
+            .filter(|referent| !self_attested.contains_key(*referent))
+            .chain(presentation_request.requested_predicates.keys())
+            .find(|referent| !selected.attrs.contains_key(*referent));
+        if let Some(referent) = unanswered {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidProofCredentialData,
+                format!(
+                    "No credential found for referent \"{}\" of the presentation request",
+                    referent
+                ),
+            ));
+        }
+        self.generate_presentation(profile, selected.to_json()?, self_attested_attrs)
+            .await
+    }
+
     pub fn get_presentation_msg(&self) -> VcxResult<Presentation> {
         Ok(self.prover_sm.get_presentation_msg()?.to_owned())
     }
@@ -106,6 +177,52 @@ impl Prover {
         Ok(())
     }
 
+    /// Sends the presentation to the `~service` of a presentation request received without
+    /// a connection, such as one attached to an out-of-band invitation.
+    ///
+    /// The prover has no endpoint of its own here, so the presentation is decorated with
+    /// `~transport` `return_route: all` and the verifier's ack, or problem report, is received
+    /// back through the transport and processed. The presentation is authcrypted with
+    /// `sender_verkey`, which the verifier packs its reply for. If the verifier returns nothing,
+    /// the prover stays in [`ProverState::PresentationSent`].
+    pub async fn send_presentation_to_service<T>(
+        &mut self,
+        profile: &Arc<dyn Profile>,
+        sender_verkey: &str,
+        transport: Arc<T>,
+    ) -> VcxResult<()>
+    where
+        T: DuplexTransport + 'static,
+    {
+        trace!(
+            "Prover::send_presentation_to_service >>> sender_verkey: {}",
+            sender_verkey
+        );
+        let service = self.presentation_request_service()?.ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Presentation request has no ~service to send the presentation to",
+            )
+        })?;
+        let wallet = profile.inject_wallet();
+        let send_to_service = build_service_send_closure(
+            Arc::clone(&wallet),
+            &service,
+            Some(sender_verkey.to_owned()),
+            Arc::new(ReturnRouted(Arc::clone(&transport))),
+        );
+        let send_message: SendClosure = Box::new(move |message: A2AMessage| {
+            Box::pin(async move { send_to_service(with_return_route(&message)?).await })
+        });
+        self.send_presentation(send_message).await?;
+
+        if let Some(payload) = transport.receive_message(&service.service_endpoint).await? {
+            let (reply, _) = EncryptionEnvelope::anon_unpack(&wallet, payload).await?;
+            self.step(profile, reply.into(), None).await?;
+        }
+        Ok(())
+    }
+
     pub fn process_presentation_ack(&mut self, ack: PresentationAck) -> VcxResult<()> {
         trace!("Prover::process_presentation_ack >>>");
         self.prover_sm = self.prover_sm.clone().receive_presentation_ack(ack)?;
@@ -138,6 +255,11 @@ impl Prover {
             .map_err(|err| err.into())
     }
 
+    /// Returns the `~service` of the presentation request, present if it was sent without a connection.
+    pub fn presentation_request_service(&self) -> VcxResult<Option<ServiceDecorator>> {
+        Ok(self.prover_sm.get_presentation_request()?.service.clone())
+    }
+
     pub fn get_proof_request_attachment(&self) -> VcxResult<String> {
         let data = self
             .prover_sm
@@ -267,11 +389,142 @@ pub mod test_utils {
 #[cfg(feature = "general_test")]
 #[cfg(test)]
 mod tests {
-    use crate::{common::test_utils::indy_handles_to_profile, utils::devsetup::*};
+    use crate::common::proofs::prover::credential_selection::NewestFirst;
+    use crate::utils::mockdata::mock_settings::MockBuilder;
+    use crate::{
+        common::test_utils::{indy_handles_to_profile, mock_profile},
+        utils::devsetup::*,
+    };
+    use messages::protocols::proof_presentation::presentation_request::test_utils::_presentation_request;
     use messages::protocols::proof_presentation::presentation_request::PresentationRequest;
 
     use super::*;
 
+    fn _retrieved_credentials(cred_ids: &[&str]) -> String {
+        let candidates: Vec<serde_json::Value> = cred_ids
+            .iter()
+            .map(|cred_id| {
+                json!({
+                    "cred_info": {
+                        "referent": cred_id,
+                        "attrs": {"name": "alice"},
+                        "schema_id": "V4SGRU86Z58d6TV7PBUe6f:2:degree:1.0",
+                        "cred_def_id": "V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag",
+                        "rev_reg_id": null,
+                        "cred_rev_id": null
+                    },
+                    "interval": null
+                })
+            })
+            .collect();
+        json!({ "attrs": { "attribute_0": candidates } }).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_generate_presentation_with_strategy() {
+        let _setup = SetupMocks::init();
+        let _mock_builder = MockBuilder::init()
+            .set_mock_creds_retrieved_for_proof_request(&_retrieved_credentials(&["cred_1", "cred_2"]))
+            .set_mock_generate_indy_proof("{}");
+        let mut prover = Prover::create_from_request("1", _presentation_request()).unwrap();
+
+        // the mocked wallet records no storage times, so the candidates keep their order
+        let selected = prover
+            .select_credentials(&mock_profile(), &NewestFirst, None)
+            .await
+            .unwrap();
+        assert_eq!(selected.attrs["attribute_0"].credential.cred_info.referent, "cred_1");
+
+        prover
+            .generate_presentation_with_strategy(&mock_profile(), &NewestFirst, None, "{}".to_string())
+            .await
+            .unwrap();
+        assert_eq!(prover.get_state(), ProverState::PresentationPrepared);
+    }
+
+    #[tokio::test]
+    async fn test_generate_presentation_with_strategy_fails_for_unanswered_referent() {
+        let _setup = SetupMocks::init();
+        let _mock_builder = MockBuilder::init()
+            .set_mock_creds_retrieved_for_proof_request(&_retrieved_credentials(&[]))
+            .set_mock_generate_indy_proof("{}");
+        let mut prover = Prover::create_from_request("1", _presentation_request()).unwrap();
+
+        let err = prover
+            .generate_presentation_with_strategy(&mock_profile(), &NewestFirst, None, "{}".to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidProofCredentialData);
+        assert_eq!(prover.get_state(), ProverState::PresentationRequestReceived);
+
+        prover
+            .generate_presentation_with_strategy(
+                &mock_profile(),
+                &NewestFirst,
+                None,
+                json!({"attribute_0": "alice"}).to_string(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(prover.get_state(), ProverState::PresentationPrepared);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "general_test")]
+    async fn test_send_presentation_to_service_processes_returned_ack() {
+        use messages::concepts::service_decorator::ServiceDecorator;
+        use messages::protocols::proof_presentation::presentation::test_utils::_presentation;
+        use messages::protocols::proof_presentation::test_utils::_ack;
+
+        use crate::common::test_utils::CapturingTransport;
+        use crate::indy::utils::test_setup::with_wallet;
+        use crate::protocols::connectionless::service_to_did_doc;
+
+        const ENDPOINT: &str = "https://example.org/endpoint";
+
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let profile = indy_handles_to_profile(wallet_handle, 0);
+            let wallet = profile.inject_wallet();
+            let (_, verifier_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let (_, prover_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let service = ServiceDecorator::create()
+                .set_recipient_keys(vec![verifier_vk.clone()])
+                .set_service_endpoint(ENDPOINT.to_string());
+            let mut prover =
+                Prover::create_from_request("1", _presentation_request().set_service(Some(service))).unwrap();
+            prover.set_presentation(_presentation()).unwrap();
+
+            let prover_service = ServiceDecorator::create().set_recipient_keys(vec![prover_vk.clone()]);
+            let reply = EncryptionEnvelope::create(
+                &wallet,
+                &_ack().to_a2a_message(),
+                Some(&verifier_vk),
+                &service_to_did_doc(&prover_service),
+            )
+            .await
+            .unwrap();
+            let transport = Arc::new(CapturingTransport::default());
+            transport.queue_reply(reply.0);
+
+            prover
+                .send_presentation_to_service(&profile, &prover_vk, Arc::clone(&transport))
+                .await
+                .unwrap();
+            assert_eq!(prover.get_state(), ProverState::Finished);
+
+            let (packed, endpoint) = transport.take_packed();
+            assert_eq!(endpoint, ENDPOINT);
+            let unpacked = wallet.unpack_message(&packed).await.unwrap();
+            let unpacked: serde_json::Value = serde_json::from_slice(&unpacked).unwrap();
+            assert_eq!(unpacked["sender_verkey"], prover_vk);
+            let presentation: serde_json::Value = serde_json::from_str(unpacked["message"].as_str().unwrap()).unwrap();
+            assert_eq!(presentation["~transport"]["return_route"], "all");
+            assert_eq!(presentation["~thread"]["thid"], prover.get_thread_id().unwrap());
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn test_retrieve_credentials_fails_with_no_proof_req() {
         SetupLibraryWallet::run(|setup| async move {
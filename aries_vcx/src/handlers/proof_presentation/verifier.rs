@@ -2,19 +2,23 @@ use std::collections::HashMap;
 
 use messages::protocols::proof_presentation::presentation::Presentation;
 use messages::status::Status;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use agency_client::agency_client::AgencyClient;
 
 use crate::common::proofs::proof_request::PresentationRequestData;
+use crate::common::proofs::verifier::verification_report::VerificationReport;
 use crate::core::profile::profile::Profile;
 use crate::errors::error::prelude::*;
 use crate::handlers::connection::mediated_connection::MediatedConnection;
+use crate::handlers::out_of_band::sender::OutOfBandSender;
 use crate::protocols::proof_presentation::verifier::messages::VerifierMessages;
 use crate::protocols::proof_presentation::verifier::state_machine::{VerifierSM, VerifierState};
 use crate::protocols::proof_presentation::verifier::verification_status::PresentationVerificationStatus;
 use crate::protocols::SendClosure;
 use messages::a2a::A2AMessage;
+use messages::concepts::service_decorator::ServiceDecorator;
+use messages::protocols::proof_presentation::presentation_ack::PresentationAck;
 use messages::protocols::proof_presentation::presentation_proposal::PresentationProposal;
 use messages::protocols::proof_presentation::presentation_request::PresentationRequest;
 
@@ -102,6 +106,31 @@ impl Verifier {
         Ok(())
     }
 
+    /// Verifies a presentation received without a connection. The prover has no endpoint the ack
+    /// could be sent to, so it is returned instead, to be delivered on the route the presentation
+    /// arrived on. Returns `None` if the presentation was not expected in the current state.
+    pub async fn verify_connectionless_presentation(
+        &mut self,
+        profile: &Arc<dyn Profile>,
+        presentation: Presentation,
+    ) -> VcxResult<Option<PresentationAck>> {
+        trace!("Verifier::verify_connectionless_presentation >>>");
+        let ack = Arc::new(Mutex::new(None));
+        let sent_ack = Arc::clone(&ack);
+        let keep_ack: SendClosure = Box::new(move |message: A2AMessage| {
+            let sent_ack = Arc::clone(&sent_ack);
+            Box::pin(async move {
+                if let A2AMessage::PresentationAck(ack) = message {
+                    *sent_ack.lock()? = Some(ack);
+                }
+                VcxResult::Ok(())
+            })
+        });
+        self.verify_presentation(profile, presentation, keep_ack).await?;
+        let ack = ack.lock()?.take();
+        Ok(ack)
+    }
+
     pub fn set_request(
         &mut self,
         presentation_request_data: PresentationRequestData,
@@ -119,6 +148,24 @@ impl Verifier {
         Ok(())
     }
 
+    /// Sets the `~service` the prover should send the presentation to, for a presentation
+    /// request sent without a connection.
+    pub fn set_request_service(&mut self, service: ServiceDecorator) -> VcxResult<()> {
+        trace!("Verifier::set_request_service >>> service: {:?}", service);
+        self.verifier_sm = self.verifier_sm.clone().set_request_service(service)?;
+        Ok(())
+    }
+
+    /// Attaches the presentation request to the out-of-band invitation and marks it as sent.
+    /// Used for connectionless presentations, along with [`Verifier::set_request_service`].
+    pub fn append_request_to_oob(&mut self, oob_sender: OutOfBandSender) -> VcxResult<OutOfBandSender> {
+        trace!("Verifier::append_request_to_oob >>>");
+        let presentation_request = self.verifier_sm.presentation_request_msg()?;
+        let oob_sender = oob_sender.append_a2a_message(presentation_request.to_a2a_message())?;
+        self.mark_presentation_request_msg_sent()?;
+        Ok(oob_sender)
+    }
+
     pub fn mark_presentation_request_msg_sent(&mut self) -> VcxResult<()> {
         trace!("Verifier::mark_presentation_request_msg_sent >>>");
         self.verifier_sm = self.verifier_sm.clone().mark_presentation_request_msg_sent()?;
@@ -149,6 +196,12 @@ impl Verifier {
         self.verifier_sm.get_verification_status()
     }
 
+    /// Returns the report of the checks performed when the presentation was verified, explaining
+    /// why the presentation was found valid or invalid.
+    pub fn get_verification_report(&self) -> VcxResult<VerificationReport> {
+        self.verifier_sm.get_verification_report()
+    }
+
     pub fn get_presentation_attachment(&self) -> VcxResult<String> {
         self.verifier_sm
             .get_presentation_msg()?
@@ -279,4 +332,69 @@ mod unit_tests {
         assert_eq!(presentation, _presentation());
         assert_eq!(verifier.get_state(), VerifierState::Finished);
     }
+
+    #[tokio::test]
+    async fn test_get_verification_report() {
+        let _setup = SetupMocks::init();
+        let _mock_builder = MockBuilder::init().set_mock_result_for_validate_indy_proof(Ok(true));
+        let mut verifier = _verifier().await;
+        assert_eq!(
+            verifier.get_verification_report().unwrap_err().kind(),
+            AriesVcxErrorKind::NotReady
+        );
+        verifier.to_finished_state().await;
+        let verification_report = verifier.get_verification_report().unwrap();
+        assert!(verification_report.valid);
+        assert_eq!(
+            verifier.get_verification_status(),
+            PresentationVerificationStatus::Valid
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_connectionless_presentation_returns_ack() {
+        let _setup = SetupMocks::init();
+        let _mock_builder = MockBuilder::init().set_mock_result_for_validate_indy_proof(Ok(true));
+        let mut verifier = _verifier().await;
+        verifier.to_presentation_request_sent_state().await;
+
+        let ack = verifier
+            .verify_connectionless_presentation(&_dummy_profile(), _presentation())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ack.get_thread_id(), verifier.get_thread_id().unwrap());
+        assert_eq!(verifier.get_state(), VerifierState::Finished);
+
+        // A presentation which is not expected anymore yields no ack
+        let ack = verifier
+            .verify_connectionless_presentation(&_dummy_profile(), _presentation())
+            .await
+            .unwrap();
+        assert!(ack.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_connectionless_request_in_oob_invitation() {
+        use crate::handlers::out_of_band::receiver::OutOfBandReceiver;
+        use crate::handlers::proof_presentation::prover::Prover;
+
+        let _setup = SetupMocks::init();
+        let service = ServiceDecorator::create()
+            .set_recipient_keys(vec!["recipient_key".to_string()])
+            .set_service_endpoint("https://example.org/endpoint".to_string());
+        let mut verifier = _verifier().await;
+        verifier.set_request_service(service.clone()).unwrap();
+        let oob_sender = verifier.append_request_to_oob(OutOfBandSender::create()).unwrap();
+        assert_eq!(verifier.get_state(), VerifierState::PresentationRequestSent);
+
+        let oob_receiver = OutOfBandReceiver::create_from_a2a_msg(&oob_sender.to_a2a_message()).unwrap();
+        let request = match oob_receiver.extract_a2a_message().unwrap() {
+            Some(A2AMessage::PresentationRequest(request)) => request,
+            message => panic!("expected a presentation request, got {:?}", message),
+        };
+        let prover = Prover::create_from_request("1", request).unwrap();
+        assert_eq!(prover.presentation_request_service().unwrap(), Some(service));
+        assert_eq!(prover.get_thread_id().unwrap(), verifier.get_thread_id().unwrap());
+    }
 }
@@ -0,0 +1,330 @@
+use std::sync::Arc;
+
+use messages::a2a::A2AMessage;
+use messages::protocols::proof_presentation::presentation_request::PresentationRequest;
+use messages2::{
+    msg_fields::protocols::present_proof::{v2::PresentProofV2, PresentProof},
+    AriesMessage,
+};
+
+use crate::common::proofs::prover::credential_selection::{
+    select_credentials, CredentialSelectionStrategy, RetrievedCredentials, SelectedCredentials,
+};
+use crate::core::profile::profile::Profile;
+use crate::errors::error::prelude::*;
+use crate::handlers::proof_presentation::prover::Prover;
+use crate::handlers::proof_presentation::to_aries_message;
+use crate::handlers::proof_presentation::versioned_verifier::PresentProofVersion;
+use crate::protocols::proof_presentation::prover::state_machine::ProverState;
+use crate::protocols::proof_presentation_v2::formats::hyperledger_indy::{
+    HyperledgerIndyCreatePresentationInput, HyperledgerIndyPresentationFormat,
+};
+use crate::protocols::proof_presentation_v2::prover::states::{Complete, PresentationPrepared, RequestReceived};
+use crate::protocols::proof_presentation_v2::prover::ProverV2;
+use crate::protocols::proof_presentation_v2::Failed;
+use crate::protocols::SendClosure;
+
+/// States of a present proof v2 prover answering `hlindy/proof-req@v2.0` requests.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ProverV2State {
+    RequestReceived(ProverV2<RequestReceived<HyperledgerIndyPresentationFormat>>),
+    PresentationPrepared(ProverV2<PresentationPrepared<HyperledgerIndyPresentationFormat>>),
+    PresentationSent(ProverV2<PresentationPrepared<HyperledgerIndyPresentationFormat>>),
+    Complete(ProverV2<Complete<HyperledgerIndyPresentationFormat>>),
+    Failed(ProverV2<Failed>),
+}
+
+/// A prover which answers each presentation request with the protocol version it was received with.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum VersionedProver {
+    V1(Prover),
+    V2(ProverV2State),
+}
+
+impl VersionedProver {
+    pub fn create_from_request(source_id: &str, presentation_request: PresentationRequest) -> VcxResult<Self> {
+        Ok(Self::V1(Prover::create_from_request(source_id, presentation_request)?))
+    }
+
+    /// Creates a prover from a present proof v2 request, passed on as [`A2AMessage::Generic`].
+    pub fn create_from_request_v2(message: A2AMessage) -> VcxResult<Self> {
+        trace!("VersionedProver::create_from_request_v2 >>>");
+        match to_aries_message(&message)? {
+            AriesMessage::PresentProof(PresentProof::V2(PresentProofV2::RequestPresentation(request))) => Ok(Self::V2(
+                ProverV2State::RequestReceived(ProverV2::from_request(request)?),
+            )),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                "Expected a present proof v2 request",
+            )),
+        }
+    }
+
+    pub fn version(&self) -> PresentProofVersion {
+        match self {
+            Self::V1(_) => PresentProofVersion::V1_0,
+            Self::V2(_) => PresentProofVersion::V2_0,
+        }
+    }
+
+    pub fn get_thread_id(&self) -> VcxResult<String> {
+        match self {
+            Self::V1(prover) => prover.get_thread_id(),
+            Self::V2(ProverV2State::RequestReceived(prover)) => Ok(prover.thread_id().to_owned()),
+            Self::V2(ProverV2State::PresentationPrepared(prover) | ProverV2State::PresentationSent(prover)) => {
+                Ok(prover.thread_id().to_owned())
+            }
+            Self::V2(ProverV2State::Complete(prover)) => Ok(prover.thread_id().to_owned()),
+            Self::V2(ProverV2State::Failed(prover)) => Ok(prover.thread_id().to_owned()),
+        }
+    }
+
+    /// Returns the state of the presentation, with the v2 states mapped onto their v1 counterparts.
+    pub fn get_state(&self) -> ProverState {
+        match self {
+            Self::V1(prover) => prover.get_state(),
+            Self::V2(ProverV2State::RequestReceived(_)) => ProverState::PresentationRequestReceived,
+            Self::V2(ProverV2State::PresentationPrepared(_)) => ProverState::PresentationPrepared,
+            Self::V2(ProverV2State::PresentationSent(_)) => ProverState::PresentationSent,
+            Self::V2(ProverV2State::Complete(_)) => ProverState::Finished,
+            Self::V2(ProverV2State::Failed(_)) => ProverState::Failed,
+        }
+    }
+
+    pub fn get_proof_request_attachment(&self) -> VcxResult<String> {
+        match self {
+            Self::V1(prover) => prover.get_proof_request_attachment(),
+            Self::V2(ProverV2State::RequestReceived(prover)) => {
+                String::from_utf8(prover.request_payload()?).map_err(|err| {
+                    AriesVcxError::from_msg(
+                        AriesVcxErrorKind::InvalidJson,
+                        format!("Presentation request attachment is not valid UTF-8: {}", err),
+                    )
+                })
+            }
+            Self::V2(_) => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Presentation request is only kept until the presentation is prepared",
+            )),
+        }
+    }
+
+    pub async fn retrieve_credentials(&self, profile: &Arc<dyn Profile>) -> VcxResult<String> {
+        match self {
+            Self::V1(prover) => prover.retrieve_credentials(profile).await,
+            Self::V2(ProverV2State::RequestReceived(prover)) => prover.get_matching_credentials(profile).await,
+            Self::V2(_) => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Prover is not answering a presentation request",
+            )),
+        }
+    }
+
+    /// Selects a credential for every referent of the presentation request, as in
+    /// [`Prover::select_credentials`].
+    pub async fn select_credentials(
+        &self,
+        profile: &Arc<dyn Profile>,
+        strategy: &dyn CredentialSelectionStrategy,
+        tails_dir: Option<&str>,
+    ) -> VcxResult<SelectedCredentials> {
+        trace!("VersionedProver::select_credentials >>> tails_dir: {:?}", tails_dir);
+        match self {
+            Self::V1(prover) => prover.select_credentials(profile, strategy, tails_dir).await,
+            Self::V2(_) => {
+                let mut retrieved = RetrievedCredentials::from_json(&self.retrieve_credentials(profile).await?)?;
+                retrieved.load_storage_times(&profile.inject_wallet()).await?;
+                select_credentials(strategy, &retrieved, tails_dir).await
+            }
+        }
+    }
+
+    pub async fn generate_presentation(
+        &mut self,
+        profile: &Arc<dyn Profile>,
+        credentials: String,
+        self_attested_attrs: String,
+    ) -> VcxResult<()> {
+        trace!("VersionedProver::generate_presentation >>>");
+        match self {
+            Self::V1(prover) => {
+                prover
+                    .generate_presentation(profile, credentials, self_attested_attrs)
+                    .await
+            }
+            Self::V2(state) => {
+                let ProverV2State::RequestReceived(prover) = state else {
+                    return Err(AriesVcxError::from_msg(
+                        AriesVcxErrorKind::NotReady,
+                        "Prover is not answering a presentation request",
+                    ));
+                };
+                let input = HyperledgerIndyCreatePresentationInput {
+                    credentials,
+                    self_attested_attrs,
+                };
+                let prover = prover.clone().prepare_presentation(profile, &input).await?;
+                *state = ProverV2State::PresentationPrepared(prover);
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn send_presentation(&mut self, send_message: SendClosure) -> VcxResult<()> {
+        trace!("VersionedProver::send_presentation >>>");
+        match self {
+            Self::V1(prover) => prover.send_presentation(send_message).await,
+            Self::V2(state) => {
+                let ProverV2State::PresentationPrepared(prover) = state else {
+                    return Err(AriesVcxError::from_msg(
+                        AriesVcxErrorKind::NotReady,
+                        "Prover has not prepared a presentation yet",
+                    ));
+                };
+                let presentation = AriesMessage::from(prover.get_presentation().clone());
+                send_message(A2AMessage::Generic(serde_json::to_value(presentation)?)).await?;
+                *state = ProverV2State::PresentationSent(prover.clone());
+                Ok(())
+            }
+        }
+    }
+
+    /// Processes the verifier's answer to the presentation: an acknowledgement, or for v2, a problem
+    /// report abandoning the presentation.
+    pub fn process_presentation_ack(&mut self, message: A2AMessage) -> VcxResult<()> {
+        trace!("VersionedProver::process_presentation_ack >>>");
+        match self {
+            Self::V1(prover) => match message {
+                A2AMessage::PresentationAck(ack) => prover.process_presentation_ack(ack),
+                _ => Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    "Expected a present proof v1 ack",
+                )),
+            },
+            Self::V2(state) => {
+                let ProverV2State::PresentationSent(prover) = state else {
+                    return Err(AriesVcxError::from_msg(
+                        AriesVcxErrorKind::NotReady,
+                        "Prover has not sent a presentation yet",
+                    ));
+                };
+                *state = match to_aries_message(&message)? {
+                    AriesMessage::PresentProof(PresentProof::V2(PresentProofV2::Ack(ack))) => {
+                        ProverV2State::Complete(prover.clone().receive_ack(ack)?)
+                    }
+                    AriesMessage::PresentProof(PresentProof::V2(PresentProofV2::ProblemReport(problem_report))) => {
+                        ProverV2State::Failed(prover.clone().receive_problem_report(problem_report)?)
+                    }
+                    _ => {
+                        return Err(AriesVcxError::from_msg(
+                            AriesVcxErrorKind::InvalidMessageFormat,
+                            "Expected a present proof v2 ack or problem report",
+                        ))
+                    }
+                };
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::common::proofs::proof_request::PresentationRequestData;
+    use crate::common::proofs::prover::credential_selection::NewestFirst;
+    use crate::common::test_utils::create_and_store_nonrevocable_credential;
+    use crate::core::profile::modular_libs_profile::ModularLibsProfile;
+    use crate::global::settings;
+    use crate::handlers::proof_presentation::versioned_verifier::VersionedVerifier;
+    use crate::plugins::ledger::base_ledger::BaseLedger;
+    use crate::plugins::ledger::in_memory_ledger::InMemoryLedger;
+    use crate::plugins::wallet::base_wallet::BaseWallet;
+    use crate::plugins::wallet::indy_wallet::IndySdkWallet;
+    use crate::protocols::proof_presentation::verifier::state_machine::VerifierState;
+    use crate::protocols::proof_presentation::verifier::verification_status::PresentationVerificationStatus;
+    use crate::utils::constants::DEFAULT_SCHEMA_ATTRS;
+    use crate::utils::devsetup::SetupLibraryWallet;
+
+    fn recording_send_message(sent: Arc<Mutex<Vec<A2AMessage>>>) -> SendClosure {
+        Box::new(move |message: A2AMessage| {
+            sent.lock().unwrap().push(message);
+            Box::pin(async { VcxResult::Ok(()) })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_present_proof_v2_round_trip() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let (institution_did, _) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let ledger: Arc<dyn BaseLedger> = Arc::new(InMemoryLedger::new());
+            let profile: Arc<dyn Profile> = Arc::new(ModularLibsProfile::new_with_ledger(wallet, ledger));
+            Arc::clone(&profile)
+                .inject_anoncreds()
+                .prover_create_link_secret(settings::DEFAULT_LINK_SECRET_ALIAS)
+                .await
+                .unwrap();
+            create_and_store_nonrevocable_credential(&profile, &profile, &institution_did, DEFAULT_SCHEMA_ATTRS).await;
+
+            let requested_attrs = json!([{ "name": "address1", "restrictions": [{ "issuer_did": institution_did }] }]);
+            let presentation_request = PresentationRequestData::create(&profile, "proof_v2")
+                .await
+                .unwrap()
+                .set_requested_attributes_as_string(requested_attrs.to_string())
+                .unwrap();
+            let sent = Arc::new(Mutex::new(vec![]));
+
+            let mut verifier = VersionedVerifier::create_from_request(
+                &profile,
+                "".into(),
+                &presentation_request,
+                PresentProofVersion::V2_0,
+            )
+            .await
+            .unwrap();
+            verifier
+                .send_presentation_request(recording_send_message(sent.clone()))
+                .await
+                .unwrap();
+            let request = sent.lock().unwrap().pop().unwrap();
+
+            let mut prover = VersionedProver::create_from_request_v2(request).unwrap();
+            assert_eq!(prover.get_thread_id().unwrap(), verifier.get_thread_id().unwrap());
+            let credentials = prover
+                .select_credentials(&profile, &NewestFirst, None)
+                .await
+                .unwrap()
+                .to_json()
+                .unwrap();
+            prover
+                .generate_presentation(&profile, credentials, "{}".to_owned())
+                .await
+                .unwrap();
+            prover
+                .send_presentation(recording_send_message(sent.clone()))
+                .await
+                .unwrap();
+            assert_eq!(prover.get_state(), ProverState::PresentationSent);
+            let presentation = sent.lock().unwrap().pop().unwrap();
+
+            verifier
+                .verify_presentation(&profile, presentation, recording_send_message(sent.clone()))
+                .await
+                .unwrap();
+            assert_eq!(verifier.get_state(), VerifierState::Finished);
+            assert_eq!(
+                verifier.get_verification_status(),
+                PresentationVerificationStatus::Valid
+            );
+            let ack = sent.lock().unwrap().pop().unwrap();
+
+            prover.process_presentation_ack(ack).unwrap();
+            assert_eq!(prover.get_state(), ProverState::Finished);
+        })
+        .await;
+    }
+}
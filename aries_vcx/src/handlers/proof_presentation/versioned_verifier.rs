@@ -0,0 +1,176 @@
+use std::sync::Arc;
+
+use messages::a2a::A2AMessage;
+use messages2::{
+    msg_fields::protocols::present_proof::{v2::PresentProofV2, PresentProof},
+    AriesMessage,
+};
+
+use crate::common::proofs::proof_request::PresentationRequestData;
+use crate::core::profile::profile::Profile;
+use crate::errors::error::prelude::*;
+use crate::handlers::proof_presentation::to_aries_message;
+use crate::handlers::proof_presentation::verifier::Verifier;
+use crate::protocols::proof_presentation::verifier::state_machine::VerifierState;
+use crate::protocols::proof_presentation::verifier::verification_status::PresentationVerificationStatus;
+use crate::protocols::proof_presentation_v2::formats::hyperledger_indy::HyperledgerIndyPresentationFormat;
+use crate::protocols::proof_presentation_v2::verifier::states::{PresentationVerified, RequestPrepared};
+use crate::protocols::proof_presentation_v2::verifier::VerifierV2;
+use crate::protocols::proof_presentation_v2::Failed;
+use crate::protocols::SendClosure;
+
+/// Version of the present proof protocol a verifier runs a presentation with.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PresentProofVersion {
+    #[default]
+    V1_0,
+    V2_0,
+}
+
+/// States of a present proof v2 verifier requesting `hlindy/proof-req@v2.0` presentations.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum VerifierV2State {
+    RequestPrepared(VerifierV2<RequestPrepared<HyperledgerIndyPresentationFormat>>),
+    RequestSent(VerifierV2<RequestPrepared<HyperledgerIndyPresentationFormat>>),
+    PresentationVerified(VerifierV2<PresentationVerified<HyperledgerIndyPresentationFormat>>),
+    Failed(VerifierV2<Failed>),
+}
+
+/// A verifier which runs each presentation with the protocol version picked when the request got created.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum VersionedVerifier {
+    V1(Verifier),
+    V2(VerifierV2State),
+}
+
+impl VersionedVerifier {
+    pub async fn create_from_request(
+        profile: &Arc<dyn Profile>,
+        source_id: String,
+        presentation_request: &PresentationRequestData,
+        version: PresentProofVersion,
+    ) -> VcxResult<Self> {
+        trace!(
+            "VersionedVerifier::create_from_request >>> source_id: {:?}, version: {:?}",
+            source_id,
+            version
+        );
+        match version {
+            PresentProofVersion::V1_0 => Ok(Self::V1(Verifier::create_from_request(
+                source_id,
+                presentation_request,
+            )?)),
+            PresentProofVersion::V2_0 => {
+                let verifier = VerifierV2::with_request(profile, presentation_request, None).await?;
+                Ok(Self::V2(VerifierV2State::RequestPrepared(verifier)))
+            }
+        }
+    }
+
+    pub fn version(&self) -> PresentProofVersion {
+        match self {
+            Self::V1(_) => PresentProofVersion::V1_0,
+            Self::V2(_) => PresentProofVersion::V2_0,
+        }
+    }
+
+    /// Returns the state of the presentation, with the v2 states mapped onto their v1 counterparts.
+    pub fn get_state(&self) -> VerifierState {
+        match self {
+            Self::V1(verifier) => verifier.get_state(),
+            Self::V2(VerifierV2State::RequestPrepared(_)) => VerifierState::PresentationRequestSet,
+            Self::V2(VerifierV2State::RequestSent(_)) => VerifierState::PresentationRequestSent,
+            Self::V2(VerifierV2State::PresentationVerified(_)) => VerifierState::Finished,
+            Self::V2(VerifierV2State::Failed(_)) => VerifierState::Failed,
+        }
+    }
+
+    pub fn get_thread_id(&self) -> VcxResult<String> {
+        match self {
+            Self::V1(verifier) => verifier.get_thread_id(),
+            Self::V2(VerifierV2State::RequestPrepared(verifier) | VerifierV2State::RequestSent(verifier)) => {
+                Ok(verifier.thread_id().to_owned())
+            }
+            Self::V2(VerifierV2State::PresentationVerified(verifier)) => Ok(verifier.thread_id().to_owned()),
+            Self::V2(VerifierV2State::Failed(verifier)) => Ok(verifier.thread_id().to_owned()),
+        }
+    }
+
+    pub async fn send_presentation_request(&mut self, send_message: SendClosure) -> VcxResult<()> {
+        match self {
+            Self::V1(verifier) => verifier.send_presentation_request(send_message).await,
+            Self::V2(state) => {
+                if let VerifierV2State::RequestPrepared(verifier) = state {
+                    let request = AriesMessage::from(verifier.get_request().clone());
+                    send_message(A2AMessage::Generic(serde_json::to_value(request)?)).await?;
+                    *state = VerifierV2State::RequestSent(verifier.clone());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Verifies a presentation received in response to the request and acknowledges it.
+    /// A v2 presentation which cannot be processed is answered with a problem report instead.
+    pub async fn verify_presentation(
+        &mut self,
+        profile: &Arc<dyn Profile>,
+        message: A2AMessage,
+        send_message: SendClosure,
+    ) -> VcxResult<()> {
+        trace!("VersionedVerifier::verify_presentation >>>");
+        match self {
+            Self::V1(verifier) => match message {
+                A2AMessage::Presentation(presentation) => {
+                    verifier.verify_presentation(profile, presentation, send_message).await
+                }
+                _ => Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    "Expected a present proof v1 presentation",
+                )),
+            },
+            Self::V2(state) => {
+                let VerifierV2State::RequestSent(verifier) = state else {
+                    return Err(AriesVcxError::from_msg(
+                        AriesVcxErrorKind::NotReady,
+                        "Verifier has not sent a presentation request yet",
+                    ));
+                };
+                let presentation = match to_aries_message(&message)? {
+                    AriesMessage::PresentProof(PresentProof::V2(PresentProofV2::Presentation(presentation))) => {
+                        presentation
+                    }
+                    _ => {
+                        return Err(AriesVcxError::from_msg(
+                            AriesVcxErrorKind::InvalidMessageFormat,
+                            "Expected a present proof v2 presentation",
+                        ))
+                    }
+                };
+
+                match verifier.clone().verify_presentation(profile, presentation).await {
+                    Ok(verifier) => {
+                        let ack = AriesMessage::from(verifier.get_ack());
+                        send_message(A2AMessage::Generic(serde_json::to_value(ack)?)).await?;
+                        *state = VerifierV2State::PresentationVerified(verifier);
+                    }
+                    Err(err) => {
+                        let verifier = verifier.clone().prepare_problem_report(err.to_string());
+                        let problem_report = AriesMessage::from(verifier.state().problem_report().clone());
+                        send_message(A2AMessage::Generic(serde_json::to_value(problem_report)?)).await?;
+                        *state = VerifierV2State::Failed(verifier);
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn get_verification_status(&self) -> PresentationVerificationStatus {
+        match self {
+            Self::V1(verifier) => verifier.get_verification_status(),
+            Self::V2(VerifierV2State::PresentationVerified(verifier)) => verifier.verification_status().clone(),
+            Self::V2(_) => PresentationVerificationStatus::Unavailable,
+        }
+    }
+}
@@ -30,6 +30,10 @@ impl TrustPingSender {
         self.ping.get_thread_id()
     }
 
+    pub fn response_received(&self) -> bool {
+        self.response_received
+    }
+
     pub async fn send_ping(&mut self, send_message: SendClosure) -> VcxResult<()> {
         if self.ping_sent {
             return Err(AriesVcxError::from_msg(
@@ -35,6 +35,7 @@ extern crate derive_builder;
 extern crate async_channel;
 
 pub extern crate messages;
+pub extern crate messages2;
 
 #[macro_use]
 pub mod utils;
@@ -5,6 +5,7 @@ use std::{
 };
 
 use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::plugins::ledger::base_ledger::BaseLedger;
 use crate::plugins::wallet::base_wallet::BaseWallet;
 use crate::{
     plugins::wallet::base_wallet::AsyncFnIteratorCollect,
@@ -17,9 +18,12 @@ use crate::{
 use async_trait::async_trait;
 use credx::{
     types::{
-        Credential as CredxCredential, CredentialDefinitionId, CredentialRequestMetadata, CredentialRevocationState,
-        DidValue, MasterSecret, PresentCredentials, Presentation, PresentationRequest, RevocationRegistry,
-        RevocationRegistryDefinition, RevocationRegistryDelta, RevocationRegistryId, Schema, SchemaId,
+        Credential as CredxCredential, CredentialDefinitionConfig, CredentialDefinitionId, CredentialDefinitionPrivate,
+        CredentialKeyCorrectnessProof, CredentialRequest, CredentialRequestMetadata, CredentialRevocationConfig,
+        CredentialRevocationState, CredentialValues, DidValue, IssuanceType, MasterSecret, PresentCredentials,
+        Presentation, PresentationRequest, RegistryType, RevocationRegistry, RevocationRegistryDefinition,
+        RevocationRegistryDefinitionPrivate, RevocationRegistryDelta, RevocationRegistryId, Schema, SchemaId,
+        SignatureType,
     },
     ursa::bn::BigNumber,
 };
@@ -28,6 +32,7 @@ use credx::{
     ursa::cl::MasterSecret as UrsaMasterSecret,
 };
 use indy_credx as credx;
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
 
 use super::base_anoncreds::BaseAnonCreds;
@@ -35,14 +40,83 @@ use super::base_anoncreds::BaseAnonCreds;
 const CATEGORY_CREDENTIAL: &str = "VCX_CREDENTIAL";
 const CATEGORY_LINK_SECRET: &str = "VCX_LINK_SECRET";
 
+const CATEGORY_CRED_DEF: &str = "VCX_CRED_DEF";
+const CATEGORY_CRED_DEF_PRIV: &str = "VCX_CRED_DEF_PRIV";
+const CATEGORY_CRED_KEY_CORRECTNESS_PROOF: &str = "VCX_CRED_KEY_CORRECTNESS_PROOF";
+const CATEGORY_CRED_MAP_SCHEMA_ID: &str = "VCX_CRED_MAP_SCHEMA_ID";
+
+const CATEGORY_REV_REG: &str = "VCX_REV_REG";
+const CATEGORY_REV_REG_INFO: &str = "VCX_REV_REG_INFO";
+const CATEGORY_REV_REG_DEF: &str = "VCX_REV_REG_DEF";
+const CATEGORY_REV_REG_DEF_PRIV: &str = "VCX_REV_REG_DEF_PRIV";
+// local revocations which are yet to be published to the ledger
+const CATEGORY_REV_REG_DELTA: &str = "VCX_REV_REG_DELTA";
+
+/// Issuer-side bookkeeping of a revocation registry, mirroring what vdrtools keeps for
+/// `ISSUANCE_BY_DEFAULT`/`ISSUANCE_ON_DEMAND` registries.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct RevocationRegistryInfo {
+    id: RevocationRegistryId,
+    // last index handed out to a credential
+    curr_id: u32,
+    // for ISSUANCE_BY_DEFAULT - revoked indexes, for ISSUANCE_ON_DEMAND - issued indexes
+    used_ids: HashSet<u32>,
+}
+
 #[derive(Debug)]
 pub struct IndyCredxAnonCreds {
     wallet: Arc<dyn BaseWallet>,
+    ledger: Arc<dyn BaseLedger>,
 }
 
 impl IndyCredxAnonCreds {
-    pub fn new(wallet: Arc<dyn BaseWallet>) -> Self {
-        IndyCredxAnonCreds { wallet }
+    pub fn new(wallet: Arc<dyn BaseWallet>, ledger: Arc<dyn BaseLedger>) -> Self {
+        IndyCredxAnonCreds { wallet, ledger }
+    }
+
+    async fn get_wallet_record_value<T>(&self, category: &str, id: &str) -> VcxResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        let record = self.wallet.get_wallet_record(category, id, "{}").await?;
+        let record: Value = serde_json::from_str(&record)?;
+        let value = (&record).try_get("value")?;
+
+        Ok(serde_json::from_str(value.try_as_str()?)?)
+    }
+
+    async fn add_wallet_record_value<T>(&self, category: &str, id: &str, value: &T) -> VcxResult<()>
+    where
+        T: Serialize,
+    {
+        let value = serde_json::to_string(value)?;
+        self.wallet.add_wallet_record(category, id, &value, None).await
+    }
+
+    async fn update_wallet_record_value<T>(&self, category: &str, id: &str, value: &T) -> VcxResult<()>
+    where
+        T: Serialize,
+    {
+        let value = serde_json::to_string(value)?;
+        self.wallet.update_wallet_record_value(category, id, &value).await
+    }
+
+    async fn get_rev_reg_delta(&self, rev_reg_id: &str) -> VcxResult<Option<RevocationRegistryDelta>> {
+        match self.get_wallet_record_value(CATEGORY_REV_REG_DELTA, rev_reg_id).await {
+            Ok(rev_reg_delta) => Ok(Some(rev_reg_delta)),
+            Err(err) if err.kind() == AriesVcxErrorKind::WalletRecordNotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn set_rev_reg_delta(&self, rev_reg_id: &str, rev_reg_delta: &RevocationRegistryDelta) -> VcxResult<()> {
+        if self.get_rev_reg_delta(rev_reg_id).await?.is_some() {
+            self.update_wallet_record_value(CATEGORY_REV_REG_DELTA, rev_reg_id, rev_reg_delta)
+                .await
+        } else {
+            self.add_wallet_record_value(CATEGORY_REV_REG_DELTA, rev_reg_id, rev_reg_delta)
+                .await
+        }
     }
 
     async fn get_link_secret(&self, link_secret_id: &str) -> VcxResult<MasterSecret> {
@@ -198,8 +272,45 @@ impl BaseAnonCreds for IndyCredxAnonCreds {
         max_creds: u32,
         tag: &str,
     ) -> VcxResult<(String, String, String)> {
-        let _ = (issuer_did, cred_def_id, tails_dir, max_creds, tag);
-        Err(unimplemented_method_err("credx issuer_create_and_store_revoc_reg"))
+        let issuer_did = DidValue::new(issuer_did, None);
+        let cred_def: CredentialDefinition = self.get_wallet_record_value(CATEGORY_CRED_DEF, cred_def_id).await?;
+
+        let mut tails_writer = credx::tails::TailsFileWriter::new(Some(tails_dir.to_string()));
+
+        let (rev_reg_def, rev_reg_def_priv, rev_reg, rev_reg_delta) = credx::issuer::create_revocation_registry(
+            &issuer_did,
+            &cred_def,
+            tag,
+            RegistryType::CL_ACCUM,
+            IssuanceType::ISSUANCE_BY_DEFAULT,
+            max_creds,
+            &mut tails_writer,
+        )?;
+
+        let rev_reg_id = match rev_reg_def.borrow() {
+            RevocationRegistryDefinition::RevocationRegistryDefinitionV1(r) => r.id.clone(),
+        };
+
+        let rev_reg_info = RevocationRegistryInfo {
+            id: rev_reg_id.clone(),
+            curr_id: 0,
+            used_ids: HashSet::new(),
+        };
+
+        self.add_wallet_record_value(CATEGORY_REV_REG_INFO, &rev_reg_id.0, &rev_reg_info)
+            .await?;
+        self.add_wallet_record_value(CATEGORY_REV_REG_DEF, &rev_reg_id.0, &rev_reg_def)
+            .await?;
+        self.add_wallet_record_value(CATEGORY_REV_REG_DEF_PRIV, &rev_reg_id.0, &rev_reg_def_priv)
+            .await?;
+        self.add_wallet_record_value(CATEGORY_REV_REG, &rev_reg_id.0, &rev_reg)
+            .await?;
+
+        Ok((
+            rev_reg_id.0,
+            serde_json::to_string(&rev_reg_def)?,
+            serde_json::to_string(&rev_reg_delta)?,
+        ))
     }
 
     async fn issuer_create_and_store_credential_def(
@@ -210,13 +321,67 @@ impl BaseAnonCreds for IndyCredxAnonCreds {
         sig_type: Option<&str>,
         config_json: &str,
     ) -> VcxResult<(String, String)> {
-        let _ = (issuer_did, schema_json, tag, sig_type, config_json);
-        Err(unimplemented_method_err("credx issuer_create_and_store_credential_def"))
+        let issuer_did = DidValue::new(issuer_did, None);
+        let schema: Schema = serde_json::from_str(schema_json)?;
+        let signature_type = match sig_type {
+            None | Some("CL") => SignatureType::CL,
+            Some(other) => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidInput,
+                    format!("Unsupported credential definition signature type: {}", other),
+                ))
+            }
+        };
+        let config: CredentialDefinitionConfig = serde_json::from_str(config_json)?;
+
+        let (cred_def, cred_def_priv, cred_key_correctness_proof) =
+            credx::issuer::create_credential_definition(&issuer_did, &schema, tag, signature_type, config)?;
+
+        let cred_def_id = match cred_def.borrow() {
+            CredentialDefinition::CredentialDefinitionV1(c) => c.id.0.to_string(),
+        };
+
+        let existing_cred_def = self
+            .get_wallet_record_value::<CredentialDefinition>(CATEGORY_CRED_DEF, &cred_def_id)
+            .await
+            .ok(); // ignore error, as we only care about whether it exists or not
+
+        if existing_cred_def.is_some() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::CredDefAlreadyCreated,
+                format!("Credential definition {} already exists in wallet.", cred_def_id),
+            ));
+        }
+
+        self.add_wallet_record_value(CATEGORY_CRED_DEF, &cred_def_id, &cred_def)
+            .await?;
+        self.add_wallet_record_value(CATEGORY_CRED_DEF_PRIV, &cred_def_id, &cred_def_priv)
+            .await?;
+        self.add_wallet_record_value(
+            CATEGORY_CRED_KEY_CORRECTNESS_PROOF,
+            &cred_def_id,
+            &cred_key_correctness_proof,
+        )
+        .await?;
+        // the offer must reference the full schema id, whereas the cred def may only know the schema seq_no
+        self.add_wallet_record_value(CATEGORY_CRED_MAP_SCHEMA_ID, &cred_def_id, schema.id())
+            .await?;
+
+        Ok((cred_def_id, serde_json::to_string(&cred_def)?))
     }
 
     async fn issuer_create_credential_offer(&self, cred_def_id: &str) -> VcxResult<String> {
-        let _ = cred_def_id;
-        Err(unimplemented_method_err("credx issuer_create_credential_offer"))
+        let cred_def: CredentialDefinition = self.get_wallet_record_value(CATEGORY_CRED_DEF, cred_def_id).await?;
+        let correctness_proof: CredentialKeyCorrectnessProof = self
+            .get_wallet_record_value(CATEGORY_CRED_KEY_CORRECTNESS_PROOF, cred_def_id)
+            .await?;
+        let schema_id: SchemaId = self
+            .get_wallet_record_value(CATEGORY_CRED_MAP_SCHEMA_ID, cred_def_id)
+            .await?;
+
+        let cred_offer = credx::issuer::create_credential_offer(&schema_id, &cred_def, &correctness_proof)?;
+
+        Ok(serde_json::to_string(&cred_offer)?)
     }
 
     async fn issuer_create_credential(
@@ -227,8 +392,90 @@ impl BaseAnonCreds for IndyCredxAnonCreds {
         rev_reg_id: Option<String>,
         tails_dir: Option<String>,
     ) -> VcxResult<(String, Option<String>, Option<String>)> {
-        let _ = (cred_offer_json, cred_req_json, cred_values_json, rev_reg_id, tails_dir);
-        Err(unimplemented_method_err("credx issuer_create_credential"))
+        // credx keeps the revocation registry state itself, the tails file is only needed to revoke
+        let _ = tails_dir;
+
+        let cred_offer: CredentialOffer = serde_json::from_str(cred_offer_json)?;
+        let cred_request: CredentialRequest = serde_json::from_str(cred_req_json)?;
+        let cred_values: CredentialValues = serde_json::from_str(cred_values_json)?;
+
+        let cred_def_id = &cred_offer.cred_def_id.0;
+        let cred_def: CredentialDefinition = self.get_wallet_record_value(CATEGORY_CRED_DEF, cred_def_id).await?;
+        let cred_def_priv: CredentialDefinitionPrivate = self
+            .get_wallet_record_value(CATEGORY_CRED_DEF_PRIV, cred_def_id)
+            .await?;
+
+        let mut revocation_parts = match &rev_reg_id {
+            Some(rev_reg_id) => {
+                let rev_reg_def: RevocationRegistryDefinition =
+                    self.get_wallet_record_value(CATEGORY_REV_REG_DEF, rev_reg_id).await?;
+                let rev_reg_def_priv: RevocationRegistryDefinitionPrivate = self
+                    .get_wallet_record_value(CATEGORY_REV_REG_DEF_PRIV, rev_reg_id)
+                    .await?;
+                let rev_reg: RevocationRegistry = self.get_wallet_record_value(CATEGORY_REV_REG, rev_reg_id).await?;
+                let rev_reg_info: RevocationRegistryInfo =
+                    self.get_wallet_record_value(CATEGORY_REV_REG_INFO, rev_reg_id).await?;
+
+                Some((rev_reg_def, rev_reg_def_priv, rev_reg, rev_reg_info))
+            }
+            None => None,
+        };
+
+        let revocation_config = match &mut revocation_parts {
+            Some((rev_reg_def, rev_reg_def_priv, rev_reg, rev_reg_info)) => {
+                let (issuance_type, max_cred_num) = match rev_reg_def {
+                    RevocationRegistryDefinition::RevocationRegistryDefinitionV1(r) => {
+                        (r.value.issuance_type, r.value.max_cred_num)
+                    }
+                };
+
+                rev_reg_info.curr_id += 1;
+                if rev_reg_info.curr_id > max_cred_num {
+                    return Err(AriesVcxError::from_msg(
+                        AriesVcxErrorKind::ActionNotSupported,
+                        format!("The revocation registry {} is full", rev_reg_info.id.0),
+                    ));
+                }
+
+                if issuance_type == IssuanceType::ISSUANCE_ON_DEMAND {
+                    rev_reg_info.used_ids.insert(rev_reg_info.curr_id);
+                }
+
+                Some(CredentialRevocationConfig {
+                    reg_def: rev_reg_def,
+                    reg_def_private: rev_reg_def_priv,
+                    registry: rev_reg,
+                    registry_idx: rev_reg_info.curr_id,
+                    registry_used: &rev_reg_info.used_ids,
+                })
+            }
+            None => None,
+        };
+
+        let (credential, new_rev_reg, rev_reg_delta) = credx::issuer::create_credential(
+            &cred_def,
+            &cred_def_priv,
+            &cred_offer,
+            &cred_request,
+            cred_values,
+            revocation_config,
+        )?;
+
+        let cred_rev_id = match (rev_reg_id, new_rev_reg, revocation_parts) {
+            (Some(rev_reg_id), Some(new_rev_reg), Some((_, _, _, rev_reg_info))) => {
+                self.update_wallet_record_value(CATEGORY_REV_REG, &rev_reg_id, &new_rev_reg)
+                    .await?;
+                self.update_wallet_record_value(CATEGORY_REV_REG_INFO, &rev_reg_id, &rev_reg_info)
+                    .await?;
+
+                Some(rev_reg_info.curr_id.to_string())
+            }
+            _ => None,
+        };
+
+        let rev_reg_delta_json = rev_reg_delta.as_ref().map(serde_json::to_string).transpose()?;
+
+        Ok((serde_json::to_string(&credential)?, cred_rev_id, rev_reg_delta_json))
     }
 
     /// * `requested_credentials_json`: either a credential or self-attested attribute for each requested attribute
@@ -683,13 +930,90 @@ impl BaseAnonCreds for IndyCredxAnonCreds {
     }
 
     async fn revoke_credential_local(&self, tails_dir: &str, rev_reg_id: &str, cred_rev_id: &str) -> VcxResult<()> {
-        let _ = (tails_dir, rev_reg_id, cred_rev_id);
-        Err(unimplemented_method_err("credx revoke_credential_local"))
+        let cred_rev_idx: u32 = cred_rev_id
+            .parse()
+            .map_err(|e| AriesVcxError::from_msg(AriesVcxErrorKind::ParsingError, e))?;
+
+        let rev_reg_def: RevocationRegistryDefinition =
+            self.get_wallet_record_value(CATEGORY_REV_REG_DEF, rev_reg_id).await?;
+        let rev_reg: RevocationRegistry = self.get_wallet_record_value(CATEGORY_REV_REG, rev_reg_id).await?;
+        let mut rev_reg_info: RevocationRegistryInfo =
+            self.get_wallet_record_value(CATEGORY_REV_REG_INFO, rev_reg_id).await?;
+
+        let (issuance_type, tails_file_hash) = match rev_reg_def.borrow() {
+            RevocationRegistryDefinition::RevocationRegistryDefinitionV1(r) => {
+                (r.value.issuance_type, r.value.tails_hash.to_string())
+            }
+        };
+
+        let already_revoked = match issuance_type {
+            IssuanceType::ISSUANCE_ON_DEMAND => !rev_reg_info.used_ids.remove(&cred_rev_idx),
+            IssuanceType::ISSUANCE_BY_DEFAULT => !rev_reg_info.used_ids.insert(cred_rev_idx),
+        };
+        if already_revoked {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!(
+                    "Credential with revocation id {} is not issued or already revoked in {}",
+                    cred_rev_id, rev_reg_id
+                ),
+            ));
+        }
+
+        let tails_file_path = format!("{}/{}", tails_dir, tails_file_hash);
+        let tails_reader: credx::tails::TailsReader = credx::tails::TailsFileReader::new(&tails_file_path);
+
+        let (new_rev_reg, new_rev_reg_delta) =
+            credx::issuer::revoke_credential(&rev_reg_def, &rev_reg, cred_rev_idx, &tails_reader)?;
+
+        debug!("revoke_credential_local >>> new_rev_reg_delta: {:?}", new_rev_reg_delta);
+
+        let rev_reg_delta = match self.get_rev_reg_delta(rev_reg_id).await? {
+            Some(old_rev_reg_delta) => {
+                credx::issuer::merge_revocation_registry_deltas(&old_rev_reg_delta, &new_rev_reg_delta)?
+            }
+            None => new_rev_reg_delta,
+        };
+
+        self.update_wallet_record_value(CATEGORY_REV_REG, rev_reg_id, &new_rev_reg)
+            .await?;
+        self.update_wallet_record_value(CATEGORY_REV_REG_INFO, rev_reg_id, &rev_reg_info)
+            .await?;
+        self.set_rev_reg_delta(rev_reg_id, &rev_reg_delta).await
     }
 
     async fn publish_local_revocations(&self, submitter_did: &str, rev_reg_id: &str) -> VcxResult<()> {
-        let _ = (submitter_did, rev_reg_id);
-        Err(unimplemented_method_err("credx publish_local_revocations"))
+        let rev_reg_delta = self.get_rev_reg_delta(rev_reg_id).await?.ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::RevDeltaNotFound,
+                format!(
+                    "Failed to publish revocation delta for revocation registry {}, no delta found. Possibly already published?",
+                    rev_reg_id
+                ),
+            )
+        })?;
+
+        self.ledger
+            .publish_rev_reg_delta(rev_reg_id, &serde_json::to_string(&rev_reg_delta)?, submitter_did)
+            .await?;
+
+        info!(
+            "publish_local_revocations >>> rev_reg_delta published for rev_reg_id {}",
+            rev_reg_id
+        );
+
+        self.wallet
+            .delete_wallet_record(CATEGORY_REV_REG_DELTA, rev_reg_id)
+            .await
+            .map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::RevDeltaFailedToClear,
+                    format!(
+                        "Failed to clear revocation delta storage for rev_reg_id: {}, error: {}",
+                        rev_reg_id, err
+                    ),
+                )
+            })
     }
 
     async fn generate_nonce(&self) -> VcxResult<String> {
@@ -779,13 +1103,6 @@ fn _format_attribute_as_marker_tag_name(attribute_name: &str) -> String {
     format!("attr::{attribute_name}::marker")
 }
 
-fn unimplemented_method_err(method_name: &str) -> AriesVcxError {
-    AriesVcxError::from_msg(
-        AriesVcxErrorKind::UnimplementedFeature,
-        format!("method '{}' is not yet implemented in AriesVCX", method_name),
-    )
-}
-
 // common transformation requirement in credx
 fn hashmap_as_ref<'a, T, U>(map: &'a HashMap<T, U>) -> HashMap<T, &'a U>
 where
@@ -804,31 +1121,215 @@ where
 #[cfg(test)]
 #[cfg(feature = "general_test")]
 mod unit_tests {
-    use crate::errors::error::{AriesVcxErrorKind, VcxResult};
-    use crate::{common::test_utils::mock_profile, plugins::anoncreds::base_anoncreds::BaseAnonCreds};
-
-    use super::IndyCredxAnonCreds;
+    use std::sync::Arc;
+
+    use aries_askar::{PassKey, StoreKeyMethod};
+
+    use crate::common::credentials::encoding::encode_attributes;
+    use crate::common::primitives::revocation_registry::RevocationRegistryDefinition;
+    use crate::core::profile::modular_libs_profile::ModularLibsProfile;
+    use crate::core::profile::profile::Profile;
+    use crate::errors::error::AriesVcxErrorKind;
+    use crate::plugins::ledger::in_memory_ledger::InMemoryLedger;
+    use crate::plugins::wallet::askar_wallet::AskarWallet;
+    use crate::utils::constants::{DEFAULT_SCHEMA_ATTRS, TAILS_DIR};
+    use crate::utils::get_temp_dir_path;
+
+    async fn modular_profile() -> Arc<dyn Profile> {
+        let wallet = AskarWallet::create("sqlite://:memory:", StoreKeyMethod::Unprotected, PassKey::empty(), None)
+            .await
+            .unwrap();
+        Arc::new(ModularLibsProfile::new_with_ledger(
+            Arc::new(wallet),
+            Arc::new(InMemoryLedger::new()),
+        ))
+    }
 
     #[tokio::test]
-    async fn test_unimplemented_methods() {
-        // test used to assert which methods are unimplemented currently, can be removed after all methods implemented
+    async fn test_issue_present_revoke_round_trip() {
+        let profile = modular_profile().await;
+        let wallet = profile.inject_wallet();
+        let ledger = Arc::clone(&profile).inject_ledger();
+        let anoncreds = Arc::clone(&profile).inject_anoncreds();
+
+        let (did, _) = wallet.create_and_store_my_did(None, None).await.unwrap();
+        let tails_dir = get_temp_dir_path(TAILS_DIR).to_str().unwrap().to_string();
+        std::fs::create_dir_all(&tails_dir).unwrap();
+        let link_secret_id = "test_link_secret";
+        anoncreds.prover_create_link_secret(link_secret_id).await.unwrap();
+
+        // issue
+        let (schema_id, schema_json) = anoncreds
+            .issuer_create_schema(&did, "test_schema", "1.0", DEFAULT_SCHEMA_ATTRS)
+            .await
+            .unwrap();
+        ledger.publish_schema(&schema_json, &did, None).await.unwrap();
+        let (cred_def_id, cred_def_json) = anoncreds
+            .issuer_create_and_store_credential_def(
+                &did,
+                &schema_json,
+                "1",
+                None,
+                &json!({"support_revocation": true}).to_string(),
+            )
+            .await
+            .unwrap();
+        ledger.publish_cred_def(&cred_def_json, &did).await.unwrap();
+        let (rev_reg_id, rev_reg_def_json, rev_reg_delta_json) = anoncreds
+            .issuer_create_and_store_revoc_reg(&did, &cred_def_id, &tails_dir, 10, "tag1")
+            .await
+            .unwrap();
+        let rev_reg_def: RevocationRegistryDefinition = serde_json::from_str(&rev_reg_def_json).unwrap();
+        ledger.publish_rev_reg_def(&rev_reg_def, &did).await.unwrap();
+        ledger
+            .publish_rev_reg_delta(&rev_reg_id, &rev_reg_delta_json, &did)
+            .await
+            .unwrap();
 
-        fn assert_unimplemented<T: std::fmt::Debug>(result: VcxResult<T>) {
-            assert_eq!(result.unwrap_err().kind(), AriesVcxErrorKind::UnimplementedFeature)
-        }
+        let offer = anoncreds.issuer_create_credential_offer(&cred_def_id).await.unwrap();
+        let cred_def_json = ledger.get_cred_def(&cred_def_id, None).await.unwrap();
+        let (req, req_meta) = anoncreds
+            .prover_create_credential_req(&did, &offer, &cred_def_json, link_secret_id)
+            .await
+            .unwrap();
+        let cred_values = encode_attributes(
+            r#"{"address1": "123 Main St", "address2": "Suite 3", "city": "Draper", "state": "UT", "zip": "84000"}"#,
+        )
+        .unwrap();
+        let (cred, cred_rev_id, _) = anoncreds
+            .issuer_create_credential(
+                &offer,
+                &req,
+                &cred_values,
+                Some(rev_reg_id.clone()),
+                Some(tails_dir.clone()),
+            )
+            .await
+            .unwrap();
+        let cred_rev_id = cred_rev_id.unwrap();
+        assert_eq!(cred_rev_id, "1");
+
+        let rev_reg_def_json = ledger.get_rev_reg_def_json(&rev_reg_id).await.unwrap();
+        let cred_id = anoncreds
+            .prover_store_credential(None, &req_meta, &cred, &cred_def_json, Some(&rev_reg_def_json))
+            .await
+            .unwrap();
+
+        // present
+        let (_, rev_reg_delta_json, timestamp) = ledger.get_rev_reg_delta_json(&rev_reg_id, None, None).await.unwrap();
+        let proof_req = json!({
+            "nonce": anoncreds.generate_nonce().await.unwrap(),
+            "name": "proof_req_1",
+            "version": "0.1",
+            "requested_attributes": {
+                "address1_1": {
+                    "name": "address1",
+                    "restrictions": [{ "cred_def_id": cred_def_id }]
+                }
+            },
+            "requested_predicates": {
+                "zip_2": { "name": "zip", "p_type": ">=", "p_value": 80000 }
+            },
+            "non_revoked": { "to": timestamp }
+        })
+        .to_string();
+        let requested_credentials = json!({
+            "self_attested_attributes": {},
+            "requested_attributes": {
+                "address1_1": { "cred_id": cred_id, "timestamp": timestamp, "revealed": true }
+            },
+            "requested_predicates": {
+                "zip_2": { "cred_id": cred_id, "timestamp": timestamp }
+            }
+        })
+        .to_string();
+
+        let rev_state_json = anoncreds
+            .create_revocation_state(
+                &tails_dir,
+                &rev_reg_def_json,
+                &rev_reg_delta_json,
+                timestamp,
+                &cred_rev_id,
+            )
+            .await
+            .unwrap();
+        let rev_state: serde_json::Value = serde_json::from_str(&rev_state_json).unwrap();
+        let rev_states = json!({ rev_reg_id.clone(): { timestamp.to_string(): rev_state } }).to_string();
+
+        let schema: serde_json::Value =
+            serde_json::from_str(&ledger.get_schema(&schema_id, None).await.unwrap()).unwrap();
+        let schemas = json!({ schema_id: schema }).to_string();
+        let cred_def: serde_json::Value = serde_json::from_str(&cred_def_json).unwrap();
+        let cred_defs = json!({ cred_def_id: cred_def }).to_string();
+        let rev_reg_def: serde_json::Value = serde_json::from_str(&rev_reg_def_json).unwrap();
+        let rev_reg_defs = json!({ rev_reg_id.clone(): rev_reg_def }).to_string();
+
+        let proof = anoncreds
+            .prover_create_proof(
+                &proof_req,
+                &requested_credentials,
+                link_secret_id,
+                &schemas,
+                &cred_defs,
+                Some(&rev_states),
+            )
+            .await
+            .unwrap();
 
-        let profile = mock_profile();
-        let anoncreds: Box<dyn BaseAnonCreds> = Box::new(IndyCredxAnonCreds::new(profile.inject_wallet()));
+        let (_, rev_reg_json, _) = ledger.get_rev_reg(&rev_reg_id, timestamp).await.unwrap();
+        let rev_reg: serde_json::Value = serde_json::from_str(&rev_reg_json).unwrap();
+        let rev_regs = json!({ rev_reg_id.clone(): { timestamp.to_string(): rev_reg } }).to_string();
 
-        assert_unimplemented(anoncreds.issuer_create_and_store_revoc_reg("", "", "", 0, "").await);
-        assert_unimplemented(
+        assert!(anoncreds
+            .verifier_verify_proof(&proof_req, &proof, &schemas, &cred_defs, &rev_reg_defs, &rev_regs)
+            .await
+            .unwrap());
+
+        // revoke
+        anoncreds
+            .revoke_credential_local(&tails_dir, &rev_reg_id, &cred_rev_id)
+            .await
+            .unwrap();
+        assert_eq!(
             anoncreds
-                .issuer_create_and_store_credential_def("", "", "", None, "")
-                .await,
+                .revoke_credential_local(&tails_dir, &rev_reg_id, &cred_rev_id)
+                .await
+                .unwrap_err()
+                .kind(),
+            AriesVcxErrorKind::InvalidInput
         );
-        assert_unimplemented(anoncreds.issuer_create_credential_offer("").await);
-        assert_unimplemented(anoncreds.issuer_create_credential("", "", "", None, None).await);
-        assert_unimplemented(anoncreds.revoke_credential_local("", "", "").await);
-        assert_unimplemented(anoncreds.publish_local_revocations("", "").await);
+        // the revocation is only local until it is published
+        let (_, _, unpublished_timestamp) = ledger.get_rev_reg_delta_json(&rev_reg_id, None, None).await.unwrap();
+        assert_eq!(unpublished_timestamp, timestamp);
+
+        anoncreds.publish_local_revocations(&did, &rev_reg_id).await.unwrap();
+        assert_eq!(
+            anoncreds
+                .publish_local_revocations(&did, &rev_reg_id)
+                .await
+                .unwrap_err()
+                .kind(),
+            AriesVcxErrorKind::RevDeltaNotFound
+        );
+
+        // the presentation no longer holds against the post-revocation accumulator on the ledger
+        let (_, _, revoked_timestamp) = ledger.get_rev_reg_delta_json(&rev_reg_id, None, None).await.unwrap();
+        assert!(revoked_timestamp > timestamp);
+        let (_, revoked_rev_reg_json, _) = ledger.get_rev_reg(&rev_reg_id, revoked_timestamp).await.unwrap();
+        let revoked_rev_reg: serde_json::Value = serde_json::from_str(&revoked_rev_reg_json).unwrap();
+        let revoked_rev_regs = json!({ rev_reg_id: { timestamp.to_string(): revoked_rev_reg } }).to_string();
+
+        assert!(!anoncreds
+            .verifier_verify_proof(
+                &proof_req,
+                &proof,
+                &schemas,
+                &cred_defs,
+                &rev_reg_defs,
+                &revoked_rev_regs
+            )
+            .await
+            .unwrap());
     }
 }
@@ -0,0 +1,615 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+
+use crate::common::primitives::revocation_registry::RevocationRegistryDefinition;
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::utils::json::{AsTypeOrDeserializationError, TryGetIndex};
+
+use super::base_ledger::BaseLedger;
+
+const NYM_TXN_TYPE: &str = "1";
+const ATTRIB_TXN_TYPE: &str = "100";
+const SCHEMA_TXN_TYPE: &str = "101";
+const CRED_DEF_TXN_TYPE: &str = "102";
+const REVOC_REG_DEF_TXN_TYPE: &str = "113";
+const REVOC_REG_ENTRY_TXN_TYPE: &str = "114";
+
+/// Accumulated state of a revocation registry after a published entry
+#[derive(Debug, Clone)]
+struct RevRegEntry {
+    txn_time: u64,
+    accum: Value,
+    issued: BTreeSet<u64>,
+    revoked: BTreeSet<u64>,
+}
+
+#[derive(Debug, Default)]
+struct LedgerState {
+    txns: Vec<Value>,
+    last_txn_time: u64,
+    nyms: HashMap<String, Value>,
+    attribs: HashMap<String, Map<String, Value>>,
+    schemas: HashMap<String, Value>,
+    cred_defs: HashMap<String, Value>,
+    rev_reg_defs: HashMap<String, Value>,
+    rev_reg_entries: HashMap<String, Vec<RevRegEntry>>,
+}
+
+impl LedgerState {
+    // returns the seqNo and txnTime of the written transaction. txnTimes are strictly increasing, so
+    // that entries written within the same second can still be told apart by timestamp queries
+    fn write_txn(&mut self, txn_type: &str, submitter_did: &str, data: Value) -> (u64, u64) {
+        let txn_time = std::cmp::max(current_epoch_time(), self.last_txn_time + 1);
+        self.last_txn_time = txn_time;
+        let seq_no = self.txns.len() as u64 + 1;
+        self.txns.push(json!({
+            "txn": {
+                "type": txn_type,
+                "data": data,
+                "metadata": { "from": submitter_did }
+            },
+            "txnMetadata": { "seqNo": seq_no, "txnTime": txn_time }
+        }));
+        (seq_no, txn_time)
+    }
+
+    fn rev_reg_entries(&self, rev_reg_id: &str) -> VcxResult<&Vec<RevRegEntry>> {
+        self.rev_reg_entries.get(rev_reg_id).ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::LedgerItemNotFound,
+                format!("No revocation registry entries found for {}", rev_reg_id),
+            )
+        })
+    }
+}
+
+/// Implementation of [BaseLedger] which keeps all published transactions in memory. Intended for
+/// running issuance, presentation and revocation flows end-to-end without a ledger pool.
+#[derive(Debug, Default)]
+pub struct InMemoryLedger {
+    state: RwLock<LedgerState>,
+}
+
+impl InMemoryLedger {
+    pub fn new() -> Self {
+        InMemoryLedger::default()
+    }
+}
+
+fn current_epoch_time() -> u64 {
+    time::get_time().sec as u64
+}
+
+fn reply(result: Value) -> String {
+    json!({ "op": "REPLY", "result": result }).to_string()
+}
+
+fn unimplemented_method_err(method_name: &str) -> AriesVcxError {
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::UnimplementedFeature,
+        format!(
+            "method called '{}' is not supported by the in-memory ledger",
+            method_name
+        ),
+    )
+}
+
+fn ledger_item_not_found(item: &str, id: &str) -> AriesVcxError {
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::LedgerItemNotFound,
+        format!("{} {} not found on the ledger", item, id),
+    )
+}
+
+fn get_id_field(json: &Value) -> VcxResult<String> {
+    Ok(json.try_get("id")?.try_as_str()?.to_string())
+}
+
+fn index_set(value: &Value, field: &str) -> BTreeSet<u64> {
+    value
+        .get(field)
+        .and_then(Value::as_array)
+        .map(|indices| indices.iter().filter_map(Value::as_u64).collect())
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl BaseLedger for InMemoryLedger {
+    async fn sign_and_submit_request(&self, submitter_did: &str, request_json: &str) -> VcxResult<String> {
+        let _ = (submitter_did, request_json);
+        Err(unimplemented_method_err("sign_and_submit_request"))
+    }
+
+    async fn submit_request(&self, request_json: &str) -> VcxResult<String> {
+        let _ = request_json;
+        Err(unimplemented_method_err("submit_request"))
+    }
+
+    async fn endorse_transaction(&self, endorser_did: &str, request_json: &str) -> VcxResult<()> {
+        let _ = (endorser_did, request_json);
+        Err(unimplemented_method_err("endorse_transaction"))
+    }
+
+    async fn set_endorser(&self, submitter_did: &str, request: &str, endorser: &str) -> VcxResult<String> {
+        let _ = (submitter_did, request, endorser);
+        Err(unimplemented_method_err("set_endorser"))
+    }
+
+    async fn get_txn_author_agreement(&self) -> VcxResult<String> {
+        // the in-memory ledger does not require a transaction author agreement
+        Ok(json!({}).to_string())
+    }
+
+    async fn get_nym(&self, did: &str) -> VcxResult<String> {
+        let state = self.state.read()?;
+        let data = state.nyms.get(did).map(|nym| nym.to_string());
+        Ok(reply(json!({ "type": "105", "dest": did, "data": data })))
+    }
+
+    async fn publish_nym(
+        &self,
+        submitter_did: &str,
+        target_did: &str,
+        verkey: Option<&str>,
+        data: Option<&str>,
+        role: Option<&str>,
+    ) -> VcxResult<String> {
+        let mut state = self.state.write()?;
+        let mut nym = state
+            .nyms
+            .get(target_did)
+            .cloned()
+            .unwrap_or_else(|| json!({ "dest": target_did, "identifier": submitter_did }));
+        if let Some(verkey) = verkey {
+            nym["verkey"] = json!(verkey);
+        }
+        if let Some(alias) = data {
+            nym["alias"] = json!(alias);
+        }
+        if let Some(role) = role {
+            nym["role"] = json!(role);
+        }
+        let (seq_no, txn_time) = state.write_txn(NYM_TXN_TYPE, submitter_did, nym.clone());
+        nym["seqNo"] = json!(seq_no);
+        nym["txnTime"] = json!(txn_time);
+        state.nyms.insert(target_did.to_string(), nym);
+        Ok(reply(json!({ "seqNo": seq_no, "txnTime": txn_time })))
+    }
+
+    async fn get_schema(&self, schema_id: &str, submitter_did: Option<&str>) -> VcxResult<String> {
+        let _ = submitter_did;
+        let state = self.state.read()?;
+        let schema = state
+            .schemas
+            .get(schema_id)
+            .ok_or_else(|| ledger_item_not_found("Schema", schema_id))?;
+        Ok(schema.to_string())
+    }
+
+    async fn get_cred_def(&self, cred_def_id: &str, submitter_did: Option<&str>) -> VcxResult<String> {
+        let _ = submitter_did;
+        let state = self.state.read()?;
+        let cred_def = state
+            .cred_defs
+            .get(cred_def_id)
+            .ok_or_else(|| ledger_item_not_found("Credential definition", cred_def_id))?;
+        Ok(cred_def.to_string())
+    }
+
+    async fn get_attr(&self, target_did: &str, attr_name: &str) -> VcxResult<String> {
+        let state = self.state.read()?;
+        let data = state
+            .attribs
+            .get(target_did)
+            .and_then(|attribs| attribs.get(attr_name))
+            .map(|attr| json!({ attr_name: attr }).to_string());
+        Ok(reply(
+            json!({ "type": "104", "dest": target_did, "raw": attr_name, "data": data }),
+        ))
+    }
+
+    async fn add_attr(&self, target_did: &str, attrib_json: &str) -> VcxResult<String> {
+        let attrib: Map<String, Value> = serde_json::from_str(attrib_json)?;
+        let mut state = self.state.write()?;
+        let (seq_no, txn_time) = state.write_txn(
+            ATTRIB_TXN_TYPE,
+            target_did,
+            json!({ "dest": target_did, "raw": attrib_json }),
+        );
+        state.attribs.entry(target_did.to_string()).or_default().extend(attrib);
+        Ok(reply(json!({ "seqNo": seq_no, "txnTime": txn_time })))
+    }
+
+    async fn get_rev_reg_def_json(&self, rev_reg_id: &str) -> VcxResult<String> {
+        let state = self.state.read()?;
+        let rev_reg_def = state
+            .rev_reg_defs
+            .get(rev_reg_id)
+            .ok_or_else(|| ledger_item_not_found("Revocation registry definition", rev_reg_id))?;
+        Ok(rev_reg_def.to_string())
+    }
+
+    async fn get_rev_reg_delta_json(
+        &self,
+        rev_reg_id: &str,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> VcxResult<(String, String, u64)> {
+        let state = self.state.read()?;
+        let entries = state.rev_reg_entries(rev_reg_id)?;
+
+        let to = to.unwrap_or(u64::MAX);
+        let entry_to = entries
+            .iter()
+            .rev()
+            .find(|entry| entry.txn_time <= to)
+            .ok_or_else(|| ledger_item_not_found("Revocation registry delta", rev_reg_id))?;
+        let entry_from = from.and_then(|from| entries.iter().rev().find(|entry| entry.txn_time <= from));
+
+        let delta_value = match entry_from {
+            Some(entry_from) => json!({
+                "prevAccum": entry_from.accum,
+                "accum": entry_to.accum,
+                "issued": entry_to.issued.difference(&entry_from.issued).collect::<Vec<_>>(),
+                "revoked": entry_to.revoked.difference(&entry_from.revoked).collect::<Vec<_>>(),
+            }),
+            None => json!({
+                "accum": entry_to.accum,
+                "issued": entry_to.issued,
+                "revoked": entry_to.revoked,
+            }),
+        };
+
+        let reg_delta = json!({ "ver": "1.0", "value": delta_value });
+
+        Ok((
+            rev_reg_id.to_string(),
+            serde_json::to_string(&reg_delta)?,
+            entry_to.txn_time,
+        ))
+    }
+
+    async fn get_rev_reg(&self, rev_reg_id: &str, timestamp: u64) -> VcxResult<(String, String, u64)> {
+        let state = self.state.read()?;
+        let entry = state
+            .rev_reg_entries(rev_reg_id)?
+            .iter()
+            .rev()
+            .find(|entry| entry.txn_time <= timestamp)
+            .ok_or_else(|| ledger_item_not_found("Revocation registry", rev_reg_id))?;
+
+        let rev_reg = json!({ "ver": "1.0", "value": { "accum": entry.accum } });
+
+        Ok((rev_reg_id.to_string(), serde_json::to_string(&rev_reg)?, entry.txn_time))
+    }
+
+    async fn get_ledger_txn(&self, seq_no: i32, submitter_did: Option<&str>) -> VcxResult<String> {
+        let _ = submitter_did;
+        let state = self.state.read()?;
+        let data = usize::try_from(seq_no)
+            .ok()
+            .and_then(|seq_no| seq_no.checked_sub(1))
+            .and_then(|index| state.txns.get(index));
+        Ok(reply(json!({ "type": "3", "seqNo": seq_no, "data": data })))
+    }
+
+    async fn build_schema_request(&self, submitter_did: &str, schema_json: &str) -> VcxResult<String> {
+        let schema: Value = serde_json::from_str(schema_json)?;
+        let request = json!({
+            "identifier": submitter_did,
+            "operation": {
+                "type": SCHEMA_TXN_TYPE,
+                "data": {
+                    "name": (&schema).try_get("name")?,
+                    "version": (&schema).try_get("version")?,
+                    "attr_names": (&schema).try_get("attrNames")?,
+                }
+            },
+            "protocolVersion": 2
+        });
+        Ok(request.to_string())
+    }
+
+    async fn publish_schema(
+        &self,
+        schema_json: &str,
+        submitter_did: &str,
+        endorser_did: Option<String>,
+    ) -> VcxResult<()> {
+        let _ = endorser_did;
+        let mut schema: Value = serde_json::from_str(schema_json)?;
+        let schema_id = get_id_field(&schema)?;
+
+        let mut state = self.state.write()?;
+        if state.schemas.contains_key(&schema_id) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::DuplicationSchema,
+                format!("Schema {} is already present on the ledger", schema_id),
+            ));
+        }
+        let (seq_no, _) = state.write_txn(SCHEMA_TXN_TYPE, submitter_did, schema.clone());
+        schema["seqNo"] = json!(seq_no);
+        state.schemas.insert(schema_id, schema);
+        Ok(())
+    }
+
+    async fn publish_cred_def(&self, cred_def_json: &str, submitter_did: &str) -> VcxResult<()> {
+        let cred_def: Value = serde_json::from_str(cred_def_json)?;
+        let cred_def_id = get_id_field(&cred_def)?;
+
+        let mut state = self.state.write()?;
+        if state.cred_defs.contains_key(&cred_def_id) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::CredDefAlreadyCreated,
+                format!("Credential definition {} is already present on the ledger", cred_def_id),
+            ));
+        }
+        state.write_txn(CRED_DEF_TXN_TYPE, submitter_did, cred_def.clone());
+        state.cred_defs.insert(cred_def_id, cred_def);
+        Ok(())
+    }
+
+    async fn publish_rev_reg_def(
+        &self,
+        rev_reg_def: &RevocationRegistryDefinition,
+        submitter_did: &str,
+    ) -> VcxResult<()> {
+        let rev_reg_def_json = serde_json::to_value(rev_reg_def)?;
+
+        let mut state = self.state.write()?;
+        state.write_txn(REVOC_REG_DEF_TXN_TYPE, submitter_did, rev_reg_def_json.clone());
+        state.rev_reg_defs.insert(rev_reg_def.id.clone(), rev_reg_def_json);
+        Ok(())
+    }
+
+    async fn publish_rev_reg_delta(
+        &self,
+        rev_reg_id: &str,
+        rev_reg_entry_json: &str,
+        submitter_did: &str,
+    ) -> VcxResult<()> {
+        let rev_reg_entry: Value = serde_json::from_str(rev_reg_entry_json)?;
+        let value = (&rev_reg_entry).try_get("value")?;
+        let accum = value.try_get("accum")?.to_owned();
+
+        let mut state = self.state.write()?;
+        if !state.rev_reg_defs.contains_key(rev_reg_id) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::RevRegDefNotFound,
+                format!(
+                    "Revocation registry definition {} is not present on the ledger",
+                    rev_reg_id
+                ),
+            ));
+        }
+
+        let (mut issued, mut revoked) = state
+            .rev_reg_entries
+            .get(rev_reg_id)
+            .and_then(|entries| entries.last())
+            .map(|entry| (entry.issued.clone(), entry.revoked.clone()))
+            .unwrap_or_default();
+        for index in index_set(value, "issued") {
+            revoked.remove(&index);
+            issued.insert(index);
+        }
+        for index in index_set(value, "revoked") {
+            issued.remove(&index);
+            revoked.insert(index);
+        }
+
+        let (_, txn_time) = state.write_txn(
+            REVOC_REG_ENTRY_TXN_TYPE,
+            submitter_did,
+            json!({ "revocRegDefId": rev_reg_id, "value": value }),
+        );
+        state
+            .rev_reg_entries
+            .entry(rev_reg_id.to_string())
+            .or_default()
+            .push(RevRegEntry {
+                txn_time,
+                accum,
+                issued,
+                revoked,
+            });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use std::sync::Arc;
+
+    use serde_json::Value;
+
+    use crate::common::primitives::revocation_registry::RevocationRegistryDefinition;
+    use crate::common::test_utils::create_and_store_credential;
+    use crate::core::profile::modular_libs_profile::ModularLibsProfile;
+    use crate::core::profile::profile::Profile;
+    use crate::errors::error::AriesVcxErrorKind;
+    use crate::global::settings;
+    use crate::plugins::ledger::base_ledger::BaseLedger;
+    use crate::plugins::wallet::base_wallet::BaseWallet;
+    use crate::plugins::wallet::indy_wallet::IndySdkWallet;
+    use crate::utils::constants::DEFAULT_SCHEMA_ATTRS;
+    use crate::utils::devsetup::SetupLibraryWallet;
+
+    use super::InMemoryLedger;
+
+    const DID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+    const REV_REG_ID: &str = "V4SGRU86Z58d6TV7PBUe6f:4:V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag1:CL_ACCUM:tag1";
+
+    fn data_field(response: &str) -> Value {
+        let response: Value = serde_json::from_str(response).unwrap();
+        serde_json::from_str(response["result"]["data"].as_str().unwrap()).unwrap()
+    }
+
+    fn delta_value(delta_json: &str) -> Value {
+        let delta: Value = serde_json::from_str(delta_json).unwrap();
+        delta["value"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_nym_and_attrib() {
+        let ledger = InMemoryLedger::new();
+
+        let response: Value = serde_json::from_str(&ledger.get_nym(DID).await.unwrap()).unwrap();
+        assert!(response["result"]["data"].is_null());
+
+        ledger
+            .publish_nym(DID, DID, Some("verkey"), Some("alias"), Some("TRUST_ANCHOR"))
+            .await
+            .unwrap();
+        let nym = data_field(&ledger.get_nym(DID).await.unwrap());
+        assert_eq!(nym["verkey"], "verkey");
+        assert_eq!(nym["alias"], "alias");
+        assert_eq!(nym["role"], "TRUST_ANCHOR");
+
+        ledger
+            .add_attr(DID, r#"{"endpoint": {"endpoint": "http://localhost:8080"}}"#)
+            .await
+            .unwrap();
+        let attr = data_field(&ledger.get_attr(DID, "endpoint").await.unwrap());
+        assert_eq!(attr, json!({"endpoint": {"endpoint": "http://localhost:8080"}}));
+    }
+
+    #[tokio::test]
+    async fn test_schema_and_cred_def() {
+        let ledger = InMemoryLedger::new();
+        let schema_id = format!("{}:2:name:1.0", DID);
+        let schema_json = json!({
+            "ver": "1.0",
+            "id": schema_id,
+            "name": "name",
+            "version": "1.0",
+            "attrNames": ["a", "b"]
+        })
+        .to_string();
+
+        let err = ledger.get_schema(&schema_id, None).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::LedgerItemNotFound);
+
+        ledger.publish_schema(&schema_json, DID, None).await.unwrap();
+        let schema: Value = serde_json::from_str(&ledger.get_schema(&schema_id, None).await.unwrap()).unwrap();
+        assert_eq!(schema["seqNo"], 1);
+        let err = ledger.publish_schema(&schema_json, DID, None).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::DuplicationSchema);
+
+        let cred_def_id = format!("{}:3:CL:1:tag", DID);
+        let cred_def_json = json!({ "ver": "1.0", "id": cred_def_id, "schemaId": "1" }).to_string();
+        ledger.publish_cred_def(&cred_def_json, DID).await.unwrap();
+        let cred_def: Value = serde_json::from_str(&ledger.get_cred_def(&cred_def_id, None).await.unwrap()).unwrap();
+        assert_eq!(cred_def["schemaId"], "1");
+
+        let txn: Value = serde_json::from_str(&ledger.get_ledger_txn(1, None).await.unwrap()).unwrap();
+        assert_eq!(txn["result"]["data"]["txn"]["data"]["id"], schema_id);
+        let txn: Value = serde_json::from_str(&ledger.get_ledger_txn(10, None).await.unwrap()).unwrap();
+        assert!(txn["result"]["data"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_rev_reg_entries_by_timestamp() {
+        let ledger = InMemoryLedger::new();
+
+        let delta = json!({"ver": "1.0", "value": {"accum": "accum1", "issued": [1, 2, 3]}}).to_string();
+        let err = ledger.publish_rev_reg_delta(REV_REG_ID, &delta, DID).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::RevRegDefNotFound);
+
+        let rev_reg_def = RevocationRegistryDefinition {
+            id: REV_REG_ID.to_string(),
+            ..RevocationRegistryDefinition::default()
+        };
+        ledger.publish_rev_reg_def(&rev_reg_def, DID).await.unwrap();
+        let rev_reg_def_json: Value =
+            serde_json::from_str(&ledger.get_rev_reg_def_json(REV_REG_ID).await.unwrap()).unwrap();
+        assert_eq!(rev_reg_def_json["id"], REV_REG_ID);
+
+        ledger.publish_rev_reg_delta(REV_REG_ID, &delta, DID).await.unwrap();
+        let (_, _, first_timestamp) = ledger.get_rev_reg_delta_json(REV_REG_ID, None, None).await.unwrap();
+
+        let delta = json!({"ver": "1.0", "value": {"prevAccum": "accum1", "accum": "accum2", "revoked": [2]}});
+        ledger
+            .publish_rev_reg_delta(REV_REG_ID, &delta.to_string(), DID)
+            .await
+            .unwrap();
+
+        let (_, delta_json, second_timestamp) = ledger.get_rev_reg_delta_json(REV_REG_ID, None, None).await.unwrap();
+        assert!(second_timestamp > first_timestamp);
+        assert_eq!(
+            delta_value(&delta_json),
+            json!({"accum": "accum2", "issued": [1, 3], "revoked": [2]})
+        );
+
+        let (_, delta_json, timestamp) = ledger
+            .get_rev_reg_delta_json(REV_REG_ID, None, Some(first_timestamp))
+            .await
+            .unwrap();
+        assert_eq!(timestamp, first_timestamp);
+        assert_eq!(
+            delta_value(&delta_json),
+            json!({"accum": "accum1", "issued": [1, 2, 3], "revoked": []})
+        );
+
+        let (_, delta_json, _) = ledger
+            .get_rev_reg_delta_json(REV_REG_ID, Some(first_timestamp), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            delta_value(&delta_json),
+            json!({"prevAccum": "accum1", "accum": "accum2", "issued": [], "revoked": [2]})
+        );
+
+        let (_, rev_reg_json, timestamp) = ledger.get_rev_reg(REV_REG_ID, first_timestamp).await.unwrap();
+        assert_eq!(timestamp, first_timestamp);
+        let rev_reg: Value = serde_json::from_str(&rev_reg_json).unwrap();
+        assert_eq!(rev_reg, json!({"ver": "1.0", "value": {"accum": "accum1"}}));
+
+        let err = ledger.get_rev_reg(REV_REG_ID, first_timestamp - 1).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::LedgerItemNotFound);
+    }
+
+    #[tokio::test]
+    async fn test_issue_and_revoke_credential_offline() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let (institution_did, _) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let ledger: Arc<dyn BaseLedger> = Arc::new(InMemoryLedger::new());
+            let profile: Arc<dyn Profile> = Arc::new(ModularLibsProfile::new_with_ledger(wallet, ledger));
+            let anoncreds = Arc::clone(&profile).inject_anoncreds();
+            anoncreds
+                .prover_create_link_secret(settings::DEFAULT_LINK_SECRET_ALIAS)
+                .await
+                .unwrap();
+
+            let (_, _, _, _, _, _, _, _, rev_reg_id, cred_rev_id, tails_dir) =
+                create_and_store_credential(&profile, &profile, &institution_did, DEFAULT_SCHEMA_ATTRS).await;
+
+            let ledger = Arc::clone(&profile).inject_ledger();
+            let (_, first_delta, first_timestamp) =
+                ledger.get_rev_reg_delta_json(&rev_reg_id, None, None).await.unwrap();
+
+            anoncreds
+                .revoke_credential_local(&tails_dir, &rev_reg_id, &cred_rev_id)
+                .await
+                .unwrap();
+            anoncreds
+                .publish_local_revocations(&institution_did, &rev_reg_id)
+                .await
+                .unwrap();
+
+            let (_, second_delta, second_timestamp) =
+                ledger.get_rev_reg_delta_json(&rev_reg_id, None, None).await.unwrap();
+            assert_ne!(first_delta, second_delta);
+            assert!(second_timestamp > first_timestamp);
+
+            let cred_rev_id: u64 = cred_rev_id.parse().unwrap();
+            assert_eq!(delta_value(&second_delta)["revoked"], json!([cred_rev_id]));
+        })
+        .await;
+    }
+}
@@ -11,7 +11,8 @@ use tokio::sync::oneshot;
 use vdr::common::error::VdrError;
 use vdr::config::PoolConfig as IndyVdrPoolConfig;
 use vdr::ledger::identifiers::{CredentialDefinitionId, RevocationRegistryId, SchemaId};
-use vdr::ledger::requests::author_agreement::TxnAuthrAgrmtAcceptanceData;
+use vdr::ledger::requests::rev_reg::RevocationRegistryDelta;
+use vdr::ledger::requests::rev_reg_def::RegistryType;
 use vdr::ledger::RequestBuilder;
 use vdr::pool::{PoolBuilder, PoolTransactions};
 use vdr::pool::{PoolRunner, PreparedRequest, ProtocolVersion, RequestResult};
@@ -52,39 +53,22 @@ impl IndyVdrLedgerPool {
     }
 }
 
-impl Debug for IndyVdrLedgerPool {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("IndyVdrLedgerPool")
-            .field("runner", &"PoolRunner")
-            .finish()
-    }
-}
-
-pub struct IndyVdrLedger {
-    wallet: Arc<dyn BaseWallet>,
-    pool: Arc<IndyVdrLedgerPool>,
+/// Abstraction over the submission of prepared indy-vdr requests to a ledger, allowing [IndyVdrLedger]
+/// to be used with any request transport (e.g. an [IndyVdrLedgerPool], or a stand-in for testing).
+#[async_trait]
+pub trait RequestSubmitter: Send + Sync {
+    async fn submit(&self, request: PreparedRequest) -> VcxResult<String>;
 }
 
-impl IndyVdrLedger {
-    pub fn new(wallet: Arc<dyn BaseWallet>, pool: Arc<IndyVdrLedgerPool>) -> Self {
-        IndyVdrLedger { wallet, pool }
-    }
-
-    pub fn request_builder(&self) -> VcxResult<RequestBuilder> {
-        // TODO - confirm correct protocol version?
-        let v = settings::get_protocol_version();
-        let version = ProtocolVersion::from_id(v as u64)?;
-        Ok(RequestBuilder::new(version))
-    }
-
-    async fn _submit_request(&self, request: PreparedRequest) -> VcxResult<String> {
+#[async_trait]
+impl RequestSubmitter for IndyVdrLedgerPool {
+    async fn submit(&self, request: PreparedRequest) -> VcxResult<String> {
         // indyvdr send_request is Async via a callback.
         // Use oneshot channel to send result from callback, converting the fn to future.
         type VdrSendRequestResult =
             Result<(RequestResult<String>, Option<HashMap<String, f32, RandomState>>), VdrError>;
         let (sender, recv) = oneshot::channel::<VdrSendRequestResult>();
-        self.pool
-            .runner
+        self.runner
             .as_ref()
             .ok_or(
                 // should not happen - strictly for unit testing
@@ -113,6 +97,39 @@ impl IndyVdrLedger {
 
         Ok(reply?)
     }
+}
+
+impl Debug for IndyVdrLedgerPool {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndyVdrLedgerPool")
+            .field("runner", &"PoolRunner")
+            .finish()
+    }
+}
+
+pub struct IndyVdrLedger {
+    wallet: Arc<dyn BaseWallet>,
+    request_submitter: Arc<dyn RequestSubmitter>,
+}
+
+impl IndyVdrLedger {
+    pub fn new(wallet: Arc<dyn BaseWallet>, request_submitter: Arc<dyn RequestSubmitter>) -> Self {
+        IndyVdrLedger {
+            wallet,
+            request_submitter,
+        }
+    }
+
+    pub fn request_builder(&self) -> VcxResult<RequestBuilder> {
+        // TODO - confirm correct protocol version?
+        let v = settings::get_protocol_version();
+        let version = ProtocolVersion::from_id(v as u64)?;
+        Ok(RequestBuilder::new(version))
+    }
+
+    async fn _submit_request(&self, request: PreparedRequest) -> VcxResult<String> {
+        self.request_submitter.submit(request).await
+    }
 
     async fn _sign_and_submit_request(&self, submitter_did: &str, request: PreparedRequest) -> VcxResult<String> {
         let mut request = request;
@@ -127,6 +144,37 @@ impl IndyVdrLedger {
         self._submit_request(request).await
     }
 
+    async fn _multisign_request(&self, did: &str, request: PreparedRequest) -> VcxResult<PreparedRequest> {
+        let mut request = request;
+        let to_sign = request.get_signature_input()?;
+
+        let signer_verkey = self.wallet.key_for_local_did(did).await?;
+
+        let signature = self.wallet.sign(&signer_verkey, to_sign.as_bytes()).await?;
+
+        request.set_multi_signature(&DidValue::from_str(did)?, &signature)?;
+
+        Ok(request)
+    }
+
+    async fn _append_txn_author_agreement_to_request(&self, request: PreparedRequest) -> VcxResult<PreparedRequest> {
+        if let Some(taa) = get_txn_author_agreement()? {
+            let mut request = request;
+            let acceptance = self.request_builder()?.prepare_txn_author_agreement_acceptance_data(
+                taa.text.as_deref(),
+                taa.version.as_deref(),
+                taa.taa_digest.as_deref(),
+                &taa.acceptance_mechanism_type,
+                taa.time_of_acceptance,
+            )?;
+            request.set_txn_author_agreement_acceptance(&acceptance)?;
+
+            Ok(request)
+        } else {
+            Ok(request)
+        }
+    }
+
     async fn _build_get_cred_def_request(
         &self,
         submitter_did: Option<&str>,
@@ -204,17 +252,48 @@ impl BaseLedger for IndyVdrLedger {
     }
 
     async fn endorse_transaction(&self, endorser_did: &str, request_json: &str) -> VcxResult<()> {
-        let _ = (endorser_did, request_json);
-        Err(unimplemented_method_err("indy_vdr endorse_transaction"))
+        let request = PreparedRequest::from_request_json(request_json)?;
+        _verify_transaction_can_be_endorsed(&request, endorser_did)?;
+
+        let request = self._multisign_request(endorser_did, request).await?;
+        self._submit_request(request).await?;
+
+        Ok(())
     }
 
     async fn set_endorser(&self, submitter_did: &str, request_json: &str, endorser: &str) -> VcxResult<String> {
-        let _ = (submitter_did, request_json, endorser);
-        Err(unimplemented_method_err("indy_vdr set_endorser"))
+        let mut request = PreparedRequest::from_request_json(request_json)?;
+        request.set_endorser(&DidValue::from_str(endorser)?)?;
+
+        let request = self._multisign_request(submitter_did, request).await?;
+
+        Ok(serde_json::to_string(&request.req_json)?)
     }
 
     async fn get_txn_author_agreement(&self) -> VcxResult<String> {
-        Err(unimplemented_method_err("indy_vdr get_txn_author_agreement"))
+        let request = self
+            .request_builder()?
+            .build_get_txn_author_agreement_request(None, None)?;
+        let response = self._submit_request(request).await?;
+
+        let response_json: Value = serde_json::from_str(&response)
+            .map_err(|err| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidLedgerResponse, format!("{:?}", err)))?;
+        let mut author_agreement_data = response_json["result"]["data"]
+            .as_object()
+            .map_or(json!({}), |data| json!(data));
+
+        let request = self
+            .request_builder()?
+            .build_get_acceptance_mechanisms_request(None, None, None)?;
+        let response = self._submit_request(request).await?;
+
+        let response_json: Value = serde_json::from_str(&response)
+            .map_err(|err| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidLedgerResponse, format!("{:?}", err)))?;
+        if let Some(aml) = response_json["result"]["data"]["aml"].as_object() {
+            author_agreement_data["aml"] = json!(aml);
+        }
+
+        Ok(author_agreement_data.to_string())
     }
 
     async fn get_nym(&self, did: &str) -> VcxResult<String> {
@@ -232,21 +311,17 @@ impl BaseLedger for IndyVdrLedger {
         data: Option<&str>,
         role: Option<&str>,
     ) -> VcxResult<String> {
-        // TODO - FUTURE: convert data into "alias" for indy vdr. for now throw unimplemented
-        if data.is_some() {
-            return Err(unimplemented_method_err("indy_vdr publish_nym with data"));
-        }
-        let alias = None;
-
+        // `data` is published as the NYM alias, consistent with the vdrtools ledger
         let identifier = DidValue::from_str(submitter_did)?;
         let dest = DidValue::from_str(target_did)?;
         let request = self.request_builder()?.build_nym_request(
             &identifier,
             &dest,
             verkey.map(String::from),
-            alias,
+            data.map(String::from),
             role.map(String::from),
         )?;
+        let request = self._append_txn_author_agreement_to_request(request).await?;
 
         self._sign_and_submit_request(submitter_did, request).await
     }
@@ -344,7 +419,7 @@ impl BaseLedger for IndyVdrLedger {
 
     async fn add_attr(&self, target_did: &str, attrib_json: &str) -> VcxResult<String> {
         let request = self._build_attrib_request(target_did, target_did, Some(attrib_json))?;
-        let request = _append_txn_author_agreement_to_request(request).await?;
+        let request = self._append_txn_author_agreement_to_request(request).await?;
 
         self._sign_and_submit_request(target_did, request).await
     }
@@ -432,8 +507,34 @@ impl BaseLedger for IndyVdrLedger {
     }
 
     async fn get_rev_reg(&self, rev_reg_id: &str, timestamp: u64) -> VcxResult<(String, String, u64)> {
-        let _ = (rev_reg_id, timestamp);
-        Err(unimplemented_method_err("indy_vdr get_rev_reg"))
+        let revoc_reg_def_id = RevocationRegistryId::from_str(rev_reg_id)?;
+
+        let request = self
+            .request_builder()?
+            .build_get_revoc_reg_request(None, &revoc_reg_def_id, timestamp as i64)?;
+        let res = self._submit_request(request).await?;
+
+        let res: Value = serde_json::from_str(&res)?;
+        let result = (&res).try_get("result")?;
+        let data = result.try_get("data")?;
+
+        let response_reg_def_id = result.try_get("revocRegDefId")?;
+        let response_reg_def_id = response_reg_def_id.try_as_str()?;
+        if response_reg_def_id != rev_reg_id {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidRevocationDetails,
+                "ID of revocation registry response does not match requested ID",
+            ));
+        }
+
+        let rev_reg = json!({"ver": "1.0", "value": data.try_get("value")?});
+
+        let txn_time = result.try_get("txnTime")?.as_u64().ok_or(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            "Error parsing txnTime value as u64",
+        ))?;
+
+        Ok((rev_reg_id.to_string(), serde_json::to_string(&rev_reg)?, txn_time))
     }
 
     async fn get_ledger_txn(&self, seq_no: i32, submitter_did: Option<&str>) -> VcxResult<String> {
@@ -476,8 +577,21 @@ impl BaseLedger for IndyVdrLedger {
         rev_reg_entry_json: &str,
         submitter_did: &str,
     ) -> VcxResult<()> {
-        let _ = (rev_reg_entry_json, rev_reg_id, submitter_did);
-        Err(unimplemented_method_err("indy_vdr publish_rev_reg_delta"))
+        let identifier = DidValue::from_str(submitter_did)?;
+        let revoc_reg_def_id = RevocationRegistryId::from_str(rev_reg_id)?;
+        let rev_reg_entry: RevocationRegistryDelta = serde_json::from_str(rev_reg_entry_json)?;
+
+        let request = self.request_builder()?.build_revoc_reg_entry_request(
+            &identifier,
+            &revoc_reg_def_id,
+            &RegistryType::CL_ACCUM,
+            rev_reg_entry,
+        )?;
+        let request = self._append_txn_author_agreement_to_request(request).await?;
+
+        self._sign_and_submit_request(submitter_did, request).await?;
+
+        Ok(())
     }
 }
 
@@ -492,21 +606,23 @@ fn current_epoch_time() -> i64 {
     time::get_time().sec
 }
 
-async fn _append_txn_author_agreement_to_request(request: PreparedRequest) -> VcxResult<PreparedRequest> {
-    if let Some(taa) = get_txn_author_agreement()? {
-        let mut request = request;
-        let acceptance = TxnAuthrAgrmtAcceptanceData {
-            mechanism: taa.acceptance_mechanism_type,
-            // TODO - investigate default digest
-            taa_digest: taa.taa_digest.map_or(String::from(""), |v| v),
-            time: taa.time_of_acceptance,
-        };
-        request.set_txn_author_agreement_acceptance(&acceptance)?;
+fn _verify_transaction_can_be_endorsed(request: &PreparedRequest, endorser_did: &str) -> VcxResult<()> {
+    let transaction_endorser = request.req_json["endorser"].as_str().ok_or(AriesVcxError::from_msg(
+        AriesVcxErrorKind::InvalidJson,
+        "Transaction cannot be endorsed: endorser DID is not set.",
+    ))?;
 
-        Ok(request)
-    } else {
-        Ok(request)
+    if transaction_endorser != endorser_did {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!(
+                "Transaction cannot be endorsed: transaction endorser DID `{}` and sender DID `{}` are different",
+                transaction_endorser, endorser_did
+            ),
+        ));
     }
+
+    Ok(())
 }
 
 fn _get_response_json_data_field(response_json: &str) -> VcxResult<Value> {
@@ -518,15 +634,64 @@ fn _get_response_json_data_field(response_json: &str) -> VcxResult<Value> {
 #[cfg(test)]
 #[cfg(feature = "general_test")]
 mod unit_tests {
-    use std::sync::Arc;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use indy_vdr::pool::PreparedRequest;
+    use indy_vdr::utils::did::DidValue;
+    use indy_vdr::utils::Qualifiable;
+    use serde_json::Value;
 
     use crate::errors::error::{AriesVcxErrorKind, VcxResult};
+    use crate::utils::author_agreement::set_txn_author_agreement;
+    use crate::utils::constants::REV_REG_ID;
+    use crate::utils::devsetup::SetupDefaults;
     use crate::{
         common::{primitives::revocation_registry::RevocationRegistryDefinition, test_utils::mock_profile},
         plugins::ledger::{base_ledger::BaseLedger, indy_vdr_ledger::IndyVdrLedgerPool},
     };
 
-    use super::IndyVdrLedger;
+    use super::{IndyVdrLedger, RequestSubmitter};
+
+    const SUBMITTER_DID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+    const ENDORSER_DID: &str = "Th7MpTaRZVRYnPiabds81Y";
+
+    /// Stand-in for the ledger pool: records each submitted request and replies with queued responses
+    struct MockRequestSubmitter {
+        responses: Mutex<VecDeque<String>>,
+        requests: Mutex<Vec<Value>>,
+    }
+
+    impl MockRequestSubmitter {
+        fn with_responses(responses: Vec<Value>) -> Arc<Self> {
+            Arc::new(MockRequestSubmitter {
+                responses: Mutex::new(responses.iter().map(Value::to_string).collect()),
+                requests: Mutex::new(vec![]),
+            })
+        }
+
+        fn submitted_requests(&self) -> Vec<Value> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl RequestSubmitter for MockRequestSubmitter {
+        async fn submit(&self, request: PreparedRequest) -> VcxResult<String> {
+            self.requests.lock().unwrap().push(request.req_json.clone());
+            Ok(self
+                .responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or_else(|| json!({"op": "REPLY", "result": {}}).to_string()))
+        }
+    }
+
+    fn ledger_with_submitter(submitter: Arc<MockRequestSubmitter>) -> IndyVdrLedger {
+        IndyVdrLedger::new(mock_profile().inject_wallet(), submitter)
+    }
 
     #[tokio::test]
     async fn test_unimplemented_methods() {
@@ -540,10 +705,6 @@ mod unit_tests {
         let pool = Arc::new(IndyVdrLedgerPool { runner: None });
         let ledger: Box<dyn BaseLedger> = Box::new(IndyVdrLedger::new(profile.inject_wallet(), pool));
 
-        assert_unimplemented(ledger.endorse_transaction("", "").await);
-        assert_unimplemented(ledger.set_endorser("", "", "").await);
-        assert_unimplemented(ledger.get_txn_author_agreement().await);
-        assert_unimplemented(ledger.get_rev_reg("", 0).await);
         assert_unimplemented(ledger.get_ledger_txn(0, None).await);
         assert_unimplemented(ledger.build_schema_request("", "").await);
         assert_unimplemented(ledger.publish_schema("", "", None).await);
@@ -554,4 +715,123 @@ mod unit_tests {
                 .await,
         );
     }
+
+    #[tokio::test]
+    async fn test_publish_nym_with_alias_and_taa() {
+        let _setup = SetupDefaults::init();
+        set_txn_author_agreement(
+            Some("indy agreement".to_string()),
+            Some("1.0.0".to_string()),
+            None,
+            "on_file".to_string(),
+            123456789,
+        )
+        .unwrap();
+
+        let submitter = MockRequestSubmitter::with_responses(vec![]);
+        let ledger = ledger_with_submitter(Arc::clone(&submitter));
+
+        ledger
+            .publish_nym(SUBMITTER_DID, ENDORSER_DID, None, Some("alias"), None)
+            .await
+            .unwrap();
+
+        let requests = submitter.submitted_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["operation"]["dest"], ENDORSER_DID);
+        assert_eq!(requests[0]["operation"]["alias"], "alias");
+        assert_eq!(requests[0]["taaAcceptance"]["mechanism"], "on_file");
+        assert!(requests[0]["taaAcceptance"]["taaDigest"].as_str().is_some());
+        assert!(requests[0]["signature"].as_str().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_set_endorser_and_endorse_transaction() {
+        let _setup = SetupDefaults::init();
+        let submitter = MockRequestSubmitter::with_responses(vec![]);
+        let ledger = ledger_with_submitter(Arc::clone(&submitter));
+
+        let request = ledger
+            .request_builder()
+            .unwrap()
+            .build_nym_request(
+                &DidValue::from_str(SUBMITTER_DID).unwrap(),
+                &DidValue::from_str(SUBMITTER_DID).unwrap(),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        let request_json = serde_json::to_string(&request.req_json).unwrap();
+
+        let endorsed_request = ledger
+            .set_endorser(SUBMITTER_DID, &request_json, ENDORSER_DID)
+            .await
+            .unwrap();
+        let endorsed_request_value: Value = serde_json::from_str(&endorsed_request).unwrap();
+        assert_eq!(endorsed_request_value["endorser"], ENDORSER_DID);
+        assert!(endorsed_request_value["signatures"][SUBMITTER_DID].as_str().is_some());
+
+        let err = ledger
+            .endorse_transaction(SUBMITTER_DID, &endorsed_request)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidJson);
+        let err = ledger
+            .endorse_transaction(ENDORSER_DID, &request_json)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidJson);
+        assert!(submitter.submitted_requests().is_empty());
+
+        ledger
+            .endorse_transaction(ENDORSER_DID, &endorsed_request)
+            .await
+            .unwrap();
+        let requests = submitter.submitted_requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0]["signatures"][SUBMITTER_DID].as_str().is_some());
+        assert!(requests[0]["signatures"][ENDORSER_DID].as_str().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_get_txn_author_agreement() {
+        let _setup = SetupDefaults::init();
+        let submitter = MockRequestSubmitter::with_responses(vec![
+            json!({"op": "REPLY", "result": {"data": {"text": "indy agreement", "version": "1.0.0"}}}),
+            json!({"op": "REPLY", "result": {"data": {"aml": {"on_file": "Agreement is on file"}}}}),
+        ]);
+        let ledger = ledger_with_submitter(Arc::clone(&submitter));
+
+        let taa: Value = serde_json::from_str(&ledger.get_txn_author_agreement().await.unwrap()).unwrap();
+        assert_eq!(
+            taa,
+            json!({"text": "indy agreement", "version": "1.0.0", "aml": {"on_file": "Agreement is on file"}})
+        );
+        assert_eq!(submitter.submitted_requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_rev_reg() {
+        let _setup = SetupDefaults::init();
+        let submitter = MockRequestSubmitter::with_responses(vec![json!({
+            "op": "REPLY",
+            "result": {
+                "revocRegDefId": REV_REG_ID,
+                "txnTime": 1000,
+                "data": {"value": {"accum": "1 0000"}}
+            }
+        })]);
+        let ledger = ledger_with_submitter(Arc::clone(&submitter));
+
+        let (rev_reg_id, rev_reg_json, timestamp) = ledger.get_rev_reg(REV_REG_ID, 1500).await.unwrap();
+        assert_eq!(rev_reg_id, REV_REG_ID);
+        assert_eq!(timestamp, 1000);
+        let rev_reg: Value = serde_json::from_str(&rev_reg_json).unwrap();
+        assert_eq!(rev_reg, json!({"ver": "1.0", "value": {"accum": "1 0000"}}));
+
+        let requests = submitter.submitted_requests();
+        assert_eq!(requests[0]["operation"]["revocRegDefId"], REV_REG_ID);
+        assert_eq!(requests[0]["operation"]["timestamp"], 1500);
+    }
 }
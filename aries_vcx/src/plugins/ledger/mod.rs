@@ -1,4 +1,5 @@
 pub mod base_ledger;
+pub mod in_memory_ledger;
 #[cfg(feature = "vdrtools")]
 pub mod indy_ledger;
 #[cfg(feature = "modular_libs")]
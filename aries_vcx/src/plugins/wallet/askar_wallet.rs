@@ -0,0 +1,812 @@
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use aries_askar::any::{AnySession, AnyStore};
+use aries_askar::crypto::alg::Chacha20Types;
+use aries_askar::kms::{
+    crypto_box, crypto_box_open, crypto_box_random_nonce, crypto_box_seal, crypto_box_seal_open, KeyAlg, LocalKey,
+};
+use aries_askar::{Entry, EntryTag, ManageBackend, PassKey, Scan, StoreKeyMethod, TagFilter};
+use async_trait::async_trait;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::utils::async_fn_iterator::AsyncFnIterator;
+use crate::utils::encryption_envelope_v2::crypto::x25519_diffie_hellman;
+
+use super::base_wallet::BaseWallet;
+
+const CATEGORY_DID: &str = "VCX_DID";
+const CATEGORY_TEMP_DID_KEY: &str = "VCX_TEMP_DID_KEY";
+
+const PACK_ENC: &str = "xchacha20poly1305_ietf";
+const PACK_TYP: &str = "JWM/1.0";
+const PACK_ALG_AUTHCRYPT: &str = "Authcrypt";
+const PACK_ALG_ANONCRYPT: &str = "Anoncrypt";
+
+/// [BaseWallet] implementation backed by an [aries_askar] [AnyStore] (e.g. SQLite). Signing keys and X25519 key
+/// agreement keys are stored in the askar key store under their base58 public key, while DIDs and generic records
+/// are stored as askar entries.
+/// Packed messages follow the DIDComm v1 envelope format used by vdrtools, so the two wallets are interoperable.
+pub struct AskarWallet {
+    backend: AnyStore,
+    profile: Option<String>,
+}
+
+impl std::fmt::Debug for AskarWallet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AskarWallet").field("profile", &self.profile).finish()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DidRecord {
+    did: String,
+    verkey: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordOptions {
+    #[serde(default)]
+    retrieve_type: bool,
+    #[serde(default = "default_true")]
+    retrieve_value: bool,
+    #[serde(default)]
+    retrieve_tags: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize)]
+struct Jwe {
+    protected: String,
+    iv: String,
+    ciphertext: String,
+    tag: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JweRecipient {
+    encrypted_key: String,
+    header: JweRecipientHeader,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JweRecipientHeader {
+    kid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    iv: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sender: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JweProtected {
+    enc: String,
+    typ: String,
+    alg: String,
+    recipients: Vec<JweRecipient>,
+}
+
+#[derive(Serialize)]
+struct UnpackMessage {
+    message: String,
+    recipient_verkey: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sender_verkey: Option<String>,
+}
+
+impl AskarWallet {
+    pub fn new(backend: AnyStore, profile: Option<String>) -> Self {
+        AskarWallet { backend, profile }
+    }
+
+    /// Provisions a new askar store at `db_url` (e.g. `sqlite://path/to/wallet.db`) and wraps it as a wallet.
+    pub async fn create(
+        db_url: &str,
+        key_method: StoreKeyMethod,
+        pass_key: PassKey<'_>,
+        profile: Option<&str>,
+    ) -> VcxResult<Self> {
+        let backend = db_url
+            .provision_backend(key_method, pass_key, profile, false)
+            .await
+            .map_err(|err| AriesVcxError::from_msg(AriesVcxErrorKind::WalletCreate, err))?;
+        Ok(Self::new(backend, profile.map(String::from)))
+    }
+
+    /// Opens an existing askar store at `db_url`.
+    pub async fn open(
+        db_url: &str,
+        key_method: Option<StoreKeyMethod>,
+        pass_key: PassKey<'_>,
+        profile: Option<&str>,
+    ) -> VcxResult<Self> {
+        let backend = db_url.open_backend(key_method, pass_key, profile).await?;
+        Ok(Self::new(backend, profile.map(String::from)))
+    }
+
+    async fn session(&self) -> VcxResult<AnySession> {
+        Ok(self.backend.session(self.profile.clone()).await?)
+    }
+
+    /// Starts a transaction, for operations reading a record before writing it or writing several records. Inserts
+    /// which may fail as duplicates use one too: the sqlite backend of askar 0.2.0 leaves the connection of a plain
+    /// session inside an unfinished transaction when an insert fails.
+    async fn transaction(&self) -> VcxResult<AnySession> {
+        Ok(self.backend.transaction(self.profile.clone()).await?)
+    }
+
+    async fn fetch_entry(&self, session: &mut AnySession, category: &str, name: &str) -> VcxResult<Entry> {
+        session.fetch(category, name, true).await?.ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::WalletRecordNotFound,
+                format!("Wallet record not found, type: {}, id: {}", category, name),
+            )
+        })
+    }
+
+    async fn fetch_local_key(&self, verkey: &str) -> VcxResult<Option<LocalKey>> {
+        let mut session = self.session().await?;
+        let key_entry = session.fetch_key(verkey, false).await?;
+        Ok(key_entry.map(|entry| entry.load_local_key()).transpose()?)
+    }
+
+    async fn get_local_key(&self, verkey: &str) -> VcxResult<LocalKey> {
+        self.fetch_local_key(verkey).await?.ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::WalletRecordNotFound,
+                format!("Key not found in wallet, verkey: {}", verkey),
+            )
+        })
+    }
+
+    async fn get_did_record(&self, session: &mut AnySession, did: &str) -> VcxResult<DidRecord> {
+        let entry = self.fetch_entry(session, CATEGORY_DID, did).await?;
+        Ok(serde_json::from_slice(entry.value.as_ref())?)
+    }
+}
+
+async fn insert_new_key(session: &mut AnySession, seed: Option<&str>) -> VcxResult<(LocalKey, String)> {
+    let key = match seed {
+        Some(seed) => {
+            if seed.len() != 32 {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidInput,
+                    "Seed must be 32 characters long",
+                ));
+            }
+            LocalKey::from_secret_bytes(KeyAlg::Ed25519, seed.as_bytes())?
+        }
+        None => LocalKey::generate(KeyAlg::Ed25519, false)?,
+    };
+    let verkey = bs58::encode(key.to_public_bytes()?.to_vec()).into_string();
+    session.insert_key(&verkey, &key, None, None, None).await?;
+    Ok((key, verkey))
+}
+
+fn public_key_from_verkey(verkey: &str) -> VcxResult<LocalKey> {
+    let bytes = bs58::decode(verkey).into_vec().map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidVerkey,
+            format!("Verkey {} is not valid base58: {}", verkey, err),
+        )
+    })?;
+    Ok(LocalKey::from_public_bytes(KeyAlg::Ed25519, &bytes)?)
+}
+
+fn encode_b64(bytes: &[u8]) -> String {
+    base64::encode_config(bytes, base64::URL_SAFE)
+}
+
+fn decode_b64(value: &str) -> VcxResult<Vec<u8>> {
+    base64::decode_config(value.trim_end_matches('='), base64::URL_SAFE_NO_PAD).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!("Failed to decode base64 value: {}", err),
+        )
+    })
+}
+
+fn tags_from_json(tags_json: &str) -> VcxResult<Vec<EntryTag>> {
+    let tags: HashMap<String, String> = serde_json::from_str(tags_json).map_err(|err| {
+        AriesVcxError::from_msg(AriesVcxErrorKind::InvalidJson, format!("Invalid tags json: {}", err))
+    })?;
+    Ok(tags
+        .into_iter()
+        .map(|(name, value)| match name.strip_prefix('~') {
+            Some(plaintext_name) => EntryTag::Plaintext(plaintext_name.to_string(), value),
+            None => EntryTag::Encrypted(name, value),
+        })
+        .collect())
+}
+
+fn tag_name(tag: &EntryTag) -> String {
+    match tag {
+        EntryTag::Encrypted(name, _) => name.clone(),
+        EntryTag::Plaintext(name, _) => format!("~{}", name),
+    }
+}
+
+fn tag_value(tag: &EntryTag) -> &str {
+    match tag {
+        EntryTag::Encrypted(_, value) | EntryTag::Plaintext(_, value) => value,
+    }
+}
+
+fn entry_to_record_json(entry: &Entry, options: &RecordOptions) -> VcxResult<String> {
+    let value = if options.retrieve_value {
+        Some(String::from_utf8(entry.value.to_vec()).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::ParsingError,
+                format!("Wallet record value is not valid utf8: {}", err),
+            )
+        })?)
+    } else {
+        None
+    };
+    let tags = options.retrieve_tags.then(|| {
+        entry
+            .tags
+            .iter()
+            .map(|tag| (tag_name(tag), tag_value(tag).to_string()))
+            .collect::<HashMap<String, String>>()
+    });
+    let record = json!({
+        "type": options.retrieve_type.then(|| entry.category.clone()),
+        "id": entry.name,
+        "value": value,
+        "tags": tags,
+    });
+    Ok(record.to_string())
+}
+
+#[async_trait]
+impl BaseWallet for AskarWallet {
+    async fn create_and_store_my_did(
+        &self,
+        seed: Option<&str>,
+        method_name: Option<&str>,
+    ) -> VcxResult<(String, String)> {
+        let mut session = self.transaction().await?;
+        let (key, verkey) = insert_new_key(&mut session, seed).await?;
+        let unqualified_did = bs58::encode(&key.to_public_bytes()?.to_vec()[0..16]).into_string();
+        let did = match method_name {
+            Some(method) => format!("did:{}:{}", method, unqualified_did),
+            None => unqualified_did,
+        };
+        let record = DidRecord {
+            did: did.clone(),
+            verkey: verkey.clone(),
+        };
+        session
+            .insert(CATEGORY_DID, &did, &serde_json::to_vec(&record)?, None, None)
+            .await?;
+        session.commit().await?;
+        Ok((did, verkey))
+    }
+
+    async fn key_for_local_did(&self, did: &str) -> VcxResult<String> {
+        let mut session = self.session().await?;
+        Ok(self.get_did_record(&mut session, did).await?.verkey)
+    }
+
+    async fn replace_did_keys_start(&self, target_did: &str) -> VcxResult<String> {
+        let mut session = self.transaction().await?;
+        self.get_did_record(&mut session, target_did).await?;
+        let (_, verkey) = insert_new_key(&mut session, None).await?;
+        if session.fetch(CATEGORY_TEMP_DID_KEY, target_did, true).await?.is_some() {
+            session.remove(CATEGORY_TEMP_DID_KEY, target_did).await?;
+        }
+        session
+            .insert(CATEGORY_TEMP_DID_KEY, target_did, verkey.as_bytes(), None, None)
+            .await?;
+        session.commit().await?;
+        Ok(verkey)
+    }
+
+    async fn replace_did_keys_apply(&self, target_did: &str) -> VcxResult<()> {
+        let mut session = self.transaction().await?;
+        let temp_entry = self
+            .fetch_entry(&mut session, CATEGORY_TEMP_DID_KEY, target_did)
+            .await?;
+        let mut record = self.get_did_record(&mut session, target_did).await?;
+        record.verkey = String::from_utf8(temp_entry.value.to_vec()).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidVerkey,
+                format!("Invalid temporary verkey: {}", err),
+            )
+        })?;
+        session
+            .replace(CATEGORY_DID, target_did, &serde_json::to_vec(&record)?, None, None)
+            .await?;
+        session.remove(CATEGORY_TEMP_DID_KEY, target_did).await?;
+        session.commit().await?;
+        Ok(())
+    }
+
+    async fn add_wallet_record(&self, xtype: &str, id: &str, value: &str, tags_json: Option<&str>) -> VcxResult<()> {
+        let tags = tags_json.map(tags_from_json).transpose()?;
+        let mut session = self.transaction().await?;
+        session
+            .insert(xtype, id, value.as_bytes(), tags.as_deref(), None)
+            .await?;
+        session.commit().await?;
+        Ok(())
+    }
+
+    async fn get_wallet_record(&self, xtype: &str, id: &str, options_json: &str) -> VcxResult<String> {
+        let options: RecordOptions = serde_json::from_str(options_json)?;
+        let mut session = self.session().await?;
+        let entry = self.fetch_entry(&mut session, xtype, id).await?;
+        entry_to_record_json(&entry, &options)
+    }
+
+    async fn delete_wallet_record(&self, xtype: &str, id: &str) -> VcxResult<()> {
+        self.session().await?.remove(xtype, id).await?;
+        Ok(())
+    }
+
+    async fn update_wallet_record_value(&self, xtype: &str, id: &str, value: &str) -> VcxResult<()> {
+        let mut session = self.transaction().await?;
+        let entry = self.fetch_entry(&mut session, xtype, id).await?;
+        session
+            .replace(xtype, id, value.as_bytes(), Some(&entry.tags), None)
+            .await?;
+        session.commit().await?;
+        Ok(())
+    }
+
+    async fn add_wallet_record_tags(&self, xtype: &str, id: &str, tags_json: &str) -> VcxResult<()> {
+        let new_tags = tags_from_json(tags_json)?;
+        let mut session = self.transaction().await?;
+        let entry = self.fetch_entry(&mut session, xtype, id).await?;
+        let mut tags: Vec<EntryTag> = entry
+            .tags
+            .iter()
+            .filter(|tag| !new_tags.iter().any(|new_tag| tag_name(new_tag) == tag_name(tag)))
+            .cloned()
+            .collect();
+        tags.extend(new_tags);
+        session
+            .replace(xtype, id, entry.value.as_ref(), Some(&tags), None)
+            .await?;
+        session.commit().await?;
+        Ok(())
+    }
+
+    async fn update_wallet_record_tags(&self, xtype: &str, id: &str, tags_json: &str) -> VcxResult<()> {
+        let tags = tags_from_json(tags_json)?;
+        let mut session = self.transaction().await?;
+        let entry = self.fetch_entry(&mut session, xtype, id).await?;
+        session
+            .replace(xtype, id, entry.value.as_ref(), Some(&tags), None)
+            .await?;
+        session.commit().await?;
+        Ok(())
+    }
+
+    async fn delete_wallet_record_tags(&self, xtype: &str, id: &str, tag_names: &str) -> VcxResult<()> {
+        let tag_names: Vec<String> = serde_json::from_str(tag_names)?;
+        let mut session = self.transaction().await?;
+        let entry = self.fetch_entry(&mut session, xtype, id).await?;
+        let tags: Vec<EntryTag> = entry
+            .tags
+            .iter()
+            .filter(|tag| !tag_names.contains(&tag_name(tag)))
+            .cloned()
+            .collect();
+        session
+            .replace(xtype, id, entry.value.as_ref(), Some(&tags), None)
+            .await?;
+        session.commit().await?;
+        Ok(())
+    }
+
+    async fn iterate_wallet_records(
+        &self,
+        xtype: &str,
+        query: &str,
+        options: &str,
+    ) -> VcxResult<Box<dyn AsyncFnIterator<Item = VcxResult<String>>>> {
+        let options: RecordOptions = serde_json::from_str(options)?;
+        let tag_filter = TagFilter::from_str(query)?;
+        let scan = self
+            .backend
+            .scan(self.profile.clone(), xtype.to_string(), Some(tag_filter), None, None)
+            .await?;
+        Ok(Box::new(AskarWalletRecordIterator::new(scan, options)))
+    }
+
+    async fn sign(&self, my_vk: &str, msg: &[u8]) -> VcxResult<Vec<u8>> {
+        let key = self.get_local_key(my_vk).await?;
+        Ok(key.sign_message(msg, None)?)
+    }
+
+    async fn verify(&self, vk: &str, msg: &[u8], signature: &[u8]) -> VcxResult<bool> {
+        let key = public_key_from_verkey(vk)?;
+        Ok(key.verify_signature(msg, signature, None)?)
+    }
+
+    async fn pack_message(&self, sender_vk: Option<&str>, receiver_keys: &str, msg: &[u8]) -> VcxResult<Vec<u8>> {
+        let receiver_keys: Vec<String> = serde_json::from_str(receiver_keys).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!("Invalid RecipientKeys has been passed: {}", err),
+            )
+        })?;
+        if receiver_keys.is_empty() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidLibindyParam,
+                "Empty RecipientKeys has been passed",
+            ));
+        }
+
+        let sender = match sender_vk {
+            Some(sender_vk) => {
+                let sender_key = self.get_local_key(sender_vk).await?;
+                Some((sender_vk, sender_key.convert_key(KeyAlg::X25519)?))
+            }
+            None => None,
+        };
+
+        let cek = LocalKey::generate(KeyAlg::Chacha20(Chacha20Types::C20P), true)?;
+        let cek_bytes = cek.to_secret_bytes()?;
+
+        let mut recipients = Vec::with_capacity(receiver_keys.len());
+        for receiver_vk in receiver_keys {
+            let receiver_key = public_key_from_verkey(&receiver_vk)?.convert_key(KeyAlg::X25519)?;
+            let recipient = match &sender {
+                Some((sender_vk, sender_key)) => {
+                    let nonce = crypto_box_random_nonce()?;
+                    let encrypted_key = crypto_box(&receiver_key, sender_key, &cek_bytes, &nonce)?;
+                    let encrypted_sender = crypto_box_seal(&receiver_key, sender_vk.as_bytes())?;
+                    JweRecipient {
+                        encrypted_key: encode_b64(&encrypted_key),
+                        header: JweRecipientHeader {
+                            kid: receiver_vk,
+                            iv: Some(encode_b64(&nonce)),
+                            sender: Some(encode_b64(&encrypted_sender)),
+                        },
+                    }
+                }
+                None => {
+                    let encrypted_key = crypto_box_seal(&receiver_key, &cek_bytes)?;
+                    JweRecipient {
+                        encrypted_key: encode_b64(&encrypted_key),
+                        header: JweRecipientHeader {
+                            kid: receiver_vk,
+                            iv: None,
+                            sender: None,
+                        },
+                    }
+                }
+            };
+            recipients.push(recipient);
+        }
+
+        let protected = JweProtected {
+            enc: PACK_ENC.to_string(),
+            typ: PACK_TYP.to_string(),
+            alg: match sender {
+                Some(_) => PACK_ALG_AUTHCRYPT,
+                None => PACK_ALG_ANONCRYPT,
+            }
+            .to_string(),
+            recipients,
+        };
+        let protected = encode_b64(&serde_json::to_vec(&protected)?);
+
+        let nonce = cek.aead_random_nonce()?;
+        let encrypted = cek.aead_encrypt(msg, &nonce, protected.as_bytes())?;
+
+        let jwe = Jwe {
+            protected,
+            iv: encode_b64(&nonce),
+            ciphertext: encode_b64(encrypted.ciphertext()),
+            tag: encode_b64(encrypted.tag()),
+        };
+        Ok(serde_json::to_vec(&jwe)?)
+    }
+
+    async fn unpack_message(&self, msg: &[u8]) -> VcxResult<Vec<u8>> {
+        let jwe: Jwe = serde_json::from_slice(msg).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Failed to deserialize packed message: {}", err),
+            )
+        })?;
+        let protected: JweProtected = serde_json::from_slice(&decode_b64(&jwe.protected)?).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Failed to deserialize protected header: {}", err),
+            )
+        })?;
+
+        let mut found = None;
+        for recipient in protected.recipients {
+            if let Some(key) = self.fetch_local_key(&recipient.header.kid).await? {
+                found = Some((recipient, key));
+                break;
+            }
+        }
+        let (recipient, recipient_key) = found.ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::WalletRecordNotFound,
+                "No matching recipient key found in wallet",
+            )
+        })?;
+        let recipient_key = recipient_key.convert_key(KeyAlg::X25519)?;
+        let encrypted_key = decode_b64(&recipient.encrypted_key)?;
+
+        let (cek_bytes, sender_verkey) = match protected.alg.as_str() {
+            PACK_ALG_AUTHCRYPT => {
+                let missing_field = |field: &str| {
+                    AriesVcxError::from_msg(
+                        AriesVcxErrorKind::InvalidMessageFormat,
+                        format!("Authcrypt recipient header is missing {}", field),
+                    )
+                };
+                let encrypted_sender = decode_b64(&recipient.header.sender.ok_or_else(|| missing_field("sender"))?)?;
+                let nonce = decode_b64(&recipient.header.iv.ok_or_else(|| missing_field("iv"))?)?;
+                let sender_verkey = String::from_utf8(
+                    crypto_box_seal_open(&recipient_key, &encrypted_sender)?.to_vec(),
+                )
+                .map_err(|err| {
+                    AriesVcxError::from_msg(
+                        AriesVcxErrorKind::InvalidVerkey,
+                        format!("Invalid sender verkey: {}", err),
+                    )
+                })?;
+                let sender_key = public_key_from_verkey(&sender_verkey)?.convert_key(KeyAlg::X25519)?;
+                let cek_bytes = crypto_box_open(&recipient_key, &sender_key, &encrypted_key, &nonce)?;
+                (cek_bytes, Some(sender_verkey))
+            }
+            PACK_ALG_ANONCRYPT => (crypto_box_seal_open(&recipient_key, &encrypted_key)?, None),
+            alg => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    format!("Unsupported pack algorithm: {}", alg),
+                ))
+            }
+        };
+
+        let cek = LocalKey::from_secret_bytes(KeyAlg::Chacha20(Chacha20Types::C20P), &cek_bytes)?;
+        let ciphertext = decode_b64(&jwe.ciphertext)?;
+        let tag = decode_b64(&jwe.tag)?;
+        let iv = decode_b64(&jwe.iv)?;
+        let message = cek.aead_decrypt((ciphertext.as_slice(), tag.as_slice()), &iv, jwe.protected.as_bytes())?;
+
+        let unpacked = UnpackMessage {
+            message: String::from_utf8(message.to_vec()).map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    format!("Unpacked message is not valid utf8: {}", err),
+                )
+            })?,
+            recipient_verkey: recipient.header.kid,
+            sender_verkey,
+        };
+        Ok(serde_json::to_vec(&unpacked)?)
+    }
+
+    async fn create_key_agreement_key(&self) -> VcxResult<String> {
+        let key = LocalKey::generate(KeyAlg::X25519, false)?;
+        let public_key = bs58::encode(key.to_public_bytes()?.to_vec()).into_string();
+        self.session()
+            .await?
+            .insert_key(&public_key, &key, None, None, None)
+            .await?;
+        Ok(public_key)
+    }
+
+    async fn key_agreement(&self, my_key: &str, their_key: &str) -> VcxResult<Vec<u8>> {
+        let key = self.get_local_key(my_key).await?;
+        if key.algorithm() != KeyAlg::X25519 {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!("Key {} is not an X25519 key agreement key", my_key),
+            ));
+        }
+        let their_key = bs58::decode(their_key).into_vec().map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotBase58,
+                format!("Key {} is not valid base58: {}", their_key, err),
+            )
+        })?;
+        x25519_diffie_hellman(&key, &their_key)
+    }
+}
+
+struct AskarWalletRecordIterator {
+    scan: Mutex<Scan<'static, Entry>>,
+    buffer: VecDeque<Entry>,
+    options: RecordOptions,
+}
+
+impl AskarWalletRecordIterator {
+    fn new(scan: Scan<'static, Entry>, options: RecordOptions) -> Self {
+        AskarWalletRecordIterator {
+            scan: Mutex::new(scan),
+            buffer: VecDeque::new(),
+            options,
+        }
+    }
+
+    async fn fetch_next_record(&mut self) -> VcxResult<Option<String>> {
+        if self.buffer.is_empty() {
+            let scan = self.scan.get_mut()?;
+            if let Some(entries) = scan.fetch_next().await? {
+                self.buffer.extend(entries);
+            }
+        }
+        self.buffer
+            .pop_front()
+            .map(|entry| entry_to_record_json(&entry, &self.options))
+            .transpose()
+    }
+}
+
+/// Implementation of a generic [AsyncFnIterator] iterator for askar wallet record iteration.
+/// Wraps over an askar [Scan], buffering each fetched batch of entries.
+#[async_trait]
+impl AsyncFnIterator for AskarWalletRecordIterator {
+    type Item = VcxResult<String>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        self.fetch_next_record().await.transpose()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use aries_askar::{PassKey, StoreKeyMethod};
+    use serde_json::Value;
+
+    use crate::errors::error::AriesVcxErrorKind;
+    use crate::plugins::wallet::base_wallet::{AsyncFnIteratorCollect, BaseWallet};
+
+    use super::AskarWallet;
+
+    async fn create_wallet() -> AskarWallet {
+        AskarWallet::create("sqlite://:memory:", StoreKeyMethod::Unprotected, PassKey::empty(), None)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_askar_wallet_dids_and_signing() {
+        let wallet = create_wallet().await;
+
+        let (did, verkey) = wallet
+            .create_and_store_my_did(Some("000000000000000000000000Trustee1"), None)
+            .await
+            .unwrap();
+        assert_eq!(did, "V4SGRU86Z58d6TV7PBUe6f");
+        assert_eq!(verkey, "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL");
+        assert_eq!(wallet.key_for_local_did(&did).await.unwrap(), verkey);
+
+        let signature = wallet.sign(&verkey, b"hello").await.unwrap();
+        assert!(wallet.verify(&verkey, b"hello", &signature).await.unwrap());
+        assert!(!wallet.verify(&verkey, b"goodbye", &signature).await.unwrap());
+
+        let new_verkey = wallet.replace_did_keys_start(&did).await.unwrap();
+        assert_eq!(wallet.key_for_local_did(&did).await.unwrap(), verkey);
+        wallet.replace_did_keys_apply(&did).await.unwrap();
+        assert_eq!(wallet.key_for_local_did(&did).await.unwrap(), new_verkey);
+
+        let (qualified_did, _) = wallet.create_and_store_my_did(None, Some("sov")).await.unwrap();
+        assert!(qualified_did.starts_with("did:sov:"));
+    }
+
+    #[tokio::test]
+    async fn test_askar_wallet_records() {
+        let wallet = create_wallet().await;
+        let options = r#"{"retrieveType": true, "retrieveValue": true, "retrieveTags": true}"#;
+
+        wallet
+            .add_wallet_record("type", "id1", "value1", Some(r#"{"a": "1", "~b": "2"}"#))
+            .await
+            .unwrap();
+        wallet
+            .add_wallet_record("type", "id2", "value2", Some(r#"{"a": "2"}"#))
+            .await
+            .unwrap();
+        let err = wallet
+            .add_wallet_record("type", "id1", "value1", None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::DuplicationWalletRecord);
+
+        wallet
+            .update_wallet_record_value("type", "id1", "value3")
+            .await
+            .unwrap();
+        wallet
+            .add_wallet_record_tags("type", "id1", r#"{"c": "3"}"#)
+            .await
+            .unwrap();
+        wallet
+            .delete_wallet_record_tags("type", "id1", r#"["~b"]"#)
+            .await
+            .unwrap();
+        let record: Value =
+            serde_json::from_str(&wallet.get_wallet_record("type", "id1", options).await.unwrap()).unwrap();
+        assert_eq!(
+            record,
+            json!({"type": "type", "id": "id1", "value": "value3", "tags": {"a": "1", "c": "3"}})
+        );
+
+        let records = wallet
+            .iterate_wallet_records("type", r#"{"a": "2"}"#, "{}")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        let record: Value = serde_json::from_str(&records[0]).unwrap();
+        assert_eq!(record["id"], "id2");
+        assert_eq!(record["value"], "value2");
+
+        wallet.delete_wallet_record("type", "id1").await.unwrap();
+        let err = wallet.get_wallet_record("type", "id1", "{}").await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::WalletRecordNotFound);
+    }
+
+    #[tokio::test]
+    async fn test_askar_wallet_pack_unpack() {
+        let sender_wallet = create_wallet().await;
+        let receiver_wallet = create_wallet().await;
+        let (_, sender_vk) = sender_wallet.create_and_store_my_did(None, None).await.unwrap();
+        let (_, receiver_vk) = receiver_wallet.create_and_store_my_did(None, None).await.unwrap();
+        let receiver_keys = json!([receiver_vk]).to_string();
+
+        let packed = sender_wallet
+            .pack_message(Some(&sender_vk), &receiver_keys, b"authcrypted")
+            .await
+            .unwrap();
+        let unpacked: Value = serde_json::from_slice(&receiver_wallet.unpack_message(&packed).await.unwrap()).unwrap();
+        assert_eq!(unpacked["message"], "authcrypted");
+        assert_eq!(unpacked["recipient_verkey"], receiver_vk);
+        assert_eq!(unpacked["sender_verkey"], sender_vk);
+
+        let packed = sender_wallet
+            .pack_message(None, &receiver_keys, b"anoncrypted")
+            .await
+            .unwrap();
+        let unpacked: Value = serde_json::from_slice(&receiver_wallet.unpack_message(&packed).await.unwrap()).unwrap();
+        assert_eq!(unpacked["message"], "anoncrypted");
+        assert!(unpacked.get("sender_verkey").is_none());
+
+        let err = sender_wallet.unpack_message(&packed).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::WalletRecordNotFound);
+        let err = sender_wallet.pack_message(None, "[]", b"msg").await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidLibindyParam);
+    }
+
+    #[tokio::test]
+    async fn test_askar_wallet_key_agreement() {
+        let alice_wallet = create_wallet().await;
+        let bob_wallet = create_wallet().await;
+        let alice_key = alice_wallet.create_key_agreement_key().await.unwrap();
+        let bob_key = bob_wallet.create_key_agreement_key().await.unwrap();
+
+        let shared_secret = alice_wallet.key_agreement(&alice_key, &bob_key).await.unwrap();
+        assert_eq!(shared_secret.len(), 32);
+        assert_eq!(
+            shared_secret,
+            bob_wallet.key_agreement(&bob_key, &alice_key).await.unwrap()
+        );
+
+        let err = alice_wallet.key_agreement(&bob_key, &alice_key).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::WalletRecordNotFound);
+        let (_, verkey) = alice_wallet.create_and_store_my_did(None, None).await.unwrap();
+        let err = alice_wallet.key_agreement(&verkey, &bob_key).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidInput);
+    }
+}
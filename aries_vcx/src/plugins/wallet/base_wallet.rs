@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 
-use crate::errors::error::VcxResult;
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
 use crate::utils::async_fn_iterator::AsyncFnIterator;
 
 /// Trait defining standard 'wallet' related functionality. The APIs, including
@@ -56,6 +56,26 @@ pub trait BaseWallet: std::fmt::Debug + Send + Sync {
     async fn pack_message(&self, sender_vk: Option<&str>, receiver_keys: &str, msg: &[u8]) -> VcxResult<Vec<u8>>;
 
     async fn unpack_message(&self, msg: &[u8]) -> VcxResult<Vec<u8>>;
+
+    // ---- key agreement
+
+    // creates an X25519 key agreement key held by the wallet, returns its base58 encoded public key
+    async fn create_key_agreement_key(&self) -> VcxResult<String> {
+        Err(unsupported_key_agreement())
+    }
+
+    // X25519 Diffie-Hellman between the key agreement key `my_key` held by the wallet and the public key `their_key`,
+    // both base58 encoded, returns the raw shared secret
+    async fn key_agreement(&self, _my_key: &str, _their_key: &str) -> VcxResult<Vec<u8>> {
+        Err(unsupported_key_agreement())
+    }
+}
+
+fn unsupported_key_agreement() -> AriesVcxError {
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::ActionNotSupported,
+        "The wallet does not support X25519 key agreement keys",
+    )
 }
 
 #[async_trait]
@@ -1,4 +1,6 @@
 pub mod agency_client_wallet;
+#[cfg(feature = "modular_libs")]
+pub mod askar_wallet;
 pub mod base_wallet;
 #[cfg(feature = "vdrtools")]
 pub mod indy_wallet;
@@ -1,4 +1,21 @@
+use std::fmt::Debug;
+
 use messages::concepts::problem_report::ProblemReport;
+use messages2::{
+    decorators::{
+        attachment::{Attachment, AttachmentData, AttachmentType},
+        thread::Thread,
+    },
+    maybe_known::MaybeKnown,
+    misc::MimeType,
+    msg_fields::protocols::common::attachment_format_id::AttachmentFormatSpecifier,
+};
+
+use crate::{
+    common::signing::attachment_payload,
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    utils::uuid::uuid,
+};
 
 pub fn build_problem_report_msg(comment: Option<String>, thread_id: &str) -> ProblemReport {
     ProblemReport::create()
@@ -7,6 +24,75 @@ pub fn build_problem_report_msg(comment: Option<String>, thread_id: &str) -> Pro
         .set_thread_id(thread_id)
 }
 
+/// Builds an attachment holding `payload` together with the `formats` entry describing it.
+pub(crate) fn build_format_attachment<F>(format: F, payload: Vec<u8>) -> (AttachmentFormatSpecifier<F>, Attachment) {
+    let attach_id = uuid();
+    let mut attachment = Attachment::new(AttachmentData::new(AttachmentType::Base64(payload)));
+    attachment.id = Some(attach_id.clone());
+    attachment.mime_type = Some(MimeType::Json);
+
+    (
+        AttachmentFormatSpecifier::new(attach_id, MaybeKnown::Known(format)),
+        attachment,
+    )
+}
+
+/// Looks up the attachment the `formats` array maps to the given format and returns its payload.
+pub(crate) fn get_format_attachment_payload<F>(
+    formats: &[AttachmentFormatSpecifier<F>],
+    attachments: &[Attachment],
+    format: F,
+) -> VcxResult<Vec<u8>>
+where
+    F: PartialEq + Debug,
+{
+    let attach_id = formats
+        .iter()
+        .find_map(|specifier| match &specifier.format {
+            MaybeKnown::Known(known) if *known == format => Some(&specifier.attach_id),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::ActionNotSupported,
+                format!("Message carries no attachment of format {:?}", format),
+            )
+        })?;
+
+    let attachment = attachments
+        .iter()
+        .find(|attachment| attachment.id.as_ref() == Some(attach_id))
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!(
+                    "Message references attachment {} in its formats, but does not carry it",
+                    attach_id
+                ),
+            )
+        })?;
+
+    attachment_payload(attachment)
+}
+
+/// Thread id of a message which may start a new thread, in which case its own id is the thread id.
+pub(crate) fn msg_thread_id<'a>(msg_id: &'a str, thread: Option<&'a Thread>) -> &'a str {
+    thread.map(|thread| thread.thid.as_str()).unwrap_or(msg_id)
+}
+
+pub(crate) fn verify_thread_id(expected: &str, received: &str) -> VcxResult<()> {
+    if expected != received {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!(
+                "Cannot handle message: thread id does not match, expected {}, received {}",
+                expected, received
+            ),
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 #[cfg(feature = "general_test")]
 mod test {
@@ -5,8 +5,14 @@ use std::sync::Arc;
 use messages::{diddoc::aries::diddoc::AriesDidDoc, protocols::connection::invite::Invitation};
 
 use crate::{
-    common::ledger::transactions::into_did_doc, core::profile::profile::Profile, errors::error::VcxResult,
-    protocols::connection::trait_bounds::ThreadId, transport::Transport,
+    common::ledger::transactions::into_did_doc,
+    core::profile::profile::Profile,
+    errors::error::VcxResult,
+    protocols::{
+        connection::trait_bounds::{TheirDidDoc, ThreadId},
+        coordinate_mediation::recipient::MediationRecipient,
+    },
+    transport::Transport,
 };
 
 use self::states::{
@@ -127,6 +133,37 @@ impl InviteeConnection<Invited> {
             initiation_type: Invitee,
         })
     }
+
+    /// Sends a [`Request`] advertising the endpoint and routing keys granted by a mediator
+    /// and transitions to [`InviteeConnection<Requested>`].
+    ///
+    /// The connection's verkey is registered with the mediator over `mediator_connection`
+    /// before the request is sent, so that the inviter's response can be routed back to us.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if mediation has not been granted or if sending either message fails.
+    pub async fn send_request_mediated<I, S, T>(
+        self,
+        wallet: &Arc<dyn BaseWallet>,
+        mediation: &mut MediationRecipient,
+        mediator_connection: &Connection<I, S>,
+        transport: &T,
+    ) -> VcxResult<InviteeConnection<Requested>>
+    where
+        S: TheirDidDoc,
+        T: Transport,
+    {
+        let service_endpoint = mediation.service_endpoint()?.to_owned();
+        let routing_keys = mediation.routing_keys()?.to_vec();
+
+        mediation
+            .add_recipient_key(wallet, mediator_connection, &self.pairwise_info.pw_vk, transport)
+            .await?;
+
+        self.send_request(wallet, service_endpoint, routing_keys, transport)
+            .await
+    }
 }
 
 impl InviteeConnection<Requested> {
@@ -3,7 +3,8 @@ pub mod states;
 use std::sync::Arc;
 
 use crate::handlers::util::verify_thread_id;
-use crate::protocols::connection::trait_bounds::ThreadId;
+use crate::protocols::connection::trait_bounds::{TheirDidDoc, ThreadId};
+use crate::protocols::coordinate_mediation::recipient::MediationRecipient;
 use crate::transport::Transport;
 use crate::utils::uuid;
 use crate::{
@@ -155,8 +156,13 @@ impl InviterConnection<Invited> {
         }
 
         // Generate new pairwise info that will be used from this point on
-        // and incorporate that into the response.
-        let new_pairwise_info = PairwiseInfo::create(wallet).await?;
+        // and incorporate that into the response. Inviters identified by a did:peer
+        // keep advertising did:peer DIDs.
+        let new_pairwise_info = if self.pairwise_info.is_peer_did() {
+            PairwiseInfo::create_with_peer_did(wallet, &new_service_endpoint, &new_routing_keys).await?
+        } else {
+            PairwiseInfo::create(wallet).await?
+        };
         let signed_response = self
             .build_response(
                 wallet,
@@ -178,6 +184,42 @@ impl InviterConnection<Invited> {
         })
     }
 
+    /// Processes a [`Request`] like [`InviterConnection::handle_request`], using the endpoint
+    /// and routing keys granted by a mediator in the DidDoc sent back to the invitee.
+    ///
+    /// The newly generated verkey is registered with the mediator over `mediator_connection`
+    /// before the response is sent.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if mediation has not been granted, if processing the request fails
+    /// or if sending the keylist update fails.
+    pub async fn handle_request_mediated<I, S, T>(
+        self,
+        wallet: &Arc<dyn BaseWallet>,
+        request: Request,
+        mediation: &mut MediationRecipient,
+        mediator_connection: &Connection<I, S>,
+        transport: &T,
+    ) -> VcxResult<InviterConnection<Requested>>
+    where
+        S: TheirDidDoc,
+        T: Transport,
+    {
+        let service_endpoint = mediation.service_endpoint()?.to_owned();
+        let routing_keys = mediation.routing_keys()?.to_vec();
+
+        let connection = self
+            .handle_request(wallet, request, service_endpoint, routing_keys, transport)
+            .await?;
+
+        mediation
+            .add_recipient_key(wallet, mediator_connection, &connection.pairwise_info.pw_vk, transport)
+            .await?;
+
+        Ok(connection)
+    }
+
     /// Returns the [`Invitation`] generated by this inviter.
     ///
     /// NOTE: Calling [`InviterConnection<Initial>::into_invited()`]
@@ -4,7 +4,7 @@ pub mod invitee;
 pub mod inviter;
 pub mod pairwise_info;
 mod serializable;
-mod trait_bounds;
+pub(crate) mod trait_bounds;
 
 use messages::{
     a2a::{protocol_registry::ProtocolRegistry, A2AMessage},
@@ -14,12 +14,13 @@ use messages::{
         discovery::disclose::{Disclose, ProtocolDescriptor},
     },
 };
+use messages2::AriesMessage;
 use std::{error::Error, sync::Arc};
 
 use crate::{
     errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
     plugins::wallet::base_wallet::BaseWallet,
-    transport::Transport,
+    transport::{with_return_route, DuplexTransport, ReturnRouted, Transport},
     utils::encryption_envelope::EncryptionEnvelope,
 };
 
@@ -120,6 +121,65 @@ where
         let did_doc = self.their_did_doc();
         wrap_and_send_msg(wallet, message, sender_verkey, did_doc, transport).await
     }
+
+    /// Sends a message of the protocols implemented in `messages2` to the counterparty.
+    pub async fn send_aries_message<T>(
+        &self,
+        wallet: &Arc<dyn BaseWallet>,
+        message: &AriesMessage,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        T: Transport,
+    {
+        let message = A2AMessage::Generic(serde_json::to_value(message)?);
+        self.send_message(wallet, &message, transport).await
+    }
+
+    /// Sends a message asking the counterparty to reply over the same channel, by decorating it
+    /// with `~transport` `return_route: all`, and waits for the reply on the transport.
+    ///
+    /// Returns `None` if the counterparty closed the channel without replying, and fails with
+    /// [`AriesVcxErrorKind::Timeout`] if no reply arrives within the transport's receive timeout.
+    pub async fn send_message_with_return_route<T>(
+        &self,
+        wallet: &Arc<dyn BaseWallet>,
+        message: &A2AMessage,
+        transport: &T,
+    ) -> VcxResult<Option<A2AMessage>>
+    where
+        T: DuplexTransport,
+    {
+        let message = with_return_route(message)?;
+        self.send_message(wallet, &message, &ReturnRouted(transport)).await?;
+
+        let service_endpoint = self.their_did_doc().get_endpoint();
+        match transport.receive_message(&service_endpoint).await? {
+            Some(payload) => {
+                let remote_vk = self.remote_vk()?;
+                let reply = EncryptionEnvelope::auth_unpack(wallet, payload, &remote_vk).await?;
+                Ok(Some(reply))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Same as [`Self::send_message_with_return_route`], for [`AriesMessage`]s.
+    pub async fn send_aries_message_with_return_route<T>(
+        &self,
+        wallet: &Arc<dyn BaseWallet>,
+        message: &AriesMessage,
+        transport: &T,
+    ) -> VcxResult<Option<AriesMessage>>
+    where
+        T: DuplexTransport,
+    {
+        let message = A2AMessage::Generic(serde_json::to_value(message)?);
+        match self.send_message_with_return_route(wallet, &message, transport).await? {
+            Some(reply) => Ok(Some(serde_json::from_str(&serde_json::to_string(&reply)?)?)),
+            None => Ok(None),
+        }
+    }
 }
 
 impl<I, S> Connection<I, S>
@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use messages::diddoc::peer::{generate_numalgo2, is_peer_did};
+
 use crate::errors::error::VcxResult;
 use crate::plugins::wallet::base_wallet::BaseWallet;
 
@@ -14,4 +16,21 @@ impl PairwiseInfo {
         let (pw_did, pw_vk) = wallet.create_and_store_my_did(None, None).await?;
         Ok(PairwiseInfo { pw_did, pw_vk })
     }
+
+    /// Creates pairwise info identified by a did:peer (numalgo 2) rather than an unqualified DID.
+    /// The DID encodes the verkey along with the service we are reachable at, so it can be
+    /// advertised to peers which require did:peer.
+    pub async fn create_with_peer_did(
+        wallet: &Arc<dyn BaseWallet>,
+        service_endpoint: &str,
+        routing_keys: &[String],
+    ) -> VcxResult<PairwiseInfo> {
+        let (_, pw_vk) = wallet.create_and_store_my_did(None, None).await?;
+        let pw_did = generate_numalgo2(&pw_vk, service_endpoint, routing_keys)?;
+        Ok(PairwiseInfo { pw_did, pw_vk })
+    }
+
+    pub fn is_peer_did(&self) -> bool {
+        is_peer_did(&self.pw_did)
+    }
 }
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use messages::a2a::A2AMessage;
+use messages::concepts::service_decorator::ServiceDecorator;
+use messages::diddoc::aries::diddoc::AriesDidDoc;
+
+use crate::plugins::wallet::base_wallet::BaseWallet;
+use crate::protocols::SendClosure;
+use crate::transport::Transport;
+use crate::utils::encryption_envelope::EncryptionEnvelope;
+
+/// Builds a DIDDoc describing the `~service` of a message received without a connection,
+/// so replies can be packed and routed like messages for a connection counterparty.
+pub fn service_to_did_doc(service: &ServiceDecorator) -> AriesDidDoc {
+    let mut did_doc = AriesDidDoc::default();
+    did_doc.set_service_endpoint(service.service_endpoint.clone());
+    did_doc.set_recipient_keys(service.recipient_keys.clone());
+    did_doc.set_routing_keys(service.routing_keys.clone());
+    did_doc
+}
+
+/// Builds a [`SendClosure`] which packs a message for the `~service` and sends it to the
+/// service endpoint through the transport. The message is anoncrypted unless `sender_verkey`
+/// is provided.
+pub fn build_service_send_closure<T>(
+    wallet: Arc<dyn BaseWallet>,
+    service: &ServiceDecorator,
+    sender_verkey: Option<String>,
+    transport: Arc<T>,
+) -> SendClosure
+where
+    T: Transport + 'static,
+{
+    let did_doc = service_to_did_doc(service);
+    Box::new(move |message: A2AMessage| {
+        Box::pin(async move {
+            let envelope = EncryptionEnvelope::create(&wallet, &message, sender_verkey.as_deref(), &did_doc).await?;
+            transport.send_message(envelope.0, &did_doc.get_endpoint()).await
+        })
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use messages::{a2a::MessageId, protocols::trust_ping::ping::Ping};
+
+    use super::*;
+    use crate::{
+        common::test_utils::{indy_handles_to_profile, CapturingTransport},
+        indy::utils::test_setup::with_wallet,
+        utils::devsetup::SetupEmpty,
+    };
+
+    const ENDPOINT: &str = "https://example.org/endpoint";
+
+    #[tokio::test]
+    async fn test_service_send_closure_packs_for_service() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let wallet = indy_handles_to_profile(wallet_handle, 0).inject_wallet();
+            let (_, recipient_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let service = ServiceDecorator::create()
+                .set_recipient_keys(vec![recipient_key])
+                .set_service_endpoint(ENDPOINT.to_string());
+            let transport = Arc::new(CapturingTransport::default());
+
+            let ping = Ping::create(MessageId::new()).set_thread_id("thread_id");
+            let send_message = build_service_send_closure(Arc::clone(&wallet), &service, None, Arc::clone(&transport));
+            send_message(ping.to_a2a_message()).await.unwrap();
+
+            let (packed, endpoint) = transport.take_packed();
+            assert_eq!(endpoint, ENDPOINT);
+            let (message, sender_vk) = EncryptionEnvelope::anon_unpack(&wallet, packed).await.unwrap();
+            assert_eq!(message, ping.to_a2a_message());
+            assert_eq!(sender_vk, None);
+        })
+        .await;
+    }
+}
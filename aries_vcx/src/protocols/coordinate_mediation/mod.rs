@@ -0,0 +1,7 @@
+//! Client side of the [coordinate mediation protocol](https://github.com/hyperledger/aries-rfcs/blob/main/features/0211-route-coordination/README.md).
+//!
+//! A [`recipient::MediationRecipient`] asks a mediator for mediation over an established connection,
+//! keeps track of the endpoint and routing keys granted by the mediator and registers the
+//! recipient keys the mediator should route messages for.
+
+pub mod recipient;
@@ -0,0 +1,526 @@
+use std::sync::Arc;
+
+use messages2::{
+    msg_fields::protocols::coordinate_mediation::{
+        keylist::Keylist,
+        keylist_query::{KeylistQuery, KeylistQueryContent, KeylistQueryPaginate},
+        keylist_update::{KeylistUpdate, KeylistUpdateAction, KeylistUpdateContent, KeylistUpdateItem},
+        keylist_update_response::{KeylistUpdateResponse, KeylistUpdateResult},
+        mediate_deny::MediateDeny,
+        mediate_grant::MediateGrant,
+        mediate_request::{MediateRequest, MediateRequestContent},
+        CoordinateMediation, CoordinateMediationDecorators,
+    },
+    AriesMessage,
+};
+
+use crate::{
+    common::did_resolver::key::normalize_keys_as_naked,
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    plugins::wallet::base_wallet::BaseWallet,
+    protocols::{
+        connection::{trait_bounds::TheirDidDoc, Connection},
+        did_exchange::verify_thread_id,
+    },
+    transport::Transport,
+    utils::uuid::uuid,
+};
+
+/// State of the mediation as seen by the recipient.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum MediationState {
+    Initial,
+    Requested {
+        thread_id: String,
+    },
+    Granted {
+        thread_id: String,
+        endpoint: String,
+        routing_keys: Vec<String>,
+    },
+    Denied {
+        thread_id: String,
+    },
+}
+
+/// Recipient role of the coordinate mediation protocol.
+///
+/// All messages are sent to the mediator over the provided connection. Once mediation is granted,
+/// [`MediationRecipient::service_endpoint`] and [`MediationRecipient::routing_keys`] are what
+/// must be advertised to other parties for their messages to reach us through the mediator.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MediationRecipient {
+    state: MediationState,
+    recipient_keys: Vec<String>,
+    pending_updates: Vec<String>,
+}
+
+impl Default for MediationRecipient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MediationRecipient {
+    pub fn new() -> Self {
+        Self {
+            state: MediationState::Initial,
+            recipient_keys: Vec::new(),
+            pending_updates: Vec::new(),
+        }
+    }
+
+    pub fn state(&self) -> &MediationState {
+        &self.state
+    }
+
+    pub fn is_granted(&self) -> bool {
+        matches!(self.state, MediationState::Granted { .. })
+    }
+
+    /// Recipient keys the mediator confirmed it routes messages for.
+    pub fn recipient_keys(&self) -> &[String] {
+        &self.recipient_keys
+    }
+
+    /// Endpoint of the mediator which other parties should deliver our messages to.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if mediation has not been granted.
+    pub fn service_endpoint(&self) -> VcxResult<&str> {
+        match &self.state {
+            MediationState::Granted { endpoint, .. } => Ok(endpoint),
+            _ => Err(self.not_granted_error()),
+        }
+    }
+
+    /// Routing keys granted by the mediator, as base58 encoded verkeys.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if mediation has not been granted.
+    pub fn routing_keys(&self) -> VcxResult<&[String]> {
+        match &self.state {
+            MediationState::Granted { routing_keys, .. } => Ok(routing_keys),
+            _ => Err(self.not_granted_error()),
+        }
+    }
+
+    /// Sends a `mediate-request` to the mediator.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if mediation was already granted or if sending the message fails.
+    pub async fn send_request<I, S, T>(
+        &mut self,
+        wallet: &Arc<dyn BaseWallet>,
+        connection: &Connection<I, S>,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        S: TheirDidDoc,
+        T: Transport,
+    {
+        if self.is_granted() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Cannot send mediate-request: mediation was already granted",
+            ));
+        }
+
+        let id = uuid();
+        let request = MediateRequest::with_decorators(
+            id.clone(),
+            MediateRequestContent::default(),
+            CoordinateMediationDecorators::default(),
+        );
+        connection
+            .send_aries_message(wallet, &AriesMessage::from(request), transport)
+            .await?;
+
+        self.state = MediationState::Requested { thread_id: id };
+        Ok(())
+    }
+
+    /// Processes a `mediate-grant`, storing the granted endpoint and routing keys.
+    /// Routing keys provided as did:key are converted to base58 verkeys.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if no mediation was requested or the grant is not threaded to the request.
+    pub fn handle_grant(&mut self, grant: MediateGrant) -> VcxResult<()> {
+        let thread_id = self.requested_thread_id()?.to_owned();
+        verify_thread_id(&thread_id, reply_thread_id(&grant.decorators)?)?;
+
+        let routing_keys = normalize_keys_as_naked(grant.content.routing_keys)?;
+        self.state = MediationState::Granted {
+            thread_id,
+            endpoint: grant.content.endpoint,
+            routing_keys,
+        };
+        Ok(())
+    }
+
+    /// Processes a `mediate-deny`.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if no mediation was requested or the denial is not threaded to the request.
+    pub fn handle_deny(&mut self, deny: MediateDeny) -> VcxResult<()> {
+        let thread_id = self.requested_thread_id()?.to_owned();
+        verify_thread_id(&thread_id, reply_thread_id(&deny.decorators)?)?;
+
+        self.state = MediationState::Denied { thread_id };
+        Ok(())
+    }
+
+    /// Asks the mediator to start routing messages sent to `recipient_key`.
+    pub async fn add_recipient_key<I, S, T>(
+        &mut self,
+        wallet: &Arc<dyn BaseWallet>,
+        connection: &Connection<I, S>,
+        recipient_key: &str,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        S: TheirDidDoc,
+        T: Transport,
+    {
+        let update = KeylistUpdateItem::new(recipient_key.to_owned(), KeylistUpdateAction::Add);
+        self.send_keylist_update(wallet, connection, vec![update], transport)
+            .await
+    }
+
+    /// Asks the mediator to stop routing messages sent to `recipient_key`.
+    pub async fn remove_recipient_key<I, S, T>(
+        &mut self,
+        wallet: &Arc<dyn BaseWallet>,
+        connection: &Connection<I, S>,
+        recipient_key: &str,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        S: TheirDidDoc,
+        T: Transport,
+    {
+        let update = KeylistUpdateItem::new(recipient_key.to_owned(), KeylistUpdateAction::Remove);
+        self.send_keylist_update(wallet, connection, vec![update], transport)
+            .await
+    }
+
+    /// Sends a `keylist-update` with the provided updates.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if mediation has not been granted or if sending the message fails.
+    pub async fn send_keylist_update<I, S, T>(
+        &mut self,
+        wallet: &Arc<dyn BaseWallet>,
+        connection: &Connection<I, S>,
+        updates: Vec<KeylistUpdateItem>,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        S: TheirDidDoc,
+        T: Transport,
+    {
+        if !self.is_granted() {
+            return Err(self.not_granted_error());
+        }
+
+        let id = uuid();
+        let update = KeylistUpdate::with_decorators(
+            id.clone(),
+            KeylistUpdateContent::new(updates),
+            CoordinateMediationDecorators::default(),
+        );
+        connection
+            .send_aries_message(wallet, &AriesMessage::from(update), transport)
+            .await?;
+
+        self.pending_updates.push(id);
+        Ok(())
+    }
+
+    /// Processes a `keylist-update-response`, keeping track of the keys the mediator
+    /// successfully added or removed.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the response does not answer a pending `keylist-update`.
+    pub fn handle_keylist_update_response(&mut self, response: KeylistUpdateResponse) -> VcxResult<()> {
+        let thread_id = reply_thread_id(&response.decorators)?;
+        let pos = self
+            .pending_updates
+            .iter()
+            .position(|id| id == thread_id)
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    format!("Received keylist-update-response for unknown thread: {}", thread_id),
+                )
+            })?;
+        self.pending_updates.remove(pos);
+
+        for item in response.content.updated {
+            match item.result {
+                KeylistUpdateResult::Success | KeylistUpdateResult::NoChange => {}
+                KeylistUpdateResult::ClientError | KeylistUpdateResult::ServerError => {
+                    warn!(
+                        "Mediator failed to {:?} recipient key {}: {:?}",
+                        item.action, item.recipient_key, item.result
+                    );
+                    continue;
+                }
+            }
+            self.recipient_keys.retain(|key| key != &item.recipient_key);
+            if item.action == KeylistUpdateAction::Add {
+                self.recipient_keys.push(item.recipient_key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends a `keylist-query` to retrieve the keys the mediator routes for us.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if mediation has not been granted or if sending the message fails.
+    pub async fn send_keylist_query<I, S, T>(
+        &self,
+        wallet: &Arc<dyn BaseWallet>,
+        connection: &Connection<I, S>,
+        paginate: Option<KeylistQueryPaginate>,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        S: TheirDidDoc,
+        T: Transport,
+    {
+        if !self.is_granted() {
+            return Err(self.not_granted_error());
+        }
+
+        let mut content = KeylistQueryContent::default();
+        content.paginate = paginate;
+        let query = KeylistQuery::with_decorators(uuid(), content, CoordinateMediationDecorators::default());
+        connection
+            .send_aries_message(wallet, &AriesMessage::from(query), transport)
+            .await
+    }
+
+    /// Processes a `keylist`, synchronizing the known recipient keys with the mediator's.
+    /// Paginated keylists starting at a non-zero offset extend the known keys.
+    pub fn handle_keylist(&mut self, keylist: Keylist) -> VcxResult<()> {
+        let keys = keylist.content.keys.into_iter().map(|item| item.recipient_key);
+        match keylist.content.pagination {
+            Some(pagination) if pagination.offset > 0 => {
+                for key in keys {
+                    if !self.recipient_keys.contains(&key) {
+                        self.recipient_keys.push(key);
+                    }
+                }
+            }
+            _ => self.recipient_keys = keys.collect(),
+        }
+        Ok(())
+    }
+
+    /// Processes any coordinate mediation message addressed to the recipient.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the message is only meant for mediators.
+    pub fn handle_message(&mut self, message: CoordinateMediation) -> VcxResult<()> {
+        match message {
+            CoordinateMediation::MediateGrant(grant) => self.handle_grant(grant),
+            CoordinateMediation::MediateDeny(deny) => self.handle_deny(deny),
+            CoordinateMediation::KeylistUpdateResponse(response) => self.handle_keylist_update_response(response),
+            CoordinateMediation::Keylist(keylist) => self.handle_keylist(keylist),
+            message => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!("Mediation recipient cannot handle message: {:?}", message),
+            )),
+        }
+    }
+
+    fn requested_thread_id(&self) -> VcxResult<&str> {
+        match &self.state {
+            MediationState::Requested { thread_id } => Ok(thread_id),
+            state => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!("Mediation was not requested, current state: {:?}", state),
+            )),
+        }
+    }
+
+    fn not_granted_error(&self) -> AriesVcxError {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidState,
+            format!("Mediation has not been granted, current state: {:?}", self.state),
+        )
+    }
+}
+
+fn reply_thread_id(decorators: &CoordinateMediationDecorators) -> VcxResult<&str> {
+    decorators
+        .thread
+        .as_ref()
+        .map(|thread| thread.thid.as_str())
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                "Coordinate mediation reply is missing its ~thread decorator",
+            )
+        })
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use messages2::{
+        decorators::thread::Thread,
+        msg_fields::protocols::coordinate_mediation::{
+            keylist_update_response::{KeylistUpdateResponseContent, KeylistUpdateResponseItem},
+            mediate_deny::MediateDenyContent,
+            mediate_grant::MediateGrantContent,
+        },
+    };
+
+    use super::*;
+    use crate::{
+        common::{
+            did_resolver::key::ed25519_public_key_to_did_key,
+            test_utils::{indy_handles_to_profile, CapturingTransport, CompletedConnection},
+        },
+        indy::utils::test_setup::with_wallet,
+        protocols::connection::{initiation_type::Invitee, invitee::states::completed::Completed},
+        utils::devsetup::SetupEmpty,
+    };
+
+    const MEDIATOR_ENDPOINT: &str = "https://mediator.example.org";
+
+    async fn take_mediation_message(
+        transport: &CapturingTransport,
+        wallet: &Arc<dyn BaseWallet>,
+    ) -> CoordinateMediation {
+        match transport.take_message(wallet).await {
+            AriesMessage::CoordinateMediation(msg) => msg,
+            msg => panic!("expected a coordinate mediation message, got {:?}", msg),
+        }
+    }
+
+    fn reply_decorators(thread_id: &str) -> CoordinateMediationDecorators {
+        let mut decorators = CoordinateMediationDecorators::default();
+        decorators.thread = Some(Thread::new(thread_id.to_owned()));
+        decorators
+    }
+
+    async fn granted_recipient(
+        wallet: &Arc<dyn BaseWallet>,
+        connection: &Connection<Invitee, Completed>,
+        transport: &CapturingTransport,
+        routing_keys: Vec<String>,
+    ) -> MediationRecipient {
+        let mut recipient = MediationRecipient::new();
+        recipient.send_request(wallet, connection, transport).await.unwrap();
+
+        let CoordinateMediation::MediateRequest(request) = take_mediation_message(transport, wallet).await else {
+            panic!("expected a mediate-request");
+        };
+        let content = MediateGrantContent::new(MEDIATOR_ENDPOINT.to_owned(), routing_keys);
+        let grant = MediateGrant::with_decorators(uuid(), content, reply_decorators(&request.id));
+        recipient.handle_grant(grant).unwrap();
+        recipient
+    }
+
+    #[tokio::test]
+    async fn test_mediation_grant_normalizes_routing_keys() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let wallet = indy_handles_to_profile(wallet_handle, 0).inject_wallet();
+            let transport = CapturingTransport::default();
+            let connection = CompletedConnection::new(&wallet, MEDIATOR_ENDPOINT).await.connection;
+
+            let (_, routing_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let did_key = ed25519_public_key_to_did_key(&routing_key).unwrap();
+            let recipient = granted_recipient(&wallet, &connection, &transport, vec![did_key]).await;
+
+            assert!(recipient.is_granted());
+            assert_eq!(recipient.service_endpoint().unwrap(), MEDIATOR_ENDPOINT);
+            assert_eq!(recipient.routing_keys().unwrap(), &[routing_key]);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_mediation_rejects_reply_on_other_thread() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let wallet = indy_handles_to_profile(wallet_handle, 0).inject_wallet();
+            let transport = CapturingTransport::default();
+            let connection = CompletedConnection::new(&wallet, MEDIATOR_ENDPOINT).await.connection;
+
+            let mut recipient = MediationRecipient::new();
+            recipient.send_request(&wallet, &connection, &transport).await.unwrap();
+
+            let content = MediateGrantContent::new(MEDIATOR_ENDPOINT.to_owned(), vec![]);
+            let grant = MediateGrant::with_decorators(uuid(), content, reply_decorators("other"));
+            assert!(recipient.handle_grant(grant).is_err());
+            assert!(recipient.service_endpoint().is_err());
+
+            let deny = MediateDeny::with_decorators(
+                uuid(),
+                MediateDenyContent::default(),
+                reply_decorators(recipient.requested_thread_id().unwrap()),
+            );
+            recipient.handle_deny(deny).unwrap();
+            assert!(matches!(recipient.state(), MediationState::Denied { .. }));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_mediation_keylist_update() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let wallet = indy_handles_to_profile(wallet_handle, 0).inject_wallet();
+            let transport = CapturingTransport::default();
+            let connection = CompletedConnection::new(&wallet, MEDIATOR_ENDPOINT).await.connection;
+
+            let mut recipient = MediationRecipient::new();
+            assert!(recipient
+                .add_recipient_key(&wallet, &connection, "key_1", &transport)
+                .await
+                .is_err());
+
+            let mut recipient = granted_recipient(&wallet, &connection, &transport, vec![]).await;
+            recipient
+                .add_recipient_key(&wallet, &connection, "key_1", &transport)
+                .await
+                .unwrap();
+
+            let CoordinateMediation::KeylistUpdate(update) = take_mediation_message(&transport, &wallet).await else {
+                panic!("expected a keylist-update");
+            };
+            assert_eq!(update.content.updates[0].recipient_key, "key_1");
+            assert_eq!(update.content.updates[0].action, KeylistUpdateAction::Add);
+
+            let content = KeylistUpdateResponseContent::new(vec![KeylistUpdateResponseItem::new(
+                "key_1".to_owned(),
+                KeylistUpdateAction::Add,
+                KeylistUpdateResult::Success,
+            )]);
+            let response =
+                KeylistUpdateResponse::with_decorators(uuid(), content.clone(), reply_decorators(&update.id));
+            recipient.handle_keylist_update_response(response).unwrap();
+            assert_eq!(recipient.recipient_keys(), &["key_1".to_owned()]);
+
+            // The update is no longer pending, so the same response is rejected.
+            let response = KeylistUpdateResponse::with_decorators(uuid(), content, reply_decorators(&update.id));
+            assert!(recipient.handle_keylist_update_response(response).is_err());
+        })
+        .await;
+    }
+}
@@ -0,0 +1,615 @@
+pub mod requester;
+pub mod responder;
+pub mod states;
+
+use std::{error::Error, sync::Arc};
+
+use messages::{
+    a2a::A2AMessage,
+    diddoc::{aries::diddoc::AriesDidDoc, peer::is_peer_did},
+};
+use messages2::{
+    decorators::{
+        attachment::{Attachment, AttachmentData, AttachmentType},
+        thread::Thread,
+    },
+    misc::MimeType,
+    msg_fields::protocols::{
+        did_exchange::problem_report::{ProblemCode, ProblemReport, ProblemReportContent, ProblemReportDecorators},
+        out_of_band::invitation::{Invitation, OobService},
+    },
+    msg_types::Protocol,
+    AriesMessage,
+};
+
+use crate::{
+    common::{
+        did_resolver::{
+            did_method, key::normalize_keys_as_naked, method_specific_id, peer::DidPeerResolver, DidResolverRegistry,
+            UNQUALIFIED_DID_METHOD,
+        },
+        signing::{sign_attachment, verify_signed_attachment},
+    },
+    core::profile::profile::Profile,
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    plugins::wallet::base_wallet::BaseWallet,
+    protocols::connection::{
+        pairwise_info::PairwiseInfo,
+        trait_bounds::{TheirDidDoc, ThreadId},
+        wrap_and_send_msg,
+    },
+    transport::Transport,
+    utils::uuid::uuid,
+};
+
+/// Unit struct illustrating that the DID exchange is driven by the party that received the invitation.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Requester;
+
+/// Unit struct illustrating that the DID exchange is driven by the party that issued the invitation.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Responder;
+
+/// A state machine for progressing through the [DID exchange protocol](https://github.com/hyperledger/aries-rfcs/blob/main/features/0023-did-exchange/README.md).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DidExchange<I, S> {
+    pairwise_info: PairwiseInfo,
+    role: I,
+    state: S,
+}
+
+impl<I, S> DidExchange<I, S> {
+    pub fn from_parts(pairwise_info: PairwiseInfo, role: I, state: S) -> Self {
+        Self {
+            pairwise_info,
+            role,
+            state,
+        }
+    }
+
+    pub fn into_parts(self) -> (PairwiseInfo, I, S) {
+        let Self {
+            pairwise_info,
+            role,
+            state,
+        } = self;
+        (pairwise_info, role, state)
+    }
+
+    pub fn pairwise_info(&self) -> &PairwiseInfo {
+        &self.pairwise_info
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<I, S> DidExchange<I, S>
+where
+    S: ThreadId,
+{
+    pub fn thread_id(&self) -> &str {
+        self.state.thread_id()
+    }
+}
+
+impl<I, S> DidExchange<I, S>
+where
+    S: TheirDidDoc,
+{
+    pub fn their_did_doc(&self) -> &AriesDidDoc {
+        self.state.their_did_doc()
+    }
+
+    pub async fn send_message<T>(
+        &self,
+        wallet: &Arc<dyn BaseWallet>,
+        message: &AriesMessage,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        T: Transport,
+    {
+        send_aries_message(
+            wallet,
+            message,
+            &self.pairwise_info.pw_vk,
+            self.their_did_doc(),
+            transport,
+        )
+        .await
+    }
+}
+
+impl<I, S> DidExchange<I, S> {
+    async fn send_problem_report<E, T>(
+        &self,
+        wallet: &Arc<dyn BaseWallet>,
+        err: &E,
+        problem_code: ProblemCode,
+        thread_id: &str,
+        did_doc: &AriesDidDoc,
+        transport: &T,
+    ) where
+        E: Error,
+        T: Transport,
+    {
+        let content = ProblemReportContent {
+            problem_code: Some(problem_code),
+            explain: Some(err.to_string()),
+        };
+
+        let decorators = ProblemReportDecorators::new(Thread::new(thread_id.to_owned()));
+        let problem_report = ProblemReport::with_decorators(uuid(), content, decorators);
+
+        let res = send_aries_message(
+            wallet,
+            &problem_report.into(),
+            &self.pairwise_info.pw_vk,
+            did_doc,
+            transport,
+        )
+        .await;
+
+        if let Err(e) = res {
+            trace!("Error encountered when sending ProblemReport: {}", e);
+        } else {
+            info!("Error report sent!");
+        }
+    }
+}
+
+/// Returns `true` if the invitation lists DID exchange among its handshake protocols.
+pub fn invitation_accepts_did_exchange(invitation: &Invitation) -> bool {
+    invitation
+        .content
+        .handshake_protocols
+        .iter()
+        .flatten()
+        .any(|protocol| matches!(protocol, Protocol::DidExchangeType(_)))
+}
+
+/// Builds the [`AriesDidDoc`] the inviter can be reached at, based on the
+/// first service of the invitation.
+pub(crate) async fn invitation_did_doc(profile: &Arc<dyn Profile>, invitation: &Invitation) -> VcxResult<AriesDidDoc> {
+    let service = match invitation.content.services.first() {
+        Some(OobService::AriesService(service)) => service.clone(),
+        Some(OobService::Did(did)) => Arc::clone(profile).inject_did_resolver().resolve_service(did).await?,
+        None => {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                "Out of band invitation does not contain any services",
+            ))
+        }
+    };
+
+    let mut did_doc = AriesDidDoc::default();
+    did_doc.set_id(invitation.id.clone());
+    did_doc.set_service_endpoint(service.service_endpoint);
+    did_doc.set_recipient_keys(normalize_keys_as_naked(service.recipient_keys)?);
+    did_doc.set_routing_keys(normalize_keys_as_naked(service.routing_keys)?);
+    Ok(did_doc)
+}
+
+pub(crate) fn build_our_did_doc(
+    pairwise_info: &PairwiseInfo,
+    service_endpoint: String,
+    routing_keys: Vec<String>,
+) -> AriesDidDoc {
+    let mut did_doc = AriesDidDoc::default();
+    did_doc.set_id(pairwise_info.pw_did.clone());
+    did_doc.set_service_endpoint(service_endpoint);
+    did_doc.set_recipient_keys(vec![pairwise_info.pw_vk.clone()]);
+    did_doc.set_routing_keys(routing_keys);
+    did_doc
+}
+
+/// Expands the did:peer the other party identified itself with into an [`AriesDidDoc`],
+/// for requests and responses which do not carry a DidDoc attachment.
+pub(crate) async fn peer_did_doc(did: &str) -> VcxResult<AriesDidDoc> {
+    if !is_peer_did(did) {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidDid,
+            format!("{} is not a did:peer", did),
+        ));
+    }
+    DidResolverRegistry::new()
+        .register_resolver("peer", Arc::new(DidPeerResolver))
+        .resolve_aries_did_doc(did)
+        .await
+}
+
+/// Serializes the DID Document into an attachment signed with the provided key.
+pub(crate) async fn signed_did_doc_attachment(
+    wallet: &Arc<dyn BaseWallet>,
+    key: &str,
+    did_doc: &AriesDidDoc,
+) -> VcxResult<Attachment> {
+    let content = AttachmentType::Base64(serde_json::to_vec(did_doc)?);
+    let mut attachment = Attachment::new(AttachmentData::new(content));
+    attachment.id = Some(uuid());
+    attachment.mime_type = Some(MimeType::Json);
+
+    sign_attachment(wallet, key, &mut attachment).await?;
+    Ok(attachment)
+}
+
+/// Verifies the signature of a DID Document attachment against the provided key
+/// and deserializes the signed document.
+pub(crate) async fn verify_did_doc_attachment(
+    wallet: &Arc<dyn BaseWallet>,
+    key: &str,
+    attachment: &Attachment,
+) -> VcxResult<AriesDidDoc> {
+    let payload = verify_signed_attachment(wallet, key, attachment).await?;
+    decode_did_doc(&payload)
+}
+
+pub(crate) fn decode_did_doc(payload: &[u8]) -> VcxResult<AriesDidDoc> {
+    serde_json::from_slice(payload).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("Cannot deserialize attached DID Document: {}", err),
+        )
+    })
+}
+
+/// Returns the key a DidDoc attachment is signed with, provided that it is bound to `did`
+/// independently of the attached document: listed in the document a did:peer expands to, or
+/// the key an unqualified (or did:sov) DID is derived from.
+pub(crate) async fn did_bound_signing_key(did: &str, attachment: &Attachment) -> VcxResult<String> {
+    let kid = attachment
+        .data
+        .jws
+        .as_ref()
+        .and_then(|jws| jws.header.as_ref())
+        .map(|header| header.kid.as_str())
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                "Cannot handle DidDoc attachment: attachment is not signed",
+            )
+        })?;
+    let key = normalize_keys_as_naked(vec![kid.to_string()])?.remove(0);
+
+    let bound = if is_peer_did(did) {
+        peer_did_doc(did).await?.recipient_keys()?.contains(&key)
+    } else if matches!(did_method(did), None | Some(UNQUALIFIED_DID_METHOD)) {
+        let key_bytes = bs58::decode(&key).into_vec().map_err(|_| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidVerkey,
+                format!("Could not base58 decode verkey: {}", key),
+            )
+        })?;
+        key_bytes.len() >= 16 && bs58::encode(&key_bytes[..16]).into_string() == method_specific_id(did)
+    } else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::ActionNotSupported,
+            format!("Cannot verify a DidDoc attachment signed on behalf of {}", did),
+        ));
+    };
+    if !bound {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("DidDoc attachment is signed by {}, which is not a key of {}", key, did),
+        ));
+    }
+    Ok(key)
+}
+
+pub(crate) fn verify_thread_id(expected: &str, received: &str) -> VcxResult<()> {
+    if expected != received {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidState,
+            format!(
+                "Cannot handle message: thread id does not match, expected {:?}, received {:?}",
+                expected, received
+            ),
+        ));
+    }
+    Ok(())
+}
+
+async fn send_aries_message<T>(
+    wallet: &Arc<dyn BaseWallet>,
+    message: &AriesMessage,
+    sender_verkey: &str,
+    did_doc: &AriesDidDoc,
+    transport: &T,
+) -> VcxResult<()>
+where
+    T: Transport,
+{
+    let message = A2AMessage::Generic(serde_json::to_value(message)?);
+    wrap_and_send_msg(wallet, &message, sender_verkey, did_doc, transport).await
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use messages::diddoc::aries::service::AriesService;
+    use messages2::{
+        msg_fields::protocols::{
+            did_exchange::DidExchange as DidExchangeMsg,
+            out_of_band::invitation::{InvitationContent, InvitationDecorators},
+        },
+        msg_types::did_exchange::DidExchangeTypeV1,
+    };
+
+    use super::{requester::RequesterDidExchange, responder::ResponderDidExchange, *};
+    use crate::{
+        common::test_utils::{indy_handles_to_profile, CapturingTransport},
+        indy::utils::test_setup::with_wallet,
+        utils::devsetup::SetupEmpty,
+    };
+
+    const SERVICE_ENDPOINT: &str = "https://localhost:8080";
+
+    fn make_invitation(invitation_key: &str, handshake_protocols: Option<Vec<Protocol>>) -> Invitation {
+        let service = AriesService::create()
+            .set_service_endpoint(SERVICE_ENDPOINT.to_owned())
+            .set_recipient_keys(vec![invitation_key.to_owned()]);
+
+        let mut content = InvitationContent::new(vec![OobService::AriesService(service)], vec![]);
+        content.handshake_protocols = handshake_protocols;
+
+        Invitation::with_decorators(uuid(), content, InvitationDecorators::default())
+    }
+
+    #[tokio::test]
+    async fn test_did_exchange_from_oob_invitation() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let profile = indy_handles_to_profile(wallet_handle, 0);
+            let wallet = profile.inject_wallet();
+            let transport = CapturingTransport::default();
+
+            let (_, invitation_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let invitation = make_invitation(&invitation_key, Some(vec![DidExchangeTypeV1::new_v1_0().into()]));
+
+            let requester_pw = PairwiseInfo::create(&wallet).await.unwrap();
+            let responder_pw = PairwiseInfo::create(&wallet).await.unwrap();
+
+            let requester = RequesterDidExchange::send_request(
+                &profile,
+                &invitation,
+                requester_pw.clone(),
+                "requester".to_owned(),
+                SERVICE_ENDPOINT.to_owned(),
+                vec![],
+                &transport,
+            )
+            .await
+            .unwrap();
+
+            let AriesMessage::DidExchange(DidExchangeMsg::Request(request)) = transport.take_message(&wallet).await
+            else {
+                panic!("expected a DID exchange request");
+            };
+            assert_eq!(
+                request.decorators.thread.as_ref().unwrap().pthid,
+                Some(invitation.id.clone())
+            );
+
+            let responder = ResponderDidExchange::receive_request(
+                &wallet,
+                request,
+                responder_pw.clone(),
+                &invitation.id,
+                invitation_key,
+            )
+            .await
+            .unwrap();
+            assert_eq!(responder.their_did_doc().id, requester_pw.pw_did);
+
+            let responder = responder
+                .send_response(&wallet, SERVICE_ENDPOINT.to_owned(), vec![], &transport)
+                .await
+                .unwrap();
+
+            let AriesMessage::DidExchange(DidExchangeMsg::Response(response)) = transport.take_message(&wallet).await
+            else {
+                panic!("expected a DID exchange response");
+            };
+
+            let requester = requester.handle_response(&wallet, response, &transport).await.unwrap();
+            assert_eq!(requester.their_did_doc().id, responder_pw.pw_did);
+
+            let requester = requester.send_complete(&wallet, &transport).await.unwrap();
+
+            let AriesMessage::DidExchange(DidExchangeMsg::Complete(complete)) = transport.take_message(&wallet).await
+            else {
+                panic!("expected a DID exchange complete message");
+            };
+
+            let responder = responder.handle_complete(complete).unwrap();
+            assert_eq!(requester.thread_id(), responder.thread_id());
+
+            let connection = requester.into_connection("requester".to_owned());
+            assert_eq!(connection.remote_did(), responder_pw.pw_did);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_did_exchange_rejects_invitation_without_handshake() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let profile = indy_handles_to_profile(wallet_handle, 0);
+            let wallet = profile.inject_wallet();
+            let transport = CapturingTransport::default();
+
+            let (_, invitation_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let invitation = make_invitation(&invitation_key, None);
+            let pairwise_info = PairwiseInfo::create(&wallet).await.unwrap();
+
+            let err = RequesterDidExchange::send_request(
+                &profile,
+                &invitation,
+                pairwise_info,
+                "requester".to_owned(),
+                SERVICE_ENDPOINT.to_owned(),
+                vec![],
+                &transport,
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(err.kind(), AriesVcxErrorKind::ActionNotSupported);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_did_exchange_accepts_peer_did_without_did_doc() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let profile = indy_handles_to_profile(wallet_handle, 0);
+            let wallet = profile.inject_wallet();
+            let transport = CapturingTransport::default();
+
+            let (_, invitation_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let invitation = make_invitation(&invitation_key, Some(vec![DidExchangeTypeV1::new_v1_0().into()]));
+
+            let requester_pw = PairwiseInfo::create_with_peer_did(&wallet, SERVICE_ENDPOINT, &[])
+                .await
+                .unwrap();
+            let responder_pw = PairwiseInfo::create(&wallet).await.unwrap();
+            assert!(requester_pw.is_peer_did());
+
+            RequesterDidExchange::send_request(
+                &profile,
+                &invitation,
+                requester_pw.clone(),
+                "requester".to_owned(),
+                SERVICE_ENDPOINT.to_owned(),
+                vec![],
+                &transport,
+            )
+            .await
+            .unwrap();
+
+            let AriesMessage::DidExchange(DidExchangeMsg::Request(mut request)) = transport.take_message(&wallet).await
+            else {
+                panic!("expected a DID exchange request");
+            };
+            assert_eq!(request.content.did, requester_pw.pw_did);
+            request.content.did_doc = None;
+
+            let responder =
+                ResponderDidExchange::receive_request(&wallet, request, responder_pw, &invitation.id, invitation_key)
+                    .await
+                    .unwrap();
+            assert_eq!(responder.their_did_doc().id, requester_pw.pw_did);
+            assert_eq!(responder.their_did_doc().get_endpoint(), SERVICE_ENDPOINT);
+            assert_eq!(
+                responder.their_did_doc().recipient_keys().unwrap(),
+                vec![requester_pw.pw_vk]
+            );
+        })
+        .await;
+    }
+
+    async fn _receive_request(
+        profile: &Arc<dyn Profile>,
+        transport: &CapturingTransport,
+        invitation: &Invitation,
+        requester_pw: &PairwiseInfo,
+    ) -> messages2::msg_fields::protocols::did_exchange::request::Request {
+        let wallet = profile.inject_wallet();
+        RequesterDidExchange::send_request(
+            profile,
+            invitation,
+            requester_pw.clone(),
+            "requester".to_owned(),
+            SERVICE_ENDPOINT.to_owned(),
+            vec![],
+            transport,
+        )
+        .await
+        .unwrap();
+        let AriesMessage::DidExchange(DidExchangeMsg::Request(request)) = transport.take_message(&wallet).await else {
+            panic!("expected a DID exchange request");
+        };
+        request
+    }
+
+    #[tokio::test]
+    async fn test_did_exchange_rejects_request_not_answering_invitation() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let profile = indy_handles_to_profile(wallet_handle, 0);
+            let wallet = profile.inject_wallet();
+            let transport = CapturingTransport::default();
+
+            let (_, invitation_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let invitation = make_invitation(&invitation_key, Some(vec![DidExchangeTypeV1::new_v1_0().into()]));
+            let requester_pw = PairwiseInfo::create(&wallet).await.unwrap();
+            let responder_pw = PairwiseInfo::create(&wallet).await.unwrap();
+            let request = _receive_request(&profile, &transport, &invitation, &requester_pw).await;
+
+            let err = ResponderDidExchange::receive_request(
+                &wallet,
+                request.clone(),
+                responder_pw.clone(),
+                "other_invitation_id",
+                invitation_key.clone(),
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(err.kind(), AriesVcxErrorKind::InvalidState);
+
+            let mut request = request;
+            request.decorators.thread.as_mut().unwrap().pthid = None;
+            let err =
+                ResponderDidExchange::receive_request(&wallet, request, responder_pw, &invitation.id, invitation_key)
+                    .await
+                    .unwrap_err();
+            assert_eq!(err.kind(), AriesVcxErrorKind::InvalidState);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_did_exchange_requires_did_doc_signed_by_key_of_did() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let profile = indy_handles_to_profile(wallet_handle, 0);
+            let wallet = profile.inject_wallet();
+            let transport = CapturingTransport::default();
+
+            let (_, invitation_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let invitation = make_invitation(&invitation_key, Some(vec![DidExchangeTypeV1::new_v1_0().into()]));
+            let requester_pw = PairwiseInfo::create(&wallet).await.unwrap();
+            let responder_pw = PairwiseInfo::create(&wallet).await.unwrap();
+            let request = _receive_request(&profile, &transport, &invitation, &requester_pw).await;
+
+            // Unsigned DidDoc
+            let mut unsigned = request.clone();
+            unsigned.content.did_doc.as_mut().unwrap().data.jws = None;
+            let err = ResponderDidExchange::receive_request(
+                &wallet,
+                unsigned,
+                responder_pw.clone(),
+                &invitation.id,
+                invitation_key.clone(),
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(err.kind(), AriesVcxErrorKind::InvalidJson);
+
+            // DidDoc listing the key it is signed with, which is not the key of the DID
+            let (_, other_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let mut did_doc = build_our_did_doc(&requester_pw, SERVICE_ENDPOINT.to_owned(), vec![]);
+            did_doc.set_recipient_keys(vec![other_vk.clone()]);
+            let mut forged = request;
+            forged.content.did_doc = Some(signed_did_doc_attachment(&wallet, &other_vk, &did_doc).await.unwrap());
+            let err =
+                ResponderDidExchange::receive_request(&wallet, forged, responder_pw, &invitation.id, invitation_key)
+                    .await
+                    .unwrap_err();
+            assert_eq!(err.kind(), AriesVcxErrorKind::InvalidJson);
+        })
+        .await;
+    }
+}
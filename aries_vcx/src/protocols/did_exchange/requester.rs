@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use messages::diddoc::aries::diddoc::AriesDidDoc;
+use messages2::{
+    decorators::thread::Thread,
+    msg_fields::protocols::{
+        did_exchange::{
+            complete::{Complete, CompleteContent, CompleteDecorators},
+            problem_report::ProblemCode,
+            request::{Request, RequestContent, RequestDecorators},
+            response::Response,
+        },
+        out_of_band::invitation::Invitation,
+    },
+};
+
+use super::{
+    build_our_did_doc, invitation_accepts_did_exchange, invitation_did_doc, peer_did_doc, send_aries_message,
+    signed_did_doc_attachment,
+    states::{completed::Completed, request_sent::RequestSent, response_received::ResponseReceived},
+    verify_did_doc_attachment, verify_thread_id, DidExchange, Requester,
+};
+use crate::{
+    core::profile::profile::Profile,
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    plugins::wallet::base_wallet::BaseWallet,
+    protocols::connection::{
+        initiation_type::Invitee,
+        invitee::{states::completed::Completed as InviteeCompleted, InviteeConnection},
+        pairwise_info::PairwiseInfo,
+        trait_bounds::BootstrapDidDoc,
+        Connection,
+    },
+    transport::Transport,
+    utils::uuid::uuid,
+};
+
+/// Convenience alias
+pub type RequesterDidExchange<S> = DidExchange<Requester, S>;
+
+impl RequesterDidExchange<RequestSent> {
+    /// Answers an out-of-band [`Invitation`] offering DID exchange as a handshake protocol by
+    /// sending a [`Request`] and transitions to [`RequesterDidExchange<RequestSent>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if:
+    ///     * the invitation does not list DID exchange in its handshake protocols
+    ///     * a DidDoc could not be resolved from the invitation services
+    ///     * sending the request fails
+    pub async fn send_request<T>(
+        profile: &Arc<dyn Profile>,
+        invitation: &Invitation,
+        pairwise_info: PairwiseInfo,
+        label: String,
+        service_endpoint: String,
+        routing_keys: Vec<String>,
+        transport: &T,
+    ) -> VcxResult<Self>
+    where
+        T: Transport,
+    {
+        trace!("DidExchange::send_request >>> invitation: {:?}", invitation);
+
+        if !invitation_accepts_did_exchange(invitation) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::ActionNotSupported,
+                "Out of band invitation does not offer DID exchange as a handshake protocol",
+            ));
+        }
+
+        let wallet = profile.inject_wallet();
+        let bootstrap_did_doc = invitation_did_doc(profile, invitation).await?;
+        let our_did_doc = build_our_did_doc(&pairwise_info, service_endpoint, routing_keys);
+
+        // The request ID doubles as the thread ID, while the invitation ID is used
+        // as the parent thread ID so that the responder can correlate the request
+        // with the invitation it sent out.
+        let thread_id = uuid();
+        let mut thread = Thread::new(thread_id.clone());
+        thread.pthid = Some(invitation.id.clone());
+
+        let mut content = RequestContent::new(label, pairwise_info.pw_did.clone());
+        content.did_doc = Some(signed_did_doc_attachment(&wallet, &pairwise_info.pw_vk, &our_did_doc).await?);
+
+        let decorators = RequestDecorators {
+            thread: Some(thread),
+            timing: None,
+        };
+
+        let request = Request::with_decorators(thread_id.clone(), content, decorators);
+        send_aries_message(
+            &wallet,
+            &request.into(),
+            &pairwise_info.pw_vk,
+            &bootstrap_did_doc,
+            transport,
+        )
+        .await?;
+
+        Ok(DidExchange {
+            state: RequestSent::new(invitation.id.clone(), thread_id, bootstrap_did_doc),
+            pairwise_info,
+            role: Requester,
+        })
+    }
+
+    /// Processes a [`Response`] from the responder and transitions to [`RequesterDidExchange<ResponseReceived>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if:
+    ///     * the thread ID of the response does not match the exchange thread ID
+    ///     * the response carries neither a DidDoc attachment nor a did:peer
+    ///     * the attachment is not signed by the key the invitation was sent with
+    pub async fn handle_response<T>(
+        self,
+        wallet: &Arc<dyn BaseWallet>,
+        response: Response,
+        transport: &T,
+    ) -> VcxResult<RequesterDidExchange<ResponseReceived>>
+    where
+        T: Transport,
+    {
+        verify_thread_id(self.thread_id(), &response.decorators.thread.thid)?;
+
+        let invitation_key =
+            self.state
+                .bootstrap_did_doc
+                .recipient_keys()?
+                .first()
+                .cloned()
+                .ok_or(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Cannot handle response: invitation verkey not found",
+                ))?;
+
+        let did_doc = match &response.content.did_doc {
+            Some(attachment) => verify_did_doc_attachment(wallet, &invitation_key, attachment).await,
+            None => peer_did_doc(&response.content.did).await.map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidJson,
+                    format!("Cannot handle response: DidDoc attachment is missing, {}", err),
+                )
+            }),
+        };
+
+        let did_doc = match did_doc {
+            Ok(did_doc) => did_doc,
+            Err(err) => {
+                error!("Response DidDoc validation failed! Sending ProblemReport...");
+
+                self.send_problem_report(
+                    wallet,
+                    &err,
+                    ProblemCode::ResponseNotAccepted,
+                    self.thread_id(),
+                    &self.state.bootstrap_did_doc,
+                    transport,
+                )
+                .await;
+
+                return Err(err);
+            }
+        };
+
+        let RequestSent {
+            invitation_id,
+            thread_id,
+            bootstrap_did_doc,
+        } = self.state;
+
+        Ok(DidExchange {
+            state: ResponseReceived::new(invitation_id, thread_id, did_doc, bootstrap_did_doc),
+            pairwise_info: self.pairwise_info,
+            role: Requester,
+        })
+    }
+}
+
+impl RequesterDidExchange<ResponseReceived> {
+    /// Sends a [`Complete`] message to the responder and transitions to [`RequesterDidExchange<Completed>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if sending the message fails.
+    pub async fn send_complete<T>(
+        self,
+        wallet: &Arc<dyn BaseWallet>,
+        transport: &T,
+    ) -> VcxResult<RequesterDidExchange<Completed>>
+    where
+        T: Transport,
+    {
+        let mut thread = Thread::new(self.state.thread_id.clone());
+        thread.pthid = Some(self.state.invitation_id.clone());
+
+        let complete = Complete::with_decorators(uuid(), CompleteContent::default(), CompleteDecorators::new(thread));
+        self.send_message(wallet, &complete.into(), transport).await?;
+
+        let ResponseReceived {
+            thread_id,
+            did_doc,
+            bootstrap_did_doc,
+            ..
+        } = self.state;
+
+        Ok(DidExchange {
+            state: Completed::new(thread_id, did_doc, Some(bootstrap_did_doc)),
+            pairwise_info: self.pairwise_info,
+            role: Requester,
+        })
+    }
+}
+
+impl RequesterDidExchange<Completed> {
+    /// Converts the finished exchange into a completed [`InviteeConnection`], so that the
+    /// established relationship can be used anywhere a connection is expected.
+    pub fn into_connection(self, source_id: String) -> InviteeConnection<InviteeCompleted> {
+        let Completed {
+            thread_id,
+            did_doc,
+            bootstrap_did_doc,
+        } = self.state;
+        let bootstrap_did_doc = bootstrap_did_doc.unwrap_or_else(|| did_doc.clone());
+        let state = InviteeCompleted::new(did_doc, bootstrap_did_doc, thread_id, None);
+
+        Connection::from_parts(source_id, self.pairwise_info, Invitee, state)
+    }
+}
+
+impl<S> RequesterDidExchange<S>
+where
+    S: BootstrapDidDoc,
+{
+    pub fn bootstrap_did_doc(&self) -> &AriesDidDoc {
+        self.state.bootstrap_did_doc()
+    }
+}
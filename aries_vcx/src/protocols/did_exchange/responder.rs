@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use messages2::{
+    decorators::thread::Thread,
+    msg_fields::protocols::did_exchange::{
+        complete::Complete,
+        problem_report::ProblemCode,
+        request::Request,
+        response::{Response, ResponseContent, ResponseDecorators},
+    },
+};
+
+use super::{
+    build_our_did_doc, did_bound_signing_key, peer_did_doc, signed_did_doc_attachment,
+    states::{completed::Completed, request_received::RequestReceived, response_sent::ResponseSent},
+    verify_did_doc_attachment, verify_thread_id, DidExchange, Responder,
+};
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    plugins::wallet::base_wallet::BaseWallet,
+    protocols::connection::{
+        initiation_type::Inviter,
+        inviter::{states::completed::Completed as InviterCompleted, InviterConnection},
+        pairwise_info::PairwiseInfo,
+        Connection,
+    },
+    transport::Transport,
+    utils::uuid::uuid,
+};
+
+/// Convenience alias
+pub type ResponderDidExchange<S> = DidExchange<Responder, S>;
+
+impl ResponderDidExchange<RequestReceived> {
+    /// Processes a [`Request`] sent in reply to one of our invitations and
+    /// transitions to [`ResponderDidExchange<RequestReceived>`].
+    ///
+    /// The `invitation_id` is the ID of the invitation the request answers, which the request
+    /// must reference as its parent thread. The `invitation_key` is the recipient key of that
+    /// invitation and is later used to sign our DidDoc, proving to the requester that the
+    /// response comes from the party that issued the invitation.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if:
+    ///     * the parent thread of the request is not the invitation
+    ///     * the request carries neither a DidDoc attachment nor a did:peer
+    ///     * the DidDoc attachment is not signed, or not by a key bound to the DID of the request
+    pub async fn receive_request(
+        wallet: &Arc<dyn BaseWallet>,
+        request: Request,
+        pairwise_info: PairwiseInfo,
+        invitation_id: &str,
+        invitation_key: String,
+    ) -> VcxResult<Self> {
+        trace!("DidExchange::receive_request >>> request: {:?}", request);
+
+        let (thread_id, parent_thread_id) = match request.decorators.thread {
+            Some(thread) => (thread.thid, thread.pthid),
+            None => (request.id, None),
+        };
+        if parent_thread_id.as_deref() != Some(invitation_id) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!(
+                    "Cannot handle request: parent thread id does not match, expected invitation {:?}, received {:?}",
+                    invitation_id, parent_thread_id
+                ),
+            ));
+        }
+
+        // Requesters identified by a did:peer may leave out the DidDoc attachment,
+        // in which case the document is expanded from the DID itself.
+        let did_doc = match request.content.did_doc.as_ref() {
+            Some(attachment) => {
+                // The signing key is bound to the DID of the request, a key listed in the
+                // attached DidDoc would only prove that the document is self-consistent.
+                let their_vk = did_bound_signing_key(&request.content.did, attachment).await?;
+                verify_did_doc_attachment(wallet, &their_vk, attachment).await?
+            }
+            None => peer_did_doc(&request.content.did).await.map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidJson,
+                    format!("Cannot handle request: DidDoc attachment is missing, {}", err),
+                )
+            })?,
+        };
+
+        Ok(DidExchange {
+            state: RequestReceived::new(thread_id, parent_thread_id, invitation_key, did_doc),
+            pairwise_info,
+            role: Responder,
+        })
+    }
+
+    /// Sends a [`Response`] carrying our DidDoc, signed with the invitation key,
+    /// and transitions to [`ResponderDidExchange<ResponseSent>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if signing the DidDoc or sending the response fails.
+    pub async fn send_response<T>(
+        self,
+        wallet: &Arc<dyn BaseWallet>,
+        service_endpoint: String,
+        routing_keys: Vec<String>,
+        transport: &T,
+    ) -> VcxResult<ResponderDidExchange<ResponseSent>>
+    where
+        T: Transport,
+    {
+        let our_did_doc = build_our_did_doc(&self.pairwise_info, service_endpoint, routing_keys);
+
+        let attachment = match signed_did_doc_attachment(wallet, &self.state.invitation_key, &our_did_doc).await {
+            Ok(attachment) => attachment,
+            Err(err) => {
+                error!("Signing DidDoc failed! Sending ProblemReport...");
+
+                self.send_problem_report(
+                    wallet,
+                    &err,
+                    ProblemCode::RequestProcessingError,
+                    self.thread_id(),
+                    &self.state.did_doc,
+                    transport,
+                )
+                .await;
+
+                return Err(err);
+            }
+        };
+
+        let mut content = ResponseContent::new(self.pairwise_info.pw_did.clone());
+        content.did_doc = Some(attachment);
+
+        let mut thread = Thread::new(self.state.thread_id.clone());
+        thread.pthid = self.state.parent_thread_id.clone();
+
+        let response = Response::with_decorators(uuid(), content, ResponseDecorators::new(thread));
+        self.send_message(wallet, &response.into(), transport).await?;
+
+        let RequestReceived { thread_id, did_doc, .. } = self.state;
+
+        Ok(DidExchange {
+            state: ResponseSent::new(thread_id, did_doc),
+            pairwise_info: self.pairwise_info,
+            role: Responder,
+        })
+    }
+}
+
+impl ResponderDidExchange<ResponseSent> {
+    /// Processes the [`Complete`] message from the requester and transitions to [`ResponderDidExchange<Completed>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the thread ID of the message does not match the exchange thread ID.
+    pub fn handle_complete(self, complete: Complete) -> VcxResult<ResponderDidExchange<Completed>> {
+        verify_thread_id(self.thread_id(), &complete.decorators.thread.thid)?;
+
+        let ResponseSent { thread_id, did_doc } = self.state;
+
+        Ok(DidExchange {
+            state: Completed::new(thread_id, did_doc, None),
+            pairwise_info: self.pairwise_info,
+            role: Responder,
+        })
+    }
+}
+
+impl ResponderDidExchange<Completed> {
+    /// Converts the finished exchange into a completed [`InviterConnection`], so that the
+    /// established relationship can be used anywhere a connection is expected.
+    pub fn into_connection(self, source_id: String) -> InviterConnection<InviterCompleted> {
+        let Completed { thread_id, did_doc, .. } = self.state;
+        let state = InviterCompleted::new(did_doc, thread_id, None);
+
+        Connection::from_parts(source_id, self.pairwise_info, Inviter, state)
+    }
+}
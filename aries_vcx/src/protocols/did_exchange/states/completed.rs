@@ -0,0 +1,32 @@
+use messages::diddoc::aries::diddoc::AriesDidDoc;
+
+use crate::protocols::connection::trait_bounds::{TheirDidDoc, ThreadId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Completed {
+    pub(crate) thread_id: String,
+    pub(crate) did_doc: AriesDidDoc,
+    pub(crate) bootstrap_did_doc: Option<AriesDidDoc>,
+}
+
+impl Completed {
+    pub fn new(thread_id: String, did_doc: AriesDidDoc, bootstrap_did_doc: Option<AriesDidDoc>) -> Self {
+        Self {
+            thread_id,
+            did_doc,
+            bootstrap_did_doc,
+        }
+    }
+}
+
+impl TheirDidDoc for Completed {
+    fn their_did_doc(&self) -> &AriesDidDoc {
+        &self.did_doc
+    }
+}
+
+impl ThreadId for Completed {
+    fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+}
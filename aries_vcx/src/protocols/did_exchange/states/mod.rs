@@ -0,0 +1,5 @@
+pub mod completed;
+pub mod request_received;
+pub mod request_sent;
+pub mod response_received;
+pub mod response_sent;
@@ -0,0 +1,39 @@
+use messages::diddoc::aries::diddoc::AriesDidDoc;
+
+use crate::protocols::connection::trait_bounds::{TheirDidDoc, ThreadId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RequestReceived {
+    pub(crate) thread_id: String,
+    pub(crate) parent_thread_id: Option<String>,
+    pub(crate) invitation_key: String,
+    pub(crate) did_doc: AriesDidDoc,
+}
+
+impl RequestReceived {
+    pub fn new(
+        thread_id: String,
+        parent_thread_id: Option<String>,
+        invitation_key: String,
+        did_doc: AriesDidDoc,
+    ) -> Self {
+        Self {
+            thread_id,
+            parent_thread_id,
+            invitation_key,
+            did_doc,
+        }
+    }
+}
+
+impl TheirDidDoc for RequestReceived {
+    fn their_did_doc(&self) -> &AriesDidDoc {
+        &self.did_doc
+    }
+}
+
+impl ThreadId for RequestReceived {
+    fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+}
@@ -0,0 +1,34 @@
+use messages::diddoc::aries::diddoc::AriesDidDoc;
+
+use crate::protocols::connection::trait_bounds::{BootstrapDidDoc, TheirDidDoc, ThreadId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RequestSent {
+    pub(crate) invitation_id: String,
+    pub(crate) thread_id: String,
+    pub(crate) bootstrap_did_doc: AriesDidDoc,
+}
+
+impl RequestSent {
+    pub fn new(invitation_id: String, thread_id: String, bootstrap_did_doc: AriesDidDoc) -> Self {
+        Self {
+            invitation_id,
+            thread_id,
+            bootstrap_did_doc,
+        }
+    }
+}
+
+impl TheirDidDoc for RequestSent {
+    fn their_did_doc(&self) -> &AriesDidDoc {
+        &self.bootstrap_did_doc
+    }
+}
+
+impl BootstrapDidDoc for RequestSent {}
+
+impl ThreadId for RequestSent {
+    fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+}
@@ -0,0 +1,40 @@
+use messages::diddoc::aries::diddoc::AriesDidDoc;
+
+use crate::protocols::connection::trait_bounds::{BootstrapDidDoc, TheirDidDoc, ThreadId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResponseReceived {
+    pub(crate) invitation_id: String,
+    pub(crate) thread_id: String,
+    pub(crate) did_doc: AriesDidDoc,
+    pub(crate) bootstrap_did_doc: AriesDidDoc,
+}
+
+impl ResponseReceived {
+    pub fn new(invitation_id: String, thread_id: String, did_doc: AriesDidDoc, bootstrap_did_doc: AriesDidDoc) -> Self {
+        Self {
+            invitation_id,
+            thread_id,
+            did_doc,
+            bootstrap_did_doc,
+        }
+    }
+}
+
+impl TheirDidDoc for ResponseReceived {
+    fn their_did_doc(&self) -> &AriesDidDoc {
+        &self.did_doc
+    }
+}
+
+impl BootstrapDidDoc for ResponseReceived {
+    fn bootstrap_did_doc(&self) -> &AriesDidDoc {
+        &self.bootstrap_did_doc
+    }
+}
+
+impl ThreadId for ResponseReceived {
+    fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+}
@@ -0,0 +1,27 @@
+use messages::diddoc::aries::diddoc::AriesDidDoc;
+
+use crate::protocols::connection::trait_bounds::{TheirDidDoc, ThreadId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResponseSent {
+    pub(crate) thread_id: String,
+    pub(crate) did_doc: AriesDidDoc,
+}
+
+impl ResponseSent {
+    pub fn new(thread_id: String, did_doc: AriesDidDoc) -> Self {
+        Self { thread_id, did_doc }
+    }
+}
+
+impl TheirDidDoc for ResponseSent {
+    fn their_did_doc(&self) -> &AriesDidDoc {
+        &self.did_doc
+    }
+}
+
+impl ThreadId for ResponseSent {
+    fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+}
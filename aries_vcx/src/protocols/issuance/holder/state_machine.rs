@@ -13,6 +13,7 @@ use messages::protocols::issuance::credential_request::CredentialRequest;
 use messages::status::Status;
 
 use crate::common::credentials::{get_cred_rev_id, is_cred_revoked};
+use crate::common::proofs::prover::credential_selection::{record_credential_stored, remove_credential_stored};
 use crate::core::profile::profile::Profile;
 use crate::errors::error::prelude::*;
 use crate::global::settings;
@@ -567,6 +568,7 @@ async fn _store_credential(
             rev_reg_def_json.as_deref(),
         )
         .await?;
+    record_credential_stored(&profile.inject_wallet(), &cred_id).await?;
     Ok((cred_id, rev_reg_def_json))
 }
 
@@ -574,7 +576,8 @@ async fn _delete_credential(profile: &Arc<dyn Profile>, cred_id: &str) -> VcxRes
     trace!("Holder::_delete_credential >>> cred_id: {}", cred_id);
 
     let anoncreds = Arc::clone(profile).inject_anoncreds();
-    anoncreds.prover_delete_credential(cred_id).await
+    anoncreds.prover_delete_credential(cred_id).await?;
+    remove_credential_stored(&profile.inject_wallet(), cred_id).await
 }
 
 pub async fn create_credential_request(
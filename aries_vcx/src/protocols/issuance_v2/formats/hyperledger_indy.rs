@@ -0,0 +1,191 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use messages2::msg_fields::protocols::cred_issuance::v2::{
+    issue_credential::IssueCredentialAttachmentFormatType, offer_credential::OfferCredentialAttachmentFormatType,
+    request_credential::RequestCredentialAttachmentFormatType,
+};
+
+use super::{HolderCredentialIssuanceFormat, IssuerCredentialIssuanceFormat};
+use crate::{
+    common::{
+        credentials::encoding::encode_attributes, proofs::prover::credential_selection::record_credential_stored,
+    },
+    core::profile::profile::Profile,
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::issuance::holder::state_machine::{create_credential_request, parse_cred_def_id_from_cred_offer},
+};
+
+/// The Hyperledger Indy (AnonCreds) credential format, as defined in
+/// [RFC 0592](https://github.com/hyperledger/aries-rfcs/blob/main/features/0592-indy-attachments/README.md).
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HyperledgerIndyCredentialIssuanceFormat;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HyperledgerIndyCreateOfferInput {
+    pub cred_def_id: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HyperledgerIndyCredentialRevocationInfo {
+    pub rev_reg_id: String,
+    pub tails_dir: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HyperledgerIndyCreateCredentialInput {
+    pub credential_attrs: HashMap<String, String>,
+    pub revocation_info: Option<HyperledgerIndyCredentialRevocationInfo>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HyperledgerIndyCreatedCredentialMetadata {
+    pub cred_rev_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HyperledgerIndyCreateRequestInput {
+    pub my_pw_did: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HyperledgerIndyCreatedRequestMetadata {
+    pub credential_request_metadata: String,
+    pub credential_def_json: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HyperledgerIndyStoredCredentialMetadata {
+    pub credential_id: String,
+    pub rev_reg_def_json: Option<String>,
+}
+
+#[async_trait]
+impl IssuerCredentialIssuanceFormat for HyperledgerIndyCredentialIssuanceFormat {
+    type CreateOfferInput = HyperledgerIndyCreateOfferInput;
+    type CreateCredentialInput = HyperledgerIndyCreateCredentialInput;
+    type CreatedCredentialMetadata = HyperledgerIndyCreatedCredentialMetadata;
+
+    fn offer_format() -> OfferCredentialAttachmentFormatType {
+        OfferCredentialAttachmentFormatType::HyperledgerIndyCredentialAbstract2_0
+    }
+
+    fn request_format() -> RequestCredentialAttachmentFormatType {
+        RequestCredentialAttachmentFormatType::HyperledgerIndyCredentialRequest2_0
+    }
+
+    fn credential_format() -> IssueCredentialAttachmentFormatType {
+        IssueCredentialAttachmentFormatType::HyperledgerIndyCredential2_0
+    }
+
+    async fn create_offer_attachment_content(
+        profile: &Arc<dyn Profile>,
+        input: &HyperledgerIndyCreateOfferInput,
+    ) -> VcxResult<Vec<u8>> {
+        let anoncreds = Arc::clone(profile).inject_anoncreds();
+        let offer = anoncreds.issuer_create_credential_offer(&input.cred_def_id).await?;
+        Ok(offer.into_bytes())
+    }
+
+    async fn create_credential_attachment_content(
+        profile: &Arc<dyn Profile>,
+        offer_payload: &[u8],
+        request_payload: &[u8],
+        input: &HyperledgerIndyCreateCredentialInput,
+    ) -> VcxResult<(Vec<u8>, HyperledgerIndyCreatedCredentialMetadata)> {
+        let anoncreds = Arc::clone(profile).inject_anoncreds();
+        let offer = payload_to_str(offer_payload)?;
+        let request = payload_to_str(request_payload)?;
+        let cred_data = encode_attributes(&serde_json::to_string(&input.credential_attrs)?)?;
+        let (rev_reg_id, tails_dir) = match &input.revocation_info {
+            Some(info) => (Some(info.rev_reg_id.clone()), Some(info.tails_dir.clone())),
+            None => (None, None),
+        };
+
+        let (credential, cred_rev_id, _) = anoncreds
+            .issuer_create_credential(offer, request, &cred_data, rev_reg_id, tails_dir)
+            .await?;
+
+        Ok((
+            credential.into_bytes(),
+            HyperledgerIndyCreatedCredentialMetadata { cred_rev_id },
+        ))
+    }
+}
+
+#[async_trait]
+impl HolderCredentialIssuanceFormat for HyperledgerIndyCredentialIssuanceFormat {
+    type CreateRequestInput = HyperledgerIndyCreateRequestInput;
+    type RequestMetadata = HyperledgerIndyCreatedRequestMetadata;
+    type StoredCredentialMetadata = HyperledgerIndyStoredCredentialMetadata;
+
+    fn offer_format() -> OfferCredentialAttachmentFormatType {
+        OfferCredentialAttachmentFormatType::HyperledgerIndyCredentialAbstract2_0
+    }
+
+    fn request_format() -> RequestCredentialAttachmentFormatType {
+        RequestCredentialAttachmentFormatType::HyperledgerIndyCredentialRequest2_0
+    }
+
+    fn credential_format() -> IssueCredentialAttachmentFormatType {
+        IssueCredentialAttachmentFormatType::HyperledgerIndyCredential2_0
+    }
+
+    async fn create_request_attachment_content(
+        profile: &Arc<dyn Profile>,
+        offer_payload: &[u8],
+        input: &HyperledgerIndyCreateRequestInput,
+    ) -> VcxResult<(Vec<u8>, HyperledgerIndyCreatedRequestMetadata)> {
+        let offer = payload_to_str(offer_payload)?;
+        let cred_def_id = parse_cred_def_id_from_cred_offer(offer)?;
+        let (request, credential_request_metadata, _, credential_def_json) =
+            create_credential_request(profile, &cred_def_id, &input.my_pw_did, offer).await?;
+
+        let metadata = HyperledgerIndyCreatedRequestMetadata {
+            credential_request_metadata,
+            credential_def_json,
+        };
+        Ok((request.into_bytes(), metadata))
+    }
+
+    async fn process_and_store_credential(
+        profile: &Arc<dyn Profile>,
+        credential_payload: &[u8],
+        request_metadata: &HyperledgerIndyCreatedRequestMetadata,
+    ) -> VcxResult<HyperledgerIndyStoredCredentialMetadata> {
+        let ledger = Arc::clone(profile).inject_ledger();
+        let anoncreds = Arc::clone(profile).inject_anoncreds();
+
+        let credential = payload_to_str(credential_payload)?;
+        let parsed_credential: serde_json::Value = serde_json::from_str(credential)?;
+        let rev_reg_def_json = match parsed_credential["rev_reg_id"].as_str() {
+            Some(rev_reg_id) => Some(ledger.get_rev_reg_def_json(rev_reg_id).await?),
+            None => None,
+        };
+
+        let credential_id = anoncreds
+            .prover_store_credential(
+                None,
+                &request_metadata.credential_request_metadata,
+                credential,
+                &request_metadata.credential_def_json,
+                rev_reg_def_json.as_deref(),
+            )
+            .await?;
+        record_credential_stored(&profile.inject_wallet(), &credential_id).await?;
+
+        Ok(HyperledgerIndyStoredCredentialMetadata {
+            credential_id,
+            rev_reg_def_json,
+        })
+    }
+}
+
+fn payload_to_str(payload: &[u8]) -> VcxResult<&str> {
+    std::str::from_utf8(payload).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("Attachment payload is not valid UTF-8: {}", err),
+        )
+    })
+}
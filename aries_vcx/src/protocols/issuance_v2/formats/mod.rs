@@ -0,0 +1,77 @@
+//! Attachment formats supported by the `issue-credential/2.0` state machines.
+//!
+//! The protocol itself is agnostic of the credential flavour being exchanged; every message carries
+//! a `formats` array linking its attachments to a format identifier. Each supported flavour is a type
+//! implementing [`IssuerCredentialIssuanceFormat`] and/or [`HolderCredentialIssuanceFormat`], which the
+//! state machines are generic over.
+
+pub mod hyperledger_indy;
+
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use messages2::msg_fields::protocols::cred_issuance::v2::{
+    issue_credential::IssueCredentialAttachmentFormatType, offer_credential::OfferCredentialAttachmentFormatType,
+    request_credential::RequestCredentialAttachmentFormatType,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{core::profile::profile::Profile, errors::error::VcxResult};
+
+/// Issuer side of a credential format: producing offers and credentials.
+#[async_trait]
+pub trait IssuerCredentialIssuanceFormat {
+    /// Format specific data needed to create an offer (e.g: a credential definition id).
+    type CreateOfferInput: Send + Sync;
+    /// Format specific data needed to create a credential (e.g: the attribute values).
+    type CreateCredentialInput: Send + Sync;
+    /// Data about the issued credential the issuer may want to keep around (e.g: revocation ids).
+    type CreatedCredentialMetadata: Clone + Debug + Serialize + DeserializeOwned + Send + Sync;
+
+    fn offer_format() -> OfferCredentialAttachmentFormatType;
+
+    fn request_format() -> RequestCredentialAttachmentFormatType;
+
+    fn credential_format() -> IssueCredentialAttachmentFormatType;
+
+    async fn create_offer_attachment_content(
+        profile: &Arc<dyn Profile>,
+        input: &Self::CreateOfferInput,
+    ) -> VcxResult<Vec<u8>>;
+
+    async fn create_credential_attachment_content(
+        profile: &Arc<dyn Profile>,
+        offer_payload: &[u8],
+        request_payload: &[u8],
+        input: &Self::CreateCredentialInput,
+    ) -> VcxResult<(Vec<u8>, Self::CreatedCredentialMetadata)>;
+}
+
+/// Holder side of a credential format: answering offers and storing credentials.
+#[async_trait]
+pub trait HolderCredentialIssuanceFormat {
+    /// Format specific data needed to create a request (e.g: the holder DID).
+    type CreateRequestInput: Send + Sync;
+    /// Data produced along with the request that is needed to process the issued credential.
+    type RequestMetadata: Clone + Debug + Serialize + DeserializeOwned + Send + Sync;
+    /// Data about the credential once it got stored (e.g: its id in the wallet).
+    type StoredCredentialMetadata: Clone + Debug + Serialize + DeserializeOwned + Send + Sync;
+
+    fn offer_format() -> OfferCredentialAttachmentFormatType;
+
+    fn request_format() -> RequestCredentialAttachmentFormatType;
+
+    fn credential_format() -> IssueCredentialAttachmentFormatType;
+
+    async fn create_request_attachment_content(
+        profile: &Arc<dyn Profile>,
+        offer_payload: &[u8],
+        input: &Self::CreateRequestInput,
+    ) -> VcxResult<(Vec<u8>, Self::RequestMetadata)>;
+
+    async fn process_and_store_credential(
+        profile: &Arc<dyn Profile>,
+        credential_payload: &[u8],
+        request_metadata: &Self::RequestMetadata,
+    ) -> VcxResult<Self::StoredCredentialMetadata>;
+}
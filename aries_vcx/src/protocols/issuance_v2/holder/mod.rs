@@ -0,0 +1,203 @@
+pub mod states;
+
+use std::{marker::PhantomData, sync::Arc};
+
+use messages2::{
+    decorators::thread::Thread,
+    msg_fields::protocols::{
+        cred_issuance::v2::{
+            ack::{AckCredentialV2, AckCredentialV2Content},
+            issue_credential::IssueCredentialV2,
+            offer_credential::OfferCredentialV2,
+            problem_report::CredIssuanceProblemReportV2,
+            request_credential::{RequestCredentialV2, RequestCredentialV2Content, RequestCredentialV2Decorators},
+            CredentialPreviewV2,
+        },
+        notification::{AckDecorators, AckStatus},
+    },
+};
+
+use self::states::{CredentialReceived, OfferReceived, RequestPrepared};
+use super::{build_problem_report, formats::HolderCredentialIssuanceFormat, problem_report_thread_id, Failed};
+use crate::{
+    core::profile::profile::Profile,
+    errors::error::VcxResult,
+    protocols::{
+        common::{build_format_attachment, get_format_attachment_payload, verify_thread_id},
+        connection::trait_bounds::ThreadId,
+    },
+    utils::uuid::uuid,
+};
+
+/// A state machine for the holder role of the issue credential protocol v2.
+///
+/// The state machine only builds and consumes messages; sending the messages it produces
+/// is left to the caller, e.g: through a [`crate::protocols::connection::Connection`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HolderV2<S> {
+    state: S,
+}
+
+impl<S> HolderV2<S> {
+    pub fn from_parts(state: S) -> Self {
+        Self { state }
+    }
+
+    pub fn into_parts(self) -> S {
+        self.state
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S> HolderV2<S>
+where
+    S: ThreadId,
+{
+    pub fn thread_id(&self) -> &str {
+        self.state.thread_id()
+    }
+
+    /// Abandons the issuance, transitioning to [`HolderV2<Failed>`].
+    /// The problem report to send to the issuer is available through [`Failed::problem_report`].
+    pub fn prepare_problem_report(self, description: String) -> HolderV2<Failed> {
+        let thread_id = self.thread_id().to_owned();
+        let problem_report = build_problem_report(&thread_id, description);
+
+        HolderV2 {
+            state: Failed::new(thread_id, problem_report),
+        }
+    }
+
+    /// Processes a problem report sent by the issuer, transitioning to [`HolderV2<Failed>`].
+    pub fn receive_problem_report(self, problem_report: CredIssuanceProblemReportV2) -> VcxResult<HolderV2<Failed>> {
+        verify_thread_id(self.thread_id(), problem_report_thread_id(&problem_report))?;
+        let thread_id = self.thread_id().to_owned();
+
+        Ok(HolderV2 {
+            state: Failed::new(thread_id, problem_report),
+        })
+    }
+}
+
+impl<F> HolderV2<OfferReceived<F>>
+where
+    F: HolderCredentialIssuanceFormat,
+{
+    /// Processes an offer received from an issuer, transitioning to [`HolderV2<OfferReceived>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the offer carries no attachment of the offer format matching `F`.
+    pub fn from_offer(offer: OfferCredentialV2) -> VcxResult<Self> {
+        get_format_attachment_payload(&offer.content.formats, &offer.content.offers_attach, F::offer_format())?;
+
+        Ok(Self {
+            state: OfferReceived {
+                offer,
+                _marker: PhantomData,
+            },
+        })
+    }
+
+    pub fn get_offer(&self) -> &OfferCredentialV2 {
+        &self.state.offer
+    }
+
+    pub fn get_credential_preview(&self) -> &CredentialPreviewV2 {
+        &self.state.offer.content.credential_preview
+    }
+
+    /// Creates a request answering the offer, transitioning to [`HolderV2<RequestPrepared>`].
+    pub async fn prepare_credential_request(
+        self,
+        profile: &Arc<dyn Profile>,
+        input: &F::CreateRequestInput,
+    ) -> VcxResult<HolderV2<RequestPrepared<F>>> {
+        let offer = &self.state.offer;
+        let offer_payload =
+            get_format_attachment_payload(&offer.content.formats, &offer.content.offers_attach, F::offer_format())?;
+
+        let (payload, request_metadata) = F::create_request_attachment_content(profile, &offer_payload, input).await?;
+        let (format, attachment) = build_format_attachment(F::request_format(), payload);
+
+        let content = RequestCredentialV2Content::new(vec![format], vec![attachment]);
+        let decorators = RequestCredentialV2Decorators {
+            thread: Some(Thread::new(self.thread_id().to_owned())),
+            timing: None,
+        };
+        let request = RequestCredentialV2::with_decorators(uuid(), content, decorators);
+
+        Ok(HolderV2 {
+            state: RequestPrepared {
+                offer: self.state.offer,
+                request,
+                request_metadata,
+            },
+        })
+    }
+}
+
+impl<F> HolderV2<RequestPrepared<F>>
+where
+    F: HolderCredentialIssuanceFormat,
+{
+    pub fn get_request(&self) -> &RequestCredentialV2 {
+        &self.state.request
+    }
+
+    /// Processes and stores the credential issued in response to our request,
+    /// transitioning to [`HolderV2<CredentialReceived>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the credential belongs to another thread, carries no attachment of the
+    /// credential format matching `F` or cannot be stored.
+    pub async fn receive_credential(
+        self,
+        profile: &Arc<dyn Profile>,
+        credential: IssueCredentialV2,
+    ) -> VcxResult<HolderV2<CredentialReceived<F>>> {
+        verify_thread_id(self.thread_id(), &credential.decorators.thread.thid)?;
+
+        let credential_payload = get_format_attachment_payload(
+            &credential.content.formats,
+            &credential.content.credentials_attach,
+            F::credential_format(),
+        )?;
+        let stored_credential_metadata =
+            F::process_and_store_credential(profile, &credential_payload, &self.state.request_metadata).await?;
+
+        Ok(HolderV2 {
+            state: CredentialReceived {
+                thread_id: self.thread_id().to_owned(),
+                please_ack: credential.decorators.please_ack.is_some(),
+                stored_credential_metadata,
+            },
+        })
+    }
+}
+
+impl<F> HolderV2<CredentialReceived<F>>
+where
+    F: HolderCredentialIssuanceFormat,
+{
+    pub fn stored_credential_metadata(&self) -> &F::StoredCredentialMetadata {
+        &self.state.stored_credential_metadata
+    }
+
+    /// Whether the issuer asked for the credential to be acknowledged.
+    pub fn is_ack_requested(&self) -> bool {
+        self.state.please_ack
+    }
+
+    /// Builds the acknowledgement of the received credential.
+    pub fn get_ack(&self) -> AckCredentialV2 {
+        let content = AckCredentialV2Content::new(AckStatus::Ok);
+        let decorators = AckDecorators::new(Thread::new(self.thread_id().to_owned()));
+
+        AckCredentialV2::with_decorators(uuid(), content, decorators)
+    }
+}
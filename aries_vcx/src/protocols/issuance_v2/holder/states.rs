@@ -0,0 +1,50 @@
+use std::marker::PhantomData;
+
+use messages2::msg_fields::protocols::cred_issuance::v2::{
+    offer_credential::OfferCredentialV2, request_credential::RequestCredentialV2,
+};
+
+use crate::protocols::{
+    common::msg_thread_id, connection::trait_bounds::ThreadId, issuance_v2::formats::HolderCredentialIssuanceFormat,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "")]
+pub struct OfferReceived<F: HolderCredentialIssuanceFormat> {
+    pub(crate) offer: OfferCredentialV2,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: HolderCredentialIssuanceFormat> ThreadId for OfferReceived<F> {
+    fn thread_id(&self) -> &str {
+        msg_thread_id(&self.offer.id, self.offer.decorators.thread.as_ref())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RequestPrepared<F: HolderCredentialIssuanceFormat> {
+    pub(crate) offer: OfferCredentialV2,
+    pub(crate) request: RequestCredentialV2,
+    pub(crate) request_metadata: F::RequestMetadata,
+}
+
+impl<F: HolderCredentialIssuanceFormat> ThreadId for RequestPrepared<F> {
+    fn thread_id(&self) -> &str {
+        msg_thread_id(&self.offer.id, self.offer.decorators.thread.as_ref())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CredentialReceived<F: HolderCredentialIssuanceFormat> {
+    pub(crate) thread_id: String,
+    pub(crate) please_ack: bool,
+    pub(crate) stored_credential_metadata: F::StoredCredentialMetadata,
+}
+
+impl<F: HolderCredentialIssuanceFormat> ThreadId for CredentialReceived<F> {
+    fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+}
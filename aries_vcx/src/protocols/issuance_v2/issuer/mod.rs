@@ -0,0 +1,244 @@
+pub mod states;
+
+use std::{marker::PhantomData, sync::Arc};
+
+use messages2::{
+    decorators::{
+        please_ack::{AckOn, PleaseAck},
+        thread::Thread,
+    },
+    msg_fields::protocols::cred_issuance::v2::{
+        ack::AckCredentialV2,
+        issue_credential::{IssueCredentialV2, IssueCredentialV2Content, IssueCredentialV2Decorators},
+        offer_credential::{OfferCredentialV2, OfferCredentialV2Content, OfferCredentialV2Decorators},
+        problem_report::CredIssuanceProblemReportV2,
+        request_credential::RequestCredentialV2,
+        CredentialPreviewV2,
+    },
+};
+
+use self::states::{Complete, CredentialPrepared, OfferPrepared, RequestReceived};
+use super::{build_problem_report, formats::IssuerCredentialIssuanceFormat, problem_report_thread_id, Failed};
+use crate::{
+    core::profile::profile::Profile,
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::{
+        common::{build_format_attachment, get_format_attachment_payload, msg_thread_id, verify_thread_id},
+        connection::trait_bounds::ThreadId,
+    },
+    utils::uuid::uuid,
+};
+
+/// A state machine for the issuer role of the issue credential protocol v2.
+///
+/// The state machine only builds and consumes messages; sending the messages it produces
+/// is left to the caller, e.g: through a [`crate::protocols::connection::Connection`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IssuerV2<S> {
+    state: S,
+}
+
+impl<S> IssuerV2<S> {
+    pub fn from_parts(state: S) -> Self {
+        Self { state }
+    }
+
+    pub fn into_parts(self) -> S {
+        self.state
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S> IssuerV2<S>
+where
+    S: ThreadId,
+{
+    pub fn thread_id(&self) -> &str {
+        self.state.thread_id()
+    }
+
+    /// Abandons the issuance, transitioning to [`IssuerV2<Failed>`].
+    /// The problem report to send to the holder is available through [`Failed::problem_report`].
+    pub fn prepare_problem_report(self, description: String) -> IssuerV2<Failed> {
+        let thread_id = self.thread_id().to_owned();
+        let problem_report = build_problem_report(&thread_id, description);
+
+        IssuerV2 {
+            state: Failed::new(thread_id, problem_report),
+        }
+    }
+
+    /// Processes a problem report sent by the holder, transitioning to [`IssuerV2<Failed>`].
+    pub fn receive_problem_report(self, problem_report: CredIssuanceProblemReportV2) -> VcxResult<IssuerV2<Failed>> {
+        verify_thread_id(self.thread_id(), problem_report_thread_id(&problem_report))?;
+        let thread_id = self.thread_id().to_owned();
+
+        Ok(IssuerV2 {
+            state: Failed::new(thread_id, problem_report),
+        })
+    }
+}
+
+impl<F> IssuerV2<OfferPrepared<F>>
+where
+    F: IssuerCredentialIssuanceFormat,
+{
+    /// Creates an offer for a credential of format `F`, starting a new issuance thread.
+    pub async fn with_offer(
+        profile: &Arc<dyn Profile>,
+        input: &F::CreateOfferInput,
+        preview: CredentialPreviewV2,
+        comment: Option<String>,
+    ) -> VcxResult<Self> {
+        let payload = F::create_offer_attachment_content(profile, input).await?;
+        let (format, attachment) = build_format_attachment(F::offer_format(), payload);
+
+        let content = OfferCredentialV2Content {
+            comment,
+            ..OfferCredentialV2Content::new(preview, vec![format], vec![attachment])
+        };
+        let offer = OfferCredentialV2::with_decorators(uuid(), content, OfferCredentialV2Decorators::default());
+
+        Ok(Self {
+            state: OfferPrepared {
+                offer,
+                _marker: PhantomData,
+            },
+        })
+    }
+
+    pub fn get_offer(&self) -> &OfferCredentialV2 {
+        &self.state.offer
+    }
+
+    /// Processes the holder's answer to our offer, transitioning to [`IssuerV2<RequestReceived>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the request belongs to another thread or carries no attachment of the
+    /// request format matching `F`.
+    pub fn receive_request(self, request: RequestCredentialV2) -> VcxResult<IssuerV2<RequestReceived<F>>> {
+        verify_thread_id(
+            self.thread_id(),
+            msg_thread_id(&request.id, request.decorators.thread.as_ref()),
+        )?;
+        get_format_attachment_payload(
+            &request.content.formats,
+            &request.content.requests_attach,
+            F::request_format(),
+        )?;
+
+        Ok(IssuerV2 {
+            state: RequestReceived {
+                offer: self.state.offer,
+                request,
+                _marker: PhantomData,
+            },
+        })
+    }
+}
+
+impl<F> IssuerV2<RequestReceived<F>>
+where
+    F: IssuerCredentialIssuanceFormat,
+{
+    pub fn get_request(&self) -> &RequestCredentialV2 {
+        &self.state.request
+    }
+
+    /// Creates the credential answering the received request, transitioning to [`IssuerV2<CredentialPrepared>`].
+    ///
+    /// When `please_ack` is set, the holder is asked to acknowledge the credential and the issuance
+    /// can only be completed through [`IssuerV2::receive_ack`].
+    pub async fn prepare_credential(
+        self,
+        profile: &Arc<dyn Profile>,
+        input: &F::CreateCredentialInput,
+        please_ack: bool,
+    ) -> VcxResult<IssuerV2<CredentialPrepared<F>>> {
+        let offer = &self.state.offer;
+        let request = &self.state.request;
+
+        let offer_payload =
+            get_format_attachment_payload(&offer.content.formats, &offer.content.offers_attach, F::offer_format())?;
+        let request_payload = get_format_attachment_payload(
+            &request.content.formats,
+            &request.content.requests_attach,
+            F::request_format(),
+        )?;
+
+        let (payload, credential_metadata) =
+            F::create_credential_attachment_content(profile, &offer_payload, &request_payload, input).await?;
+        let (format, attachment) = build_format_attachment(F::credential_format(), payload);
+
+        let content = IssueCredentialV2Content::new(vec![format], vec![attachment]);
+        let decorators = IssueCredentialV2Decorators {
+            please_ack: please_ack.then(|| PleaseAck::new(vec![AckOn::Outcome])),
+            ..IssueCredentialV2Decorators::new(Thread::new(self.thread_id().to_owned()))
+        };
+        let credential = IssueCredentialV2::with_decorators(uuid(), content, decorators);
+
+        Ok(IssuerV2 {
+            state: CredentialPrepared {
+                credential,
+                credential_metadata,
+            },
+        })
+    }
+}
+
+impl<F> IssuerV2<CredentialPrepared<F>>
+where
+    F: IssuerCredentialIssuanceFormat,
+{
+    pub fn get_credential(&self) -> &IssueCredentialV2 {
+        &self.state.credential
+    }
+
+    pub fn credential_metadata(&self) -> &F::CreatedCredentialMetadata {
+        &self.state.credential_metadata
+    }
+
+    /// Processes the holder's acknowledgement of the credential, transitioning to [`IssuerV2<Complete>`].
+    pub fn receive_ack(self, ack: AckCredentialV2) -> VcxResult<IssuerV2<Complete<F>>> {
+        verify_thread_id(self.thread_id(), &ack.decorators.thread.thid)?;
+        Ok(self.into_complete())
+    }
+
+    /// Completes the issuance once the credential got sent, transitioning to [`IssuerV2<Complete>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the credential asked for an acknowledgement, in which case
+    /// [`IssuerV2::receive_ack`] must be used instead.
+    pub fn complete_without_ack(self) -> VcxResult<IssuerV2<Complete<F>>> {
+        if self.state.credential.decorators.please_ack.is_some() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Cannot complete issuance: the credential requested an acknowledgement",
+            ));
+        }
+        Ok(self.into_complete())
+    }
+
+    fn into_complete(self) -> IssuerV2<Complete<F>> {
+        IssuerV2 {
+            state: Complete {
+                thread_id: self.thread_id().to_owned(),
+                credential_metadata: self.state.credential_metadata,
+            },
+        }
+    }
+}
+
+impl<F> IssuerV2<Complete<F>>
+where
+    F: IssuerCredentialIssuanceFormat,
+{
+    pub fn credential_metadata(&self) -> &F::CreatedCredentialMetadata {
+        &self.state.credential_metadata
+    }
+}
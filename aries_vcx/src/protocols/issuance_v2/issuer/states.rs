@@ -0,0 +1,62 @@
+use std::marker::PhantomData;
+
+use messages2::msg_fields::protocols::cred_issuance::v2::{
+    issue_credential::IssueCredentialV2, offer_credential::OfferCredentialV2, request_credential::RequestCredentialV2,
+};
+
+use crate::protocols::{
+    common::msg_thread_id, connection::trait_bounds::ThreadId, issuance_v2::formats::IssuerCredentialIssuanceFormat,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "")]
+pub struct OfferPrepared<F: IssuerCredentialIssuanceFormat> {
+    pub(crate) offer: OfferCredentialV2,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: IssuerCredentialIssuanceFormat> ThreadId for OfferPrepared<F> {
+    fn thread_id(&self) -> &str {
+        msg_thread_id(&self.offer.id, self.offer.decorators.thread.as_ref())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "")]
+pub struct RequestReceived<F: IssuerCredentialIssuanceFormat> {
+    pub(crate) offer: OfferCredentialV2,
+    pub(crate) request: RequestCredentialV2,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: IssuerCredentialIssuanceFormat> ThreadId for RequestReceived<F> {
+    fn thread_id(&self) -> &str {
+        msg_thread_id(&self.offer.id, self.offer.decorators.thread.as_ref())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CredentialPrepared<F: IssuerCredentialIssuanceFormat> {
+    pub(crate) credential: IssueCredentialV2,
+    pub(crate) credential_metadata: F::CreatedCredentialMetadata,
+}
+
+impl<F: IssuerCredentialIssuanceFormat> ThreadId for CredentialPrepared<F> {
+    fn thread_id(&self) -> &str {
+        &self.credential.decorators.thread.thid
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Complete<F: IssuerCredentialIssuanceFormat> {
+    pub(crate) thread_id: String,
+    pub(crate) credential_metadata: F::CreatedCredentialMetadata,
+}
+
+impl<F: IssuerCredentialIssuanceFormat> ThreadId for Complete<F> {
+    fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+}
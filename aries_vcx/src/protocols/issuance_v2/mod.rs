@@ -0,0 +1,175 @@
+//! State machines for the [issue credential protocol v2](https://github.com/hyperledger/aries-rfcs/blob/main/features/0453-issue-credential-v2/README.md).
+//!
+//! Unlike the v1 protocol, the messages do not assume a credential flavour; the state machines
+//! are generic over a format (see [`formats`]) which produces and consumes the attachments
+//! referenced by the `formats` arrays of the messages.
+
+pub mod formats;
+pub mod holder;
+pub mod issuer;
+
+use messages2::{
+    decorators::thread::Thread,
+    msg_fields::protocols::{
+        cred_issuance::v2::problem_report::{CredIssuanceProblemReportV2, CredIssuanceProblemReportV2Content},
+        report_problem::{ProblemReportContent, ProblemReportDecorators},
+    },
+};
+
+use crate::{
+    protocols::{common::msg_thread_id, connection::trait_bounds::ThreadId},
+    utils::uuid::uuid,
+};
+
+/// Terminal state reached by either party when the issuance got abandoned through a problem report,
+/// regardless of whether it was sent or received.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Failed {
+    pub(crate) thread_id: String,
+    pub(crate) problem_report: CredIssuanceProblemReportV2,
+}
+
+impl Failed {
+    pub fn new(thread_id: String, problem_report: CredIssuanceProblemReportV2) -> Self {
+        Self {
+            thread_id,
+            problem_report,
+        }
+    }
+
+    pub fn problem_report(&self) -> &CredIssuanceProblemReportV2 {
+        &self.problem_report
+    }
+}
+
+impl ThreadId for Failed {
+    fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+}
+
+pub(crate) fn build_problem_report(thread_id: &str, description: String) -> CredIssuanceProblemReportV2 {
+    let content = CredIssuanceProblemReportV2Content(ProblemReportContent {
+        description: Some(description),
+        ..Default::default()
+    });
+    let decorators = ProblemReportDecorators {
+        thread: Some(Thread::new(thread_id.to_owned())),
+        ..Default::default()
+    };
+
+    CredIssuanceProblemReportV2::with_decorators(uuid(), content, decorators)
+}
+
+pub(crate) fn problem_report_thread_id(problem_report: &CredIssuanceProblemReportV2) -> &str {
+    msg_thread_id(&problem_report.id, problem_report.decorators.thread.as_ref())
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use std::collections::HashMap;
+
+    use messages2::{
+        decorators::attachment::AttachmentType,
+        maybe_known::MaybeKnown,
+        msg_fields::protocols::cred_issuance::{
+            v1::CredentialAttr,
+            v2::{offer_credential::OfferCredentialAttachmentFormatType, CredentialPreviewV2},
+        },
+    };
+
+    use super::{
+        formats::hyperledger_indy::{
+            HyperledgerIndyCreateCredentialInput, HyperledgerIndyCreateOfferInput, HyperledgerIndyCreateRequestInput,
+            HyperledgerIndyCredentialIssuanceFormat,
+        },
+        holder::{states::OfferReceived, HolderV2},
+        issuer::{states::OfferPrepared, IssuerV2},
+        *,
+    };
+    use crate::{
+        common::test_utils::mock_profile,
+        errors::error::AriesVcxErrorKind,
+        utils::{constants::CRED_OFFER, devsetup::SetupMocks},
+    };
+
+    type Format = HyperledgerIndyCredentialIssuanceFormat;
+
+    async fn make_issuer() -> IssuerV2<OfferPrepared<Format>> {
+        let input = HyperledgerIndyCreateOfferInput {
+            cred_def_id: "test_cred_def_id".to_owned(),
+        };
+        let preview = CredentialPreviewV2::new(vec![CredentialAttr::new("name".to_owned(), "Alice".to_owned())]);
+
+        IssuerV2::with_offer(&mock_profile(), &input, preview, None)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_issuance_v2_hlindy_flow() {
+        let _setup = SetupMocks::init();
+        let profile = mock_profile();
+
+        let issuer = make_issuer().await;
+        let mut offer = issuer.get_offer().clone();
+        assert_eq!(
+            offer.content.formats[0].format,
+            MaybeKnown::Known(OfferCredentialAttachmentFormatType::HyperledgerIndyCredentialAbstract2_0)
+        );
+
+        // The mocked anoncreds offer lacks a cred_def_id, which the holder needs to build its request.
+        offer.content.offers_attach[0].data.content = AttachmentType::Base64(CRED_OFFER.as_bytes().to_vec());
+
+        let holder = HolderV2::<OfferReceived<Format>>::from_offer(offer).unwrap();
+        let input = HyperledgerIndyCreateRequestInput {
+            my_pw_did: "test_pw_did".to_owned(),
+        };
+        let holder = holder.prepare_credential_request(&profile, &input).await.unwrap();
+        assert_eq!(holder.thread_id(), issuer.thread_id());
+
+        let issuer = issuer.receive_request(holder.get_request().clone()).unwrap();
+        let input = HyperledgerIndyCreateCredentialInput {
+            credential_attrs: HashMap::from([("name".to_owned(), "Alice".to_owned())]),
+            revocation_info: None,
+        };
+        let issuer = issuer.prepare_credential(&profile, &input, true).await.unwrap();
+
+        let holder = holder
+            .receive_credential(&profile, issuer.get_credential().clone())
+            .await
+            .unwrap();
+        assert!(holder.is_ack_requested());
+        assert_eq!(holder.stored_credential_metadata().credential_id, "cred_id");
+
+        let issuer = issuer.receive_ack(holder.get_ack()).unwrap();
+        assert_eq!(issuer.credential_metadata().cred_rev_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_issuance_v2_rejects_unsupported_format() {
+        let _setup = SetupMocks::init();
+
+        let issuer = make_issuer().await;
+        let mut offer = issuer.get_offer().clone();
+        offer.content.formats[0].format = MaybeKnown::Unknown("anoncreds/credential-offer@v1.0".to_owned());
+
+        let err = HolderV2::<OfferReceived<Format>>::from_offer(offer).unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::ActionNotSupported);
+    }
+
+    #[tokio::test]
+    async fn test_issuance_v2_problem_report() {
+        let _setup = SetupMocks::init();
+
+        let issuer = make_issuer().await;
+        let holder = HolderV2::<OfferReceived<Format>>::from_offer(issuer.get_offer().clone()).unwrap();
+
+        let holder = holder.prepare_problem_report("issuance-abandoned".to_owned());
+        let problem_report = holder.state().problem_report().clone();
+
+        let issuer = issuer.receive_problem_report(problem_report).unwrap();
+        assert_eq!(issuer.thread_id(), holder.thread_id());
+    }
+}
@@ -1,18 +1,26 @@
 use futures::future::BoxFuture;
 use messages::a2a::A2AMessage;
 use messages::diddoc::aries::diddoc::AriesDidDoc;
+use messages2::AriesMessage;
 
 use crate::errors::error::VcxResult;
 
 pub mod common;
 pub mod connection;
+pub mod connectionless;
+pub mod coordinate_mediation;
+pub mod did_exchange;
 pub mod issuance;
+pub mod issuance_v2;
 pub mod mediated_connection;
 pub mod oob;
+pub mod pickup;
 pub mod proof_presentation;
+pub mod proof_presentation_v2;
 pub mod revocation_notification;
 pub mod trustping;
 
 pub type SendClosure = Box<dyn FnOnce(A2AMessage) -> BoxFuture<'static, VcxResult<()>> + Send + Sync>;
+pub type AriesSendClosure = Box<dyn FnOnce(AriesMessage) -> BoxFuture<'static, VcxResult<()>> + Send + Sync>;
 pub type SendClosureConnection =
     Box<dyn FnOnce(A2AMessage, String, AriesDidDoc) -> BoxFuture<'static, VcxResult<()>> + Send + Sync>;
@@ -0,0 +1,385 @@
+//! Client side of the [message pickup protocol 2.0](https://github.com/hyperledger/aries-rfcs/blob/main/features/0685-pickup-v2/README.md),
+//! used to retrieve the messages a mediator queued for us over an established connection.
+//!
+//! Status and delivery requests ask the mediator to reply on the same transport
+//! (`~transport.return_route = all`), so they are sent over a [`DuplexTransport`] and the reply is
+//! processed right away. Messages the mediator sends on its own, such as live deliveries, arrive
+//! through the regular inbound channel and are handed to [`PickupRecipient::handle_message`].
+
+use std::sync::Arc;
+
+use messages2::{
+    decorators::{
+        attachment::{Attachment, AttachmentType},
+        transport::{ReturnRoute, Transport as TransportDecorator},
+    },
+    msg_fields::protocols::pickup::{
+        delivery::Delivery,
+        delivery_request::{DeliveryRequest, DeliveryRequestContent},
+        live_delivery_change::{LiveDeliveryChange, LiveDeliveryChangeContent},
+        messages_received::{MessagesReceived, MessagesReceivedContent},
+        status::{Status, StatusContent},
+        status_request::{StatusRequest, StatusRequestContent},
+        Pickup, PickupDecorators,
+    },
+    AriesMessage,
+};
+
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    plugins::wallet::base_wallet::BaseWallet,
+    protocols::connection::{trait_bounds::TheirDidDoc, Connection},
+    transport::{DuplexTransport, Transport},
+    utils::uuid::uuid,
+};
+
+/// Packed message retrieved from the mediator.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PickedUpMessage {
+    /// Identifier of the message in the mediator's queue, to be acknowledged through `messages-received`.
+    pub id: String,
+    /// The envelope as received by the mediator, still encrypted for one of our keys.
+    pub envelope: Vec<u8>,
+}
+
+/// Recipient role of the message pickup protocol.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct PickupRecipient {
+    status: Option<StatusContent>,
+    live_delivery: bool,
+}
+
+impl PickupRecipient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Latest queue status reported by the mediator.
+    pub fn status(&self) -> Option<&StatusContent> {
+        self.status.as_ref()
+    }
+
+    /// Whether we asked the mediator to deliver messages as soon as they arrive.
+    pub fn live_delivery(&self) -> bool {
+        self.live_delivery
+    }
+
+    /// Asks the mediator how many messages are queued, optionally only for `recipient_key`, and
+    /// processes the `status` it returns, which is then available through [`Self::status`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the mediator does not reply with a message pickup message.
+    pub async fn send_status_request<I, S, T>(
+        &mut self,
+        wallet: &Arc<dyn BaseWallet>,
+        connection: &Connection<I, S>,
+        recipient_key: Option<String>,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        S: TheirDidDoc,
+        T: DuplexTransport,
+    {
+        let mut content = StatusRequestContent::default();
+        content.recipient_key = recipient_key;
+        let request = StatusRequest::with_decorators(uuid(), content, return_route_decorators());
+        self.send_with_return_route(wallet, connection, request.into(), transport)
+            .await?;
+        Ok(())
+    }
+
+    /// Asks the mediator to deliver at most `limit` queued messages, optionally only for `recipient_key`,
+    /// and returns the envelopes it delivers. The mediator replies with a `status` instead when nothing
+    /// is queued, in which case no envelopes are returned.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the mediator does not reply with a message pickup message, or if a delivered
+    /// message cannot be read.
+    pub async fn send_delivery_request<I, S, T>(
+        &mut self,
+        wallet: &Arc<dyn BaseWallet>,
+        connection: &Connection<I, S>,
+        limit: u32,
+        recipient_key: Option<String>,
+        transport: &T,
+    ) -> VcxResult<Vec<PickedUpMessage>>
+    where
+        S: TheirDidDoc,
+        T: DuplexTransport,
+    {
+        let mut content = DeliveryRequestContent::new(limit);
+        content.recipient_key = recipient_key;
+        let request = DeliveryRequest::with_decorators(uuid(), content, return_route_decorators());
+        self.send_with_return_route(wallet, connection, request.into(), transport)
+            .await
+    }
+
+    /// Acknowledges the given messages, letting the mediator remove them from its queue.
+    pub async fn send_messages_received<I, S, T>(
+        &self,
+        wallet: &Arc<dyn BaseWallet>,
+        connection: &Connection<I, S>,
+        message_ids: Vec<String>,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        S: TheirDidDoc,
+        T: Transport,
+    {
+        let content = MessagesReceivedContent::new(message_ids);
+        let ack = MessagesReceived::with_decorators(uuid(), content, PickupDecorators::default());
+        connection
+            .send_aries_message(wallet, &AriesMessage::from(ack), transport)
+            .await
+    }
+
+    /// Turns live delivery on or off. Live deliveries arrive through the regular inbound channel.
+    pub async fn send_live_delivery_change<I, S, T>(
+        &mut self,
+        wallet: &Arc<dyn BaseWallet>,
+        connection: &Connection<I, S>,
+        live_delivery: bool,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        S: TheirDidDoc,
+        T: Transport,
+    {
+        let content = LiveDeliveryChangeContent::new(live_delivery);
+        let change = LiveDeliveryChange::with_decorators(uuid(), content, PickupDecorators::default());
+        connection
+            .send_aries_message(wallet, &AriesMessage::from(change), transport)
+            .await?;
+
+        self.live_delivery = live_delivery;
+        Ok(())
+    }
+
+    async fn send_with_return_route<I, S, T>(
+        &mut self,
+        wallet: &Arc<dyn BaseWallet>,
+        connection: &Connection<I, S>,
+        message: AriesMessage,
+        transport: &T,
+    ) -> VcxResult<Vec<PickedUpMessage>>
+    where
+        S: TheirDidDoc,
+        T: DuplexTransport,
+    {
+        match connection
+            .send_aries_message_with_return_route(wallet, &message, transport)
+            .await?
+        {
+            Some(AriesMessage::Pickup(reply)) => self.handle_message(reply),
+            Some(reply) => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Mediator replied with an unexpected message: {:?}", reply),
+            )),
+            None => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::IOError,
+                "Mediator closed the channel without replying",
+            )),
+        }
+    }
+
+    /// Processes a `status` message.
+    pub fn handle_status(&mut self, status: Status) {
+        if let Some(live_delivery) = status.content.live_delivery {
+            self.live_delivery = live_delivery;
+        }
+        self.status = Some(status.content);
+    }
+
+    /// Processes a `delivery` message, returning the delivered envelopes.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if an attachment carries no ID or its content cannot be read.
+    pub fn handle_delivery(&self, delivery: Delivery) -> VcxResult<Vec<PickedUpMessage>> {
+        delivery.content.attach.into_iter().map(picked_up_message).collect()
+    }
+
+    /// Processes any message pickup message addressed to the recipient, returning the
+    /// envelopes it delivered, if any.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the message is only meant for mediators or if it cannot be processed.
+    pub fn handle_message(&mut self, message: Pickup) -> VcxResult<Vec<PickedUpMessage>> {
+        match message {
+            Pickup::Status(status) => {
+                self.handle_status(status);
+                Ok(Vec::new())
+            }
+            Pickup::Delivery(delivery) => self.handle_delivery(delivery),
+            message => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!("Pickup recipient cannot handle message: {:?}", message),
+            )),
+        }
+    }
+}
+
+fn return_route_decorators() -> PickupDecorators {
+    let mut decorators = PickupDecorators::default();
+    decorators.transport = Some(TransportDecorator::new(ReturnRoute::All));
+    decorators
+}
+
+fn picked_up_message(attachment: Attachment) -> VcxResult<PickedUpMessage> {
+    let id = attachment.id.ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            "Delivered attachment is missing its message ID",
+        )
+    })?;
+    let envelope = match attachment.data.content {
+        AttachmentType::Base64(bytes) => bytes,
+        AttachmentType::Json(value) => serde_json::to_vec(&value)?,
+        AttachmentType::Links(_) => {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Delivered message {} is only referenced through links", id),
+            ))
+        }
+    };
+    Ok(PickedUpMessage { id, envelope })
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use messages2::{
+        decorators::{attachment::AttachmentData, thread::Thread},
+        msg_fields::protocols::pickup::delivery::DeliveryContent,
+    };
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        common::test_utils::{indy_handles_to_profile, CapturingTransport, CompletedConnection},
+        indy::utils::test_setup::with_wallet,
+        utils::devsetup::SetupEmpty,
+    };
+
+    fn make_delivery(attachments: Vec<Attachment>) -> Delivery {
+        let mut decorators = PickupDecorators::default();
+        decorators.thread = Some(Thread::new(uuid()));
+        Delivery::with_decorators(uuid(), DeliveryContent::new(attachments), decorators)
+    }
+
+    fn make_attachment(id: Option<&str>, content: AttachmentType) -> Attachment {
+        let mut attachment = Attachment::new(AttachmentData::new(content));
+        attachment.id = id.map(ToOwned::to_owned);
+        attachment
+    }
+
+    #[test]
+    fn test_pickup_delivery_returns_envelopes() {
+        let mut recipient = PickupRecipient::new();
+        let envelope = json!({ "protected": "header", "ciphertext": "data" });
+        let delivery = make_delivery(vec![
+            make_attachment(Some("msg_1"), AttachmentType::Base64(b"packed".to_vec())),
+            make_attachment(Some("msg_2"), AttachmentType::Json(envelope.clone())),
+        ]);
+
+        let messages = recipient.handle_message(Pickup::Delivery(delivery)).unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].id, "msg_1");
+        assert_eq!(messages[0].envelope, b"packed".to_vec());
+        assert_eq!(messages[1].id, "msg_2");
+        assert_eq!(messages[1].envelope, serde_json::to_vec(&envelope).unwrap());
+    }
+
+    #[test]
+    fn test_pickup_delivery_rejects_attachment_without_id() {
+        let recipient = PickupRecipient::new();
+        let delivery = make_delivery(vec![make_attachment(None, AttachmentType::Base64(vec![1]))]);
+
+        assert!(recipient.handle_delivery(delivery).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pickup_delivery_request_returns_reply() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let wallet = indy_handles_to_profile(wallet_handle, 0).inject_wallet();
+            let mediator = CompletedConnection::new(&wallet, "https://mediator.example.org").await;
+            let transport = CapturingTransport::default();
+            let delivery = make_delivery(vec![make_attachment(
+                Some("msg_1"),
+                AttachmentType::Base64(b"packed".to_vec()),
+            )]);
+            let reply = mediator.pack_reply(&wallet, &delivery.into()).await;
+            transport.queue_reply(reply);
+
+            let mut recipient = PickupRecipient::new();
+            let messages = recipient
+                .send_delivery_request(&wallet, &mediator.connection, 10, None, &transport)
+                .await
+                .unwrap();
+
+            let request = serde_json::to_value(transport.take_message(&wallet).await).unwrap();
+            assert!(request["@type"].as_str().unwrap().ends_with("/delivery-request"));
+            assert_eq!(request["limit"], 10);
+            assert_eq!(request["~transport"]["return_route"], "all");
+            assert_eq!(
+                messages,
+                vec![PickedUpMessage {
+                    id: "msg_1".to_owned(),
+                    envelope: b"packed".to_vec(),
+                }]
+            );
+
+            let err = recipient
+                .send_delivery_request(&wallet, &mediator.connection, 10, None, &transport)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), AriesVcxErrorKind::IOError);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_pickup_status_request_stores_reply() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let wallet = indy_handles_to_profile(wallet_handle, 0).inject_wallet();
+            let mediator = CompletedConnection::new(&wallet, "https://mediator.example.org").await;
+            let transport = CapturingTransport::default();
+            let content = StatusContent::new(3);
+            let status = Status::with_decorators(uuid(), content.clone(), PickupDecorators::default());
+            let reply = mediator.pack_reply(&wallet, &status.into()).await;
+            transport.queue_reply(reply);
+
+            let mut recipient = PickupRecipient::new();
+            recipient
+                .send_status_request(&wallet, &mediator.connection, None, &transport)
+                .await
+                .unwrap();
+
+            let request = serde_json::to_value(transport.take_message(&wallet).await).unwrap();
+            assert!(request["@type"].as_str().unwrap().ends_with("/status-request"));
+            assert_eq!(request["~transport"]["return_route"], "all");
+            assert_eq!(recipient.status(), Some(&content));
+        })
+        .await;
+    }
+
+    #[test]
+    fn test_pickup_status_is_stored() {
+        let mut recipient = PickupRecipient::new();
+        let mut content = StatusContent::new(5);
+        content.live_delivery = Some(true);
+        let status = Status::with_decorators(uuid(), content.clone(), PickupDecorators::default());
+
+        let messages = recipient.handle_message(Pickup::Status(status)).unwrap();
+
+        assert!(messages.is_empty());
+        assert_eq!(recipient.status(), Some(&content));
+        assert!(recipient.live_delivery());
+    }
+}
@@ -3,6 +3,7 @@ use std::fmt::Display;
 use std::sync::Arc;
 
 use crate::common::proofs::proof_request::PresentationRequestData;
+use crate::common::proofs::verifier::verification_report::VerificationReport;
 use crate::core::profile::profile::Profile;
 use crate::errors::error::prelude::*;
 use crate::protocols::common::build_problem_report_msg;
@@ -17,6 +18,7 @@ use crate::protocols::proof_presentation::verifier::verify_thread_id;
 use crate::protocols::SendClosure;
 use messages::a2a::{A2AMessage, MessageId};
 use messages::concepts::problem_report::ProblemReport;
+use messages::concepts::service_decorator::ServiceDecorator;
 use messages::protocols::proof_presentation::presentation::Presentation;
 use messages::protocols::proof_presentation::presentation_ack::PresentationAck;
 use messages::protocols::proof_presentation::presentation_proposal::PresentationProposal;
@@ -216,8 +218,8 @@ impl VerifierSM {
                 let ack = build_verification_ack(&self.thread_id);
                 send_message(A2AMessage::PresentationAck(ack)).await?;
                 match verification_result {
-                    Ok(()) => {
-                        VerifierFullState::Finished((state, presentation, PresentationVerificationStatus::Valid).into())
+                    Ok(verification_report) => {
+                        VerifierFullState::Finished((state, presentation, verification_report).into())
                     }
                     Err(err) => match err.kind() {
                         AriesVcxErrorKind::InvalidProof => VerifierFullState::Finished(
@@ -317,6 +319,31 @@ impl VerifierSM {
         })
     }
 
+    pub fn set_request_service(self, service: ServiceDecorator) -> VcxResult<Self> {
+        let Self {
+            state,
+            source_id,
+            thread_id,
+        } = self;
+        let state = match state {
+            VerifierFullState::PresentationRequestSet(state) => {
+                let presentation_request = state.presentation_request.set_service(Some(service));
+                VerifierFullState::PresentationRequestSet(PresentationRequestSetState::new(presentation_request))
+            }
+            _ => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Can not set presentation request service in current state.",
+                ))
+            }
+        };
+        Ok(Self {
+            source_id,
+            thread_id,
+            state,
+        })
+    }
+
     pub fn mark_presentation_request_msg_sent(self) -> VcxResult<Self> {
         let Self {
             state,
@@ -422,6 +449,19 @@ impl VerifierSM {
         }
     }
 
+    pub fn get_verification_report(&self) -> VcxResult<VerificationReport> {
+        match self.state {
+            VerifierFullState::Finished(ref state) => state.verification_report.clone().ok_or(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "State machine is final state, but verification report is not available",
+            )),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Presentation has not been verified yet",
+            )),
+        }
+    }
+
     pub fn presentation_request_msg(&self) -> VcxResult<PresentationRequest> {
         match self.state {
             VerifierFullState::Initial(_) => Err(AriesVcxError::from_msg(
@@ -688,6 +728,34 @@ pub mod unit_tests {
             assert_match!(VerifierFullState::PresentationRequestSent(_), verifier_sm.state);
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_set_request_service_from_presentation_request_set_state() {
+            let _setup = SetupMocks::init();
+            let service = ServiceDecorator::create()
+                .set_recipient_keys(vec!["recipient_key".to_string()])
+                .set_service_endpoint("https://example.org/endpoint".to_string());
+
+            let verifier_sm = _verifier_sm_from_request()
+                .set_request_service(service.clone())
+                .unwrap();
+
+            assert_match!(VerifierFullState::PresentationRequestSet(_), verifier_sm.state);
+            assert_eq!(verifier_sm.presentation_request_msg().unwrap().service, Some(service));
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_set_request_service_fails_after_request_sent() {
+            let _setup = SetupMocks::init();
+
+            let verifier_sm = _verifier_sm_from_request()
+                .mark_presentation_request_msg_sent()
+                .unwrap();
+
+            assert!(verifier_sm.set_request_service(ServiceDecorator::create()).is_err());
+        }
+
         #[tokio::test]
         #[cfg(feature = "general_test")]
         async fn test_prover_handle_other_messages_from_presentation_request_set_state() {
@@ -825,6 +893,8 @@ pub mod unit_tests {
                 PresentationVerificationStatus::Valid,
                 verifier_sm.get_verification_status()
             );
+            let verification_report = verifier_sm.get_verification_report().unwrap();
+            assert!(verification_report.valid);
         }
 
         #[tokio::test]
@@ -849,6 +919,9 @@ pub mod unit_tests {
                 PresentationVerificationStatus::Invalid,
                 verifier_sm.get_verification_status()
             );
+            let verification_report = verifier_sm.get_verification_report().unwrap();
+            assert!(!verification_report.valid);
+            assert!(verification_report.cryptographic.is_failed());
         }
 
         #[tokio::test]
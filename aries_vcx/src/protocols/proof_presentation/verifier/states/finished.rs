@@ -5,6 +5,7 @@ use messages::protocols::proof_presentation::presentation::Presentation;
 use messages::protocols::proof_presentation::presentation_request::PresentationRequest;
 use messages::status::Status;
 
+use crate::common::proofs::verifier::verification_report::VerificationReport;
 use crate::protocols::proof_presentation::verifier::verification_status::PresentationVerificationStatus;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -13,6 +14,8 @@ pub struct FinishedState {
     pub presentation: Option<Presentation>,
     pub status: Status,
     pub verification_status: PresentationVerificationStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_report: Option<VerificationReport>,
 }
 
 impl FinishedState {
@@ -23,6 +26,7 @@ impl FinishedState {
             presentation: None,
             status: Status::Declined(problem_report),
             verification_status: PresentationVerificationStatus::Unavailable,
+            verification_report: None,
         }
     }
 }
@@ -50,6 +54,7 @@ pub mod unit_tests {
             presentation: None,
             status: Status::Success,
             verification_status: PresentationVerificationStatus::Valid,
+            verification_report: None,
         };
         let serialized = serde_json::to_string(&state).unwrap();
         let expected =
@@ -66,6 +71,7 @@ pub mod unit_tests {
             presentation: None,
             status: Status::Success,
             verification_status: PresentationVerificationStatus::Unavailable,
+            verification_report: None,
         };
         let serialized = serde_json::to_string(&state).unwrap();
         let expected = r#"{"presentation_request":null,"presentation":null,"status":"Success","verification_status":"Unavailable"}"#;
@@ -81,6 +87,7 @@ pub mod unit_tests {
             presentation: None,
             status: Status::Success,
             verification_status: PresentationVerificationStatus::Invalid,
+            verification_report: None,
         };
         let serialized = serde_json::to_string(&state).unwrap();
         let expected =
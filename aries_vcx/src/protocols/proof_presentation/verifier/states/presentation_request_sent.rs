@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
-use crate::common::proofs::verifier::verifier::validate_indy_proof;
+use crate::common::proofs::verifier::verification_report::VerificationReport;
+use crate::common::proofs::verifier::verifier::verify_indy_proof;
 use crate::core::profile::profile::Profile;
 use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
 use crate::global::settings;
@@ -22,7 +23,7 @@ impl PresentationRequestSentState {
         profile: &Arc<dyn Profile>,
         presentation: &Presentation,
         thread_id: &str,
-    ) -> VcxResult<()> {
+    ) -> VcxResult<VerificationReport> {
         if !settings::indy_mocks_enabled() && !presentation.from_thread(thread_id) {
             return Err(AriesVcxError::from_msg(
                 AriesVcxErrorKind::InvalidJson,
@@ -33,21 +34,12 @@ impl PresentationRequestSentState {
             ));
         };
 
-        let valid = validate_indy_proof(
+        verify_indy_proof(
             profile,
             &presentation.presentations_attach.content()?,
             &self.presentation_request.request_presentations_attach.content()?,
         )
-        .await?;
-
-        if !valid {
-            return Err(AriesVcxError::from_msg(
-                AriesVcxErrorKind::InvalidProof,
-                "Presentation verification failed",
-            ));
-        }
-
-        Ok(())
+        .await
     }
 }
 
@@ -71,6 +63,26 @@ impl
             presentation: Some(presentation),
             status: Status::Success,
             verification_status: verification_status,
+            verification_report: None,
+        }
+    }
+}
+
+impl From<(PresentationRequestSentState, Presentation, VerificationReport)> for FinishedState {
+    fn from(
+        (state, presentation, verification_report): (PresentationRequestSentState, Presentation, VerificationReport),
+    ) -> Self {
+        trace!("transit state from PresentationRequestSentState to FinishedState");
+        let verification_status = match verification_report.valid {
+            true => PresentationVerificationStatus::Valid,
+            false => PresentationVerificationStatus::Invalid,
+        };
+        FinishedState {
+            presentation_request: Some(state.presentation_request),
+            presentation: Some(presentation),
+            status: Status::Success,
+            verification_status,
+            verification_report: Some(verification_report),
         }
     }
 }
@@ -83,6 +95,7 @@ impl From<(PresentationRequestSentState, ProblemReport)> for FinishedState {
             presentation: None,
             status: Status::Failed(problem_report),
             verification_status: PresentationVerificationStatus::Unavailable,
+            verification_report: None,
         }
     }
 }
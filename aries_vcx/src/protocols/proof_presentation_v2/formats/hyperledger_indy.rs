@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use messages2::msg_fields::protocols::present_proof::v2::{
+    present::PresentationAttachmentFormatType, propose::ProposePresentationAttachmentFormatType,
+    request::RequestPresentationAttachmentFormatType,
+};
+
+use super::{ProverPresentationFormat, VerifierPresentationFormat};
+use crate::{
+    common::proofs::{
+        proof_request::PresentationRequestData, prover::prover::generate_indy_proof,
+        verifier::verifier::validate_indy_proof,
+    },
+    core::profile::profile::Profile,
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+};
+
+/// The Hyperledger Indy (AnonCreds) proof format, as defined in
+/// [RFC 0592](https://github.com/hyperledger/aries-rfcs/blob/main/features/0592-indy-attachments/README.md).
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HyperledgerIndyPresentationFormat;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HyperledgerIndyCreatePresentationInput {
+    /// Credentials selected for the requested attributes and predicates, as expected by
+    /// [`generate_indy_proof`].
+    pub credentials: String,
+    pub self_attested_attrs: String,
+}
+
+#[async_trait]
+impl VerifierPresentationFormat for HyperledgerIndyPresentationFormat {
+    type CreateRequestInput = PresentationRequestData;
+
+    fn proposal_format() -> ProposePresentationAttachmentFormatType {
+        ProposePresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0
+    }
+
+    fn request_format() -> RequestPresentationAttachmentFormatType {
+        RequestPresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0
+    }
+
+    fn presentation_format() -> PresentationAttachmentFormatType {
+        PresentationAttachmentFormatType::HyperledgerIndyProof2_0
+    }
+
+    async fn create_request_attachment_content(
+        _profile: &Arc<dyn Profile>,
+        input: &PresentationRequestData,
+    ) -> VcxResult<Vec<u8>> {
+        Ok(serde_json::to_vec(input)?)
+    }
+
+    async fn verify_presentation(
+        profile: &Arc<dyn Profile>,
+        request_payload: &[u8],
+        presentation_payload: &[u8],
+    ) -> VcxResult<bool> {
+        let proof_req_json = payload_to_str(request_payload)?;
+        let proof_json = payload_to_str(presentation_payload)?;
+        validate_indy_proof(profile, proof_json, proof_req_json).await
+    }
+}
+
+#[async_trait]
+impl ProverPresentationFormat for HyperledgerIndyPresentationFormat {
+    type CreateProposalInput = PresentationRequestData;
+    type MatchingCredentials = String;
+    type CreatePresentationInput = HyperledgerIndyCreatePresentationInput;
+
+    fn proposal_format() -> ProposePresentationAttachmentFormatType {
+        ProposePresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0
+    }
+
+    fn request_format() -> RequestPresentationAttachmentFormatType {
+        RequestPresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0
+    }
+
+    fn presentation_format() -> PresentationAttachmentFormatType {
+        PresentationAttachmentFormatType::HyperledgerIndyProof2_0
+    }
+
+    async fn create_proposal_attachment_content(
+        _profile: &Arc<dyn Profile>,
+        input: &PresentationRequestData,
+    ) -> VcxResult<Vec<u8>> {
+        Ok(serde_json::to_vec(input)?)
+    }
+
+    async fn get_matching_credentials(profile: &Arc<dyn Profile>, request_payload: &[u8]) -> VcxResult<String> {
+        let anoncreds = Arc::clone(profile).inject_anoncreds();
+        anoncreds
+            .prover_get_credentials_for_proof_req(payload_to_str(request_payload)?)
+            .await
+    }
+
+    async fn create_presentation_attachment_content(
+        profile: &Arc<dyn Profile>,
+        request_payload: &[u8],
+        input: &HyperledgerIndyCreatePresentationInput,
+    ) -> VcxResult<Vec<u8>> {
+        let proof = generate_indy_proof(
+            profile,
+            &input.credentials,
+            &input.self_attested_attrs,
+            payload_to_str(request_payload)?,
+        )
+        .await?;
+        Ok(proof.into_bytes())
+    }
+}
+
+fn payload_to_str(payload: &[u8]) -> VcxResult<&str> {
+    std::str::from_utf8(payload).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("Attachment payload is not valid UTF-8: {}", err),
+        )
+    })
+}
@@ -0,0 +1,98 @@
+//! Attachment formats supported by the `present-proof/2.0` state machines.
+//!
+//! As with `issue-credential/2.0`, the messages are agnostic of the proof flavour; the state machines
+//! are generic over a type implementing [`ProverPresentationFormat`] and/or [`VerifierPresentationFormat`].
+
+pub mod hyperledger_indy;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use messages2::{
+    maybe_known::MaybeKnown,
+    msg_fields::protocols::present_proof::v2::{
+        present::PresentationAttachmentFormatType,
+        propose::ProposePresentationAttachmentFormatType,
+        request::{RequestPresentationAttachmentFormatType, RequestPresentationV2},
+    },
+};
+
+use crate::{core::profile::profile::Profile, errors::error::VcxResult};
+
+/// Request formats this crate can answer, in order of preference.
+pub const SUPPORTED_REQUEST_FORMATS: [RequestPresentationAttachmentFormatType; 1] =
+    [RequestPresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0];
+
+/// Picks the format to answer a request with: the first format listed by the verifier
+/// that is also part of `supported`.
+pub fn select_request_format(
+    request: &RequestPresentationV2,
+    supported: &[RequestPresentationAttachmentFormatType],
+) -> Option<RequestPresentationAttachmentFormatType> {
+    request
+        .content
+        .formats
+        .iter()
+        .find_map(|specifier| match &specifier.format {
+            MaybeKnown::Known(format) if supported.contains(format) => Some(*format),
+            _ => None,
+        })
+}
+
+/// Verifier side of a proof format: producing requests and verifying presentations.
+#[async_trait]
+pub trait VerifierPresentationFormat {
+    /// Format specific description of the requested proof.
+    type CreateRequestInput: Send + Sync;
+
+    fn proposal_format() -> ProposePresentationAttachmentFormatType;
+
+    fn request_format() -> RequestPresentationAttachmentFormatType;
+
+    fn presentation_format() -> PresentationAttachmentFormatType;
+
+    async fn create_request_attachment_content(
+        profile: &Arc<dyn Profile>,
+        input: &Self::CreateRequestInput,
+    ) -> VcxResult<Vec<u8>>;
+
+    /// Returns whether the presentation satisfies the request.
+    async fn verify_presentation(
+        profile: &Arc<dyn Profile>,
+        request_payload: &[u8],
+        presentation_payload: &[u8],
+    ) -> VcxResult<bool>;
+}
+
+/// Prover side of a proof format: proposing, finding matching credentials and presenting.
+#[async_trait]
+pub trait ProverPresentationFormat {
+    /// Format specific description of the proof the prover offers to present.
+    type CreateProposalInput: Send + Sync;
+    /// Credentials the prover holds which may be used to answer a request.
+    type MatchingCredentials: Send + Sync;
+    /// Format specific selection of the data to present.
+    type CreatePresentationInput: Send + Sync;
+
+    fn proposal_format() -> ProposePresentationAttachmentFormatType;
+
+    fn request_format() -> RequestPresentationAttachmentFormatType;
+
+    fn presentation_format() -> PresentationAttachmentFormatType;
+
+    async fn create_proposal_attachment_content(
+        profile: &Arc<dyn Profile>,
+        input: &Self::CreateProposalInput,
+    ) -> VcxResult<Vec<u8>>;
+
+    async fn get_matching_credentials(
+        profile: &Arc<dyn Profile>,
+        request_payload: &[u8],
+    ) -> VcxResult<Self::MatchingCredentials>;
+
+    async fn create_presentation_attachment_content(
+        profile: &Arc<dyn Profile>,
+        request_payload: &[u8],
+        input: &Self::CreatePresentationInput,
+    ) -> VcxResult<Vec<u8>>;
+}
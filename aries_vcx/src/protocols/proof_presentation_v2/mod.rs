@@ -0,0 +1,163 @@
+//! State machines for the [present proof protocol v2](https://github.com/hyperledger/aries-rfcs/blob/main/features/0454-present-proof-v2/README.md).
+//!
+//! The state machines are generic over a proof format (see [`formats`]) producing and consuming
+//! the attachments referenced by the `formats` arrays of the messages.
+
+pub mod formats;
+pub mod prover;
+pub mod verifier;
+
+use messages2::{
+    decorators::thread::Thread,
+    msg_fields::protocols::{
+        present_proof::v2::problem_report::{PresentProofProblemReportV2, PresentProofProblemReportV2Content},
+        report_problem::{ProblemReportContent, ProblemReportDecorators},
+    },
+};
+
+use crate::{
+    protocols::{common::msg_thread_id, connection::trait_bounds::ThreadId},
+    utils::uuid::uuid,
+};
+
+/// Terminal state reached by either party when the presentation got abandoned through a problem report,
+/// regardless of whether it was sent or received.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Failed {
+    pub(crate) thread_id: String,
+    pub(crate) problem_report: PresentProofProblemReportV2,
+}
+
+impl Failed {
+    pub fn new(thread_id: String, problem_report: PresentProofProblemReportV2) -> Self {
+        Self {
+            thread_id,
+            problem_report,
+        }
+    }
+
+    pub fn problem_report(&self) -> &PresentProofProblemReportV2 {
+        &self.problem_report
+    }
+}
+
+impl ThreadId for Failed {
+    fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+}
+
+pub(crate) fn build_problem_report(thread_id: &str, description: String) -> PresentProofProblemReportV2 {
+    let content = PresentProofProblemReportV2Content(ProblemReportContent {
+        description: Some(description),
+        ..Default::default()
+    });
+    let decorators = ProblemReportDecorators {
+        thread: Some(Thread::new(thread_id.to_owned())),
+        ..Default::default()
+    };
+
+    PresentProofProblemReportV2::with_decorators(uuid(), content, decorators)
+}
+
+pub(crate) fn problem_report_thread_id(problem_report: &PresentProofProblemReportV2) -> &str {
+    msg_thread_id(&problem_report.id, problem_report.decorators.thread.as_ref())
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use messages2::{
+        maybe_known::MaybeKnown,
+        msg_fields::protocols::present_proof::v2::request::RequestPresentationAttachmentFormatType,
+    };
+
+    use super::{
+        formats::{
+            hyperledger_indy::HyperledgerIndyPresentationFormat, select_request_format, SUPPORTED_REQUEST_FORMATS,
+        },
+        prover::{
+            states::{ProposalPrepared, RequestReceived},
+            ProverV2,
+        },
+        verifier::{
+            states::{ProposalReceived, RequestPrepared},
+            VerifierV2,
+        },
+        *,
+    };
+    use crate::{
+        common::{proofs::proof_request::PresentationRequestData, test_utils::mock_profile},
+        errors::error::AriesVcxErrorKind,
+        utils::devsetup::SetupMocks,
+    };
+
+    type Format = HyperledgerIndyPresentationFormat;
+
+    async fn make_verifier() -> VerifierV2<RequestPrepared<Format>> {
+        let presentation_request = PresentationRequestData::create(&mock_profile(), "test_proof")
+            .await
+            .unwrap();
+
+        VerifierV2::with_request(&mock_profile(), &presentation_request, None)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_present_proof_v2_request_format_negotiation() {
+        let _setup = SetupMocks::init();
+
+        let verifier = make_verifier().await;
+        let mut request = verifier.get_request().clone();
+        assert!(request.content.will_confirm);
+        assert_eq!(
+            select_request_format(&request, &SUPPORTED_REQUEST_FORMATS),
+            Some(RequestPresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0)
+        );
+
+        let prover = ProverV2::<RequestReceived<Format>>::from_request(request.clone()).unwrap();
+        assert_eq!(prover.thread_id(), verifier.thread_id());
+
+        request.content.formats[0].format = MaybeKnown::Unknown("anoncreds/proof-request@v1.0".to_owned());
+        assert_eq!(select_request_format(&request, &SUPPORTED_REQUEST_FORMATS), None);
+
+        let err = ProverV2::<RequestReceived<Format>>::from_request(request).unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::ActionNotSupported);
+    }
+
+    #[tokio::test]
+    async fn test_present_proof_v2_proposal_flow() {
+        let _setup = SetupMocks::init();
+        let profile = mock_profile();
+
+        let presentation_request = PresentationRequestData::create(&profile, "test_proof").await.unwrap();
+        let prover = ProverV2::<ProposalPrepared<Format>>::with_proposal(&profile, &presentation_request, None)
+            .await
+            .unwrap();
+
+        let verifier = VerifierV2::<ProposalReceived<Format>>::from_proposal(prover.get_proposal().clone()).unwrap();
+        let verifier = verifier
+            .prepare_request(&profile, &presentation_request, Some("proof needed".to_owned()))
+            .await
+            .unwrap();
+        assert_eq!(verifier.thread_id(), prover.thread_id());
+
+        let prover = prover.receive_request(verifier.get_request().clone()).unwrap();
+        assert_eq!(prover.get_request().content.comment.as_deref(), Some("proof needed"));
+    }
+
+    #[tokio::test]
+    async fn test_present_proof_v2_problem_report() {
+        let _setup = SetupMocks::init();
+
+        let verifier = make_verifier().await;
+        let prover = ProverV2::<RequestReceived<Format>>::from_request(verifier.get_request().clone()).unwrap();
+
+        let prover = prover.prepare_problem_report("presentation-abandoned".to_owned());
+        let problem_report = prover.state().problem_report().clone();
+
+        let verifier = verifier.receive_problem_report(problem_report).unwrap();
+        assert_eq!(verifier.thread_id(), prover.thread_id());
+    }
+}
@@ -0,0 +1,220 @@
+pub mod states;
+
+use std::{marker::PhantomData, sync::Arc};
+
+use messages2::{
+    decorators::{
+        please_ack::{AckOn, PleaseAck},
+        thread::Thread,
+    },
+    msg_fields::protocols::present_proof::v2::{
+        ack::AckPresentationV2,
+        present::{PresentationV2, PresentationV2Content, PresentationV2Decorators},
+        problem_report::PresentProofProblemReportV2,
+        propose::{ProposePresentationV2, ProposePresentationV2Content, ProposePresentationV2Decorators},
+        request::RequestPresentationV2,
+    },
+};
+
+use self::states::{Complete, PresentationPrepared, ProposalPrepared, RequestReceived};
+use super::{build_problem_report, formats::ProverPresentationFormat, problem_report_thread_id, Failed};
+use crate::{
+    core::profile::profile::Profile,
+    errors::error::VcxResult,
+    protocols::{
+        common::{build_format_attachment, get_format_attachment_payload, msg_thread_id, verify_thread_id},
+        connection::trait_bounds::ThreadId,
+    },
+    utils::uuid::uuid,
+};
+
+/// A state machine for the prover role of the present proof protocol v2.
+///
+/// The state machine only builds and consumes messages; sending the messages it produces
+/// is left to the caller, e.g: through a [`crate::protocols::connection::Connection`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProverV2<S> {
+    state: S,
+}
+
+impl<S> ProverV2<S> {
+    pub fn from_parts(state: S) -> Self {
+        Self { state }
+    }
+
+    pub fn into_parts(self) -> S {
+        self.state
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S> ProverV2<S>
+where
+    S: ThreadId,
+{
+    pub fn thread_id(&self) -> &str {
+        self.state.thread_id()
+    }
+
+    /// Abandons the presentation, transitioning to [`ProverV2<Failed>`].
+    /// The problem report to send to the verifier is available through [`Failed::problem_report`].
+    pub fn prepare_problem_report(self, description: String) -> ProverV2<Failed> {
+        let thread_id = self.thread_id().to_owned();
+        let problem_report = build_problem_report(&thread_id, description);
+
+        ProverV2 {
+            state: Failed::new(thread_id, problem_report),
+        }
+    }
+
+    /// Processes a problem report sent by the verifier, transitioning to [`ProverV2<Failed>`].
+    pub fn receive_problem_report(self, problem_report: PresentProofProblemReportV2) -> VcxResult<ProverV2<Failed>> {
+        verify_thread_id(self.thread_id(), problem_report_thread_id(&problem_report))?;
+        let thread_id = self.thread_id().to_owned();
+
+        Ok(ProverV2 {
+            state: Failed::new(thread_id, problem_report),
+        })
+    }
+}
+
+impl<F> ProverV2<ProposalPrepared<F>>
+where
+    F: ProverPresentationFormat,
+{
+    /// Creates a proposal for a presentation of format `F`, starting a new presentation thread.
+    pub async fn with_proposal(
+        profile: &Arc<dyn Profile>,
+        input: &F::CreateProposalInput,
+        comment: Option<String>,
+    ) -> VcxResult<Self> {
+        let payload = F::create_proposal_attachment_content(profile, input).await?;
+        let (format, attachment) = build_format_attachment(F::proposal_format(), payload);
+
+        let content = ProposePresentationV2Content {
+            comment,
+            ..ProposePresentationV2Content::new(vec![format], vec![attachment])
+        };
+        let proposal =
+            ProposePresentationV2::with_decorators(uuid(), content, ProposePresentationV2Decorators::default());
+
+        Ok(Self {
+            state: ProposalPrepared {
+                proposal,
+                _marker: PhantomData,
+            },
+        })
+    }
+
+    pub fn get_proposal(&self) -> &ProposePresentationV2 {
+        &self.state.proposal
+    }
+
+    /// Processes the verifier's answer to our proposal, transitioning to [`ProverV2<RequestReceived>`].
+    pub fn receive_request(self, request: RequestPresentationV2) -> VcxResult<ProverV2<RequestReceived<F>>> {
+        verify_thread_id(
+            self.thread_id(),
+            msg_thread_id(&request.id, request.decorators.thread.as_ref()),
+        )?;
+        ProverV2::from_request(request)
+    }
+}
+
+impl<F> ProverV2<RequestReceived<F>>
+where
+    F: ProverPresentationFormat,
+{
+    /// Processes a request received from a verifier, transitioning to [`ProverV2<RequestReceived>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the request carries no attachment of the request format matching `F`.
+    /// [`super::formats::select_request_format`] can be used beforehand to find out which
+    /// format to handle the request with.
+    pub fn from_request(request: RequestPresentationV2) -> VcxResult<Self> {
+        get_format_attachment_payload(
+            &request.content.formats,
+            &request.content.request_presentations_attach,
+            F::request_format(),
+        )?;
+
+        Ok(Self {
+            state: RequestReceived {
+                request,
+                _marker: PhantomData,
+            },
+        })
+    }
+
+    pub fn get_request(&self) -> &RequestPresentationV2 {
+        &self.state.request
+    }
+
+    /// Looks up the credentials in our wallet which can be used to answer the request.
+    pub async fn get_matching_credentials(&self, profile: &Arc<dyn Profile>) -> VcxResult<F::MatchingCredentials> {
+        F::get_matching_credentials(profile, &self.request_payload()?).await
+    }
+
+    /// Creates the presentation answering the request, transitioning to [`ProverV2<PresentationPrepared>`].
+    pub async fn prepare_presentation(
+        self,
+        profile: &Arc<dyn Profile>,
+        input: &F::CreatePresentationInput,
+    ) -> VcxResult<ProverV2<PresentationPrepared<F>>> {
+        let payload = F::create_presentation_attachment_content(profile, &self.request_payload()?, input).await?;
+        let (format, attachment) = build_format_attachment(F::presentation_format(), payload);
+
+        let content = PresentationV2Content::new(vec![format], vec![attachment]);
+        let decorators = PresentationV2Decorators {
+            please_ack: self
+                .state
+                .request
+                .content
+                .will_confirm
+                .then(|| PleaseAck::new(vec![AckOn::Outcome])),
+            ..PresentationV2Decorators::new(Thread::new(self.thread_id().to_owned()))
+        };
+        let presentation = PresentationV2::with_decorators(uuid(), content, decorators);
+
+        Ok(ProverV2 {
+            state: PresentationPrepared {
+                presentation,
+                _marker: PhantomData,
+            },
+        })
+    }
+
+    /// Returns the content of the request attachment of the format matching `F`.
+    pub fn request_payload(&self) -> VcxResult<Vec<u8>> {
+        let request = &self.state.request;
+        get_format_attachment_payload(
+            &request.content.formats,
+            &request.content.request_presentations_attach,
+            F::request_format(),
+        )
+    }
+}
+
+impl<F> ProverV2<PresentationPrepared<F>>
+where
+    F: ProverPresentationFormat,
+{
+    pub fn get_presentation(&self) -> &PresentationV2 {
+        &self.state.presentation
+    }
+
+    /// Processes the verifier's acknowledgement of our presentation, transitioning to [`ProverV2<Complete>`].
+    pub fn receive_ack(self, ack: AckPresentationV2) -> VcxResult<ProverV2<Complete<F>>> {
+        verify_thread_id(self.thread_id(), &ack.decorators.thread.thid)?;
+
+        Ok(ProverV2 {
+            state: Complete {
+                presentation: self.state.presentation,
+                _marker: PhantomData,
+            },
+        })
+    }
+}
@@ -0,0 +1,61 @@
+use std::marker::PhantomData;
+
+use messages2::msg_fields::protocols::present_proof::v2::{
+    present::PresentationV2, propose::ProposePresentationV2, request::RequestPresentationV2,
+};
+
+use crate::protocols::{
+    common::msg_thread_id, connection::trait_bounds::ThreadId, proof_presentation_v2::formats::ProverPresentationFormat,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "")]
+pub struct ProposalPrepared<F: ProverPresentationFormat> {
+    pub(crate) proposal: ProposePresentationV2,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: ProverPresentationFormat> ThreadId for ProposalPrepared<F> {
+    fn thread_id(&self) -> &str {
+        msg_thread_id(&self.proposal.id, self.proposal.decorators.thread.as_ref())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "")]
+pub struct RequestReceived<F: ProverPresentationFormat> {
+    pub(crate) request: RequestPresentationV2,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: ProverPresentationFormat> ThreadId for RequestReceived<F> {
+    fn thread_id(&self) -> &str {
+        msg_thread_id(&self.request.id, self.request.decorators.thread.as_ref())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "")]
+pub struct PresentationPrepared<F: ProverPresentationFormat> {
+    pub(crate) presentation: PresentationV2,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: ProverPresentationFormat> ThreadId for PresentationPrepared<F> {
+    fn thread_id(&self) -> &str {
+        &self.presentation.decorators.thread.thid
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "")]
+pub struct Complete<F: ProverPresentationFormat> {
+    pub(crate) presentation: PresentationV2,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: ProverPresentationFormat> ThreadId for Complete<F> {
+    fn thread_id(&self) -> &str {
+        &self.presentation.decorators.thread.thid
+    }
+}
@@ -0,0 +1,243 @@
+pub mod states;
+
+use std::{marker::PhantomData, sync::Arc};
+
+use messages2::{
+    decorators::thread::Thread,
+    msg_fields::protocols::{
+        notification::{AckDecorators, AckStatus},
+        present_proof::v2::{
+            ack::{AckPresentationV2, AckPresentationV2Content},
+            present::PresentationV2,
+            problem_report::PresentProofProblemReportV2,
+            propose::ProposePresentationV2,
+            request::{RequestPresentationV2, RequestPresentationV2Content, RequestPresentationV2Decorators},
+        },
+    },
+};
+
+use self::states::{PresentationVerified, ProposalReceived, RequestPrepared};
+use super::{build_problem_report, formats::VerifierPresentationFormat, problem_report_thread_id, Failed};
+use crate::{
+    core::profile::profile::Profile,
+    errors::error::VcxResult,
+    protocols::{
+        common::{build_format_attachment, get_format_attachment_payload, verify_thread_id},
+        connection::trait_bounds::ThreadId,
+        proof_presentation::verifier::verification_status::PresentationVerificationStatus,
+    },
+    utils::uuid::uuid,
+};
+
+/// A state machine for the verifier role of the present proof protocol v2.
+///
+/// The state machine only builds and consumes messages; sending the messages it produces
+/// is left to the caller, e.g: through a [`crate::protocols::connection::Connection`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerifierV2<S> {
+    state: S,
+}
+
+impl<S> VerifierV2<S> {
+    pub fn from_parts(state: S) -> Self {
+        Self { state }
+    }
+
+    pub fn into_parts(self) -> S {
+        self.state
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S> VerifierV2<S>
+where
+    S: ThreadId,
+{
+    pub fn thread_id(&self) -> &str {
+        self.state.thread_id()
+    }
+
+    /// Abandons the presentation, transitioning to [`VerifierV2<Failed>`].
+    /// The problem report to send to the prover is available through [`Failed::problem_report`].
+    pub fn prepare_problem_report(self, description: String) -> VerifierV2<Failed> {
+        let thread_id = self.thread_id().to_owned();
+        let problem_report = build_problem_report(&thread_id, description);
+
+        VerifierV2 {
+            state: Failed::new(thread_id, problem_report),
+        }
+    }
+
+    /// Processes a problem report sent by the prover, transitioning to [`VerifierV2<Failed>`].
+    pub fn receive_problem_report(self, problem_report: PresentProofProblemReportV2) -> VcxResult<VerifierV2<Failed>> {
+        verify_thread_id(self.thread_id(), problem_report_thread_id(&problem_report))?;
+        let thread_id = self.thread_id().to_owned();
+
+        Ok(VerifierV2 {
+            state: Failed::new(thread_id, problem_report),
+        })
+    }
+}
+
+impl<F> VerifierV2<ProposalReceived<F>>
+where
+    F: VerifierPresentationFormat,
+{
+    /// Processes a proposal received from a prover, transitioning to [`VerifierV2<ProposalReceived>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the proposal carries no attachment of the proposal format matching `F`.
+    pub fn from_proposal(proposal: ProposePresentationV2) -> VcxResult<Self> {
+        get_format_attachment_payload(
+            &proposal.content.formats,
+            &proposal.content.proposals_attach,
+            F::proposal_format(),
+        )?;
+
+        Ok(Self {
+            state: ProposalReceived {
+                proposal,
+                _marker: PhantomData,
+            },
+        })
+    }
+
+    pub fn get_proposal(&self) -> &ProposePresentationV2 {
+        &self.state.proposal
+    }
+
+    /// Answers the proposal with a request, transitioning to [`VerifierV2<RequestPrepared>`].
+    pub async fn prepare_request(
+        self,
+        profile: &Arc<dyn Profile>,
+        input: &F::CreateRequestInput,
+        comment: Option<String>,
+    ) -> VcxResult<VerifierV2<RequestPrepared<F>>> {
+        let thread = Thread::new(self.thread_id().to_owned());
+        let request = build_request::<F>(profile, input, comment, Some(thread)).await?;
+
+        Ok(VerifierV2 {
+            state: RequestPrepared {
+                request,
+                _marker: PhantomData,
+            },
+        })
+    }
+}
+
+impl<F> VerifierV2<RequestPrepared<F>>
+where
+    F: VerifierPresentationFormat,
+{
+    /// Creates a request for a presentation of format `F`, starting a new presentation thread.
+    pub async fn with_request(
+        profile: &Arc<dyn Profile>,
+        input: &F::CreateRequestInput,
+        comment: Option<String>,
+    ) -> VcxResult<Self> {
+        let request = build_request::<F>(profile, input, comment, None).await?;
+
+        Ok(Self {
+            state: RequestPrepared {
+                request,
+                _marker: PhantomData,
+            },
+        })
+    }
+
+    pub fn get_request(&self) -> &RequestPresentationV2 {
+        &self.state.request
+    }
+
+    /// Verifies the presentation sent in response to our request, transitioning to
+    /// [`VerifierV2<PresentationVerified>`].
+    ///
+    /// A presentation that does not satisfy the request is not an error; it results in a
+    /// [`PresentationVerificationStatus::Invalid`] status.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the presentation belongs to another thread, carries no attachment
+    /// of the presentation format matching `F` or if the verification itself failed.
+    pub async fn verify_presentation(
+        self,
+        profile: &Arc<dyn Profile>,
+        presentation: PresentationV2,
+    ) -> VcxResult<VerifierV2<PresentationVerified<F>>> {
+        verify_thread_id(self.thread_id(), &presentation.decorators.thread.thid)?;
+
+        let request = &self.state.request;
+        let request_payload = get_format_attachment_payload(
+            &request.content.formats,
+            &request.content.request_presentations_attach,
+            F::request_format(),
+        )?;
+        let presentation_payload = get_format_attachment_payload(
+            &presentation.content.formats,
+            &presentation.content.presentations_attach,
+            F::presentation_format(),
+        )?;
+
+        let verification_status = if F::verify_presentation(profile, &request_payload, &presentation_payload).await? {
+            PresentationVerificationStatus::Valid
+        } else {
+            PresentationVerificationStatus::Invalid
+        };
+
+        Ok(VerifierV2 {
+            state: PresentationVerified {
+                request: self.state.request,
+                presentation,
+                verification_status,
+                _marker: PhantomData,
+            },
+        })
+    }
+}
+
+impl<F> VerifierV2<PresentationVerified<F>>
+where
+    F: VerifierPresentationFormat,
+{
+    pub fn get_presentation(&self) -> &PresentationV2 {
+        &self.state.presentation
+    }
+
+    pub fn verification_status(&self) -> &PresentationVerificationStatus {
+        &self.state.verification_status
+    }
+
+    /// Builds the acknowledgement of the received presentation.
+    pub fn get_ack(&self) -> AckPresentationV2 {
+        let content = AckPresentationV2Content::new(AckStatus::Ok);
+        let decorators = AckDecorators::new(Thread::new(self.thread_id().to_owned()));
+
+        AckPresentationV2::with_decorators(uuid(), content, decorators)
+    }
+}
+
+async fn build_request<F>(
+    profile: &Arc<dyn Profile>,
+    input: &F::CreateRequestInput,
+    comment: Option<String>,
+    thread: Option<Thread>,
+) -> VcxResult<RequestPresentationV2>
+where
+    F: VerifierPresentationFormat,
+{
+    let payload = F::create_request_attachment_content(profile, input).await?;
+    let (format, attachment) = build_format_attachment(F::request_format(), payload);
+
+    let content = RequestPresentationV2Content {
+        comment,
+        will_confirm: true,
+        ..RequestPresentationV2Content::new(vec![format], vec![attachment])
+    };
+    let decorators = RequestPresentationV2Decorators { thread, timing: None };
+
+    Ok(RequestPresentationV2::with_decorators(uuid(), content, decorators))
+}
@@ -0,0 +1,52 @@
+use std::marker::PhantomData;
+
+use messages2::msg_fields::protocols::present_proof::v2::{
+    present::PresentationV2, propose::ProposePresentationV2, request::RequestPresentationV2,
+};
+
+use crate::protocols::{
+    common::msg_thread_id, connection::trait_bounds::ThreadId,
+    proof_presentation::verifier::verification_status::PresentationVerificationStatus,
+    proof_presentation_v2::formats::VerifierPresentationFormat,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "")]
+pub struct ProposalReceived<F: VerifierPresentationFormat> {
+    pub(crate) proposal: ProposePresentationV2,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: VerifierPresentationFormat> ThreadId for ProposalReceived<F> {
+    fn thread_id(&self) -> &str {
+        msg_thread_id(&self.proposal.id, self.proposal.decorators.thread.as_ref())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "")]
+pub struct RequestPrepared<F: VerifierPresentationFormat> {
+    pub(crate) request: RequestPresentationV2,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: VerifierPresentationFormat> ThreadId for RequestPrepared<F> {
+    fn thread_id(&self) -> &str {
+        msg_thread_id(&self.request.id, self.request.decorators.thread.as_ref())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "")]
+pub struct PresentationVerified<F: VerifierPresentationFormat> {
+    pub(crate) request: RequestPresentationV2,
+    pub(crate) presentation: PresentationV2,
+    pub(crate) verification_status: PresentationVerificationStatus,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: VerifierPresentationFormat> ThreadId for PresentationVerified<F> {
+    fn thread_id(&self) -> &str {
+        &self.presentation.decorators.thread.thid
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use agency_client::httpclient::post_message;
+use async_trait::async_trait;
+
+use super::{DuplexTransport, Transport};
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// Sends messages as HTTP POST requests. The response body to a message sent with
+/// [`DuplexTransport::send_message_with_return_route`] is the message the recipient returned on
+/// the same request, and is kept until [`DuplexTransport::receive_message`] is called for the endpoint.
+/// Response bodies to any other message are discarded.
+#[derive(Debug, Default)]
+pub struct HttpTransport {
+    returned: Mutex<HashMap<String, VecDeque<Vec<u8>>>>,
+}
+
+impl HttpTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &str) -> VcxResult<()> {
+        post_message(msg, service_endpoint).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DuplexTransport for HttpTransport {
+    async fn receive_message(&self, service_endpoint: &str) -> VcxResult<Option<Vec<u8>>> {
+        Ok(self
+            .returned
+            .lock()
+            .map_err(|_| lock_error())?
+            .get_mut(service_endpoint)
+            .and_then(VecDeque::pop_front))
+    }
+
+    async fn send_message_with_return_route(&self, msg: Vec<u8>, service_endpoint: &str) -> VcxResult<()> {
+        let response = post_message(msg, service_endpoint).await?;
+        if !response.is_empty() {
+            self.returned
+                .lock()
+                .map_err(|_| lock_error())?
+                .entry(service_endpoint.to_owned())
+                .or_default()
+                .push_back(response);
+        }
+        Ok(())
+    }
+}
+
+fn lock_error() -> AriesVcxError {
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::InvalidState,
+        "Unable to lock the messages returned over HTTP",
+    )
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use agency_client::testing::mocking::{disable_agency_mocks, enable_agency_mocks, HttpClientMockResponse};
+
+    use super::*;
+
+    const ENDPOINT: &str = "http://localhost:8080";
+
+    #[tokio::test]
+    async fn test_http_transport_returns_response_bodies() {
+        enable_agency_mocks();
+        let transport = HttpTransport::new();
+
+        HttpClientMockResponse::set_next_response(Ok(b"returned".to_vec()));
+        transport
+            .send_message_with_return_route(b"sent".to_vec(), ENDPOINT)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            transport.receive_message(ENDPOINT).await.unwrap(),
+            Some(b"returned".to_vec())
+        );
+        assert_eq!(transport.receive_message(ENDPOINT).await.unwrap(), None);
+        disable_agency_mocks();
+    }
+
+    #[tokio::test]
+    async fn test_http_transport_discards_response_bodies_without_return_route() {
+        enable_agency_mocks();
+        let transport = HttpTransport::new();
+
+        HttpClientMockResponse::set_next_response(Ok(b"unrelated".to_vec()));
+        transport.send_message(b"sent".to_vec(), ENDPOINT).await.unwrap();
+
+        assert_eq!(transport.receive_message(ENDPOINT).await.unwrap(), None);
+        disable_agency_mocks();
+    }
+}
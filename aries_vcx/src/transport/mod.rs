@@ -0,0 +1,118 @@
+pub mod http;
+pub mod outbox;
+#[cfg(feature = "websocket")]
+pub mod websocket;
+
+use std::ops::Deref;
+
+use async_trait::async_trait;
+use messages::a2a::A2AMessage;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// Trait used for implementing a mechanism to send a message, used by [`crate::protocols::connection::Connection`].
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &str) -> VcxResult<()>;
+}
+
+// While in many cases the auto-dereferencing does the trick,
+// this implementation aids in using things such as a trait object
+// when a generic parameter is expected.
+#[async_trait]
+impl<T> Transport for &T
+where
+    T: Transport + ?Sized,
+{
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &str) -> VcxResult<()> {
+        self.send_message(msg, service_endpoint).await
+    }
+}
+
+/// A [`Transport`] which can also hand back the messages the remote party returns over the
+/// channel a message was sent through, as requested by the `~transport` decorator with
+/// `return_route: all`.
+///
+/// This lets agents without a publicly reachable endpoint receive replies.
+#[async_trait]
+pub trait DuplexTransport: Transport {
+    /// Waits for the next message returned by the party at `service_endpoint`.
+    /// Returns `None` if there is nothing left to receive from it. Implementations bound the wait
+    /// and fail with [`AriesVcxErrorKind::Timeout`] when no message arrives in time.
+    async fn receive_message(&self, service_endpoint: &str) -> VcxResult<Option<Vec<u8>>>;
+
+    /// Sends a message decorated with `return_route: all`, for transports which only keep the
+    /// messages returned on such requests. Defaults to [`Transport::send_message`].
+    async fn send_message_with_return_route(&self, msg: Vec<u8>, service_endpoint: &str) -> VcxResult<()> {
+        self.send_message(msg, service_endpoint).await
+    }
+}
+
+#[async_trait]
+impl<T> DuplexTransport for &T
+where
+    T: DuplexTransport + ?Sized,
+{
+    async fn receive_message(&self, service_endpoint: &str) -> VcxResult<Option<Vec<u8>>> {
+        (**self).receive_message(service_endpoint).await
+    }
+
+    async fn send_message_with_return_route(&self, msg: Vec<u8>, service_endpoint: &str) -> VcxResult<()> {
+        (**self).send_message_with_return_route(msg, service_endpoint).await
+    }
+}
+
+/// Sends every message through [`DuplexTransport::send_message_with_return_route`] of the referenced
+/// transport, so that message wrapping code written against [`Transport`] can be reused for return
+/// routed messages.
+pub(crate) struct ReturnRouted<P>(pub P);
+
+#[async_trait]
+impl<P> Transport for ReturnRouted<P>
+where
+    P: Deref + Send + Sync,
+    P::Target: DuplexTransport,
+{
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &str) -> VcxResult<()> {
+        self.0.send_message_with_return_route(msg, service_endpoint).await
+    }
+}
+
+/// Adds the `~transport` decorator with `return_route: all` to the message, asking the recipient
+/// to send any reply over the same channel the message arrived on.
+pub fn with_return_route(message: &A2AMessage) -> VcxResult<A2AMessage> {
+    let mut value = serde_json::to_value(message)?;
+    match value.as_object_mut() {
+        Some(obj) => {
+            obj.insert("~transport".to_owned(), json!({ "return_route": "all" }));
+            Ok(A2AMessage::Generic(value))
+        }
+        None => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!(
+                "Cannot add return route to a message which is not a JSON object: {}",
+                value
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use messages::concepts::ack::Ack;
+
+    use super::*;
+
+    #[test]
+    fn test_with_return_route_adds_transport_decorator() {
+        let ack = Ack::create().set_thread_id("thread_id");
+        let message = with_return_route(&A2AMessage::Ack(ack)).unwrap();
+
+        let A2AMessage::Generic(value) = message else {
+            panic!("expected a generic message");
+        };
+        assert_eq!(value["~transport"]["return_route"], "all");
+        assert_eq!(value["~thread"]["thid"], "thread_id");
+    }
+}
@@ -0,0 +1,489 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use super::Transport;
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    plugins::wallet::base_wallet::{AsyncFnIteratorCollect, BaseWallet},
+    utils::{
+        json::{AsTypeOrDeserializationError, TryGetIndex},
+        uuid::uuid,
+    },
+};
+
+const OUTBOX_RECORD_TYPE: &str = "aries-vcx-outbox";
+
+/// Controls how long a single delivery attempt may take and how failed attempts are retried.
+///
+/// The delay before retry `n` is `initial_backoff * backoff_multiplier^(n - 1)`, capped at `max_backoff`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub timeout: Duration,
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub backoff_multiplier: u32,
+}
+
+impl RetryPolicy {
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self.backoff_multiplier.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            backoff_multiplier: 2,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Not delivered yet, either because no attempt finished yet or because
+    /// the attempts were interrupted.
+    Pending,
+    Delivered {
+        delivered_at: DateTime<Utc>,
+    },
+    /// All attempts allowed by the [`RetryPolicy`] failed. The message stays in the outbox
+    /// and is attempted again by [`OutboxTransport::retry_undelivered`].
+    Failed {
+        error: String,
+    },
+}
+
+impl DeliveryStatus {
+    fn as_tag(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Delivered { .. } => "delivered",
+            Self::Failed { .. } => "failed",
+        }
+    }
+}
+
+/// A message kept in the outbox along with the outcome of its delivery.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub id: String,
+    pub service_endpoint: String,
+    #[serde(with = "base64_payload")]
+    pub payload: Vec<u8>,
+    pub attempts: u32,
+    #[serde(flatten)]
+    pub status: DeliveryStatus,
+}
+
+/// Wraps a [`Transport`], persisting every outbound message in the wallet before delivering it
+/// and retrying failed deliveries according to a [`RetryPolicy`].
+///
+/// Messages which could not be delivered stay in the outbox, so they can be sent again later
+/// with [`OutboxTransport::retry_undelivered`] instead of restarting the protocol which produced them.
+pub struct OutboxTransport<T> {
+    inner: T,
+    wallet: Arc<dyn BaseWallet>,
+    policy: RetryPolicy,
+}
+
+impl<T> OutboxTransport<T>
+where
+    T: Transport,
+{
+    pub fn new(inner: T, wallet: Arc<dyn BaseWallet>, policy: RetryPolicy) -> Self {
+        Self { inner, wallet, policy }
+    }
+
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    /// Stores the message in the outbox under `message_id` and attempts to deliver it.
+    /// The returned status is also available later through [`OutboxTransport::delivery_status`].
+    pub async fn send_message_with_id(
+        &self,
+        message_id: &str,
+        msg: Vec<u8>,
+        service_endpoint: &str,
+    ) -> VcxResult<DeliveryStatus> {
+        let entry = OutboxEntry {
+            id: message_id.to_owned(),
+            service_endpoint: service_endpoint.to_owned(),
+            payload: msg,
+            attempts: 0,
+            status: DeliveryStatus::Pending,
+        };
+        let value = serde_json::to_string(&entry)?;
+        self.wallet
+            .add_wallet_record(
+                OUTBOX_RECORD_TYPE,
+                message_id,
+                &value,
+                Some(&status_tags(&entry.status)),
+            )
+            .await?;
+        self.deliver(entry).await
+    }
+
+    pub async fn get_entry(&self, message_id: &str) -> VcxResult<OutboxEntry> {
+        let record = self
+            .wallet
+            .get_wallet_record(OUTBOX_RECORD_TYPE, message_id, "{}")
+            .await?;
+        parse_entry(&record)
+    }
+
+    pub async fn delivery_status(&self, message_id: &str) -> VcxResult<DeliveryStatus> {
+        Ok(self.get_entry(message_id).await?.status)
+    }
+
+    /// Returns all messages in the outbox which were not delivered yet.
+    pub async fn undelivered(&self) -> VcxResult<Vec<OutboxEntry>> {
+        let query = json!({ "status": { "$in": ["pending", "failed"] } }).to_string();
+        let mut records = self
+            .wallet
+            .iterate_wallet_records(OUTBOX_RECORD_TYPE, &query, "{}")
+            .await?;
+        records
+            .collect()
+            .await?
+            .iter()
+            .map(|record| parse_entry(record))
+            .collect()
+    }
+
+    /// Attempts to deliver every message in the outbox which was not delivered yet,
+    /// returning the new delivery status of each by message id.
+    pub async fn retry_undelivered(&self) -> VcxResult<Vec<(String, DeliveryStatus)>> {
+        let mut statuses = Vec::new();
+        for entry in self.undelivered().await? {
+            let id = entry.id.clone();
+            statuses.push((id, self.deliver(entry).await?));
+        }
+        Ok(statuses)
+    }
+
+    /// Removes the message from the outbox, regardless of its delivery status.
+    pub async fn remove(&self, message_id: &str) -> VcxResult<()> {
+        self.wallet.delete_wallet_record(OUTBOX_RECORD_TYPE, message_id).await
+    }
+
+    /// Removes every delivered message from the outbox, returning how many were removed.
+    pub async fn purge_delivered(&self) -> VcxResult<usize> {
+        let query = json!({ "status": "delivered" }).to_string();
+        let mut records = self
+            .wallet
+            .iterate_wallet_records(OUTBOX_RECORD_TYPE, &query, "{}")
+            .await?;
+        let delivered = records
+            .collect()
+            .await?
+            .iter()
+            .map(|record| parse_entry(record))
+            .collect::<VcxResult<Vec<_>>>()?;
+        for entry in delivered.iter() {
+            self.remove(&entry.id).await?;
+        }
+        Ok(delivered.len())
+    }
+
+    async fn deliver(&self, mut entry: OutboxEntry) -> VcxResult<DeliveryStatus> {
+        let first_attempt = entry.attempts;
+        loop {
+            entry.attempts += 1;
+            let attempt = entry.attempts - first_attempt;
+            match self.attempt(&entry).await {
+                Ok(()) => {
+                    entry.status = DeliveryStatus::Delivered {
+                        delivered_at: Utc::now(),
+                    };
+                    break;
+                }
+                Err(err) if attempt >= self.policy.max_attempts => {
+                    warn!(
+                        "OutboxTransport::deliver >>> giving up on message {} after {} attempts: {}",
+                        entry.id, attempt, err
+                    );
+                    entry.status = DeliveryStatus::Failed { error: err.to_string() };
+                    break;
+                }
+                Err(err) => {
+                    let backoff = self.policy.backoff(attempt);
+                    debug!(
+                        "OutboxTransport::deliver >>> attempt {} to deliver message {} failed: {}, retrying in {:?}",
+                        attempt, entry.id, err, backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                }
+            }
+        }
+        self.update_entry(&entry).await?;
+        Ok(entry.status)
+    }
+
+    async fn attempt(&self, entry: &OutboxEntry) -> VcxResult<()> {
+        let send = self.inner.send_message(entry.payload.clone(), &entry.service_endpoint);
+        tokio::time::timeout(self.policy.timeout, send).await.map_err(|_| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::PostMessageFailed,
+                format!(
+                    "Sending message to {} timed out after {:?}",
+                    entry.service_endpoint, self.policy.timeout
+                ),
+            )
+        })?
+    }
+
+    async fn update_entry(&self, entry: &OutboxEntry) -> VcxResult<()> {
+        let value = serde_json::to_string(entry)?;
+        self.wallet
+            .update_wallet_record_value(OUTBOX_RECORD_TYPE, &entry.id, &value)
+            .await?;
+        self.wallet
+            .update_wallet_record_tags(OUTBOX_RECORD_TYPE, &entry.id, &status_tags(&entry.status))
+            .await
+    }
+}
+
+/// Messages sent through the [`Transport`] interface are stored under a generated id which nobody
+/// can look up, so they only stay in the outbox while their delivery is being attempted.
+/// `Ok` means the message was delivered. If all attempts allowed by the [`RetryPolicy`] fail,
+/// the message is removed from the outbox and an error is returned, leaving any further retries
+/// to the caller so that the message is never sent twice.
+/// Use [`OutboxTransport::send_message_with_id`] to keep undelivered messages for
+/// [`OutboxTransport::retry_undelivered`].
+#[async_trait]
+impl<T> Transport for OutboxTransport<T>
+where
+    T: Transport,
+{
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &str) -> VcxResult<()> {
+        let message_id = uuid();
+        match self.send_message_with_id(&message_id, msg, service_endpoint).await? {
+            DeliveryStatus::Failed { error } => {
+                self.remove(&message_id).await?;
+                Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::PostMessageFailed,
+                    format!("Failed to deliver message {}: {}", message_id, error),
+                ))
+            }
+            DeliveryStatus::Delivered { .. } => self.remove(&message_id).await,
+            DeliveryStatus::Pending => Ok(()),
+        }
+    }
+}
+
+fn status_tags(status: &DeliveryStatus) -> String {
+    json!({ "status": status.as_tag() }).to_string()
+}
+
+fn parse_entry(record: &str) -> VcxResult<OutboxEntry> {
+    let record: Value = serde_json::from_str(record)?;
+    let value = (&record).try_get("value")?;
+    Ok(serde_json::from_str(value.try_as_str()?)?)
+}
+
+mod base64_payload {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(payload: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&base64::encode(payload))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let encoded = String::deserialize(deserializer)?;
+        base64::decode(&encoded).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{
+        common::test_utils::indy_handles_to_profile, indy::utils::test_setup::with_wallet, utils::devsetup::SetupEmpty,
+    };
+
+    const ENDPOINT: &str = "http://localhost:8080";
+
+    /// Fails the given number of sends before delivering messages.
+    struct FlakyTransport {
+        failures_left: Mutex<u32>,
+        delivered: Mutex<Vec<Vec<u8>>>,
+    }
+
+    impl FlakyTransport {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures_left: Mutex::new(failures),
+                delivered: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Transport for FlakyTransport {
+        async fn send_message(&self, msg: Vec<u8>, _service_endpoint: &str) -> VcxResult<()> {
+            let mut failures_left = self.failures_left.lock()?;
+            if *failures_left > 0 {
+                *failures_left -= 1;
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::PostMessageFailed,
+                    "endpoint unavailable",
+                ));
+            }
+            self.delivered.lock()?.push(msg);
+            Ok(())
+        }
+    }
+
+    fn no_backoff(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::ZERO,
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_retry_policy_backoff_is_exponential_and_capped() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            backoff_multiplier: 2,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_outbox_retries_until_delivered() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let wallet = indy_handles_to_profile(wallet_handle, 0).inject_wallet();
+            let outbox = OutboxTransport::new(FlakyTransport::new(2), wallet, no_backoff(3));
+
+            let status = outbox
+                .send_message_with_id("msg_1", b"hello".to_vec(), ENDPOINT)
+                .await
+                .unwrap();
+
+            assert!(matches!(status, DeliveryStatus::Delivered { .. }));
+            let entry = outbox.get_entry("msg_1").await.unwrap();
+            assert_eq!(entry.attempts, 3);
+            assert_eq!(entry.status, status);
+            assert_eq!(*outbox.inner.delivered.lock().unwrap(), vec![b"hello".to_vec()]);
+            assert!(outbox.undelivered().await.unwrap().is_empty());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_outbox_keeps_failed_messages_for_retry() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let wallet = indy_handles_to_profile(wallet_handle, 0).inject_wallet();
+            let outbox = OutboxTransport::new(FlakyTransport::new(3), wallet, no_backoff(2));
+
+            let status = outbox
+                .send_message_with_id("msg_1", b"hello".to_vec(), ENDPOINT)
+                .await
+                .unwrap();
+            assert!(matches!(status, DeliveryStatus::Failed { .. }));
+            assert_eq!(outbox.delivery_status("msg_1").await.unwrap(), status);
+
+            let undelivered = outbox.undelivered().await.unwrap();
+            assert_eq!(undelivered.len(), 1);
+            assert_eq!(undelivered[0].payload, b"hello".to_vec());
+
+            let statuses = outbox.retry_undelivered().await.unwrap();
+            assert_eq!(statuses.len(), 1);
+            assert_eq!(statuses[0].0, "msg_1");
+            assert!(matches!(statuses[0].1, DeliveryStatus::Delivered { .. }));
+            assert_eq!(outbox.get_entry("msg_1").await.unwrap().attempts, 4);
+
+            outbox.remove("msg_1").await.unwrap();
+            assert!(outbox.get_entry("msg_1").await.is_err());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_outbox_transport_removes_sent_messages() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let wallet = indy_handles_to_profile(wallet_handle, 0).inject_wallet();
+            let outbox = OutboxTransport::new(FlakyTransport::new(1), wallet, no_backoff(1));
+
+            // Neither the failed nor the delivered message is kept, the caller retries failures
+            let err = outbox.send_message(b"failed".to_vec(), ENDPOINT).await.unwrap_err();
+            assert_eq!(err.kind(), AriesVcxErrorKind::PostMessageFailed);
+            outbox.send_message(b"delivered".to_vec(), ENDPOINT).await.unwrap();
+            assert_eq!(*outbox.inner.delivered.lock().unwrap(), vec![b"delivered".to_vec()]);
+
+            let query = json!({}).to_string();
+            let records = outbox
+                .wallet
+                .iterate_wallet_records(OUTBOX_RECORD_TYPE, &query, "{}")
+                .await
+                .unwrap()
+                .collect()
+                .await
+                .unwrap();
+            assert!(records.is_empty());
+            assert!(outbox.retry_undelivered().await.unwrap().is_empty());
+            assert_eq!(outbox.inner.delivered.lock().unwrap().len(), 1);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_outbox_purges_delivered_messages() {
+        SetupEmpty::init();
+        with_wallet(|wallet_handle| async move {
+            let wallet = indy_handles_to_profile(wallet_handle, 0).inject_wallet();
+            let outbox = OutboxTransport::new(FlakyTransport::new(1), wallet, no_backoff(1));
+
+            let status = outbox
+                .send_message_with_id("msg_1", b"failed".to_vec(), ENDPOINT)
+                .await
+                .unwrap();
+            assert!(matches!(status, DeliveryStatus::Failed { .. }));
+            let status = outbox
+                .send_message_with_id("msg_2", b"delivered".to_vec(), ENDPOINT)
+                .await
+                .unwrap();
+            assert!(matches!(status, DeliveryStatus::Delivered { .. }));
+
+            assert_eq!(outbox.purge_delivered().await.unwrap(), 1);
+            assert!(outbox.get_entry("msg_2").await.is_err());
+            assert!(matches!(
+                outbox.delivery_status("msg_1").await.unwrap(),
+                DeliveryStatus::Failed { .. }
+            ));
+        })
+        .await;
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::{hash_map::Entry, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use tokio::{
+    net::TcpStream,
+    sync::{mpsc, Mutex},
+};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use super::{DuplexTransport, Transport};
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+type WsSink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+type WsStream = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
+
+const DEFAULT_RECEIVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Open socket to a single endpoint. Inbound frames are read by a background task
+/// and queued until they are received.
+struct WsChannel {
+    sink: Mutex<WsSink>,
+    inbound: Mutex<mpsc::UnboundedReceiver<Vec<u8>>>,
+}
+
+/// Sends messages over WebSockets, keeping one socket open per service endpoint.
+///
+/// Messages returned by the remote party on a socket, typically replies to messages
+/// decorated with `return_route: all`, are handed back through [`DuplexTransport::receive_message`],
+/// which gives up after the receive timeout, 30 seconds unless set with [`WsTransport::with_receive_timeout`].
+pub struct WsTransport {
+    channels: Mutex<HashMap<String, Arc<WsChannel>>>,
+    receive_timeout: Duration,
+}
+
+impl Default for WsTransport {
+    fn default() -> Self {
+        Self {
+            channels: Default::default(),
+            receive_timeout: DEFAULT_RECEIVE_TIMEOUT,
+        }
+    }
+}
+
+fn spawn_reader(endpoint: String, mut stream: WsStream, sender: mpsc::UnboundedSender<Vec<u8>>) {
+    tokio::spawn(async move {
+        while let Some(frame) = stream.next().await {
+            let data = match frame {
+                Ok(Message::Binary(data)) => data,
+                Ok(Message::Text(text)) => text.into_bytes(),
+                Ok(Message::Close(_)) => break,
+                Ok(_) => continue,
+                Err(err) => {
+                    warn!("WebSocket to {} failed: {}", endpoint, err);
+                    break;
+                }
+            };
+            if sender.send(data).is_err() {
+                break;
+            }
+        }
+        debug!("WebSocket to {} closed", endpoint);
+    });
+}
+
+impl WsTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long [`DuplexTransport::receive_message`] waits for a message before failing with
+    /// [`AriesVcxErrorKind::Timeout`].
+    pub fn with_receive_timeout(mut self, receive_timeout: Duration) -> Self {
+        self.receive_timeout = receive_timeout;
+        self
+    }
+
+    /// Closes the socket opened to `service_endpoint`, if any.
+    pub async fn close(&self, service_endpoint: &str) -> VcxResult<()> {
+        let channel = self.channels.lock().await.remove(service_endpoint);
+        if let Some(channel) = channel {
+            channel
+                .sink
+                .lock()
+                .await
+                .close()
+                .await
+                .map_err(|err| ws_error(service_endpoint, err))?;
+        }
+        Ok(())
+    }
+
+    async fn channel(&self, service_endpoint: &str) -> VcxResult<Arc<WsChannel>> {
+        if let Some(channel) = self.channels.lock().await.get(service_endpoint) {
+            return Ok(Arc::clone(channel));
+        }
+
+        // Connect without holding the lock, so that a slow handshake does not stall
+        // messages to other endpoints.
+        let (socket, _) = connect_async(service_endpoint)
+            .await
+            .map_err(|err| ws_error(service_endpoint, err))?;
+        let (mut sink, stream) = socket.split();
+
+        let existing = match self.channels.lock().await.entry(service_endpoint.to_owned()) {
+            Entry::Occupied(entry) => Arc::clone(entry.get()),
+            Entry::Vacant(entry) => {
+                let (sender, receiver) = mpsc::unbounded_channel();
+                spawn_reader(service_endpoint.to_owned(), stream, sender);
+                let channel = Arc::new(WsChannel {
+                    sink: Mutex::new(sink),
+                    inbound: Mutex::new(receiver),
+                });
+                entry.insert(Arc::clone(&channel));
+                return Ok(channel);
+            }
+        };
+
+        // Another task connected to the endpoint in the meantime, keep its socket.
+        if let Err(err) = sink.close().await {
+            debug!("Failed to close redundant WebSocket to {}: {}", service_endpoint, err);
+        }
+        Ok(existing)
+    }
+
+    async fn drop_channel(&self, service_endpoint: &str) {
+        self.channels.lock().await.remove(service_endpoint);
+    }
+}
+
+#[async_trait]
+impl Transport for WsTransport {
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &str) -> VcxResult<()> {
+        let channel = self.channel(service_endpoint).await?;
+        let res = channel.sink.lock().await.send(Message::Binary(msg)).await;
+        if let Err(err) = res {
+            // The socket is unusable, the next message opens a new one
+            self.drop_channel(service_endpoint).await;
+            return Err(ws_error(service_endpoint, err));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DuplexTransport for WsTransport {
+    async fn receive_message(&self, service_endpoint: &str) -> VcxResult<Option<Vec<u8>>> {
+        let channel = match self.channels.lock().await.get(service_endpoint) {
+            Some(channel) => Arc::clone(channel),
+            None => return Ok(None),
+        };
+        let msg = tokio::time::timeout(self.receive_timeout, async {
+            channel.inbound.lock().await.recv().await
+        })
+        .await
+        .map_err(|_| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::Timeout,
+                format!(
+                    "No message received from {} within {:?}",
+                    service_endpoint, self.receive_timeout
+                ),
+            )
+        })?;
+        if msg.is_none() {
+            self.drop_channel(service_endpoint).await;
+        }
+        Ok(msg)
+    }
+}
+
+fn ws_error<E>(service_endpoint: &str, err: E) -> AriesVcxError
+where
+    E: std::fmt::Display,
+{
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::PostMessageFailed,
+        format!("WebSocket communication with {} failed: {}", service_endpoint, err),
+    )
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    use super::*;
+
+    /// Accepts a single WebSocket connection and answers every binary frame with the output of `reply`.
+    async fn serve_once<F>(reply: F) -> String
+    where
+        F: Fn(Vec<u8>) -> Option<Vec<u8>> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            while let Some(Ok(Message::Binary(data))) = socket.next().await {
+                if let Some(reply) = reply(data) {
+                    socket.send(Message::Binary(reply)).await.unwrap();
+                }
+            }
+        });
+        format!("ws://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_ws_transport_receives_replies_over_the_same_socket() {
+        let endpoint = serve_once(|data| Some([b"reply to ".to_vec(), data].concat())).await;
+        let transport = WsTransport::new();
+
+        transport.send_message(b"ping".to_vec(), &endpoint).await.unwrap();
+        assert_eq!(
+            transport.receive_message(&endpoint).await.unwrap(),
+            Some(b"reply to ping".to_vec())
+        );
+        transport.send_message(b"pong".to_vec(), &endpoint).await.unwrap();
+        assert_eq!(
+            transport.receive_message(&endpoint).await.unwrap(),
+            Some(b"reply to pong".to_vec())
+        );
+
+        transport.close(&endpoint).await.unwrap();
+        assert_eq!(transport.receive_message(&endpoint).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_ws_transport_receive_times_out() {
+        let endpoint = serve_once(|_| None).await;
+        let transport = WsTransport::new().with_receive_timeout(Duration::from_millis(100));
+
+        transport.send_message(b"ping".to_vec(), &endpoint).await.unwrap();
+        let err = transport.receive_message(&endpoint).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::Timeout);
+    }
+}
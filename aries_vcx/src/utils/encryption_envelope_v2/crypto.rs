@@ -0,0 +1,233 @@
+//! Cryptographic primitives of DIDComm v2 JWEs, built on top of the aries-askar key management.
+
+use aries_askar::crypto::alg::{AesTypes, Chacha20Types};
+use aries_askar::kms::{KeyAlg, LocalKey};
+use openssl::sha::{sha256, Sha256};
+
+use crate::errors::error::prelude::*;
+
+const KEY_WRAP_KEY_LEN_BITS: u32 = 256;
+
+/// Content encryption algorithms supported for DIDComm v2 JWEs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContentEncryptionAlgorithm {
+    #[serde(rename = "A256CBC-HS512")]
+    A256CbcHs512,
+    #[serde(rename = "XC20P")]
+    Xc20P,
+}
+
+impl ContentEncryptionAlgorithm {
+    fn key_alg(&self) -> KeyAlg {
+        match self {
+            Self::A256CbcHs512 => KeyAlg::Aes(AesTypes::A256CbcHs512),
+            Self::Xc20P => KeyAlg::Chacha20(Chacha20Types::XC20P),
+        }
+    }
+
+    /// Generates a random content encryption key.
+    pub(super) fn generate_key(&self) -> VcxResult<LocalKey> {
+        Ok(LocalKey::generate(self.key_alg(), true)?)
+    }
+}
+
+/// Encrypts `plaintext` under `cek`, returning the IV, the ciphertext and the authentication tag.
+pub(super) fn encrypt_content(cek: &LocalKey, plaintext: &[u8], aad: &[u8]) -> VcxResult<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+    let iv = cek.aead_random_nonce()?;
+    let encrypted = cek.aead_encrypt(plaintext, &iv, aad)?;
+    Ok((iv, encrypted.ciphertext().to_vec(), encrypted.tag().to_vec()))
+}
+
+pub(super) fn decrypt_content(
+    cek: &LocalKey,
+    iv: &[u8],
+    ciphertext: &[u8],
+    tag: &[u8],
+    aad: &[u8],
+) -> VcxResult<Vec<u8>> {
+    let plaintext = cek.aead_decrypt((ciphertext, tag), iv, aad).map_err(|_| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            "Message authentication failed, the tag of the JWE does not match",
+        )
+    })?;
+    Ok(plaintext.to_vec())
+}
+
+/// X25519 Diffie-Hellman between the secret `key` and the raw `public_key`. Askar only exposes key exchange
+/// as the creation of a symmetric key, a 256-bit AES key wrapping key holds the raw shared secret as is.
+pub(crate) fn x25519_diffie_hellman(key: &LocalKey, public_key: &[u8]) -> VcxResult<Vec<u8>> {
+    let public_key = LocalKey::from_public_bytes(KeyAlg::X25519, public_key)?;
+    let shared_secret = key.to_key_exchange(KeyAlg::Aes(AesTypes::A256Kw), &public_key)?;
+    Ok(shared_secret.to_secret_bytes()?.to_vec())
+}
+
+/// An ephemeral X25519 key pair, whose public key is sent along with a JWE as its `epk`.
+pub(super) struct EphemeralKey(LocalKey);
+
+impl EphemeralKey {
+    pub(super) fn generate() -> VcxResult<Self> {
+        Ok(Self(LocalKey::generate(KeyAlg::X25519, true)?))
+    }
+
+    pub(super) fn public_key(&self) -> VcxResult<Vec<u8>> {
+        Ok(self.0.to_public_bytes()?.to_vec())
+    }
+
+    pub(super) fn diffie_hellman(&self, public_key: &[u8]) -> VcxResult<Vec<u8>> {
+        x25519_diffie_hellman(&self.0, public_key)
+    }
+}
+
+/// Concat KDF of NIST SP 800-56A, as profiled by RFC 7518 (section 4.6.2) for ECDH-ES, and by
+/// ECDH-1PU for key wrapping, where the content encryption tag is appended to `SuppPubInfo`.
+pub(super) fn concat_kdf(shared_secret: &[u8], alg: &str, apu: &[u8], apv: &[u8], cc_tag: Option<&[u8]>) -> [u8; 32] {
+    fn length_prefixed(hasher: &mut Sha256, data: &[u8]) {
+        hasher.update(&(data.len() as u32).to_be_bytes());
+        hasher.update(data);
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(&1u32.to_be_bytes());
+    hasher.update(shared_secret);
+    length_prefixed(&mut hasher, alg.as_bytes());
+    length_prefixed(&mut hasher, apu);
+    length_prefixed(&mut hasher, apv);
+    hasher.update(&KEY_WRAP_KEY_LEN_BITS.to_be_bytes());
+    if let Some(cc_tag) = cc_tag {
+        length_prefixed(&mut hasher, cc_tag);
+    }
+    hasher.finish()
+}
+
+/// Wraps `cek` with A256KW under the key encryption key `kek`.
+pub(super) fn aes_key_wrap(kek: &[u8], cek: &LocalKey) -> VcxResult<Vec<u8>> {
+    let kek = LocalKey::from_secret_bytes(KeyAlg::Aes(AesTypes::A256Kw), kek)?;
+    let wrapped = kek.wrap_key(cek, &[]).map_err(|_| key_wrap_error())?;
+    Ok([wrapped.ciphertext(), wrapped.tag()].concat())
+}
+
+/// Unwraps a content encryption key for `enc`, wrapped with A256KW under the key encryption key `kek`.
+pub(super) fn aes_key_unwrap(kek: &[u8], wrapped: &[u8], enc: ContentEncryptionAlgorithm) -> VcxResult<LocalKey> {
+    let kek = LocalKey::from_secret_bytes(KeyAlg::Aes(AesTypes::A256Kw), kek)?;
+    let tag_len = kek.aead_params()?.tag_length;
+    if wrapped.len() < tag_len {
+        return Err(key_wrap_error());
+    }
+    let (ciphertext, tag) = wrapped.split_at(wrapped.len() - tag_len);
+    kek.unwrap_key(enc.key_alg(), (ciphertext, tag), &[])
+        .map_err(|_| key_wrap_error())
+}
+
+fn key_wrap_error() -> AriesVcxError {
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::InvalidMessageFormat,
+        "Failed to wrap or unwrap the content encryption key",
+    )
+}
+
+/// Value of the `apv` header: the hash of the sorted recipient key ids.
+pub(super) fn recipients_apv(kids: &[String]) -> Vec<u8> {
+    let mut kids = kids.to_vec();
+    kids.sort();
+    sha256(kids.join(".").as_bytes()).to_vec()
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use super::super::{decode_base64url, encode_base64url};
+    use super::*;
+
+    fn hex(data: &str) -> Vec<u8> {
+        (0..data.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&data[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    // Content of the example JWE of https://datatracker.ietf.org/doc/html/draft-madden-jose-ecdh-1pu-04#appendix-B,
+    // the ECDH-1PU draft authcrypted DIDComm v2 messages are encrypted with.
+    #[test]
+    fn test_decrypt_content_of_ecdh_1pu_jwe_vector() {
+        let cek = LocalKey::from_secret_bytes(
+            KeyAlg::Aes(AesTypes::A256CbcHs512),
+            &hex(concat!(
+                "fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0efeeedecebeae9e8e7e6e5e4e3e2e1e0",
+                "dfdedddcdbdad9d8d7d6d5d4d3d2d1d0cfcecdcccbcac9c8c7c6c5c4c3c2c1c0"
+            )),
+        )
+        .unwrap();
+        let protected = r#"{"alg":"ECDH-1PU+A128KW","enc":"A256CBC-HS512","apu":"QWxpY2U","apv":"Qm9iIGFuZCBDaGFybGll","epk":{"kty":"OKP","crv":"X25519","x":"k9of_cpAajy0poW5gaixXGs9nHkwg1AFqUAFa39dyBc"}}"#;
+        let aad = encode_base64url(protected.as_bytes());
+        let iv = decode_base64url("AAECAwQFBgcICQoLDA0ODw", "iv").unwrap();
+        let ciphertext = decode_base64url("Az2IWsISEMDJvyc5XRL-3-d-RgNBOGolCsxFFoUXFYw", "ciphertext").unwrap();
+        let tag = decode_base64url("HLb4fTlm8spGmij3RyOs2gJ4DpHM4hhVRwdF_hGb3WQ", "tag").unwrap();
+
+        let plaintext = decrypt_content(&cek, &iv, &ciphertext, &tag, aad.as_bytes()).unwrap();
+        assert_eq!(plaintext, b"Three is a magic number.");
+
+        let err = decrypt_content(&cek, &iv, &ciphertext, &tag[1..], aad.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidMessageFormat);
+    }
+
+    // X25519 ECDH-ES example of https://datatracker.ietf.org/doc/html/rfc8037#appendix-A.6, as used by
+    // anoncrypted DIDComm v2 messages.
+    #[test]
+    fn test_ecdh_es_key_derivation_vector() {
+        let ephemeral_key = LocalKey::from_jwk(
+            r#"{"kty":"OKP","crv":"X25519","d":"dwdtCnMYpX08FsFyUbJmRd9ML4frwJkqsXf7pR25LCo","x":"hSDwCYkwp1R0i33ctD73Wg2_Og0mOBr066SpjqqbTmo"}"#,
+        )
+        .unwrap();
+        let bob_public_key = decode_base64url("3p7bfXt9wbTTW2HC7OQ1Nz-DQ8hbeGdNrfx-FG-IK08", "x").unwrap();
+
+        let shared_secret = x25519_diffie_hellman(&ephemeral_key, &bob_public_key).unwrap();
+        assert_eq!(
+            shared_secret,
+            hex("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742")
+        );
+        assert_eq!(
+            concat_kdf(&shared_secret, "A256GCM", b"Alice", b"Bob", None).to_vec(),
+            hex("2f3636918ddb57fe0b3569113f19c4b6c518c2843f8930f05db25cd55dee53c1")
+        );
+    }
+
+    #[test]
+    fn test_content_encryption_round_trip() {
+        for enc in [
+            ContentEncryptionAlgorithm::A256CbcHs512,
+            ContentEncryptionAlgorithm::Xc20P,
+        ] {
+            let cek = enc.generate_key().unwrap();
+            let (iv, ciphertext, tag) = encrypt_content(&cek, b"hello", b"aad").unwrap();
+            assert_eq!(decrypt_content(&cek, &iv, &ciphertext, &tag, b"aad").unwrap(), b"hello");
+
+            let err = decrypt_content(&cek, &iv, &ciphertext, &tag, b"other aad").unwrap_err();
+            assert_eq!(err.kind(), AriesVcxErrorKind::InvalidMessageFormat);
+        }
+    }
+
+    #[test]
+    fn test_x25519_key_agreement_and_key_wrap() {
+        let alice = EphemeralKey::generate().unwrap();
+        let bob = EphemeralKey::generate().unwrap();
+        let shared_secret = alice.diffie_hellman(&bob.public_key().unwrap()).unwrap();
+        assert_eq!(shared_secret.len(), 32);
+        assert_eq!(shared_secret, bob.diffie_hellman(&alice.public_key().unwrap()).unwrap());
+
+        let kek = concat_kdf(&shared_secret, "ECDH-ES+A256KW", b"", b"apv", None);
+        let cek = ContentEncryptionAlgorithm::A256CbcHs512.generate_key().unwrap();
+        let wrapped = aes_key_wrap(&kek, &cek).unwrap();
+        let unwrapped = aes_key_unwrap(&kek, &wrapped, ContentEncryptionAlgorithm::A256CbcHs512).unwrap();
+        assert_eq!(
+            unwrapped.to_secret_bytes().unwrap().to_vec(),
+            cek.to_secret_bytes().unwrap().to_vec()
+        );
+        assert!(aes_key_unwrap(
+            &concat_kdf(&shared_secret, "ECDH-ES+A256KW", b"", b"other", None),
+            &wrapped,
+            ContentEncryptionAlgorithm::A256CbcHs512
+        )
+        .is_err());
+    }
+}
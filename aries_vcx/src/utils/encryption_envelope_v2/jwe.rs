@@ -0,0 +1,260 @@
+//! DIDComm v2 encrypted messages: JWEs in the general JSON serialization, with the content
+//! encryption key wrapped for every recipient using ECDH-ES+A256KW (anoncrypt) or
+//! ECDH-1PU+A256KW (authcrypt) over X25519.
+
+use super::crypto::{
+    aes_key_unwrap, aes_key_wrap, concat_kdf, decrypt_content, encrypt_content, recipients_apv,
+    ContentEncryptionAlgorithm, EphemeralKey,
+};
+use super::{decode_base64url, encode_base64url, DIDCOMM_ENCRYPTED_MEDIA_TYPE};
+use crate::errors::error::prelude::*;
+
+pub const ALG_ECDH_ES_A256KW: &str = "ECDH-ES+A256KW";
+pub const ALG_ECDH_1PU_A256KW: &str = "ECDH-1PU+A256KW";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct Jwe {
+    pub protected: String,
+    pub recipients: Vec<JweRecipient>,
+    pub iv: String,
+    pub ciphertext: String,
+    pub tag: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct JweRecipient {
+    pub header: JweRecipientHeader,
+    pub encrypted_key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct JweRecipientHeader {
+    pub kid: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct JweProtectedHeader {
+    pub typ: String,
+    pub alg: String,
+    pub enc: ContentEncryptionAlgorithm,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apu: Option<String>,
+    pub apv: String,
+    pub epk: EphemeralPublicKey,
+}
+
+impl JweProtectedHeader {
+    /// Key id of the sender of an authcrypted message. ECDH-1PU binds the sender to the key derivation
+    /// through `apu`, which must therefore hold the same key id as `skid`.
+    pub fn sender_kid(&self) -> VcxResult<&str> {
+        let skid = self.skid.as_deref().ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                "Authcrypted JWE is missing the skid header",
+            )
+        })?;
+        let apu = decode_base64url(self.apu.as_deref().unwrap_or_default(), "JWE apu header")?;
+        if apu != skid.as_bytes() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("JWE apu header does not match its skid header {}", skid),
+            ));
+        }
+        Ok(skid)
+    }
+
+    pub fn ephemeral_public_key(&self) -> VcxResult<Vec<u8>> {
+        decode_base64url(&self.epk.x, "JWE epk header")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct EphemeralPublicKey {
+    pub kty: String,
+    pub crv: String,
+    pub x: String,
+}
+
+/// X25519 key agreement key of a recipient, identified by the DID URL of its verification method.
+pub(super) struct RecipientKey {
+    pub kid: String,
+    pub public_key: Vec<u8>,
+}
+
+/// X25519 key agreement key of an authenticated sender, along with the shared secrets it agreed on with
+/// the key of every recipient, in the order of the recipients.
+pub(super) struct SenderKey {
+    pub kid: String,
+    pub shared_secrets: Vec<Vec<u8>>,
+}
+
+impl Jwe {
+    /// Encrypts `payload` for all `recipients`. With a `sender` the message gets authcrypted, which
+    /// requires A256CBC-HS512 content encryption.
+    pub fn encrypt(
+        payload: &[u8],
+        recipients: &[RecipientKey],
+        sender: Option<&SenderKey>,
+        enc: ContentEncryptionAlgorithm,
+    ) -> VcxResult<Self> {
+        if recipients.is_empty() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                "Cannot encrypt a message without recipients",
+            ));
+        }
+        if sender.is_some() && enc != ContentEncryptionAlgorithm::A256CbcHs512 {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!("Authcrypt requires A256CBC-HS512 content encryption, got {:?}", enc),
+            ));
+        }
+
+        let kids: Vec<String> = recipients.iter().map(|recipient| recipient.kid.clone()).collect();
+        let ephemeral_key = EphemeralKey::generate()?;
+        let header = JweProtectedHeader {
+            typ: DIDCOMM_ENCRYPTED_MEDIA_TYPE.to_string(),
+            alg: match sender {
+                Some(_) => ALG_ECDH_1PU_A256KW,
+                None => ALG_ECDH_ES_A256KW,
+            }
+            .to_string(),
+            enc,
+            skid: sender.map(|sender| sender.kid.clone()),
+            apu: sender.map(|sender| encode_base64url(sender.kid.as_bytes())),
+            apv: encode_base64url(&recipients_apv(&kids)),
+            epk: EphemeralPublicKey {
+                kty: "OKP".to_string(),
+                crv: "X25519".to_string(),
+                x: encode_base64url(&ephemeral_key.public_key()?),
+            },
+        };
+        Self::encrypt_with_header(payload, recipients, sender, &header, &ephemeral_key)
+    }
+
+    /// Encrypts `payload` under the given protected `header`, whose `epk` must be the public key of
+    /// `ephemeral_key`.
+    pub(super) fn encrypt_with_header(
+        payload: &[u8],
+        recipients: &[RecipientKey],
+        sender: Option<&SenderKey>,
+        header: &JweProtectedHeader,
+        ephemeral_key: &EphemeralKey,
+    ) -> VcxResult<Self> {
+        let protected = encode_base64url(serde_json::to_string(header)?.as_bytes());
+        let apu = match &header.apu {
+            Some(apu) => decode_base64url(apu, "JWE apu header")?,
+            None => vec![],
+        };
+        let apv = decode_base64url(&header.apv, "JWE apv header")?;
+
+        let cek = header.enc.generate_key()?;
+        let (iv, ciphertext, tag) = encrypt_content(&cek, payload, protected.as_bytes())?;
+
+        let recipients = recipients
+            .iter()
+            .enumerate()
+            .map(|(index, recipient)| {
+                let mut shared_secret = ephemeral_key.diffie_hellman(&recipient.public_key)?;
+                let kek = match sender {
+                    Some(sender) => {
+                        let sender_shared_secret = sender.shared_secrets.get(index).ok_or_else(|| {
+                            AriesVcxError::from_msg(
+                                AriesVcxErrorKind::InvalidInput,
+                                format!("Missing the sender shared secret for recipient {}", recipient.kid),
+                            )
+                        })?;
+                        shared_secret.extend(sender_shared_secret);
+                        concat_kdf(&shared_secret, &header.alg, &apu, &apv, Some(&tag))
+                    }
+                    None => concat_kdf(&shared_secret, &header.alg, &apu, &apv, None),
+                };
+                Ok(JweRecipient {
+                    header: JweRecipientHeader {
+                        kid: recipient.kid.clone(),
+                    },
+                    encrypted_key: encode_base64url(&aes_key_wrap(&kek, &cek)?),
+                })
+            })
+            .collect::<VcxResult<Vec<_>>>()?;
+
+        Ok(Self {
+            protected,
+            recipients,
+            iv: encode_base64url(&iv),
+            ciphertext: encode_base64url(&ciphertext),
+            tag: encode_base64url(&tag),
+        })
+    }
+
+    pub fn protected_header(&self) -> VcxResult<JweProtectedHeader> {
+        let header = decode_base64url(&self.protected, "JWE protected header")?;
+        serde_json::from_slice(&header).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Invalid JWE protected header: {}", err),
+            )
+        })
+    }
+
+    /// Decrypts the message as the recipient identified by `kid`, given the secret its key agreement
+    /// key shares with the ephemeral key of the message. Authcrypted messages additionally require the
+    /// secret shared with the key of the sender identified by the `skid` header.
+    pub fn decrypt(
+        &self,
+        kid: &str,
+        ephemeral_shared_secret: &[u8],
+        sender_shared_secret: Option<&[u8]>,
+    ) -> VcxResult<Vec<u8>> {
+        let header = self.protected_header()?;
+        let recipient = self
+            .recipients
+            .iter()
+            .find(|recipient| recipient.header.kid == kid)
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    format!("JWE is not encrypted for {}", kid),
+                )
+            })?;
+
+        let apu = match &header.apu {
+            Some(apu) => decode_base64url(apu, "JWE apu header")?,
+            None => vec![],
+        };
+        let apv = decode_base64url(&header.apv, "JWE apv header")?;
+        let tag = decode_base64url(&self.tag, "JWE tag")?;
+        let mut shared_secret = ephemeral_shared_secret.to_vec();
+        let kek = match (header.alg.as_str(), sender_shared_secret) {
+            (ALG_ECDH_ES_A256KW, _) => concat_kdf(&shared_secret, &header.alg, &apu, &apv, None),
+            (ALG_ECDH_1PU_A256KW, Some(sender_shared_secret)) => {
+                shared_secret.extend(sender_shared_secret);
+                concat_kdf(&shared_secret, &header.alg, &apu, &apv, Some(&tag))
+            }
+            (alg, _) => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    format!(
+                        "Unsupported JWE key management algorithm or missing sender key: {}",
+                        alg
+                    ),
+                ))
+            }
+        };
+
+        let cek = aes_key_unwrap(
+            &kek,
+            &decode_base64url(&recipient.encrypted_key, "JWE encrypted key")?,
+            header.enc,
+        )?;
+        decrypt_content(
+            &cek,
+            &decode_base64url(&self.iv, "JWE iv")?,
+            &decode_base64url(&self.ciphertext, "JWE ciphertext")?,
+            &tag,
+            self.protected.as_bytes(),
+        )
+    }
+}
@@ -0,0 +1,90 @@
+//! DIDComm v2 signed messages: JWSs in the general JSON serialization, signed with EdDSA by
+//! Ed25519 keys held in the wallet.
+
+use std::sync::Arc;
+
+use super::{decode_base64url, encode_base64url, DIDCOMM_SIGNED_MEDIA_TYPE};
+use crate::errors::error::prelude::*;
+use crate::plugins::wallet::base_wallet::BaseWallet;
+
+pub const ALG_EDDSA: &str = "EdDSA";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct Jws {
+    pub payload: String,
+    pub signatures: Vec<JwsSignature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct JwsSignature {
+    pub protected: String,
+    pub signature: String,
+    pub header: JwsSignatureHeader,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct JwsSignatureHeader {
+    pub kid: String,
+}
+
+impl Jws {
+    /// Signs `payload` with the wallet key `verkey`, published under the DID URL `kid`.
+    pub async fn sign(wallet: &Arc<dyn BaseWallet>, payload: &[u8], kid: &str, verkey: &str) -> VcxResult<Self> {
+        let protected = json!({
+            "typ": DIDCOMM_SIGNED_MEDIA_TYPE,
+            "alg": ALG_EDDSA,
+        })
+        .to_string();
+        let protected = encode_base64url(protected.as_bytes());
+        let payload = encode_base64url(payload);
+        let signature = wallet
+            .sign(verkey, format!("{}.{}", protected, payload).as_bytes())
+            .await?;
+
+        Ok(Self {
+            payload,
+            signatures: vec![JwsSignature {
+                protected,
+                signature: encode_base64url(&signature),
+                header: JwsSignatureHeader { kid: kid.to_string() },
+            }],
+        })
+    }
+
+    pub fn signer_kid(&self) -> VcxResult<&str> {
+        self.signatures
+            .first()
+            .map(|signature| signature.header.kid.as_str())
+            .ok_or_else(|| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidMessageFormat, "JWS has no signature"))
+    }
+
+    /// Verifies the signature of the signer against `verkey` and returns the signed payload.
+    pub async fn verify(&self, wallet: &Arc<dyn BaseWallet>, verkey: &str) -> VcxResult<Vec<u8>> {
+        let signature = self
+            .signatures
+            .first()
+            .ok_or_else(|| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidMessageFormat, "JWS has no signature"))?;
+
+        let header: serde_json::Value =
+            serde_json::from_slice(&decode_base64url(&signature.protected, "JWS protected header")?)?;
+        if header["alg"].as_str() != Some(ALG_EDDSA) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Unsupported JWS algorithm: {}", header["alg"]),
+            ));
+        }
+
+        let signing_input = format!("{}.{}", signature.protected, self.payload);
+        let signature_bytes = decode_base64url(&signature.signature, "JWS signature")?;
+        if !wallet
+            .verify(verkey, signing_input.as_bytes(), &signature_bytes)
+            .await?
+        {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("JWS signature is invalid for key {}", signature.header.kid),
+            ));
+        }
+        decode_base64url(&self.payload, "JWS payload")
+    }
+}
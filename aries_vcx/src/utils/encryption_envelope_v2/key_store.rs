@@ -0,0 +1,32 @@
+//! X25519 key agreement keys of DIDComm v2. Their secrets never leave the wallet: keys are created and
+//! used for key agreement through the wallet, identified by their base58 encoded public key.
+
+use std::sync::Arc;
+
+use crate::errors::error::prelude::*;
+use crate::plugins::wallet::base_wallet::BaseWallet;
+
+/// Generates an X25519 key agreement key held by the wallet and returns the public key encoded as base58.
+pub async fn create_key_agreement_key(wallet: &Arc<dyn BaseWallet>) -> VcxResult<String> {
+    wallet.create_key_agreement_key().await
+}
+
+/// Performs key agreement between the key agreement key `public_key` and `their_public_key`, or returns
+/// `None` if the wallet does not hold the secret of `public_key`.
+pub(super) async fn key_agreement(
+    wallet: &Arc<dyn BaseWallet>,
+    public_key: &[u8],
+    their_public_key: &[u8],
+) -> VcxResult<Option<Vec<u8>>> {
+    match wallet
+        .key_agreement(
+            &bs58::encode(public_key).into_string(),
+            &bs58::encode(their_public_key).into_string(),
+        )
+        .await
+    {
+        Ok(shared_secret) => Ok(Some(shared_secret)),
+        Err(err) if err.kind() == AriesVcxErrorKind::WalletRecordNotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
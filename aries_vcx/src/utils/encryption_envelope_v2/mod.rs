@@ -0,0 +1,633 @@
+//! Packing and unpacking of [DIDComm v2](https://identity.foundation/didcomm-messaging/spec/) messages.
+//!
+//! Messages can be sent as plaintext, signed (JWS with EdDSA), anoncrypted (ECDH-ES+A256KW) or
+//! authcrypted (ECDH-1PU+A256KW), using X25519 key agreement keys and A256CBC-HS512 or XC20P content
+//! encryption. Keys are never passed around directly: recipients and senders are identified by their
+//! DIDs, whose `keyAgreement` and `authentication` verification methods get resolved through a
+//! [`DidResolverRegistry`].
+
+pub mod crypto;
+mod jwe;
+mod jws;
+pub mod key_store;
+
+use std::sync::Arc;
+
+use serde_json::{Map, Value};
+
+use messages::diddoc::w3c::verification_method::{PublicKeyField, VerificationMethod, VerificationMethodType};
+
+use self::crypto::ContentEncryptionAlgorithm;
+use self::jwe::{Jwe, RecipientKey, SenderKey};
+use self::jws::Jws;
+use self::key_store::key_agreement;
+use crate::common::did_resolver::DidResolverRegistry;
+use crate::errors::error::prelude::*;
+use crate::plugins::wallet::base_wallet::BaseWallet;
+use crate::utils::uuid::uuid;
+
+pub const DIDCOMM_PLAIN_MEDIA_TYPE: &str = "application/didcomm-plain+json";
+pub const DIDCOMM_SIGNED_MEDIA_TYPE: &str = "application/didcomm-signed+json";
+pub const DIDCOMM_ENCRYPTED_MEDIA_TYPE: &str = "application/didcomm-encrypted+json";
+
+// A message can at most be signed, authcrypted and anoncrypted.
+const MAX_ENVELOPE_NESTING: usize = 3;
+
+/// A DIDComm v2 plaintext message. Headers without a dedicated field, such as `attachments`,
+/// are kept in `extra`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaintextMessage {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_time: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_time: Option<u64>,
+    #[serde(default)]
+    pub body: Value,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl PlaintextMessage {
+    pub fn new(type_: &str, body: Value) -> Self {
+        Self {
+            id: uuid(),
+            typ: Some(DIDCOMM_PLAIN_MEDIA_TYPE.to_string()),
+            type_: type_.to_string(),
+            from: None,
+            to: None,
+            thid: None,
+            created_time: None,
+            expires_time: None,
+            body,
+            extra: Map::new(),
+        }
+    }
+
+    pub fn set_from(mut self, from: &str) -> Self {
+        self.from = Some(from.to_string());
+        self
+    }
+
+    pub fn set_to(mut self, to: Vec<String>) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    pub fn set_thid(mut self, thid: &str) -> Self {
+        self.thid = Some(thid.to_string());
+        self
+    }
+}
+
+/// A plaintext message recovered from an envelope, along with how it was protected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnpackedMessageV2 {
+    pub message: PlaintextMessage,
+    /// Key agreement key the message was decrypted with, if it was encrypted.
+    pub recipient_kid: Option<String>,
+    /// Key agreement key of the sender, if the message was authcrypted.
+    pub sender_kid: Option<String>,
+    /// Authentication key the message was signed with, if it was signed.
+    pub signer_kid: Option<String>,
+}
+
+impl UnpackedMessageV2 {
+    pub fn is_encrypted(&self) -> bool {
+        self.recipient_kid.is_some()
+    }
+
+    /// Whether the sender of the message is authenticated, either through authcrypt or a signature.
+    pub fn is_authenticated(&self) -> bool {
+        self.sender_kid.is_some() || self.signer_kid.is_some()
+    }
+}
+
+#[derive(Debug)]
+pub struct EncryptionEnvelopeV2(pub Vec<u8>);
+
+impl EncryptionEnvelopeV2 {
+    /// Packs `message` for the DID `to`, authcrypted when the DID of the sender is given and anoncrypted
+    /// otherwise. The `from` and `to` headers of the message are set accordingly.
+    pub async fn create(
+        wallet: &Arc<dyn BaseWallet>,
+        resolver: &DidResolverRegistry,
+        message: PlaintextMessage,
+        from: Option<&str>,
+        to: &str,
+    ) -> VcxResult<EncryptionEnvelopeV2> {
+        trace!(
+            "EncryptionEnvelopeV2::create >>> message: {:?}, from: {:?}, to: {}",
+            message,
+            from,
+            to
+        );
+        let mut message = message.set_to(vec![to.to_string()]);
+        message.from = from.map(String::from);
+        let payload = serde_json::to_vec(&message)?;
+
+        match from {
+            Some(from) => Self::authcrypt(wallet, resolver, &payload, from, to).await,
+            None => Self::anoncrypt(resolver, &payload, to, ContentEncryptionAlgorithm::A256CbcHs512).await,
+        }
+    }
+
+    pub fn plaintext(message: &PlaintextMessage) -> VcxResult<EncryptionEnvelopeV2> {
+        Ok(EncryptionEnvelopeV2(serde_json::to_vec(message)?))
+    }
+
+    /// Signs `message` with the authentication key `signer_kid`, a DID URL whose key is held by the wallet.
+    pub async fn signed(
+        wallet: &Arc<dyn BaseWallet>,
+        resolver: &DidResolverRegistry,
+        message: &PlaintextMessage,
+        signer_kid: &str,
+    ) -> VcxResult<EncryptionEnvelopeV2> {
+        let verkey = resolve_verification_key(resolver, signer_kid).await?;
+        let jws = Jws::sign(wallet, &serde_json::to_vec(message)?, signer_kid, &verkey).await?;
+        Ok(EncryptionEnvelopeV2(serde_json::to_vec(&jws)?))
+    }
+
+    /// Encrypts `payload` for all key agreement keys of the DID `to`, without revealing the sender.
+    pub async fn anoncrypt(
+        resolver: &DidResolverRegistry,
+        payload: &[u8],
+        to: &str,
+        enc: ContentEncryptionAlgorithm,
+    ) -> VcxResult<EncryptionEnvelopeV2> {
+        let recipients = resolve_recipient_keys(resolver, to).await?;
+        let jwe = Jwe::encrypt(payload, &recipients, None, enc)?;
+        Ok(EncryptionEnvelopeV2(serde_json::to_vec(&jwe)?))
+    }
+
+    /// Encrypts `payload` for all key agreement keys of the DID `to`, authenticated by the first key
+    /// agreement key of the DID `from` whose secret is held by the wallet.
+    pub async fn authcrypt(
+        wallet: &Arc<dyn BaseWallet>,
+        resolver: &DidResolverRegistry,
+        payload: &[u8],
+        from: &str,
+        to: &str,
+    ) -> VcxResult<EncryptionEnvelopeV2> {
+        let recipients = resolve_recipient_keys(resolver, to).await?;
+        let sender = resolve_sender_key(wallet, resolver, from, &recipients).await?;
+        let jwe = Jwe::encrypt(
+            payload,
+            &recipients,
+            Some(&sender),
+            ContentEncryptionAlgorithm::A256CbcHs512,
+        )?;
+        Ok(EncryptionEnvelopeV2(serde_json::to_vec(&jwe)?))
+    }
+
+    /// Unpacks a plaintext, signed or encrypted message, including messages nested in several envelopes,
+    /// and checks that the keys which authenticated the message belong to its sender.
+    pub async fn unpack(
+        wallet: &Arc<dyn BaseWallet>,
+        resolver: &DidResolverRegistry,
+        payload: &[u8],
+    ) -> VcxResult<UnpackedMessageV2> {
+        trace!(
+            "EncryptionEnvelopeV2::unpack >>> processing payload of {} bytes",
+            payload.len()
+        );
+        let mut payload = payload.to_vec();
+        let mut recipient_kid = None;
+        let mut sender_kid = None;
+        let mut signer_kid = None;
+
+        for _ in 0..=MAX_ENVELOPE_NESTING {
+            let value: Value = serde_json::from_slice(&payload).map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    format!("Cannot deserialize DIDComm v2 message: {}", err),
+                )
+            })?;
+
+            if value.get("ciphertext").is_some() {
+                let jwe: Jwe = serde_json::from_value(value)?;
+                let (decrypted, kid, skid) = decrypt_jwe(wallet, resolver, &jwe).await?;
+                payload = decrypted;
+                recipient_kid.get_or_insert(kid);
+                sender_kid = sender_kid.or(skid);
+            } else if value.get("signatures").is_some() {
+                let jws: Jws = serde_json::from_value(value)?;
+                let kid = jws.signer_kid()?.to_string();
+                let verkey = resolve_verification_key(resolver, &kid).await?;
+                payload = jws.verify(wallet, &verkey).await?;
+                signer_kid = Some(kid);
+            } else {
+                let message: PlaintextMessage = serde_json::from_value(value).map_err(|err| {
+                    AriesVcxError::from_msg(
+                        AriesVcxErrorKind::InvalidMessageFormat,
+                        format!("Cannot deserialize DIDComm v2 plaintext message: {}", err),
+                    )
+                })?;
+                for kid in sender_kid.iter().chain(signer_kid.iter()) {
+                    check_sender(&message, kid)?;
+                }
+                return Ok(UnpackedMessageV2 {
+                    message,
+                    recipient_kid,
+                    sender_kid,
+                    signer_kid,
+                });
+            }
+        }
+        Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            "DIDComm v2 message is nested in too many envelopes",
+        ))
+    }
+}
+
+async fn decrypt_jwe(
+    wallet: &Arc<dyn BaseWallet>,
+    resolver: &DidResolverRegistry,
+    jwe: &Jwe,
+) -> VcxResult<(Vec<u8>, String, Option<String>)> {
+    let header = jwe.protected_header()?;
+    // Only ECDH-1PU authenticates the sender, the `skid` header of an anoncrypted message is not bound
+    // to any key and could name anyone.
+    let sender = match header.alg.as_str() {
+        jwe::ALG_ECDH_1PU_A256KW => {
+            let skid = header.sender_kid()?;
+            Some((skid.to_string(), resolve_key_agreement_key(resolver, skid).await?))
+        }
+        _ => None,
+    };
+    let ephemeral_public_key = header.ephemeral_public_key()?;
+
+    for recipient in &jwe.recipients {
+        let kid = &recipient.header.kid;
+        let public_key = match resolve_key_agreement_key(resolver, kid).await {
+            Ok(public_key) => public_key,
+            Err(err) => {
+                warn!("Skipping JWE recipient {} which could not be resolved: {}", kid, err);
+                continue;
+            }
+        };
+        let ephemeral_shared_secret = match key_agreement(wallet, &public_key, &ephemeral_public_key).await? {
+            Some(shared_secret) => shared_secret,
+            None => continue,
+        };
+        let sender_shared_secret = match &sender {
+            Some((_, sender_public_key)) => key_agreement(wallet, &public_key, sender_public_key).await?,
+            None => None,
+        };
+        let decrypted = jwe.decrypt(kid, &ephemeral_shared_secret, sender_shared_secret.as_deref())?;
+        return Ok((decrypted, kid.clone(), sender.map(|(skid, _)| skid)));
+    }
+    Err(AriesVcxError::from_msg(
+        AriesVcxErrorKind::WalletRecordNotFound,
+        "None of the JWE recipient keys is held by the wallet",
+    ))
+}
+
+/// Authcrypted and signed messages must come from the DID owning the key that authenticated them.
+fn check_sender(message: &PlaintextMessage, kid: &str) -> VcxResult<()> {
+    let did = kid.split('#').next().unwrap_or(kid);
+    if message.from.as_deref() != Some(did) {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!(
+                "Message was authenticated by {}, which does not belong to its sender {:?}",
+                kid, message.from
+            ),
+        ));
+    }
+    Ok(())
+}
+
+async fn resolve_recipient_keys(resolver: &DidResolverRegistry, did: &str) -> VcxResult<Vec<RecipientKey>> {
+    let did_doc = resolver.resolve(did).await?;
+    let recipients = did_doc
+        .key_agreement_methods()
+        .into_iter()
+        .filter(|method| is_x25519_key(method))
+        .map(|method| {
+            Ok(RecipientKey {
+                kid: absolute_kid(&did_doc.id, &method.id),
+                public_key: public_key_bytes(method)?,
+            })
+        })
+        .collect::<VcxResult<Vec<_>>>()?;
+    if recipients.is_empty() {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidDid,
+            format!("DID document of {} has no X25519 key agreement key", did),
+        ));
+    }
+    Ok(recipients)
+}
+
+/// Finds the first key agreement key of the DID `did` held by the wallet, and agrees on a shared secret
+/// with every recipient key.
+async fn resolve_sender_key(
+    wallet: &Arc<dyn BaseWallet>,
+    resolver: &DidResolverRegistry,
+    did: &str,
+    recipients: &[RecipientKey],
+) -> VcxResult<SenderKey> {
+    'keys: for key in resolve_recipient_keys(resolver, did).await? {
+        let mut shared_secrets = Vec::with_capacity(recipients.len());
+        for recipient in recipients {
+            match key_agreement(wallet, &key.public_key, &recipient.public_key).await? {
+                Some(shared_secret) => shared_secrets.push(shared_secret),
+                None => continue 'keys,
+            }
+        }
+        return Ok(SenderKey {
+            kid: key.kid,
+            shared_secrets,
+        });
+    }
+    Err(AriesVcxError::from_msg(
+        AriesVcxErrorKind::WalletRecordNotFound,
+        format!("None of the key agreement keys of {} is held by the wallet", did),
+    ))
+}
+
+async fn resolve_key_agreement_key(resolver: &DidResolverRegistry, kid: &str) -> VcxResult<Vec<u8>> {
+    let method = resolve_verification_method(resolver, kid).await?;
+    if !is_x25519_key(&method) {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidDid,
+            format!("Verification method {} is not an X25519 key agreement key", kid),
+        ));
+    }
+    public_key_bytes(&method)
+}
+
+async fn resolve_verification_key(resolver: &DidResolverRegistry, kid: &str) -> VcxResult<String> {
+    let method = resolve_verification_method(resolver, kid).await?;
+    Ok(method.public_key_base58()?)
+}
+
+async fn resolve_verification_method(resolver: &DidResolverRegistry, kid: &str) -> VcxResult<VerificationMethod> {
+    let did = kid.split('#').next().unwrap_or(kid);
+    let did_doc = resolver.resolve(did).await?;
+    did_doc.dereference_key(kid).cloned().ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidDid,
+            format!(
+                "Verification method {} is not present in the DID document of {}",
+                kid, did
+            ),
+        )
+    })
+}
+
+fn is_x25519_key(method: &VerificationMethod) -> bool {
+    match (&method.type_, &method.public_key) {
+        (VerificationMethodType::X25519KeyAgreementKey2019, _)
+        | (VerificationMethodType::X25519KeyAgreementKey2020, _) => true,
+        (_, PublicKeyField::Jwk { public_key_jwk }) => public_key_jwk.crv.as_deref() == Some("X25519"),
+        _ => false,
+    }
+}
+
+fn public_key_bytes(method: &VerificationMethod) -> VcxResult<Vec<u8>> {
+    bs58::decode(method.public_key_base58()?).into_vec().map_err(|_| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::NotBase58,
+            format!("Key of verification method {} is not valid base58", method.id),
+        )
+    })
+}
+
+fn absolute_kid(did: &str, kid: &str) -> String {
+    match kid.strip_prefix('#') {
+        Some(fragment) => format!("{}#{}", did, fragment),
+        None => kid.to_string(),
+    }
+}
+
+pub(super) fn encode_base64url(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+pub(super) fn decode_base64url(data: &str, what: &str) -> VcxResult<Vec<u8>> {
+    base64::decode_config(data, base64::URL_SAFE_NO_PAD).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!("Cannot decode {}: {}", what, err),
+        )
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+pub mod unit_tests {
+    use aries_askar::{PassKey, StoreKeyMethod};
+    use messages::diddoc::peer::generate_numalgo2_didcomm_v2;
+
+    use super::crypto::EphemeralKey;
+    use super::jwe::{EphemeralPublicKey, JweProtectedHeader};
+    use super::key_store::create_key_agreement_key;
+    use super::*;
+    use crate::common::did_resolver::peer::DidPeerResolver;
+    use crate::plugins::wallet::askar_wallet::AskarWallet;
+    use crate::utils::devsetup::SetupEmpty;
+
+    async fn create_wallet() -> Arc<dyn BaseWallet> {
+        Arc::new(
+            AskarWallet::create("sqlite://:memory:", StoreKeyMethod::Unprotected, PassKey::empty(), None)
+                .await
+                .unwrap(),
+        )
+    }
+
+    async fn create_peer_did(wallet: &Arc<dyn BaseWallet>) -> String {
+        let (_, verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+        let key_agreement_key = create_key_agreement_key(wallet).await.unwrap();
+        generate_numalgo2_didcomm_v2(&verkey, &key_agreement_key, Some("http://example.org/agent"), &[]).unwrap()
+    }
+
+    fn resolver() -> DidResolverRegistry {
+        DidResolverRegistry::new().register_resolver("peer", Arc::new(DidPeerResolver))
+    }
+
+    fn _message() -> PlaintextMessage {
+        PlaintextMessage::new(
+            "https://didcomm.org/trust-ping/2.0/ping",
+            json!({ "response_requested": true }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_anoncrypt_round_trip() {
+        SetupEmpty::init();
+        let wallet = create_wallet().await;
+        let recipient = create_peer_did(&wallet).await;
+
+        for enc in [
+            ContentEncryptionAlgorithm::A256CbcHs512,
+            ContentEncryptionAlgorithm::Xc20P,
+        ] {
+            let message = _message();
+            let envelope =
+                EncryptionEnvelopeV2::anoncrypt(&resolver(), &serde_json::to_vec(&message).unwrap(), &recipient, enc)
+                    .await
+                    .unwrap();
+
+            let unpacked = EncryptionEnvelopeV2::unpack(&wallet, &resolver(), &envelope.0)
+                .await
+                .unwrap();
+            assert_eq!(unpacked.message, message);
+            assert_eq!(unpacked.recipient_kid, Some(format!("{}#key-1", recipient)));
+            assert!(unpacked.is_encrypted());
+            assert!(!unpacked.is_authenticated());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_anoncrypt_ignores_forged_sender() {
+        SetupEmpty::init();
+        let wallet = create_wallet().await;
+        let sender = create_peer_did(&wallet).await;
+        let recipient = create_peer_did(&wallet).await;
+        let sender_kid = format!("{}#key-1", sender);
+
+        // anyone can anoncrypt a message, naming an arbitrary sender in the skid and apu headers
+        let recipients = resolve_recipient_keys(&resolver(), &recipient).await.unwrap();
+        let kids: Vec<String> = recipients.iter().map(|recipient| recipient.kid.clone()).collect();
+        let ephemeral_key = EphemeralKey::generate().unwrap();
+        let header = JweProtectedHeader {
+            typ: DIDCOMM_ENCRYPTED_MEDIA_TYPE.to_string(),
+            alg: jwe::ALG_ECDH_ES_A256KW.to_string(),
+            enc: ContentEncryptionAlgorithm::A256CbcHs512,
+            skid: Some(sender_kid.clone()),
+            apu: Some(encode_base64url(sender_kid.as_bytes())),
+            apv: encode_base64url(&crypto::recipients_apv(&kids)),
+            epk: EphemeralPublicKey {
+                kty: "OKP".to_string(),
+                crv: "X25519".to_string(),
+                x: encode_base64url(&ephemeral_key.public_key().unwrap()),
+            },
+        };
+        let message = _message().set_from(&sender);
+        let jwe = Jwe::encrypt_with_header(
+            &serde_json::to_vec(&message).unwrap(),
+            &recipients,
+            None,
+            &header,
+            &ephemeral_key,
+        )
+        .unwrap();
+
+        let unpacked = EncryptionEnvelopeV2::unpack(&wallet, &resolver(), &serde_json::to_vec(&jwe).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(unpacked.message, message);
+        assert_eq!(unpacked.sender_kid, None);
+        assert!(!unpacked.is_authenticated());
+    }
+
+    #[tokio::test]
+    async fn test_authcrypt_round_trip() {
+        SetupEmpty::init();
+        let wallet = create_wallet().await;
+        let sender = create_peer_did(&wallet).await;
+        let recipient = create_peer_did(&wallet).await;
+
+        let envelope = EncryptionEnvelopeV2::create(&wallet, &resolver(), _message(), Some(&sender), &recipient)
+            .await
+            .unwrap();
+        let header = serde_json::from_slice::<Jwe>(&envelope.0)
+            .unwrap()
+            .protected_header()
+            .unwrap();
+        assert_eq!(header.alg, jwe::ALG_ECDH_1PU_A256KW);
+
+        let unpacked = EncryptionEnvelopeV2::unpack(&wallet, &resolver(), &envelope.0)
+            .await
+            .unwrap();
+        assert_eq!(unpacked.message.from, Some(sender.clone()));
+        assert_eq!(unpacked.message.to, Some(vec![recipient.clone()]));
+        assert_eq!(unpacked.sender_kid, Some(format!("{}#key-1", sender)));
+        assert!(unpacked.is_authenticated());
+    }
+
+    #[tokio::test]
+    async fn test_authcrypt_rejects_spoofed_sender() {
+        SetupEmpty::init();
+        let wallet = create_wallet().await;
+        let sender = create_peer_did(&wallet).await;
+        let recipient = create_peer_did(&wallet).await;
+
+        let message = _message().set_from(&recipient);
+        let envelope = EncryptionEnvelopeV2::authcrypt(
+            &wallet,
+            &resolver(),
+            &serde_json::to_vec(&message).unwrap(),
+            &sender,
+            &recipient,
+        )
+        .await
+        .unwrap();
+
+        let err = EncryptionEnvelopeV2::unpack(&wallet, &resolver(), &envelope.0)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidMessageFormat);
+    }
+
+    #[tokio::test]
+    async fn test_signed_and_encrypted_round_trip() {
+        SetupEmpty::init();
+        let wallet = create_wallet().await;
+        let sender = create_peer_did(&wallet).await;
+        let recipient = create_peer_did(&wallet).await;
+
+        let message = _message().set_from(&sender);
+        let signed = EncryptionEnvelopeV2::signed(&wallet, &resolver(), &message, &format!("{}#key-2", sender))
+            .await
+            .unwrap();
+        let envelope =
+            EncryptionEnvelopeV2::anoncrypt(&resolver(), &signed.0, &recipient, ContentEncryptionAlgorithm::Xc20P)
+                .await
+                .unwrap();
+
+        let unpacked = EncryptionEnvelopeV2::unpack(&wallet, &resolver(), &envelope.0)
+            .await
+            .unwrap();
+        assert_eq!(unpacked.message, message);
+        assert_eq!(unpacked.signer_kid, Some(format!("{}#key-2", sender)));
+        assert!(unpacked.is_encrypted());
+        assert!(unpacked.is_authenticated());
+
+        let mut jws: Value = serde_json::from_slice(&signed.0).unwrap();
+        jws["payload"] = json!(encode_base64url(
+            &serde_json::to_vec(&_message().set_from(&sender)).unwrap()
+        ));
+        let err = EncryptionEnvelopeV2::unpack(&wallet, &resolver(), &serde_json::to_vec(&jws).unwrap())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidMessageFormat);
+    }
+
+    #[tokio::test]
+    async fn test_unpack_plaintext() {
+        SetupEmpty::init();
+        let message = _message();
+        let envelope = EncryptionEnvelopeV2::plaintext(&message).unwrap();
+
+        let unpacked = EncryptionEnvelopeV2::unpack(&create_wallet().await, &resolver(), &envelope.0)
+            .await
+            .unwrap();
+        assert_eq!(unpacked.message, message);
+        assert!(!unpacked.is_encrypted());
+        assert!(!unpacked.is_authenticated());
+    }
+}
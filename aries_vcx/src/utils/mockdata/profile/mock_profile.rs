@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use crate::{
+    common::did_resolver::DidResolverRegistry,
     core::profile::profile::Profile,
     plugins::{
         anoncreds::base_anoncreds::BaseAnonCreds, ledger::base_ledger::BaseLedger, wallet::base_wallet::BaseWallet,
@@ -26,4 +27,8 @@ impl Profile for MockProfile {
     fn inject_wallet(&self) -> Arc<dyn BaseWallet> {
         Arc::new(MockWallet {})
     }
+
+    fn inject_did_resolver(self: Arc<Self>) -> Arc<DidResolverRegistry> {
+        Arc::new(DidResolverRegistry::with_default_resolvers(self.inject_ledger()))
+    }
 }
@@ -60,6 +60,8 @@ pub mod uuid;
 #[macro_use]
 pub mod test_logger;
 pub mod encryption_envelope;
+#[cfg(feature = "modular_libs")]
+pub mod encryption_envelope_v2;
 pub mod filters;
 pub mod serialization;
 pub mod validation;
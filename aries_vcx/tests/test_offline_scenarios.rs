@@ -0,0 +1,232 @@
+#[macro_use]
+extern crate serde_json;
+
+#[cfg(feature = "general_test")]
+mod integration_tests {
+    use std::sync::Arc;
+
+    use aries_askar::{PassKey, StoreKeyMethod};
+    use async_channel::{unbounded, Receiver, Sender};
+
+    use aries_vcx::common::primitives::credential_definition::CredentialDef;
+    use aries_vcx::common::primitives::revocation_registry::RevocationRegistry;
+    use aries_vcx::common::proofs::proof_request::PresentationRequestData;
+    use aries_vcx::common::proofs::prover::credential_selection::NewestFirst;
+    use aries_vcx::common::test_utils::create_and_store_credential_def;
+    use aries_vcx::core::profile::modular_libs_profile::ModularLibsProfile;
+    use aries_vcx::core::profile::profile::Profile;
+    use aries_vcx::errors::error::{AriesVcxError, AriesVcxErrorKind};
+    use aries_vcx::global::settings;
+    use aries_vcx::handlers::issuance::holder::Holder;
+    use aries_vcx::handlers::issuance::issuer::Issuer;
+    use aries_vcx::handlers::proof_presentation::prover::Prover;
+    use aries_vcx::handlers::proof_presentation::verifier::Verifier;
+    use aries_vcx::plugins::ledger::base_ledger::BaseLedger;
+    use aries_vcx::plugins::ledger::in_memory_ledger::InMemoryLedger;
+    use aries_vcx::plugins::wallet::askar_wallet::AskarWallet;
+    use aries_vcx::protocols::issuance::holder::state_machine::HolderState;
+    use aries_vcx::protocols::issuance::issuer::state_machine::IssuerState;
+    use aries_vcx::protocols::proof_presentation::verifier::state_machine::VerifierState;
+    use aries_vcx::protocols::proof_presentation::verifier::verification_status::PresentationVerificationStatus;
+    use aries_vcx::protocols::SendClosure;
+    use aries_vcx::utils::constants::{DEFAULT_SCHEMA_ATTRS, TAILS_DIR};
+    use aries_vcx::utils::get_temp_dir_path;
+    use messages::a2a::A2AMessage;
+    use messages::protocols::issuance::credential_offer::OfferInfo;
+
+    struct OfflineAgent {
+        profile: Arc<dyn Profile>,
+        did: String,
+        inbox: Receiver<A2AMessage>,
+        outbox: Sender<A2AMessage>,
+    }
+
+    impl OfflineAgent {
+        async fn setup(ledger: Arc<dyn BaseLedger>, outbox: Sender<A2AMessage>, inbox: Receiver<A2AMessage>) -> Self {
+            let wallet = AskarWallet::create("sqlite://:memory:", StoreKeyMethod::Unprotected, PassKey::empty(), None)
+                .await
+                .unwrap();
+            let profile: Arc<dyn Profile> = Arc::new(ModularLibsProfile::new_with_ledger(Arc::new(wallet), ledger));
+            let (did, _) = profile
+                .inject_wallet()
+                .create_and_store_my_did(None, None)
+                .await
+                .unwrap();
+            Arc::clone(&profile)
+                .inject_anoncreds()
+                .prover_create_link_secret(settings::DEFAULT_LINK_SECRET_ALIAS)
+                .await
+                .unwrap();
+            OfflineAgent {
+                profile,
+                did,
+                inbox,
+                outbox,
+            }
+        }
+
+        fn send_message(&self) -> SendClosure {
+            let outbox = self.outbox.clone();
+            Box::new(move |message: A2AMessage| {
+                Box::pin(async move {
+                    outbox.send(message).await.map_err(|err| {
+                        AriesVcxError::from_msg(
+                            AriesVcxErrorKind::IOError,
+                            format!("Failed to deliver message: {:?}", err),
+                        )
+                    })
+                })
+            })
+        }
+
+        async fn receive_message(&self) -> A2AMessage {
+            self.inbox.recv().await.unwrap()
+        }
+    }
+
+    async fn setup_agents() -> (OfflineAgent, OfflineAgent) {
+        let ledger: Arc<dyn BaseLedger> = Arc::new(InMemoryLedger::new());
+        let (to_alice, alice_inbox) = unbounded();
+        let (to_faber, faber_inbox) = unbounded();
+        let faber = OfflineAgent::setup(Arc::clone(&ledger), to_alice, faber_inbox).await;
+        let alice = OfflineAgent::setup(ledger, to_faber, alice_inbox).await;
+        (faber, alice)
+    }
+
+    async fn issue_credential(
+        faber: &OfflineAgent,
+        alice: &OfflineAgent,
+        cred_def: &CredentialDef,
+        rev_reg: &RevocationRegistry,
+    ) -> (Issuer, Holder) {
+        let credential_json = json!({
+            "address1": "123 Main St",
+            "address2": "Suite 3",
+            "city": "Draper",
+            "state": "UT",
+            "zip": "84000"
+        })
+        .to_string();
+        let offer_info = OfferInfo {
+            credential_json,
+            cred_def_id: cred_def.get_cred_def_id(),
+            rev_reg_id: Some(rev_reg.get_rev_reg_id()),
+            tails_file: Some(rev_reg.get_tails_dir()),
+        };
+        let mut issuer = Issuer::create("1").unwrap();
+        issuer
+            .build_credential_offer_msg(&faber.profile, offer_info, None)
+            .await
+            .unwrap();
+        issuer.send_credential_offer(faber.send_message()).await.unwrap();
+
+        let offer = match alice.receive_message().await {
+            A2AMessage::CredentialOffer(offer) => offer,
+            message => panic!("Expected a credential offer, received {:?}", message),
+        };
+        let mut holder = Holder::create_from_offer("1", offer).unwrap();
+        holder
+            .send_request(&alice.profile, alice.did.clone(), alice.send_message())
+            .await
+            .unwrap();
+
+        let request = match faber.receive_message().await {
+            A2AMessage::CredentialRequest(request) => request,
+            message => panic!("Expected a credential request, received {:?}", message),
+        };
+        issuer.process_credential_request(request).unwrap();
+        issuer
+            .send_credential(&faber.profile, faber.send_message())
+            .await
+            .unwrap();
+
+        let credential = match alice.receive_message().await {
+            A2AMessage::Credential(credential) => credential,
+            message => panic!("Expected a credential, received {:?}", message),
+        };
+        holder
+            .process_credential(&alice.profile, credential, alice.send_message())
+            .await
+            .unwrap();
+        match faber.receive_message().await {
+            A2AMessage::CredentialAck(ack) => issuer.process_credential_ack(ack).unwrap(),
+            message => panic!("Expected a credential ack, received {:?}", message),
+        };
+
+        assert_eq!(HolderState::Finished, holder.get_state());
+        assert_eq!(IssuerState::Finished, issuer.get_state());
+        (issuer, holder)
+    }
+
+    async fn present_credential(faber: &OfflineAgent, alice: &OfflineAgent, cred_def: &CredentialDef) -> Verifier {
+        let requested_attrs = json!([
+            {
+                "name": "address1",
+                "restrictions": [{ "cred_def_id": cred_def.get_cred_def_id() }]
+            }
+        ])
+        .to_string();
+        let presentation_request_data = PresentationRequestData::create(&faber.profile, "1")
+            .await
+            .unwrap()
+            .set_requested_attributes_as_string(requested_attrs)
+            .unwrap()
+            .set_not_revoked_interval(json!({ "to": chrono::Utc::now().timestamp() }).to_string())
+            .unwrap();
+        let mut verifier = Verifier::create_from_request("1".to_string(), &presentation_request_data).unwrap();
+        verifier.send_presentation_request(faber.send_message()).await.unwrap();
+
+        let request = match alice.receive_message().await {
+            A2AMessage::PresentationRequest(request) => request,
+            message => panic!("Expected a presentation request, received {:?}", message),
+        };
+        let mut prover = Prover::create_from_request("1", request).unwrap();
+        let tails_dir = get_temp_dir_path(TAILS_DIR).to_str().unwrap().to_string();
+        prover
+            .generate_presentation_with_strategy(&alice.profile, &NewestFirst, Some(&tails_dir), "{}".to_string())
+            .await
+            .unwrap();
+        prover.send_presentation(alice.send_message()).await.unwrap();
+
+        let presentation = match faber.receive_message().await {
+            A2AMessage::Presentation(presentation) => presentation,
+            message => panic!("Expected a presentation, received {:?}", message),
+        };
+        verifier
+            .verify_presentation(&faber.profile, presentation, faber.send_message())
+            .await
+            .unwrap();
+        match alice.receive_message().await {
+            A2AMessage::PresentationAck(ack) => prover.process_presentation_ack(ack).unwrap(),
+            message => panic!("Expected a presentation ack, received {:?}", message),
+        };
+
+        assert_eq!(VerifierState::Finished, verifier.get_state());
+        verifier
+    }
+
+    #[tokio::test]
+    async fn test_offline_issue_present_and_revoke() {
+        let (faber, alice) = setup_agents().await;
+        let (_, _, _, _, _, cred_def, rev_reg) =
+            create_and_store_credential_def(&faber.profile, &faber.did, DEFAULT_SCHEMA_ATTRS).await;
+
+        let (issuer, holder) = issue_credential(&faber, &alice, &cred_def, &rev_reg).await;
+        assert!(!holder.is_revoked(&alice.profile).await.unwrap());
+
+        let verifier = present_credential(&faber, &alice, &cred_def).await;
+        assert_eq!(
+            PresentationVerificationStatus::Valid,
+            verifier.get_verification_status()
+        );
+
+        issuer.revoke_credential_local(&faber.profile).await.unwrap();
+        assert!(!holder.is_revoked(&alice.profile).await.unwrap());
+        rev_reg
+            .publish_local_revocations(&faber.profile, &faber.did)
+            .await
+            .unwrap();
+        assert!(holder.is_revoked(&alice.profile).await.unwrap());
+        assert!(issuer.is_revoked(&faber.profile).await.unwrap());
+    }
+}
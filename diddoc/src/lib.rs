@@ -11,4 +11,6 @@ extern crate serde_derive;
 extern crate shared_vcx;
 pub mod aries;
 pub mod errors;
+pub(crate) mod multicodec;
+pub mod peer;
 pub mod w3c;
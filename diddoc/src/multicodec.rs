@@ -0,0 +1,72 @@
+//! Encoding of public keys as multicodec prefixed, multibase (base58btc) values,
+//! as used by did:key, did:peer and `publicKeyMultibase` verification methods.
+
+use crate::errors::error::{DiddocError, DiddocErrorKind, DiddocResult};
+
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+const X25519_MULTICODEC: [u8; 2] = [0xec, 0x01];
+const MULTIBASE_BASE58BTC: char = 'z';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyCodec {
+    Ed25519,
+    X25519,
+}
+
+impl KeyCodec {
+    fn prefix(&self) -> [u8; 2] {
+        match self {
+            KeyCodec::Ed25519 => ED25519_MULTICODEC,
+            KeyCodec::X25519 => X25519_MULTICODEC,
+        }
+    }
+}
+
+pub(crate) fn encode_multibase_key(codec: KeyCodec, key_base58: &str) -> DiddocResult<String> {
+    let key_bytes = bs58::decode(key_base58).into_vec().map_err(|err| {
+        DiddocError::from_msg(
+            DiddocErrorKind::NotBase58,
+            format!("Key {} is not valid base58: {}", key_base58, err),
+        )
+    })?;
+    let mut prefixed = codec.prefix().to_vec();
+    prefixed.extend_from_slice(&key_bytes);
+    Ok(format!(
+        "{}{}",
+        MULTIBASE_BASE58BTC,
+        bs58::encode(prefixed).into_string()
+    ))
+}
+
+/// Decodes a multibase value into raw bytes, leaving any multicodec prefix in place.
+pub(crate) fn decode_multibase(multibase: &str) -> DiddocResult<Vec<u8>> {
+    let encoded = multibase.strip_prefix(MULTIBASE_BASE58BTC).ok_or_else(|| {
+        DiddocError::from_msg(
+            DiddocErrorKind::InvalidDid,
+            format!("Only base58btc multibase values are supported, got: {}", multibase),
+        )
+    })?;
+    bs58::decode(encoded).into_vec().map_err(|err| {
+        DiddocError::from_msg(
+            DiddocErrorKind::NotBase58,
+            format!("Value {} is not valid base58: {}", multibase, err),
+        )
+    })
+}
+
+pub(crate) fn decode_multibase_key(multibase_key: &str) -> DiddocResult<(KeyCodec, String)> {
+    let decoded = decode_multibase(multibase_key)?;
+    [KeyCodec::Ed25519, KeyCodec::X25519]
+        .into_iter()
+        .find_map(|codec| {
+            decoded
+                .strip_prefix(&codec.prefix())
+                .map(|key| (codec, bs58::encode(key).into_string()))
+        })
+        .ok_or_else(|| {
+            DiddocError::from_msg(
+                DiddocErrorKind::InvalidDid,
+                format!("Only Ed25519 and X25519 keys are supported, got: {}", multibase_key),
+            )
+        })
+}
@@ -0,0 +1,330 @@
+//! Creation and expansion of [did:peer](https://identity.foundation/peer-did-method-spec/) DIDs.
+//!
+//! Numalgo 0 DIDs consist of a single Ed25519 inception key, while numalgo 2 DIDs encode
+//! several keys and services directly in the DID, so that both can be expanded into a DID
+//! document without any external resolution.
+
+use serde_json::Value;
+
+use crate::errors::error::{DiddocError, DiddocErrorKind, DiddocResult};
+use crate::multicodec::{decode_multibase_key, encode_multibase_key, KeyCodec};
+use crate::w3c::diddoc::W3cDidDoc;
+use crate::w3c::service::{DidDocService, SERVICE_TYPE_DID_COMMUNICATION};
+use crate::w3c::verification_method::{
+    PublicKeyField, VerificationMethod, VerificationMethodKind, VerificationMethodType,
+};
+
+pub const DID_PEER_PREFIX: &str = "did:peer:";
+
+const SERVICE_TYPE_DIDCOMM_MESSAGING: &str = "DIDCommMessaging";
+const SERVICE_TYPE_DIDCOMM_MESSAGING_ABBREVIATION: &str = "dm";
+
+pub fn is_peer_did(did: &str) -> bool {
+    did.starts_with(DID_PEER_PREFIX)
+}
+
+/// Creates a numalgo 0 did:peer from an Ed25519 verkey.
+pub fn generate_numalgo0(verkey: &str) -> DiddocResult<String> {
+    Ok(format!(
+        "{}0{}",
+        DID_PEER_PREFIX,
+        encode_multibase_key(KeyCodec::Ed25519, verkey)?
+    ))
+}
+
+/// Creates a numalgo 2 did:peer authenticated by an Ed25519 verkey, with a single DIDComm
+/// service reachable at `service_endpoint` through the mediators owning `routing_keys`.
+///
+/// Routing keys given as base58 verkeys get encoded as did:key.
+pub fn generate_numalgo2(verkey: &str, service_endpoint: &str, routing_keys: &[String]) -> DiddocResult<String> {
+    let routing_keys = routing_keys
+        .iter()
+        .map(|key| {
+            if key.starts_with("did:") {
+                Ok(key.clone())
+            } else {
+                Ok(format!("did:key:{}", encode_multibase_key(KeyCodec::Ed25519, key)?))
+            }
+        })
+        .collect::<DiddocResult<Vec<_>>>()?;
+    let service = serde_json::json!({
+        "t": SERVICE_TYPE_DID_COMMUNICATION,
+        "s": service_endpoint,
+        "r": routing_keys,
+        "recipientKeys": ["#key-1"],
+    });
+
+    Ok(format!(
+        "{}2.V{}.S{}",
+        DID_PEER_PREFIX,
+        encode_multibase_key(KeyCodec::Ed25519, verkey)?,
+        base64::encode_config(service.to_string(), base64::URL_SAFE_NO_PAD)
+    ))
+}
+
+/// Creates a numalgo 2 did:peer for DIDComm v2, with an X25519 key agreement key, an Ed25519
+/// authentication key and, if `service_endpoint` is given, a `DIDCommMessaging` service routed
+/// through `routing_keys`, which are expected to be DID URLs.
+pub fn generate_numalgo2_didcomm_v2(
+    verkey: &str,
+    key_agreement_key: &str,
+    service_endpoint: Option<&str>,
+    routing_keys: &[String],
+) -> DiddocResult<String> {
+    let mut did = format!(
+        "{}2.E{}.V{}",
+        DID_PEER_PREFIX,
+        encode_multibase_key(KeyCodec::X25519, key_agreement_key)?,
+        encode_multibase_key(KeyCodec::Ed25519, verkey)?
+    );
+    if let Some(service_endpoint) = service_endpoint {
+        let service = serde_json::json!({
+            "t": SERVICE_TYPE_DIDCOMM_MESSAGING_ABBREVIATION,
+            "s": service_endpoint,
+            "r": routing_keys,
+            "a": ["didcomm/v2"],
+        });
+        did.push_str(&format!(
+            ".S{}",
+            base64::encode_config(service.to_string(), base64::URL_SAFE_NO_PAD)
+        ));
+    }
+    Ok(did)
+}
+
+/// Expands a numalgo 0 or 2 did:peer into its DID document.
+pub fn resolve_peer_did(did: &str) -> DiddocResult<W3cDidDoc> {
+    let peer_id = did
+        .strip_prefix(DID_PEER_PREFIX)
+        .ok_or_else(|| DiddocError::from_msg(DiddocErrorKind::InvalidDid, format!("Not a did:peer: {}", did)))?;
+
+    let mut did_doc = W3cDidDoc::new(did.to_string());
+    if let Some(fingerprint) = peer_id.strip_prefix('0') {
+        add_key(&mut did_doc, fingerprint, KeyPurpose::Authentication)?;
+    } else if let Some(elements) = peer_id.strip_prefix("2.") {
+        for element in elements.split('.') {
+            let (purpose, value) = element.split_at(element.len().min(1));
+            match purpose {
+                "V" => add_key(&mut did_doc, value, KeyPurpose::Authentication)?,
+                "A" => add_key(&mut did_doc, value, KeyPurpose::AssertionMethod)?,
+                "E" => add_key(&mut did_doc, value, KeyPurpose::KeyAgreement)?,
+                "I" => add_key(&mut did_doc, value, KeyPurpose::CapabilityInvocation)?,
+                "D" => add_key(&mut did_doc, value, KeyPurpose::CapabilityDelegation)?,
+                "S" => add_service(&mut did_doc, value)?,
+                _ => {
+                    return Err(DiddocError::from_msg(
+                        DiddocErrorKind::InvalidDid,
+                        format!("Unknown purpose code \"{}\" in did:peer: {}", purpose, did),
+                    ))
+                }
+            }
+        }
+    } else {
+        return Err(DiddocError::from_msg(
+            DiddocErrorKind::InvalidDid,
+            format!("Only did:peer numalgo 0 and 2 are supported, got: {}", did),
+        ));
+    }
+    Ok(did_doc)
+}
+
+enum KeyPurpose {
+    Authentication,
+    AssertionMethod,
+    KeyAgreement,
+    CapabilityInvocation,
+    CapabilityDelegation,
+}
+
+fn add_key(did_doc: &mut W3cDidDoc, fingerprint: &str, purpose: KeyPurpose) -> DiddocResult<()> {
+    let (codec, _) = decode_multibase_key(fingerprint)?;
+    let key_id = format!("{}#key-{}", did_doc.id, did_doc.verification_method.len() + 1);
+    let type_ = match codec {
+        KeyCodec::Ed25519 => VerificationMethodType::Ed25519VerificationKey2020,
+        KeyCodec::X25519 => VerificationMethodType::X25519KeyAgreementKey2020,
+    };
+
+    let relationship = match purpose {
+        KeyPurpose::Authentication => &mut did_doc.authentication,
+        KeyPurpose::AssertionMethod => &mut did_doc.assertion_method,
+        KeyPurpose::KeyAgreement => &mut did_doc.key_agreement,
+        KeyPurpose::CapabilityInvocation => &mut did_doc.capability_invocation,
+        KeyPurpose::CapabilityDelegation => &mut did_doc.capability_delegation,
+    };
+    relationship.push(VerificationMethodKind::Resolvable(key_id.clone()));
+    did_doc.verification_method.push(VerificationMethod::new(
+        key_id,
+        type_,
+        did_doc.id.clone(),
+        PublicKeyField::Multibase {
+            public_key_multibase: fingerprint.to_string(),
+        },
+    ));
+    Ok(())
+}
+
+fn add_service(did_doc: &mut W3cDidDoc, encoded_service: &str) -> DiddocResult<()> {
+    let service = base64::decode_config(encoded_service, base64::URL_SAFE_NO_PAD).map_err(|err| {
+        DiddocError::from_msg(
+            DiddocErrorKind::InvalidDid,
+            format!("did:peer service is not valid base64url: {}", err),
+        )
+    })?;
+    let service: Value = serde_json::from_slice(&service).map_err(|err| {
+        DiddocError::from_msg(
+            DiddocErrorKind::InvalidJson,
+            format!("did:peer service is not valid JSON: {}", err),
+        )
+    })?;
+
+    let type_ = match service["t"].as_str() {
+        Some(SERVICE_TYPE_DIDCOMM_MESSAGING_ABBREVIATION) => SERVICE_TYPE_DIDCOMM_MESSAGING.to_string(),
+        Some(type_) => type_.to_string(),
+        None => {
+            return Err(DiddocError::from_msg(
+                DiddocErrorKind::InvalidJson,
+                format!("did:peer service is missing its type: {}", service),
+            ))
+        }
+    };
+    let service_endpoint = service["s"].as_str().unwrap_or_default().to_string();
+    let routing_keys = string_array(&service["r"]);
+    // Relative references are resolved against the DID being expanded.
+    let recipient_keys = string_array(&service["recipientKeys"])
+        .into_iter()
+        .map(|key| match key.strip_prefix('#') {
+            Some(fragment) => format!("{}#{}", did_doc.id, fragment),
+            None => key,
+        })
+        .collect();
+
+    let id = match did_doc.service.len() {
+        0 => format!("{}#service", did_doc.id),
+        index => format!("{}#service-{}", did_doc.id, index),
+    };
+    did_doc.service.push(DidDocService {
+        id,
+        type_,
+        service_endpoint,
+        priority: did_doc.service.len() as u32,
+        recipient_keys,
+        routing_keys,
+    });
+    Ok(())
+}
+
+fn string_array(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|values| values.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use crate::aries::diddoc::test_utils::{_key_1, _key_1_did_key, _key_2, _service_endpoint};
+    use crate::peer::*;
+
+    #[test]
+    fn test_peer_did_numalgo0_round_trip() {
+        let did = generate_numalgo0(&_key_1()).unwrap();
+        assert_eq!(did, _key_1_did_key().replace("did:key:", "did:peer:0"));
+
+        let did_doc = resolve_peer_did(&did).unwrap();
+        assert_eq!(did_doc.id, did);
+        assert_eq!(did_doc.verification_method[0].public_key_base58().unwrap(), _key_1());
+        assert_eq!(did_doc.authentication[0].id(), format!("{}#key-1", did));
+        assert!(did_doc.service.is_empty());
+    }
+
+    #[test]
+    fn test_peer_did_numalgo2_round_trip() {
+        let did = generate_numalgo2(&_key_1(), &_service_endpoint(), &[_key_2()]).unwrap();
+        assert!(did.starts_with("did:peer:2.Vz6Mk"));
+
+        let did_doc = resolve_peer_did(&did).unwrap();
+        assert_eq!(did_doc.verification_method.len(), 1);
+        assert_eq!(did_doc.verification_method[0].public_key_base58().unwrap(), _key_1());
+
+        let service = &did_doc.service[0];
+        assert_eq!(service.id, format!("{}#service", did));
+        assert_eq!(service.type_, SERVICE_TYPE_DID_COMMUNICATION);
+        assert_eq!(service.service_endpoint, _service_endpoint());
+        assert_eq!(service.recipient_keys, vec![format!("{}#key-1", did)]);
+        assert_eq!(
+            did_doc
+                .dereference_key(&service.recipient_keys[0])
+                .unwrap()
+                .public_key_base58()
+                .unwrap(),
+            _key_1()
+        );
+        assert_eq!(
+            service.routing_keys,
+            vec!["did:key:z6Mkw7FfEGiwh6YQbCLTNbJWAYR8boGNMt7PCjh35GLNxmMo".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_peer_did_numalgo2_didcomm_v2_round_trip() {
+        let key_agreement_key = "JhNWeSVLMYccCk7iopQW4guaSJTojqpMEELgSLhKwRr";
+        let did = generate_numalgo2_didcomm_v2(&_key_1(), key_agreement_key, Some(&_service_endpoint()), &[]).unwrap();
+        assert!(did.starts_with("did:peer:2.Ez6LS"));
+
+        let did_doc = resolve_peer_did(&did).unwrap();
+        let key_agreement = did_doc.key_agreement_methods();
+        assert_eq!(key_agreement.len(), 1);
+        assert_eq!(key_agreement[0].public_key_base58().unwrap(), key_agreement_key);
+        assert_eq!(
+            did_doc
+                .dereference_key(did_doc.authentication[0].id())
+                .unwrap()
+                .public_key_base58()
+                .unwrap(),
+            _key_1()
+        );
+        assert_eq!(did_doc.service[0].type_, "DIDCommMessaging");
+        assert_eq!(did_doc.service[0].service_endpoint, _service_endpoint());
+
+        let did = generate_numalgo2_didcomm_v2(&_key_1(), key_agreement_key, None, &[]).unwrap();
+        assert!(resolve_peer_did(&did).unwrap().service.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_peer_did_numalgo2_from_spec() {
+        let did = "did:peer:2.Ez6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc.Vz6MkqRYqQiSgvZQdnBytw86Qbs2ZWUkGv22od935YF4s8M7V.SeyJ0IjoiZG0iLCJzIjoiaHR0cHM6Ly9leGFtcGxlLmNvbS9lbmRwb2ludCIsInIiOlsiZGlkOmV4YW1wbGU6c29tZW1lZGlhdG9yI3NvbWVrZXkiXX0";
+        let did_doc = resolve_peer_did(did).unwrap();
+
+        assert_eq!(did_doc.verification_method.len(), 2);
+        assert_eq!(
+            did_doc.verification_method[0].type_,
+            VerificationMethodType::X25519KeyAgreementKey2020
+        );
+        assert_eq!(
+            did_doc.verification_method[1].type_,
+            VerificationMethodType::Ed25519VerificationKey2020
+        );
+        assert_eq!(did_doc.key_agreement.len(), 1);
+        assert_eq!(did_doc.key_agreement_methods()[0].id, format!("{}#key-1", did));
+        assert_eq!(did_doc.authentication.len(), 1);
+        assert_eq!(did_doc.authentication[0].id(), format!("{}#key-2", did));
+
+        let service = &did_doc.service[0];
+        assert_eq!(service.type_, "DIDCommMessaging");
+        assert_eq!(service.service_endpoint, "https://example.com/endpoint");
+        assert_eq!(
+            service.routing_keys,
+            vec!["did:example:somemediator#somekey".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_resolve_peer_did_rejects_unsupported_numalgo() {
+        let err = resolve_peer_did("did:peer:1zQmZMygzYqNwU6Uhmewx5Xepf2VLp5S4HLSwwgf2aiKZuwa").unwrap_err();
+        assert_eq!(err.kind(), DiddocErrorKind::InvalidDid);
+
+        let err = resolve_peer_did("did:sov:VsKV7grR1BUE29mG2Fm2kX").unwrap_err();
+        assert_eq!(err.kind(), DiddocErrorKind::InvalidDid);
+    }
+}
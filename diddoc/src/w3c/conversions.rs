@@ -0,0 +1,362 @@
+use std::convert::TryFrom;
+
+use serde_json::Value;
+
+use crate::aries::diddoc::AriesDidDoc;
+use crate::aries::service::AriesService;
+use crate::errors::error::{DiddocError, DiddocErrorKind, DiddocResult};
+use crate::w3c::diddoc::W3cDidDoc;
+use crate::w3c::model::{Authentication, Ed25519PublicKey, CONTEXT, DID_CORE_CONTEXT, KEY_AUTHENTICATION_TYPE};
+use crate::w3c::service::DidDocService;
+use crate::w3c::verification_method::{
+    PublicKeyField, VerificationMethod, VerificationMethodKind, VerificationMethodType,
+};
+
+impl From<AriesDidDoc> for W3cDidDoc {
+    fn from(did_doc: AriesDidDoc) -> Self {
+        let mut w3c_did_doc = W3cDidDoc::new(did_doc.id);
+        w3c_did_doc.context = Value::String(did_doc.context);
+        w3c_did_doc.verification_method = did_doc
+            .public_key
+            .into_iter()
+            .map(|key| {
+                VerificationMethod::new(
+                    key.id,
+                    key.type_.as_str().into(),
+                    key.controller,
+                    PublicKeyField::Base58 {
+                        public_key_base58: key.public_key_base_58,
+                    },
+                )
+            })
+            .collect();
+        w3c_did_doc.authentication = did_doc
+            .authentication
+            .into_iter()
+            .map(|authentication| VerificationMethodKind::Resolvable(authentication.public_key))
+            .collect();
+        w3c_did_doc.service = did_doc
+            .service
+            .into_iter()
+            .map(|service| DidDocService {
+                id: service.id,
+                type_: service.type_,
+                service_endpoint: service.service_endpoint,
+                priority: service.priority,
+                recipient_keys: service.recipient_keys,
+                routing_keys: service.routing_keys,
+            })
+            .collect();
+        w3c_did_doc
+    }
+}
+
+/// Converts a DID Core document into the legacy format, failing instead of dropping anything the
+/// legacy format cannot hold: `@context` entries other than the DID v1 context, `alsoKnownAs`,
+/// `controller`, verification relationships other than `authentication`, verification methods
+/// embedded in `authentication`, and verification methods which are not Ed25519 keys. Multibase and JWK keys are re-encoded in base58. Use
+/// [`AriesDidDoc::from_w3c_lossy`] to convert such documents anyway.
+impl TryFrom<W3cDidDoc> for AriesDidDoc {
+    type Error = DiddocError;
+
+    fn try_from(did_doc: W3cDidDoc) -> DiddocResult<Self> {
+        ensure_representable(&did_doc)?;
+        AriesDidDoc::from_w3c_lossy(did_doc)
+    }
+}
+
+impl AriesDidDoc {
+    /// Converts a DID Core document into the legacy format, dropping what the legacy format
+    /// cannot hold. Every verification method, including the ones embedded in verification
+    /// relationships, becomes a base58 `publicKey`, whatever its type, and `authentication` entries
+    /// reference them with the `Ed25519SignatureAuthentication2018` type. An `@context` other than a
+    /// single string is replaced by the legacy DID v1 context, and `alsoKnownAs`, `controller` and the
+    /// verification relationships other than `authentication` are dropped.
+    /// Fails if a key cannot be represented in base58.
+    pub fn from_w3c_lossy(did_doc: W3cDidDoc) -> DiddocResult<Self> {
+        let public_key = did_doc
+            .verification_method
+            .iter()
+            .chain(did_doc.embedded_verification_methods())
+            .map(|method| {
+                Ok(Ed25519PublicKey {
+                    id: method.id.clone(),
+                    type_: method.type_.as_str().to_string(),
+                    controller: method.controller.clone(),
+                    public_key_base_58: method.public_key_base58()?,
+                })
+            })
+            .collect::<DiddocResult<Vec<_>>>()?;
+        let authentication = did_doc
+            .authentication
+            .iter()
+            .map(|kind| Authentication {
+                type_: String::from(KEY_AUTHENTICATION_TYPE),
+                public_key: kind.id().to_string(),
+            })
+            .collect();
+        let service = did_doc
+            .service
+            .into_iter()
+            .map(|service| AriesService {
+                id: service.id,
+                type_: service.type_,
+                priority: service.priority,
+                recipient_keys: service.recipient_keys,
+                routing_keys: service.routing_keys,
+                service_endpoint: service.service_endpoint,
+            })
+            .collect();
+        Ok(AriesDidDoc {
+            context: legacy_context(&did_doc.context).unwrap_or_else(|| String::from(CONTEXT)),
+            id: did_doc.id,
+            public_key,
+            authentication,
+            service,
+        })
+    }
+}
+
+/// Returns the legacy `@context` equivalent to `context`: a single string is kept as is, and an array
+/// is accepted if it only lists the DID v1 context, under its current or its legacy URL.
+fn legacy_context(context: &Value) -> Option<String> {
+    match context {
+        Value::String(context) => Some(context.clone()),
+        Value::Array(contexts) if !contexts.is_empty() => contexts
+            .iter()
+            .all(|context| matches!(context.as_str(), Some(DID_CORE_CONTEXT) | Some(CONTEXT)))
+            .then(|| String::from(CONTEXT)),
+        _ => None,
+    }
+}
+
+fn ensure_representable(did_doc: &W3cDidDoc) -> DiddocResult<()> {
+    let unsupported = |property: &str| {
+        Err(DiddocError::from_msg(
+            DiddocErrorKind::InvalidState,
+            format!(
+                "DID document {} cannot be converted to the legacy format without losing its {}",
+                did_doc.id, property
+            ),
+        ))
+    };
+    if legacy_context(&did_doc.context).is_none() {
+        return unsupported("@context");
+    }
+    if !did_doc.also_known_as.is_empty() {
+        return unsupported("alsoKnownAs");
+    }
+    if did_doc.controller.is_some() {
+        return unsupported("controller");
+    }
+    if !did_doc.assertion_method.is_empty() {
+        return unsupported("assertionMethod");
+    }
+    if !did_doc.key_agreement.is_empty() {
+        return unsupported("keyAgreement");
+    }
+    if !did_doc.capability_invocation.is_empty() {
+        return unsupported("capabilityInvocation");
+    }
+    if !did_doc.capability_delegation.is_empty() {
+        return unsupported("capabilityDelegation");
+    }
+    if did_doc.embedded_verification_methods().next().is_some() {
+        return unsupported("embedded verification methods");
+    }
+    if let Some(method) = did_doc.verification_method.iter().find(|method| {
+        !matches!(
+            method.type_,
+            VerificationMethodType::Ed25519VerificationKey2018 | VerificationMethodType::Ed25519VerificationKey2020
+        )
+    }) {
+        return unsupported(&format!("{} verification method {}", method.type_.as_str(), method.id));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use std::convert::TryFrom;
+
+    use serde_json::json;
+
+    use crate::aries::diddoc::test_utils::{_did_doc_inlined_recipient_keys, _did_doc_vcx_legacy};
+    use crate::aries::diddoc::AriesDidDoc;
+    use crate::errors::error::DiddocErrorKind;
+    use crate::w3c::diddoc::W3cDidDoc;
+    use crate::w3c::verification_method::{VerificationMethodKind, VerificationMethodType};
+
+    #[test]
+    fn test_aries_did_doc_round_trip() {
+        for did_doc in [_did_doc_vcx_legacy(), _did_doc_inlined_recipient_keys()] {
+            let w3c_did_doc = W3cDidDoc::from(did_doc.clone());
+            let converted = AriesDidDoc::try_from(w3c_did_doc).unwrap();
+            assert_eq!(
+                serde_json::to_value(&converted).unwrap(),
+                serde_json::to_value(&did_doc).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_aries_did_doc_to_w3c() {
+        let did_doc = _did_doc_vcx_legacy();
+        let w3c_did_doc = W3cDidDoc::from(did_doc.clone());
+
+        assert_eq!(w3c_did_doc.verification_method.len(), did_doc.public_key.len());
+        assert_eq!(
+            w3c_did_doc.verification_method[0].type_,
+            VerificationMethodType::Ed25519VerificationKey2018
+        );
+        assert_eq!(
+            w3c_did_doc.authentication[0],
+            VerificationMethodKind::Resolvable(did_doc.authentication[0].public_key.clone())
+        );
+        assert_eq!(w3c_did_doc.service[0].recipient_keys, did_doc.service[0].recipient_keys);
+    }
+
+    #[test]
+    fn test_w3c_did_doc_with_embedded_and_multibase_keys_to_aries_lossy() {
+        let w3c_did_doc: W3cDidDoc = serde_json::from_value(json!({
+            "@context": ["https://www.w3.org/ns/did/v1"],
+            "id": "did:example:123",
+            "verificationMethod": [{
+                "id": "did:example:123#key-1",
+                "type": "Ed25519VerificationKey2020",
+                "controller": "did:example:123",
+                "publicKeyMultibase": "z6MkszZtxCmA2Ce4vUV132PCuLQmwnaDD5mw2L23fGNnsiX3"
+            }],
+            "authentication": [
+                "did:example:123#key-1",
+                {
+                    "id": "did:example:123#key-2",
+                    "type": "Ed25519VerificationKey2018",
+                    "controller": "did:example:123",
+                    "publicKeyBase58": "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL"
+                }
+            ],
+            "service": [{
+                "id": "did:example:123#didcomm",
+                "type": "did-communication",
+                "serviceEndpoint": "https://example.org/agent",
+                "recipientKeys": ["did:example:123#key-1"]
+            }, {
+                "id": "did:example:123#linked-domain",
+                "type": "LinkedDomains",
+                "serviceEndpoint": "https://example.org"
+            }]
+        }))
+        .unwrap();
+
+        assert!(AriesDidDoc::try_from(w3c_did_doc.clone()).is_err());
+        let did_doc = AriesDidDoc::from_w3c_lossy(w3c_did_doc).unwrap();
+        assert_eq!(did_doc.context, "https://w3id.org/did/v1");
+        assert_eq!(did_doc.public_key.len(), 2);
+        assert_eq!(
+            did_doc.public_key[0].public_key_base_58,
+            "EYJrMxWigf9boyeJMTRN4Ern8DJMoCXaLK77pzQmxVjf"
+        );
+        assert_eq!(did_doc.authentication[1].public_key, "did:example:123#key-2");
+        assert_eq!(did_doc.service.len(), 2);
+    }
+
+    #[test]
+    fn test_w3c_did_doc_with_unsupported_jwk_fails() {
+        let w3c_did_doc: W3cDidDoc = serde_json::from_value(json!({
+            "id": "did:example:123",
+            "verificationMethod": [{
+                "id": "did:example:123#key-1",
+                "type": "JsonWebKey2020",
+                "controller": "did:example:123",
+                "publicKeyJwk": { "kty": "EC", "crv": "P-256", "x": "abc", "y": "def" }
+            }]
+        }))
+        .unwrap();
+        assert!(AriesDidDoc::from_w3c_lossy(w3c_did_doc).is_err());
+    }
+
+    #[test]
+    fn test_w3c_did_doc_round_trip() {
+        let w3c_did_doc: W3cDidDoc = serde_json::from_value(json!({
+            "@context": "https://w3id.org/did/v1",
+            "id": "did:example:123",
+            "verificationMethod": [{
+                "id": "did:example:123#key-1",
+                "type": "Ed25519VerificationKey2018",
+                "controller": "did:example:123",
+                "publicKeyBase58": "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL"
+            }],
+            "authentication": ["did:example:123#key-1"],
+            "service": [{
+                "id": "did:example:123#didcomm",
+                "type": "did-communication",
+                "serviceEndpoint": "https://example.org/agent",
+                "recipientKeys": ["did:example:123#key-1"],
+                "routingKeys": []
+            }]
+        }))
+        .unwrap();
+
+        let did_doc = AriesDidDoc::try_from(w3c_did_doc.clone()).unwrap();
+        assert_eq!(W3cDidDoc::from(did_doc), w3c_did_doc);
+    }
+
+    #[test]
+    fn test_w3c_did_doc_with_did_v1_context_array_to_aries() {
+        let w3c_did_doc: W3cDidDoc = serde_json::from_value(json!({
+            "@context": ["https://www.w3.org/ns/did/v1"],
+            "id": "did:example:123"
+        }))
+        .unwrap();
+        let did_doc = AriesDidDoc::try_from(w3c_did_doc).unwrap();
+        assert_eq!(did_doc.context, "https://w3id.org/did/v1");
+    }
+
+    #[test]
+    fn test_w3c_did_doc_with_unrepresentable_data_is_rejected() {
+        let did_doc = json!({
+            "id": "did:example:123",
+            "verificationMethod": [{
+                "id": "did:example:123#key-1",
+                "type": "Ed25519VerificationKey2018",
+                "controller": "did:example:123",
+                "publicKeyBase58": "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL"
+            }]
+        });
+        let x25519_key = json!({
+            "id": "did:example:123#key-x25519",
+            "type": "X25519KeyAgreementKey2019",
+            "controller": "did:example:123",
+            "publicKeyBase58": "JhNWeSVLMYccCk7iopQW4guaSJTojqpMEELgSLhKwRr"
+        });
+        let additions = [
+            (
+                "@context",
+                json!([
+                    "https://www.w3.org/ns/did/v1",
+                    "https://w3id.org/security/suites/x25519-2020/v1"
+                ]),
+            ),
+            ("alsoKnownAs", json!(["did:example:456"])),
+            ("controller", json!("did:example:456")),
+            ("assertionMethod", json!(["did:example:123#key-1"])),
+            ("keyAgreement", json!([x25519_key.clone()])),
+            ("authentication", json!([x25519_key.clone()])),
+        ];
+        for (property, value) in additions {
+            let mut did_doc = did_doc.clone();
+            did_doc[property] = value;
+            let w3c_did_doc: W3cDidDoc = serde_json::from_value(did_doc).unwrap();
+            let err = AriesDidDoc::try_from(w3c_did_doc.clone()).unwrap_err();
+            assert_eq!(err.kind(), DiddocErrorKind::InvalidState, "{}", property);
+            assert!(AriesDidDoc::from_w3c_lossy(w3c_did_doc).is_ok());
+        }
+
+        let mut did_doc = did_doc;
+        did_doc["verificationMethod"] = json!([x25519_key]);
+        let w3c_did_doc: W3cDidDoc = serde_json::from_value(did_doc).unwrap();
+        assert!(AriesDidDoc::try_from(w3c_did_doc).is_err());
+    }
+}
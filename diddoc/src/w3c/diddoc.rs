@@ -1,16 +1,180 @@
-use crate::w3c::model::{Authentication, Ed25519PublicKey};
+use serde_json::Value;
+
+use crate::w3c::model::CONTEXT;
 use crate::w3c::service::DidDocService;
+use crate::w3c::verification_method::{VerificationMethod, VerificationMethodKind};
+
+fn default_context() -> Value {
+    Value::String(CONTEXT.to_string())
+}
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// A DID document as defined by [DID Core](https://www.w3.org/TR/did-core/). Verification relationships
+/// can either reference a verification method of the document or embed one. The legacy `publicKey`
+/// property is accepted as an alias of `verificationMethod`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct W3cDidDoc {
-    #[serde(rename = "@context")]
-    pub context: String,
+    #[serde(rename = "@context", default = "default_context")]
+    pub context: Value,
     #[serde(default)]
     pub id: String,
+    #[serde(default, rename = "alsoKnownAs", skip_serializing_if = "Vec::is_empty")]
+    pub also_known_as: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub controller: Option<Value>,
+    #[serde(default, rename = "verificationMethod", alias = "publicKey")]
+    pub verification_method: Vec<VerificationMethod>,
     #[serde(default)]
-    #[serde(rename = "publicKey")] // todo: remove this, use authentication
-    pub public_key: Vec<Ed25519PublicKey>,
+    pub authentication: Vec<VerificationMethodKind>,
+    #[serde(default, rename = "assertionMethod", skip_serializing_if = "Vec::is_empty")]
+    pub assertion_method: Vec<VerificationMethodKind>,
+    #[serde(default, rename = "keyAgreement", skip_serializing_if = "Vec::is_empty")]
+    pub key_agreement: Vec<VerificationMethodKind>,
+    #[serde(default, rename = "capabilityInvocation", skip_serializing_if = "Vec::is_empty")]
+    pub capability_invocation: Vec<VerificationMethodKind>,
+    #[serde(default, rename = "capabilityDelegation", skip_serializing_if = "Vec::is_empty")]
+    pub capability_delegation: Vec<VerificationMethodKind>,
     #[serde(default)]
-    pub authentication: Vec<Authentication>,
     pub service: Vec<DidDocService>,
 }
+
+impl W3cDidDoc {
+    pub fn new(id: String) -> Self {
+        Self {
+            context: default_context(),
+            id,
+            also_known_as: vec![],
+            controller: None,
+            verification_method: vec![],
+            authentication: vec![],
+            assertion_method: vec![],
+            key_agreement: vec![],
+            capability_invocation: vec![],
+            capability_delegation: vec![],
+            service: vec![],
+        }
+    }
+
+    /// Finds the verification method identified by `key_ref`, which can either be a full DID URL
+    /// (`did:example:123#key-1`) or a fragment relative to this document (`#key-1`). Methods embedded
+    /// in verification relationships are searched as well.
+    pub fn dereference_key(&self, key_ref: &str) -> Option<&VerificationMethod> {
+        let fragment = key_ref.rsplit_once('#').map(|(_, fragment)| fragment)?;
+        let matches = |id: &str| id == key_ref || id.rsplit_once('#').map(|(_, id)| id) == Some(fragment);
+        self.verification_method
+            .iter()
+            .chain(self.embedded_verification_methods())
+            .find(|method| matches(&method.id))
+    }
+
+    /// Returns the verification methods embedded in any of the verification relationships.
+    pub fn embedded_verification_methods(&self) -> impl Iterator<Item = &VerificationMethod> {
+        self.authentication
+            .iter()
+            .chain(self.assertion_method.iter())
+            .chain(self.key_agreement.iter())
+            .chain(self.capability_invocation.iter())
+            .chain(self.capability_delegation.iter())
+            .filter_map(|kind| match kind {
+                VerificationMethodKind::Resolved(method) => Some(method),
+                VerificationMethodKind::Resolvable(_) => None,
+            })
+    }
+
+    /// Returns the verification methods usable for key agreement, resolving references.
+    pub fn key_agreement_methods(&self) -> Vec<&VerificationMethod> {
+        self.key_agreement
+            .iter()
+            .filter_map(|kind| match kind {
+                VerificationMethodKind::Resolved(method) => Some(method),
+                VerificationMethodKind::Resolvable(reference) => self.dereference_key(reference),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use serde_json::json;
+
+    use crate::w3c::diddoc::W3cDidDoc;
+    use crate::w3c::verification_method::{PublicKeyField, VerificationMethod, VerificationMethodKind};
+
+    fn _did_doc() -> W3cDidDoc {
+        let mut did_doc = W3cDidDoc::new("did:example:123".to_string());
+        did_doc.verification_method.push(VerificationMethod::new(
+            "did:example:123#key-1".to_string(),
+            "Ed25519VerificationKey2018".into(),
+            "did:example:123".to_string(),
+            PublicKeyField::Base58 {
+                public_key_base58: "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL".to_string(),
+            },
+        ));
+        did_doc
+    }
+
+    #[test]
+    fn test_dereference_key_by_did_url() {
+        let did_doc = _did_doc();
+        let key = did_doc.dereference_key("did:example:123#key-1").unwrap();
+        assert_eq!(
+            key.public_key_base58().unwrap(),
+            "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL"
+        );
+    }
+
+    #[test]
+    fn test_dereference_key_by_fragment() {
+        let did_doc = _did_doc();
+        assert!(did_doc.dereference_key("#key-1").is_some());
+        assert!(did_doc.dereference_key("#key-2").is_none());
+        assert!(did_doc
+            .dereference_key("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL")
+            .is_none());
+    }
+
+    #[test]
+    fn test_deserialize_did_core_document() {
+        let did_doc: W3cDidDoc = serde_json::from_value(json!({
+            "@context": ["https://www.w3.org/ns/did/v1", "https://w3id.org/security/suites/x25519-2020/v1"],
+            "id": "did:example:123",
+            "alsoKnownAs": ["did:example:456"],
+            "verificationMethod": [{
+                "id": "did:example:123#key-1",
+                "type": "Ed25519VerificationKey2020",
+                "controller": "did:example:123",
+                "publicKeyMultibase": "z6MkszZtxCmA2Ce4vUV132PCuLQmwnaDD5mw2L23fGNnsiX3"
+            }],
+            "authentication": ["#key-1"],
+            "keyAgreement": [{
+                "id": "did:example:123#key-2",
+                "type": "X25519KeyAgreementKey2020",
+                "controller": "did:example:123",
+                "publicKeyMultibase": "z6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc"
+            }],
+            "service": [{
+                "id": "did:example:123#didcomm",
+                "type": "did-communication",
+                "serviceEndpoint": "https://example.org/agent",
+                "recipientKeys": ["did:example:123#key-2"]
+            }, {
+                "id": "did:example:123#linked-domain",
+                "type": "LinkedDomains",
+                "serviceEndpoint": "https://example.org"
+            }]
+        }))
+        .unwrap();
+
+        assert_eq!(did_doc.also_known_as, vec!["did:example:456".to_string()]);
+        assert_eq!(
+            did_doc.authentication[0],
+            VerificationMethodKind::Resolvable("#key-1".to_string())
+        );
+        assert!(did_doc.dereference_key("#key-2").is_some());
+        assert_eq!(did_doc.key_agreement_methods().len(), 1);
+        assert_eq!(did_doc.service.len(), 2);
+
+        let serialized = serde_json::to_value(&did_doc).unwrap();
+        assert_eq!(serde_json::from_value::<W3cDidDoc>(serialized).unwrap(), did_doc);
+    }
+}
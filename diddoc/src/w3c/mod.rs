@@ -1,3 +1,5 @@
+pub mod conversions;
 pub mod diddoc;
 pub mod model;
 pub mod service;
+pub mod verification_method;
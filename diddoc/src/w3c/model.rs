@@ -1,6 +1,7 @@
 use serde::{Serialize, Serializer};
 
 pub const CONTEXT: &str = "https://w3id.org/did/v1";
+pub const DID_CORE_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
 pub const KEY_TYPE: &str = "Ed25519VerificationKey2018";
 pub const KEY_AUTHENTICATION_TYPE: &str = "Ed25519SignatureAuthentication2018";
 
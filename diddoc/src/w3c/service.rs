@@ -1,8 +1,26 @@
-#[derive(Debug, Deserialize, Serialize, Clone)]
+pub const SERVICE_TYPE_DID_COMMUNICATION: &str = "did-communication";
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct DidDocService {
     pub id: String,
     #[serde(rename = "type")]
     pub type_: String,
     #[serde(rename = "serviceEndpoint")]
     pub service_endpoint: String,
+    // DIDComm service properties, see https://github.com/hyperledger/aries-rfcs/blob/main/features/0067-didcomm-diddoc-conventions/README.md#service-conventions
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub priority: u32,
+    #[serde(default)]
+    #[serde(rename = "recipientKeys")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recipient_keys: Vec<String>,
+    #[serde(default)]
+    #[serde(rename = "routingKeys")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub routing_keys: Vec<String>,
+}
+
+fn is_zero(priority: &u32) -> bool {
+    *priority == 0
 }
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::errors::error::{DiddocError, DiddocErrorKind, DiddocResult};
+use crate::multicodec::{decode_multibase, decode_multibase_key};
+
+/// A verification method as defined by [DID Core](https://www.w3.org/TR/did-core/#verification-methods).
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct VerificationMethod {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub type_: VerificationMethodType,
+    pub controller: String,
+    #[serde(flatten)]
+    pub public_key: PublicKeyField,
+}
+
+impl VerificationMethod {
+    pub fn new(id: String, type_: VerificationMethodType, controller: String, public_key: PublicKeyField) -> Self {
+        Self {
+            id,
+            type_,
+            controller,
+            public_key,
+        }
+    }
+
+    /// Returns the raw public key encoded as base58, regardless of the representation it is given in.
+    pub fn public_key_base58(&self) -> DiddocResult<String> {
+        match &self.public_key {
+            PublicKeyField::Base58 { public_key_base58 } => Ok(public_key_base58.clone()),
+            PublicKeyField::Multibase { public_key_multibase } => match decode_multibase_key(public_key_multibase) {
+                Ok((_, key)) => Ok(key),
+                // Keys of the 2018 and 2019 suites carry no multicodec prefix
+                Err(_) => Ok(bs58::encode(decode_multibase(public_key_multibase)?).into_string()),
+            },
+            PublicKeyField::Jwk { public_key_jwk } => public_key_jwk.public_key_base58(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationMethodType {
+    Ed25519VerificationKey2018,
+    Ed25519VerificationKey2020,
+    X25519KeyAgreementKey2019,
+    X25519KeyAgreementKey2020,
+    JsonWebKey2020,
+    /// Any other type, kept as is.
+    Other(String),
+}
+
+impl VerificationMethodType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Ed25519VerificationKey2018 => "Ed25519VerificationKey2018",
+            Self::Ed25519VerificationKey2020 => "Ed25519VerificationKey2020",
+            Self::X25519KeyAgreementKey2019 => "X25519KeyAgreementKey2019",
+            Self::X25519KeyAgreementKey2020 => "X25519KeyAgreementKey2020",
+            Self::JsonWebKey2020 => "JsonWebKey2020",
+            Self::Other(type_) => type_,
+        }
+    }
+}
+
+impl From<&str> for VerificationMethodType {
+    fn from(type_: &str) -> Self {
+        match type_ {
+            "Ed25519VerificationKey2018" => Self::Ed25519VerificationKey2018,
+            "Ed25519VerificationKey2020" => Self::Ed25519VerificationKey2020,
+            "X25519KeyAgreementKey2019" => Self::X25519KeyAgreementKey2019,
+            "X25519KeyAgreementKey2020" => Self::X25519KeyAgreementKey2020,
+            "JsonWebKey2020" => Self::JsonWebKey2020,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl Serialize for VerificationMethodType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for VerificationMethodType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let type_ = String::deserialize(deserializer)?;
+        Ok(Self::from(type_.as_str()))
+    }
+}
+
+/// The representation of the public key of a [`VerificationMethod`].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum PublicKeyField {
+    Base58 {
+        #[serde(rename = "publicKeyBase58")]
+        public_key_base58: String,
+    },
+    Multibase {
+        #[serde(rename = "publicKeyMultibase")]
+        public_key_multibase: String,
+    },
+    Jwk {
+        #[serde(rename = "publicKeyJwk")]
+        public_key_jwk: JsonWebKey,
+    },
+}
+
+/// A public key in the [JWK](https://www.rfc-editor.org/rfc/rfc7517) format. Members other than
+/// the key type, curve and coordinates are kept in `extra`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct JsonWebKey {
+    pub kty: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl JsonWebKey {
+    /// Returns the key of an octet key pair (Ed25519 or X25519) encoded as base58.
+    pub fn public_key_base58(&self) -> DiddocResult<String> {
+        let x = match (self.kty.as_str(), &self.x) {
+            ("OKP", Some(x)) => x,
+            _ => {
+                return Err(DiddocError::from_msg(
+                    DiddocErrorKind::InvalidJson,
+                    format!(
+                        "Only octet key pair JWKs can be converted to base58, got key type: {}",
+                        self.kty
+                    ),
+                ))
+            }
+        };
+        let key = base64::decode_config(x, base64::URL_SAFE_NO_PAD).map_err(|err| {
+            DiddocError::from_msg(
+                DiddocErrorKind::InvalidJson,
+                format!("JWK x coordinate is not valid base64url: {}", err),
+            )
+        })?;
+        Ok(bs58::encode(key).into_string())
+    }
+}
+
+/// An entry of a verification relationship (e.g. `authentication`), which either references
+/// a verification method of the document or embeds one.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum VerificationMethodKind {
+    Resolved(VerificationMethod),
+    Resolvable(String),
+}
+
+impl VerificationMethodKind {
+    /// Returns the id of the referenced or embedded verification method.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Resolved(verification_method) => &verification_method.id,
+            Self::Resolvable(reference) => reference,
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use serde_json::json;
+
+    use crate::w3c::verification_method::*;
+
+    #[test]
+    fn test_verification_method_public_key_representations() {
+        let base58: VerificationMethod = serde_json::from_value(json!({
+            "id": "did:example:123#key-1",
+            "type": "Ed25519VerificationKey2018",
+            "controller": "did:example:123",
+            "publicKeyBase58": "H3C2AVvLMv6gmMNam3uVAjZpfkcJCwDwnZn6z3wXmqPV"
+        }))
+        .unwrap();
+        let multibase: VerificationMethod = serde_json::from_value(json!({
+            "id": "did:example:123#key-2",
+            "type": "Ed25519VerificationKey2020",
+            "controller": "did:example:123",
+            "publicKeyMultibase": "z6MkszZtxCmA2Ce4vUV132PCuLQmwnaDD5mw2L23fGNnsiX3"
+        }))
+        .unwrap();
+        let jwk: VerificationMethod = serde_json::from_value(json!({
+            "id": "did:example:123#key-3",
+            "type": "JsonWebKey2020",
+            "controller": "did:example:123",
+            "publicKeyJwk": {
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "x": "7rGlDImlHxHQD_iQyXbfw8ftEEM9vqjlFJEVADgZbK4",
+                "kid": "key-3"
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(base58.type_, VerificationMethodType::Ed25519VerificationKey2018);
+        assert_eq!(
+            base58.public_key_base58().unwrap(),
+            "H3C2AVvLMv6gmMNam3uVAjZpfkcJCwDwnZn6z3wXmqPV"
+        );
+        assert_eq!(multibase.public_key_base58().unwrap().len(), 44);
+        assert!(matches!(jwk.public_key, PublicKeyField::Jwk { .. }));
+        assert_eq!(jwk.public_key_base58().unwrap().len(), 44);
+
+        let serialized = serde_json::to_value(&jwk).unwrap();
+        assert_eq!(serialized["publicKeyJwk"]["kid"], "key-3");
+        assert_eq!(serialized["type"], "JsonWebKey2020");
+    }
+
+    #[test]
+    fn test_verification_method_kind_by_reference_or_embedded() {
+        let kinds: Vec<VerificationMethodKind> = serde_json::from_value(json!([
+            "did:example:123#key-1",
+            {
+                "id": "did:example:123#key-2",
+                "type": "X25519KeyAgreementKey2019",
+                "controller": "did:example:123",
+                "publicKeyBase58": "JhNWeSVLMYccCk7iopQW4guaSJTojqpMEELgSLhKwRr"
+            }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            kinds[0],
+            VerificationMethodKind::Resolvable("did:example:123#key-1".to_string())
+        );
+        assert_eq!(kinds[1].id(), "did:example:123#key-2");
+    }
+
+    #[test]
+    fn test_unknown_verification_method_type_is_kept() {
+        let type_: VerificationMethodType = serde_json::from_value(json!("EcdsaSecp256k1VerificationKey2019")).unwrap();
+        assert_eq!(
+            type_,
+            VerificationMethodType::Other("EcdsaSecp256k1VerificationKey2019".to_string())
+        );
+        assert_eq!(
+            serde_json::to_value(&type_).unwrap(),
+            json!("EcdsaSecp256k1VerificationKey2019")
+        );
+    }
+}
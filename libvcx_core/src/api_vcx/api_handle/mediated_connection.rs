@@ -1,17 +1,24 @@
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
+use async_trait::async_trait;
 use serde_json;
 
 use aries_vcx::agency_client::api::downloaded_message::DownloadedMessage;
 use aries_vcx::agency_client::MessageStatusCode;
 use aries_vcx::common::ledger::transactions::into_did_doc;
+use aries_vcx::core::profile::profile::Profile;
+use aries_vcx::errors::error::VcxResult;
+use aries_vcx::handlers::compat::{from_legacy_message, from_legacy_send_closure, to_legacy_message};
 use aries_vcx::handlers::connection::mediated_connection::MediatedConnection;
+use aries_vcx::handlers::dispatcher::{DispatchOutcome, FamilyHandler, MessageDispatcher, ProtocolFamily};
 use aries_vcx::messages::a2a::A2AMessage;
 use aries_vcx::messages::protocols::connection::invite::Invitation as InvitationV3;
 use aries_vcx::messages::protocols::connection::invite::PublicInvitation;
 use aries_vcx::messages::protocols::connection::request::Request;
+use aries_vcx::messages2::AriesMessage;
 use aries_vcx::protocols::mediated_connection::pairwise_info::PairwiseInfo;
-use aries_vcx::protocols::SendClosure;
+use aries_vcx::protocols::{AriesSendClosure, SendClosure};
 
 use crate::api_vcx::api_global::agency_client::get_main_agency_client;
 use crate::api_vcx::api_global::profile::{get_main_profile, get_main_profile_optional_pool};
@@ -199,8 +206,43 @@ pub async fn update_state_with_message(handle: u32, message: &str) -> LibvcxResu
     Ok(state)
 }
 
+/// Answers the messages an established connection handles by itself: trust pings, out-of-band
+/// handshake reuses and feature discovery.
+struct ConnectionMessageHandler;
+
+#[async_trait]
+impl FamilyHandler<Mutex<MediatedConnection>> for ConnectionMessageHandler {
+    async fn handle_message(
+        &self,
+        profile: &Arc<dyn Profile>,
+        connection: &Mutex<MediatedConnection>,
+        message: AriesMessage,
+        _send_message: AriesSendClosure,
+    ) -> VcxResult<()> {
+        let mut updated = connection.lock()?.clone();
+        updated.handle_message(to_legacy_message(&message)?, profile).await?;
+        *connection.lock()? = updated;
+        Ok(())
+    }
+}
+
+fn connection_message_dispatcher() -> LibvcxResult<MessageDispatcher<Mutex<MediatedConnection>>> {
+    let dispatcher = MessageDispatcher::new();
+    let handler = Arc::new(ConnectionMessageHandler);
+    for family in [
+        ProtocolFamily::TrustPing,
+        ProtocolFamily::OutOfBand,
+        ProtocolFamily::DiscoverFeatures,
+    ] {
+        dispatcher.register_family(family, handler.clone())?;
+    }
+    Ok(dispatcher)
+}
+
+/// Handles a message received on the connection. Messages of protocols the connection does not
+/// answer by itself are answered with a problem-report, messages of unknown protocols are ignored.
 pub async fn handle_message(handle: u32, message: &str) -> LibvcxResult<()> {
-    let mut connection = CONNECTION_MAP.get_cloned(handle)?;
+    let connection = CONNECTION_MAP.get_cloned(handle)?;
     let message: A2AMessage = serde_json::from_str(message).map_err(|err| {
         LibvcxError::from_msg(
             LibvcxErrorKind::InvalidJson,
@@ -210,9 +252,26 @@ pub async fn handle_message(handle: u32, message: &str) -> LibvcxResult<()> {
             ),
         )
     })?;
+    let message = match from_legacy_message(&message) {
+        Ok(message) => message,
+        Err(err) => {
+            info!("Message {:?} will not be answered: {}", message, err);
+            return Ok(());
+        }
+    };
     let profile = get_main_profile_optional_pool(); // do not throw if pool is not open
-    connection.handle_message(message, &profile).await?;
-    CONNECTION_MAP.insert(handle, connection)
+    let send_message = from_legacy_send_closure(connection.send_message_closure(&profile).await?);
+    let connection = Mutex::new(connection);
+    let outcome = connection_message_dispatcher()?
+        .dispatch(&profile, &connection, message, send_message)
+        .await?;
+    if outcome == DispatchOutcome::ProblemReported {
+        info!(
+            "connection::handle_message >> no handler for message received on connection {}",
+            handle
+        );
+    }
+    CONNECTION_MAP.insert(handle, connection.into_inner()?)
 }
 
 pub async fn update_state(handle: u32) -> LibvcxResult<u32> {
@@ -487,7 +546,9 @@ pub mod tests {
     };
 
     use crate::api_vcx::api_handle::mediated_connection;
-    use crate::api_vcx::api_handle::mediated_connection::test_utils::build_test_connection_inviter_invited;
+    use crate::api_vcx::api_handle::mediated_connection::test_utils::{
+        build_test_connection_invitee_completed, build_test_connection_inviter_invited,
+    };
     use crate::api_vcx::VcxStateType;
 
     use super::*;
@@ -724,6 +785,38 @@ pub mod tests {
         assert_eq!(err.kind(), LibvcxErrorKind::NotReady);
     }
 
+    #[tokio::test]
+    #[cfg(feature = "general_test")]
+    async fn test_handle_message_on_completed_connection() {
+        let _setup = SetupMocks::init();
+
+        let handle = build_test_connection_invitee_completed();
+        let ping = json!({
+            "@type": "https://didcomm.org/trust_ping/1.0/ping",
+            "@id": "ping_id",
+            "response_requested": true
+        });
+        handle_message(handle, &ping.to_string()).await.unwrap();
+        // Answered with a problem-report
+        let basic_message = json!({
+            "@type": "https://didcomm.org/basicmessage/1.0/message",
+            "@id": "message_id",
+            "content": "hello",
+            "sent_time": "2023-01-01T00:00:00Z"
+        });
+        handle_message(handle, &basic_message.to_string()).await.unwrap();
+        // Ignored
+        let unknown = json!({
+            "@type": "https://didcomm.org/foo/5.0/bar",
+            "@id": "unknown_id"
+        });
+        handle_message(handle, &unknown.to_string()).await.unwrap();
+
+        let err = handle_message(handle, "not a message").await.unwrap_err();
+        assert_eq!(err.kind(), LibvcxErrorKind::InvalidJson);
+        assert_eq!(get_state(handle), VcxStateType::VcxStateAccepted as u32);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_generate_public_invitation() {
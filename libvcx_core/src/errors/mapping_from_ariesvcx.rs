@@ -54,6 +54,7 @@ impl From<AriesVcxErrorKind> for LibvcxErrorKind {
             AriesVcxErrorKind::InvalidLedgerResponse => LibvcxErrorKind::InvalidLedgerResponse,
             AriesVcxErrorKind::NoPoolOpen => LibvcxErrorKind::NoPoolOpen,
             AriesVcxErrorKind::PostMessageFailed => LibvcxErrorKind::PostMessageFailed,
+            AriesVcxErrorKind::Timeout => LibvcxErrorKind::PostMessageFailed,
             AriesVcxErrorKind::WalletCreate => LibvcxErrorKind::WalletCreate,
             AriesVcxErrorKind::WalletAccessFailed => LibvcxErrorKind::WalletAccessFailed,
             AriesVcxErrorKind::InvalidWalletHandle => LibvcxErrorKind::InvalidWalletHandle,
@@ -29,7 +29,8 @@ use crate::{
     cache::wallet_cache::{WalletCache, WalletCacheHitData, WalletCacheHitMetrics},
     export_import::{export_continue, finish_import, preparse_file_to_import},
     storage::{
-        default::SQLiteStorageType, mysql::MySqlStorageType, WalletStorage, WalletStorageType,
+        default::SQLiteStorageType, mysql::MySqlStorageType, postgres::PostgresStorageType,
+        WalletStorage, WalletStorageType,
     },
     wallet::{Keys, Wallet},
 };
@@ -82,10 +83,12 @@ impl WalletService {
         let storage_types = {
             let s1: Arc<dyn WalletStorageType> = Arc::new(SQLiteStorageType::new());
             let s2: Arc<dyn WalletStorageType> = Arc::new(MySqlStorageType::new());
+            let s3: Arc<dyn WalletStorageType> = Arc::new(PostgresStorageType::new());
 
             Mutex::new(HashMap::from([
                 ("default".to_string(), s1),
                 ("mysql".to_string(), s2),
+                ("postgres".to_string(), s3),
             ]))
         };
 
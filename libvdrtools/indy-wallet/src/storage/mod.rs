@@ -5,6 +5,8 @@ use crate::{language, wallet::EncryptedValue};
 
 pub mod default;
 pub mod mysql;
+pub mod postgres;
+mod sql;
 
 #[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
 pub enum Tag {
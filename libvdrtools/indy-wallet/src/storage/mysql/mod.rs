@@ -1,7 +1,4 @@
-use std::{
-    collections::{HashMap, VecDeque},
-    iter::Iterator,
-};
+use std::{collections::HashMap, iter::Iterator};
 
 use async_trait::async_trait;
 use futures::lock::Mutex;
@@ -11,56 +8,25 @@ use log::LevelFilter;
 use query::{wql_to_sql, wql_to_sql_count};
 use serde::Deserialize;
 use sqlx::{
-    mysql::{MySqlConnectOptions, MySqlPoolOptions, MySqlRow},
-    ConnectOptions, MySqlPool, Row,
+    mysql::{MySqlConnectOptions, MySqlPoolOptions},
+    ConnectOptions, MySqlPool,
 };
 
 use crate::{
     language,
-    storage::{StorageIterator, StorageRecord, Tag, TagName, WalletStorage, WalletStorageType},
+    storage::{
+        sql::{
+            _tag_names_to_plain, _tags_from_json, _tags_to_json, _tags_to_plain, stream_records,
+            SqlArgument, SqlStorageIterator,
+        },
+        StorageIterator, StorageRecord, Tag, TagName, WalletStorage, WalletStorageType,
+    },
     wallet::EncryptedValue,
     RecordOptions, SearchOptions,
 };
 
 mod query;
 
-struct MySQLStorageIterator {
-    records: Option<VecDeque<IndyResult<StorageRecord>>>,
-    total_count: Option<usize>,
-}
-
-impl MySQLStorageIterator {
-    fn new(
-        records: Option<VecDeque<IndyResult<StorageRecord>>>,
-        total_count: Option<usize>,
-    ) -> IndyResult<MySQLStorageIterator> {
-        Ok(MySQLStorageIterator {
-            records,
-            total_count,
-        })
-    }
-}
-
-#[async_trait]
-impl StorageIterator for MySQLStorageIterator {
-    async fn next(&mut self) -> IndyResult<Option<StorageRecord>> {
-        // TODO: Optimize!!!
-        if let Some(ref mut records) = self.records {
-            if let Some(record) = records.pop_front() {
-                return Ok(Some(record?));
-            } else {
-                Ok(None)
-            }
-        } else {
-            Ok(None)
-        }
-    }
-
-    fn get_total_count(&self) -> IndyResult<Option<usize>> {
-        Ok(self.total_count.to_owned())
-    }
-}
-
 #[derive(Deserialize, Debug)]
 struct Config {
     pub read_host: String,
@@ -564,41 +530,41 @@ impl WalletStorage for MySqlStorage {
     }
 
     async fn get_all(&self) -> IndyResult<Box<dyn StorageIterator>> {
-        let records: VecDeque<_> = sqlx::query(
+        let (total_count,) = sqlx::query_as::<sqlx::MySql, (i64,)>(
             r#"
-            SELECT type, name, value, tags
+            SELECT count(*)
             FROM items
             WHERE wallet_id = ?
-            ORDER BY id
             "#,
         )
         .bind(self.wallet_id)
-        .map(|r: MySqlRow| -> IndyResult<StorageRecord> {
-            let type_: String = r.get(0);
-            let id: String = r.get(1);
-            let value: Vec<u8> = r.get(2);
-            let tags: serde_json::Value = r.get(3);
-
-            let res = StorageRecord::new(
-                base64::decode(&id)?,
-                Some(EncryptedValue::from_bytes(&value)?),
-                Some(base64::decode(&type_)?),
-                Some(_tags_from_json(tags)?),
-            );
+        .fetch_one(&self.read_pool)
+        .await?;
 
-            Ok(res)
-        })
-        .fetch_all(&self.read_pool)
-        .await?
-        .into_iter()
-        .collect();
+        let options = SearchOptions {
+            retrieve_records: true,
+            retrieve_total_count: true,
+            retrieve_type: true,
+            retrieve_value: true,
+            retrieve_tags: true,
+        };
 
-        let total_len = records.len();
+        let records = stream_records(
+            self.read_pool.clone(),
+            r#"
+            SELECT type, name, value, tags
+            FROM items
+            WHERE wallet_id = ?
+            ORDER BY id
+            "#
+            .to_string(),
+            vec![SqlArgument::Int(self.wallet_id)],
+            options,
+        );
 
-        // FIXME: Fetch total count
-        Ok(Box::new(MySQLStorageIterator::new(
+        Ok(Box::new(SqlStorageIterator::new(
             Some(records),
-            Some(total_len),
+            Some(total_count as usize),
         )?))
     }
 
@@ -608,7 +574,7 @@ impl WalletStorage for MySqlStorage {
         query: &language::Operator,
         options: Option<&str>,
     ) -> IndyResult<Box<dyn StorageIterator>> {
-        let options = if let Some(options) = options {
+        let options: SearchOptions = if let Some(options) = options {
             serde_json::from_str(options).to_indy(
                 IndyErrorKind::InvalidStructure,
                 "Search options is malformed json",
@@ -617,26 +583,18 @@ impl WalletStorage for MySqlStorage {
             SearchOptions::default()
         };
 
-        let mut conn = self.read_pool.acquire().await?;
-
         let total_count = if options.retrieve_total_count {
             let (query, args) = wql_to_sql_count(self.wallet_id, type_, query)?;
             let mut query = sqlx::query_as::<sqlx::MySql, (i64,)>(&query);
 
-            for arg in args.iter() {
-                query = if arg.is_i64() {
-                    query.bind(arg.as_i64().unwrap())
-                } else if arg.is_string() {
-                    query.bind(arg.as_str().unwrap())
-                } else {
-                    return Err(err_msg(
-                        IndyErrorKind::InvalidState,
-                        "Unexpected sql parameter type.",
-                    ));
-                }
+            for arg in SqlArgument::from_values(args)? {
+                query = match arg {
+                    SqlArgument::Int(value) => query.bind(value),
+                    SqlArgument::Text(value) => query.bind(value),
+                };
             }
 
-            let (total_count,) = query.fetch_one(&mut conn).await?;
+            let (total_count,) = query.fetch_one(&self.read_pool).await?;
             Some(total_count as usize)
         } else {
             None
@@ -644,65 +602,13 @@ impl WalletStorage for MySqlStorage {
 
         let records = if options.retrieve_records {
             let (query, args) = wql_to_sql(self.wallet_id, type_, query, &options)?;
-
-            let mut query = sqlx::query::<sqlx::MySql>(&query);
-
-            for arg in args.iter() {
-                query = if arg.is_i64() {
-                    query.bind(arg.as_i64().unwrap())
-                } else if arg.is_string() {
-                    query.bind(arg.as_str().unwrap())
-                } else {
-                    return Err(err_msg(
-                        IndyErrorKind::InvalidState,
-                        "Unexpected sql parameter type.",
-                    ));
-                }
-            }
-
-            let records: VecDeque<_> = query
-                .map(|r: MySqlRow| -> IndyResult<StorageRecord> {
-                    let type_ = if options.retrieve_type {
-                        let type_: String = r.get(0);
-                        Some(base64::decode(&type_)?)
-                    } else {
-                        None
-                    };
-
-                    let id = {
-                        let id: String = r.get(1);
-                        base64::decode(&id)?
-                    };
-
-                    let value = if options.retrieve_value {
-                        let value: Vec<u8> = r.get(2);
-                        Some(EncryptedValue::from_bytes(&value)?)
-                    } else {
-                        None
-                    };
-
-                    let tags = if options.retrieve_tags {
-                        let tags: serde_json::Value = r.get(3);
-                        Some(_tags_from_json(tags)?)
-                    } else {
-                        None
-                    };
-
-                    let res = StorageRecord::new(id, value, type_, tags);
-
-                    Ok(res)
-                })
-                .fetch_all(&self.read_pool)
-                .await?
-                .into_iter()
-                .collect();
-
-            Some(records)
+            let args = SqlArgument::from_values(args)?;
+            Some(stream_records(self.read_pool.clone(), query, args, options))
         } else {
             None
         };
 
-        Ok(Box::new(MySQLStorageIterator::new(records, total_count)?))
+        Ok(Box::new(SqlStorageIterator::new(records, total_count)?))
     }
 
     fn close(&mut self) -> IndyResult<()> {
@@ -1420,6 +1326,59 @@ mod tests {
         _cleanup("mysql_storage_get_all_works_for_empty").await;
     }
 
+    #[async_std::test]
+    #[cfg(feature = "mysql")]
+    async fn mysql_storage_iterators_work_for_more_records_than_batch() {
+        use crate::storage::sql::FETCH_BATCH_SIZE;
+
+        _cleanup("mysql_storage_iterators_work_for_more_records_than_batch").await;
+
+        {
+            let storage =
+                _storage("mysql_storage_iterators_work_for_more_records_than_batch").await;
+            let count = 2 * FETCH_BATCH_SIZE as u8 + 10;
+
+            for i in 0..count {
+                storage
+                    .add(&_type1(), &_id(i), &_value(i), &_tags())
+                    .await
+                    .unwrap();
+            }
+
+            let mut storage_iterator = storage.get_all().await.unwrap();
+            assert_eq!(
+                storage_iterator.get_total_count().unwrap(),
+                Some(count as usize)
+            );
+
+            for i in 0..count {
+                let record = storage_iterator.next().await.unwrap().unwrap();
+                assert_eq!(record.id, _id(i));
+                assert_eq!(record.value.unwrap(), _value(i));
+            }
+            assert!(storage_iterator.next().await.unwrap().is_none());
+
+            let options = r#"{"retrieveTotalCount": true, "retrieveType": true}"#;
+            let mut storage_iterator = storage
+                .search(&_type1(), &language::Operator::And(vec![]), Some(options))
+                .await
+                .unwrap();
+            assert_eq!(
+                storage_iterator.get_total_count().unwrap(),
+                Some(count as usize)
+            );
+
+            let mut records = 0;
+            while let Some(record) = storage_iterator.next().await.unwrap() {
+                assert_eq!(record.type_.unwrap(), _type1());
+                records += 1;
+            }
+            assert_eq!(records, count as usize);
+        }
+
+        _cleanup("mysql_storage_iterators_work_for_more_records_than_batch").await;
+    }
+
     #[async_std::test]
     #[cfg(feature = "mysql")]
     async fn mysql_storage_update_works() {
@@ -2063,84 +2022,3 @@ mod tests {
         path.to_str().unwrap().to_owned()
     }
 }
-
-// FIXME: copy/paste
-fn _tags_to_plain(tags: &[Tag]) -> HashMap<String, String> {
-    let mut map = HashMap::with_capacity(tags.len());
-
-    for tag in tags {
-        match *tag {
-            Tag::Encrypted(ref name, ref value) => {
-                map.insert(base64::encode(name), base64::encode(value))
-            }
-            Tag::PlainText(ref name, ref value) => {
-                map.insert(format!("~{}", &base64::encode(name)), value.to_string())
-            }
-        };
-    }
-
-    map
-}
-
-// FIXME: copy/paste
-fn _tags_to_json(tags: &[Tag]) -> IndyResult<String> {
-    serde_json::to_string(&_tags_to_plain(tags)).to_indy(
-        IndyErrorKind::InvalidState,
-        "Unable to serialize tags as json",
-    )
-}
-
-// FIXME: copy/paste
-fn _tags_from_json(json: serde_json::Value) -> IndyResult<Vec<Tag>> {
-    let string_tags: HashMap<String, String> = serde_json::from_value(json).to_indy(
-        IndyErrorKind::InvalidState,
-        "Unable to deserialize tags from json",
-    )?;
-
-    let mut tags = Vec::with_capacity(string_tags.len());
-
-    for (k, v) in string_tags {
-        if k.starts_with('~') {
-            let mut key = k;
-            key.remove(0);
-            tags.push(Tag::PlainText(
-                base64::decode(&key).to_indy(
-                    IndyErrorKind::InvalidState,
-                    "Unable to decode tag key from base64",
-                )?,
-                v,
-            ));
-        } else {
-            tags.push(Tag::Encrypted(
-                base64::decode(&k).to_indy(
-                    IndyErrorKind::InvalidState,
-                    "Unable to decode tag key from base64",
-                )?,
-                base64::decode(&v).to_indy(
-                    IndyErrorKind::InvalidState,
-                    "Unable to decode tag value from base64",
-                )?,
-            ));
-        }
-    }
-    Ok(tags)
-}
-
-// FIXME: copy/paste
-fn _tag_names_to_plain(tag_names: &[TagName]) -> Vec<String> {
-    tag_names
-        .iter()
-        .map(|tag_name| match *tag_name {
-            TagName::OfEncrypted(ref tag_name) => base64::encode(tag_name),
-            TagName::OfPlain(ref tag_name) => format!("~{}", base64::encode(tag_name)),
-        })
-        .collect()
-}
-
-// FIXME: copy/paste
-fn _tag_names_to_json(tag_names: &[TagName]) -> IndyResult<String> {
-    serde_json::to_string(&_tag_names_to_plain(tag_names)).to_indy(
-        IndyErrorKind::InvalidState,
-        "Unable to serialize tag names as json",
-    )
-}
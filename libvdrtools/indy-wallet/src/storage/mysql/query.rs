@@ -4,6 +4,7 @@ use serde_json::Value;
 
 use crate::{
     language::{Operator, TagName, TargetValue},
+    storage::sql::ToPlain,
     SearchOptions,
 };
 
@@ -292,28 +293,3 @@ fn join_operators(
 
     Ok(s)
 }
-
-// FIXME: It is quite smilar for to_string method of tag and value, but for some reason
-// to_string uses "". It is added to avoid potential damage as i have no time
-// for investigation.
-trait ToPlain {
-    fn to_plain(&self) -> String;
-}
-
-impl ToPlain for TagName {
-    fn to_plain(&self) -> String {
-        match *self {
-            TagName::EncryptedTagName(ref v) => base64::encode(v),
-            TagName::PlainTagName(ref v) => format!("~{}", base64::encode(v)),
-        }
-    }
-}
-
-impl ToPlain for TargetValue {
-    fn to_plain(&self) -> String {
-        match *self {
-            TargetValue::Unencrypted(ref s) => s.to_owned(),
-            TargetValue::Encrypted(ref v) => base64::encode(v),
-        }
-    }
-}
@@ -0,0 +1,1387 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::lock::Mutex;
+use indy_api_types::errors::prelude::*;
+use indy_utils::crypto::base64;
+use log::LevelFilter;
+use query::{wql_to_sql, wql_to_sql_count};
+use serde::Deserialize;
+use sqlx::{
+    migrate::Migrator,
+    postgres::{PgConnectOptions, PgPoolOptions},
+    ConnectOptions, PgPool,
+};
+
+use crate::{
+    language,
+    storage::{
+        sql::{
+            _tag_names_to_plain, _tags_from_json, _tags_to_json, stream_records, SqlArgument,
+            SqlStorageIterator,
+        },
+        StorageIterator, StorageRecord, Tag, TagName, WalletStorage, WalletStorageType,
+    },
+    wallet::EncryptedValue,
+    RecordOptions, SearchOptions,
+};
+
+mod query;
+
+/// Schema of the wallets database. Applied whenever a write connection pool is created, so the
+/// database only has to exist beforehand.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+/// Postgres code of the unique_violation error.
+const UNIQUE_VIOLATION: &str = "23505";
+
+#[derive(Deserialize, Debug)]
+struct Config {
+    pub read_host: String,
+    pub write_host: String,
+    pub port: u16,
+    pub db_name: String,
+    #[serde(default = "default_connection_limit")]
+    pub connection_limit: u32,
+}
+
+fn default_connection_limit() -> u32 {
+    100
+}
+
+#[derive(Deserialize)]
+pub struct Credentials {
+    pub user: String,
+    pub pass: String,
+}
+
+/// Wallet stored in a Postgres database shared by many wallets. Its records are the rows of the
+/// `items` table referencing the `wallets` row of the wallet.
+#[derive(Debug)]
+struct PostgresStorage {
+    wallet_id: i64,
+    read_pool: PgPool,
+    write_pool: PgPool,
+}
+
+pub struct PostgresStorageType {
+    connections: Mutex<HashMap<String, PgPool>>,
+}
+
+impl PostgresStorageType {
+    pub fn new() -> PostgresStorageType {
+        PostgresStorageType {
+            connections: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn _connect(
+        &self,
+        read_only: bool,
+        config: Option<&str>,
+        credentials: Option<&str>,
+    ) -> IndyResult<PgPool> {
+        let config = config
+            .map(serde_json::from_str::<Config>)
+            .transpose()
+            .to_indy(IndyErrorKind::InvalidStructure, "Malformed config json")?
+            .ok_or(err_msg(
+                IndyErrorKind::InvalidStructure,
+                "Absent config json",
+            ))?;
+
+        let credentials = credentials
+            .map(serde_json::from_str::<Credentials>)
+            .transpose()
+            .to_indy(
+                IndyErrorKind::InvalidStructure,
+                "Malformed credentials json",
+            )?
+            .ok_or(err_msg(
+                IndyErrorKind::InvalidStructure,
+                "Absent credentials json",
+            ))?;
+
+        let host_addr = if read_only {
+            &config.read_host
+        } else {
+            &config.write_host
+        };
+
+        let connection_string = format!(
+            "{}:{}@{}:{}/{}",
+            credentials.user, credentials.pass, host_addr, config.port, config.db_name
+        );
+
+        let mut connref = self.connections.lock().await;
+
+        if let Some(connection) = connref.get(&connection_string) {
+            return Ok(connection.clone());
+        }
+
+        let mut pg_connect_options = PgConnectOptions::new()
+            .host(host_addr)
+            .port(config.port)
+            .database(&config.db_name)
+            .username(&credentials.user)
+            .password(&credentials.pass);
+        pg_connect_options.log_statements(LevelFilter::Debug);
+
+        let connection = PgPoolOptions::default()
+            .max_connections(config.connection_limit)
+            .test_before_acquire(false)
+            .connect_with(pg_connect_options)
+            .await?;
+
+        if !read_only {
+            MIGRATOR.run(&connection).await.to_indy(
+                IndyErrorKind::IOError,
+                "Unable to apply the wallet database migrations",
+            )?;
+        }
+
+        connref.insert(connection_string, connection.clone());
+        Ok(connection)
+    }
+}
+
+#[async_trait]
+impl WalletStorage for PostgresStorage {
+    ///
+    /// Tries to fetch values and/or tags from the storage.
+    /// Returns Result with StorageEntity object which holds requested data in case of success or
+    /// Result with IndyError in case of failure.
+    ///
+    ///
+    /// # Arguments
+    ///
+    ///  * `type_` - type_ of the item in storage
+    ///  * `id` - id of the item in storage
+    ///  * `options` - JSon containing what needs to be fetched.
+    ///  Example: {"retrieveValue": true, "retrieveTags": true}
+    ///
+    /// # Returns
+    ///
+    /// Result that can be either:
+    ///
+    ///  * `StorageEntity` - Contains name, optional value and optional tags
+    ///  * `IndyError`
+    ///
+    /// # Errors
+    ///
+    /// Any of the following `IndyError` type_ of errors can be throw by this method:
+    ///
+    ///  * `IndyError::ItemNotFound` - Item is not found in database
+    ///  * `IOError("IO error during storage operation:...")` - Failed connection or SQL query
+    ///
+    async fn get(&self, type_: &[u8], id: &[u8], options: &str) -> IndyResult<StorageRecord> {
+        let options: RecordOptions = serde_json::from_str(options).to_indy(
+            IndyErrorKind::InvalidStructure,
+            "RecordOptions is malformed json",
+        )?;
+
+        let mut conn = self.read_pool.acquire().await?;
+
+        let (value, tags): (Option<Vec<u8>>, Option<serde_json::Value>) = sqlx::query_as(&format!(
+            r#"
+            SELECT {}, {}
+            FROM items
+            WHERE
+                wallet_id = $1
+                    AND type = $2
+                    AND name = $3
+            "#,
+            if options.retrieve_value {
+                "value"
+            } else {
+                "NULL::bytea"
+            },
+            if options.retrieve_tags {
+                "tags"
+            } else {
+                "NULL::jsonb"
+            },
+        ))
+        .bind(self.wallet_id)
+        .bind(&base64::encode(type_))
+        .bind(&base64::encode(id))
+        .fetch_one(&mut conn)
+        .await?;
+
+        let value = if let Some(value) = value {
+            Some(EncryptedValue::from_bytes(&value)?)
+        } else {
+            None
+        };
+
+        let type_ = if options.retrieve_type {
+            Some(type_.to_vec())
+        } else {
+            None
+        };
+
+        let tags = if let Some(tags) = tags {
+            Some(_tags_from_json(tags)?)
+        } else {
+            None
+        };
+
+        Ok(StorageRecord::new(id.to_vec(), value, type_, tags))
+    }
+
+    ///
+    /// inserts value and tags into storage.
+    /// Returns Result with () on success or
+    /// Result with IndyError in case of failure.
+    ///
+    ///
+    /// # Arguments
+    ///
+    ///  * `type_` - type of the item in storage
+    ///  * `id` - id of the item in storage
+    ///  * `value` - value of the item in storage
+    ///  * `tags` - tags assigned to the value
+    ///
+    /// # Returns
+    ///
+    /// Result that can be either:
+    ///
+    ///  * `()`
+    ///  * `IndyError`
+    ///
+    /// # Errors
+    ///
+    /// Any of the following `IndyError` class of errors can be throw by this method:
+    ///
+    ///  * `IndyError::ItemAlreadyExists` - Item is already present in database
+    ///  * `IOError("IO error during storage operation:...")` - Failed connection or SQL query
+    ///
+    async fn add(
+        &self,
+        type_: &[u8],
+        id: &[u8],
+        value: &EncryptedValue,
+        tags: &[Tag],
+    ) -> IndyResult<()> {
+        let mut tx = self.write_pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO items (type, name, value, tags, wallet_id)
+            VALUES ($1, $2, $3, $4::jsonb, $5)
+            "#,
+        )
+        .bind(&base64::encode(type_))
+        .bind(&base64::encode(id))
+        .bind(&value.to_bytes())
+        .bind(&_tags_to_json(tags)?)
+        .bind(self.wallet_id)
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn update(&self, type_: &[u8], id: &[u8], value: &EncryptedValue) -> IndyResult<()> {
+        let mut tx = self.write_pool.begin().await?;
+
+        let row_updated = sqlx::query(
+            r#"
+            UPDATE items
+            SET value = $1
+            WHERE type = $2
+                AND name = $3
+                AND wallet_id = $4
+            "#,
+        )
+        .bind(&value.to_bytes())
+        .bind(&base64::encode(type_))
+        .bind(&base64::encode(id))
+        .bind(self.wallet_id)
+        .execute(&mut tx)
+        .await?
+        .rows_affected();
+
+        _commit_single_row_update(tx, row_updated).await
+    }
+
+    async fn add_tags(&self, type_: &[u8], id: &[u8], tags: &[Tag]) -> IndyResult<()> {
+        let mut tx = self.write_pool.begin().await?;
+
+        let row_updated = sqlx::query(
+            r#"
+            UPDATE items
+            SET tags = tags || $1::jsonb
+            WHERE type = $2
+                AND name = $3
+                AND wallet_id = $4
+            "#,
+        )
+        .bind(&_tags_to_json(tags)?)
+        .bind(&base64::encode(type_))
+        .bind(&base64::encode(id))
+        .bind(self.wallet_id)
+        .execute(&mut tx)
+        .await?
+        .rows_affected();
+
+        _commit_single_row_update(tx, row_updated).await
+    }
+
+    async fn update_tags(&self, type_: &[u8], id: &[u8], tags: &[Tag]) -> IndyResult<()> {
+        let mut tx = self.write_pool.begin().await?;
+
+        let row_updated = sqlx::query(
+            r#"
+            UPDATE items
+            SET tags = $1::jsonb
+            WHERE type = $2
+                AND name = $3
+                AND wallet_id = $4
+            "#,
+        )
+        .bind(&_tags_to_json(tags)?)
+        .bind(&base64::encode(type_))
+        .bind(&base64::encode(id))
+        .bind(self.wallet_id)
+        .execute(&mut tx)
+        .await?
+        .rows_affected();
+
+        _commit_single_row_update(tx, row_updated).await
+    }
+
+    async fn delete_tags(&self, type_: &[u8], id: &[u8], tag_names: &[TagName]) -> IndyResult<()> {
+        let mut tx = self.write_pool.begin().await?;
+
+        let row_updated = sqlx::query(
+            r#"
+            UPDATE items
+            SET tags = tags - $1::text[]
+            WHERE type = $2
+                AND name = $3
+                AND wallet_id = $4
+            "#,
+        )
+        .bind(_tag_names_to_plain(tag_names))
+        .bind(&base64::encode(type_))
+        .bind(&base64::encode(id))
+        .bind(self.wallet_id)
+        .execute(&mut tx)
+        .await?
+        .rows_affected();
+
+        _commit_single_row_update(tx, row_updated).await
+    }
+
+    ///
+    /// deletes value and tags into storage.
+    /// Returns Result with () on success or
+    /// Result with IndyError in case of failure.
+    ///
+    ///
+    /// # Arguments
+    ///
+    ///  * `type_` - type of the item in storage
+    ///  * `id` - id of the item in storage
+    ///
+    /// # Returns
+    ///
+    /// Result that can be either:
+    ///
+    ///  * `()`
+    ///  * `IndyError`
+    ///
+    /// # Errors
+    ///
+    /// Any of the following `IndyError` type_ of errors can be throw by this method:
+    ///
+    ///  * `IndyError::ItemNotFound` - Item is not found in database
+    ///  * `IOError("IO error during storage operation:...")` - Failed connection or SQL query
+    ///
+    async fn delete(&self, type_: &[u8], id: &[u8]) -> IndyResult<()> {
+        let mut tx = self.write_pool.begin().await?;
+
+        let rows_affected = sqlx::query(
+            r#"
+            DELETE FROM items
+            WHERE type = $1
+                AND name = $2
+                AND wallet_id = $3"#,
+        )
+        .bind(&base64::encode(type_))
+        .bind(&base64::encode(id))
+        .bind(self.wallet_id)
+        .execute(&mut tx)
+        .await?
+        .rows_affected();
+
+        match rows_affected {
+            1 => {
+                tx.commit().await?;
+                Ok(())
+            }
+            0 => Err(err_msg(
+                IndyErrorKind::WalletItemNotFound,
+                "Item to delete not found",
+            )),
+            _ => Err(err_msg(
+                IndyErrorKind::InvalidState,
+                "More than one row deleted. Seems wallet structure is inconsistent",
+            )),
+        }
+    }
+
+    async fn get_storage_metadata(&self) -> IndyResult<Vec<u8>> {
+        let mut conn = self.read_pool.acquire().await?;
+
+        let (metadata,): (String,) = sqlx::query_as::<_, (String,)>(
+            r#"
+            SELECT metadata
+            FROM wallets
+            WHERE id = $1
+            "#,
+        )
+        .bind(self.wallet_id)
+        .fetch_one(&mut conn)
+        .await?;
+
+        base64::decode(&metadata)
+    }
+
+    async fn set_storage_metadata(&self, metadata: &[u8]) -> IndyResult<()> {
+        let mut tx = self.write_pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE wallets
+            SET metadata = $1
+            WHERE id = $2
+            "#,
+        )
+        .bind(base64::encode(metadata))
+        .bind(self.wallet_id)
+        .execute(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_all(&self) -> IndyResult<Box<dyn StorageIterator>> {
+        let (total_count,) = sqlx::query_as::<sqlx::Postgres, (i64,)>(
+            r#"
+            SELECT count(*)
+            FROM items
+            WHERE wallet_id = $1
+            "#,
+        )
+        .bind(self.wallet_id)
+        .fetch_one(&self.read_pool)
+        .await?;
+
+        let options = SearchOptions {
+            retrieve_records: true,
+            retrieve_total_count: true,
+            retrieve_type: true,
+            retrieve_value: true,
+            retrieve_tags: true,
+        };
+
+        let records = stream_records(
+            self.read_pool.clone(),
+            r#"
+            SELECT type, name, value, tags
+            FROM items
+            WHERE wallet_id = $1
+            ORDER BY id
+            "#
+            .to_string(),
+            vec![SqlArgument::Int(self.wallet_id)],
+            options,
+        );
+
+        Ok(Box::new(SqlStorageIterator::new(
+            Some(records),
+            Some(total_count as usize),
+        )?))
+    }
+
+    async fn search(
+        &self,
+        type_: &[u8],
+        query: &language::Operator,
+        options: Option<&str>,
+    ) -> IndyResult<Box<dyn StorageIterator>> {
+        let options: SearchOptions = if let Some(options) = options {
+            serde_json::from_str(options).to_indy(
+                IndyErrorKind::InvalidStructure,
+                "Search options is malformed json",
+            )?
+        } else {
+            SearchOptions::default()
+        };
+
+        let total_count = if options.retrieve_total_count {
+            let (query, args) = wql_to_sql_count(self.wallet_id, type_, query)?;
+            let mut query = sqlx::query_as::<sqlx::Postgres, (i64,)>(&query);
+
+            for arg in SqlArgument::from_values(args)? {
+                query = match arg {
+                    SqlArgument::Int(value) => query.bind(value),
+                    SqlArgument::Text(value) => query.bind(value),
+                };
+            }
+
+            let (total_count,) = query.fetch_one(&self.read_pool).await?;
+            Some(total_count as usize)
+        } else {
+            None
+        };
+
+        let records = if options.retrieve_records {
+            let (query, args) = wql_to_sql(self.wallet_id, type_, query, &options)?;
+            let args = SqlArgument::from_values(args)?;
+            Some(stream_records(self.read_pool.clone(), query, args, options))
+        } else {
+            None
+        };
+
+        Ok(Box::new(SqlStorageIterator::new(records, total_count)?))
+    }
+
+    fn close(&mut self) -> IndyResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WalletStorageType for PostgresStorageType {
+    ///
+    /// Deletes the wallet with the provided id from the Postgres database specified in the
+    /// config, together with all of its records.
+    ///
+    /// # Arguments
+    ///
+    ///  * `id` - id of the wallet
+    ///  * `config` - config containing the location of the Postgres database
+    ///  * `credentials` - DB credentials
+    ///
+    /// # Returns
+    ///
+    /// Result that can be either:
+    ///
+    ///  * `()`
+    ///  * `IndyError`
+    ///
+    /// # Errors
+    ///
+    /// Any of the following `IndyError` type_ of errors can be throw by this method:
+    ///
+    ///  * `IndyError::NotFound` - Wallet with the provided id not found
+    ///  * `IOError("IO error during storage operation:...")` - Failed connection or SQL query
+    ///
+    async fn delete_storage(
+        &self,
+        id: &str,
+        config: Option<&str>,
+        credentials: Option<&str>,
+    ) -> IndyResult<()> {
+        let mut tx = self
+            ._connect(false, config, credentials)
+            .await?
+            .begin()
+            .await?;
+
+        let rows_affected = sqlx::query(
+            r#"
+            DELETE FROM wallets
+            WHERE name = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&mut tx)
+        .await?
+        .rows_affected();
+
+        match rows_affected {
+            1 => {
+                tx.commit().await?;
+                Ok(())
+            }
+            0 => Err(err_msg(
+                IndyErrorKind::WalletNotFound,
+                "Item to delete not found",
+            )),
+            _ => Err(err_msg(
+                IndyErrorKind::InvalidState,
+                "More than one row deleted. Seems wallet structure is inconsistent",
+            )),
+        }
+    }
+
+    ///
+    /// Creates the wallet with the provided id in the Postgres database specified in the config,
+    /// storing the encryption keys needed for encryption and decryption of its data.
+    ///
+    /// # Arguments
+    ///
+    ///  * `id` - id of the wallet
+    ///  * `config` - config containing the location of the Postgres database
+    ///  * `credentials` - DB credentials
+    ///  * `metadata` - encryption keys that need to be stored for the newly created wallet
+    ///
+    /// # Returns
+    ///
+    /// Result that can be either:
+    ///
+    ///  * `()`
+    ///  * `IndyError`
+    ///
+    /// # Errors
+    ///
+    /// Any of the following `IndyError` type_ of errors can be throw by this method:
+    ///
+    ///  * `AlreadyExists` - Wallet with a given id already exists in the database
+    ///  * `IOError("IO error during storage operation:...")` - Connection to the DB failed
+    ///  * `IOError("Unable to apply the wallet database migrations")` - Creation of schema failed
+    ///
+    async fn create_storage(
+        &self,
+        id: &str,
+        config: Option<&str>,
+        credentials: Option<&str>,
+        metadata: &[u8],
+    ) -> IndyResult<()> {
+        let mut tx = self
+            ._connect(false, config, credentials)
+            .await?
+            .begin()
+            .await?;
+
+        let res = sqlx::query(
+            r#"
+            INSERT INTO wallets (name, metadata)
+            VALUES ($1, $2)
+            "#,
+        )
+        .bind(id)
+        .bind(base64::encode(metadata))
+        .execute(&mut tx)
+        .await;
+
+        match res {
+            Err(sqlx::Error::Database(e))
+                if e.code().map_or(false, |code| code == UNIQUE_VIOLATION) =>
+            {
+                return Err(err_msg(
+                    IndyErrorKind::WalletAlreadyExists,
+                    "Wallet already exists",
+                ))
+            }
+            e => e?,
+        };
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    ///
+    /// Establishes a connection to the Postgres database specified in the config and looks up
+    /// the wallet with the provided id. In case of success returns a Storage object embedding
+    /// the connection pools and the id of the wallet its operations are restricted to.
+    ///
+    ///
+    /// # Arguments
+    ///
+    ///  * `id` - id of the wallet
+    ///  * `config` - config containing the location of the Postgres database
+    ///  * `credentials` - DB credentials
+    ///
+    /// # Returns
+    ///
+    /// Result that can be either:
+    ///
+    ///  * `Box<Storage>` - `PostgresStorage` of the wallet
+    ///  * `IndyError`
+    ///
+    /// # Errors
+    ///
+    /// Any of the following `IndyError` type_ of errors can be throw by this method:
+    ///
+    ///  * `IndyError::NotFound` - Wallet with the provided id not found
+    ///  * `IOError("IO error during storage operation:...")` - Failed connection or SQL query
+    ///
+    async fn open_storage(
+        &self,
+        id: &str,
+        config: Option<&str>,
+        credentials: Option<&str>,
+    ) -> IndyResult<Box<dyn WalletStorage>> {
+        // The write pool goes first so the schema is migrated before the wallet is looked up.
+        let write_pool = self._connect(false, config, credentials).await?;
+        let read_pool = self._connect(true, config, credentials).await?;
+
+        let res = sqlx::query_as::<_, (i64,)>(
+            r#"
+            SELECT id FROM wallets
+            WHERE name = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&read_pool)
+        .await;
+
+        let (wallet_id,) = match res {
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(err_msg(IndyErrorKind::WalletNotFound, "Wallet not found"));
+            }
+            e => e?,
+        };
+
+        Ok(Box::new(PostgresStorage {
+            read_pool,
+            write_pool,
+            wallet_id,
+        }))
+    }
+}
+
+async fn _commit_single_row_update(
+    tx: sqlx::Transaction<'_, sqlx::Postgres>,
+    row_updated: u64,
+) -> IndyResult<()> {
+    match row_updated {
+        1 => {
+            tx.commit().await?;
+            Ok(())
+        }
+        0 => Err(err_msg(
+            IndyErrorKind::WalletItemNotFound,
+            "Item to update not found",
+        )),
+        _ => Err(err_msg(
+            IndyErrorKind::InvalidState,
+            "More than one row update. Seems wallet structure is inconsistent",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indy_utils::assert_kind;
+
+    use super::{super::Tag, *};
+
+    // docker run --rm --name indy-postgres -e POSTGRES_PASSWORD=pass@word1 -e POSTGRES_DB=indy -p 5432:5432 -d postgres:15
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_type_create_works() {
+        _cleanup("postgres_storage_type_create_works").await;
+
+        let storage_type = PostgresStorageType::new();
+
+        storage_type
+            .create_storage(
+                "postgres_storage_type_create_works",
+                _config(),
+                _credentials(),
+                &_metadata(),
+            )
+            .await
+            .unwrap();
+
+        _cleanup("postgres_storage_type_create_works").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_type_create_works_for_twice() {
+        _cleanup("postgres_storage_type_create_works_for_twice").await;
+
+        let storage_type = PostgresStorageType::new();
+        storage_type
+            .create_storage(
+                "postgres_storage_type_create_works_for_twice",
+                _config(),
+                _credentials(),
+                &_metadata(),
+            )
+            .await
+            .unwrap();
+
+        let res = storage_type
+            .create_storage(
+                "postgres_storage_type_create_works_for_twice",
+                _config(),
+                _credentials(),
+                &_metadata(),
+            )
+            .await;
+
+        assert_kind!(IndyErrorKind::WalletAlreadyExists, res);
+
+        _cleanup("postgres_storage_type_create_works_for_twice").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_get_storage_metadata_works() {
+        _cleanup("postgres_storage_get_storage_metadata_works").await;
+
+        {
+            let storage = _storage("postgres_storage_get_storage_metadata_works").await;
+            let metadata = storage.get_storage_metadata().await.unwrap();
+
+            assert_eq!(metadata, _metadata());
+        }
+
+        _cleanup("postgres_storage_get_storage_metadata_works").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_type_delete_works_for_non_existing() {
+        let res = PostgresStorageType::new()
+            .delete_storage(
+                "postgres_storage_type_delete_works_for_non_existing",
+                _config(),
+                _credentials(),
+            )
+            .await;
+
+        assert_kind!(IndyErrorKind::WalletNotFound, res);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_type_open_works_for_not_created() {
+        let res = PostgresStorageType::new()
+            .open_storage(
+                "postgres_storage_type_open_works_for_not_created",
+                _config(),
+                _credentials(),
+            )
+            .await;
+
+        assert_kind!(IndyErrorKind::WalletNotFound, res);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_set_get_works() {
+        _cleanup("postgres_storage_set_get_works").await;
+
+        {
+            let storage = _storage("postgres_storage_set_get_works").await;
+
+            storage
+                .add(&_type1(), &_id1(), &_value1(), &_tags())
+                .await
+                .unwrap();
+
+            let record = storage
+                .get(
+                    &_type1(),
+                    &_id1(),
+                    r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##,
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(record.value.unwrap(), _value1());
+            assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+
+            let res = storage.add(&_type1(), &_id1(), &_value2(), &_tags()).await;
+
+            assert_kind!(IndyErrorKind::WalletItemAlreadyExists, res);
+        }
+
+        _cleanup("postgres_storage_set_get_works").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_isolates_wallets() {
+        _cleanup("postgres_storage_isolates_wallets_1").await;
+        _cleanup("postgres_storage_isolates_wallets_2").await;
+
+        {
+            let storage1 = _storage("postgres_storage_isolates_wallets_1").await;
+            let storage2 = _storage("postgres_storage_isolates_wallets_2").await;
+
+            storage1
+                .add(&_type1(), &_id1(), &_value1(), &_tags())
+                .await
+                .unwrap();
+
+            storage2
+                .add(&_type1(), &_id1(), &_value2(), &_tags())
+                .await
+                .unwrap();
+
+            let record = storage2
+                .get(&_type1(), &_id1(), r##"{"retrieveValue": true}"##)
+                .await
+                .unwrap();
+            assert_eq!(record.value.unwrap(), _value2());
+
+            storage1.delete(&_type1(), &_id1()).await.unwrap();
+
+            let res = storage1
+                .get(&_type1(), &_id1(), r##"{"retrieveValue": true}"##)
+                .await;
+            assert_kind!(IndyErrorKind::WalletItemNotFound, res);
+
+            let mut storage_iterator = storage2.get_all().await.unwrap();
+            assert_eq!(storage_iterator.get_total_count().unwrap(), Some(1));
+        }
+
+        _cleanup("postgres_storage_isolates_wallets_1").await;
+        _cleanup("postgres_storage_isolates_wallets_2").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_delete_works_for_non_existing() {
+        _cleanup("postgres_storage_delete_works_for_non_existing").await;
+
+        {
+            let storage = _storage("postgres_storage_delete_works_for_non_existing").await;
+
+            let res = storage.delete(&_type1(), &_id1()).await;
+            assert_kind!(IndyErrorKind::WalletItemNotFound, res);
+        }
+
+        _cleanup("postgres_storage_delete_works_for_non_existing").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_tags_change_works_for_non_existing_with_no_tags() {
+        _cleanup("postgres_storage_tags_change_works_for_non_existing_with_no_tags").await;
+
+        {
+            let storage =
+                _storage("postgres_storage_tags_change_works_for_non_existing_with_no_tags").await;
+
+            let res = storage.add_tags(&_type1(), &_id1(), &[]).await;
+            assert_kind!(IndyErrorKind::WalletItemNotFound, res);
+
+            let res = storage.delete_tags(&_type1(), &_id1(), &[]).await;
+            assert_kind!(IndyErrorKind::WalletItemNotFound, res);
+        }
+
+        _cleanup("postgres_storage_tags_change_works_for_non_existing_with_no_tags").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_get_all_works() {
+        _cleanup("postgres_storage_get_all_works").await;
+
+        {
+            let storage = _storage("postgres_storage_get_all_works").await;
+
+            storage
+                .add(&_type1(), &_id1(), &_value1(), &_tags())
+                .await
+                .unwrap();
+
+            storage
+                .add(&_type2(), &_id2(), &_value2(), &_tags())
+                .await
+                .unwrap();
+
+            let mut storage_iterator = storage.get_all().await.unwrap();
+            assert_eq!(storage_iterator.get_total_count().unwrap(), Some(2));
+
+            let record = storage_iterator.next().await.unwrap().unwrap();
+            assert_eq!(record.type_.unwrap(), _type1());
+            assert_eq!(record.value.unwrap(), _value1());
+            assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+
+            let record = storage_iterator.next().await.unwrap().unwrap();
+            assert_eq!(record.type_.unwrap(), _type2());
+            assert_eq!(record.value.unwrap(), _value2());
+            assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+
+            let record = storage_iterator.next().await.unwrap();
+            assert!(record.is_none());
+        }
+
+        _cleanup("postgres_storage_get_all_works").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_iterators_work_for_more_records_than_batch() {
+        use crate::storage::sql::FETCH_BATCH_SIZE;
+
+        _cleanup("postgres_storage_iterators_work_for_more_records_than_batch").await;
+
+        {
+            let storage =
+                _storage("postgres_storage_iterators_work_for_more_records_than_batch").await;
+            let count = FETCH_BATCH_SIZE as u8 + 10;
+
+            for i in 0..count {
+                storage
+                    .add(&_type1(), &_id(i), &_value(i), &_tags())
+                    .await
+                    .unwrap();
+            }
+
+            let mut storage_iterator = storage.get_all().await.unwrap();
+            for i in 0..count {
+                let record = storage_iterator.next().await.unwrap().unwrap();
+                assert_eq!(record.id, _id(i));
+            }
+            assert!(storage_iterator.next().await.unwrap().is_none());
+
+            let mut storage_iterator = storage
+                .search(
+                    &_type1(),
+                    &language::Operator::And(vec![]),
+                    Some(r##"{"retrieveRecords": true, "retrieveTotalCount": true}"##),
+                )
+                .await
+                .unwrap();
+            assert_eq!(
+                storage_iterator.get_total_count().unwrap(),
+                Some(count as usize)
+            );
+            for i in 0..count {
+                let record = storage_iterator.next().await.unwrap().unwrap();
+                assert_eq!(record.id, _id(i));
+            }
+            assert!(storage_iterator.next().await.unwrap().is_none());
+        }
+
+        _cleanup("postgres_storage_iterators_work_for_more_records_than_batch").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_search_works() {
+        _cleanup("postgres_storage_search_works").await;
+
+        {
+            let storage = _storage("postgres_storage_search_works").await;
+
+            storage
+                .add(&_type1(), &_id1(), &_value1(), &_tags())
+                .await
+                .unwrap();
+
+            storage
+                .add(&_type1(), &_id2(), &_value2(), &_new_tags())
+                .await
+                .unwrap();
+
+            let query = language::Operator::Or(vec![
+                language::Operator::Eq(
+                    language::TagName::EncryptedTagName(vec![1, 5, 8]),
+                    language::TargetValue::Encrypted(vec![3, 5, 6]),
+                ),
+                language::Operator::Like(
+                    language::TagName::PlainTagName(vec![1, 1, 1]),
+                    language::TargetValue::Unencrypted("tag_value_%".to_string()),
+                ),
+            ]);
+
+            let mut storage_iterator = storage
+                .search(
+                    &_type1(),
+                    &query,
+                    Some(r##"{"retrieveTotalCount": true, "retrieveTags": true}"##),
+                )
+                .await
+                .unwrap();
+            assert_eq!(storage_iterator.get_total_count().unwrap(), Some(2));
+
+            let record = storage_iterator.next().await.unwrap().unwrap();
+            assert_eq!(record.id, _id1());
+            assert_eq!(record.value.unwrap(), _value1());
+            assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+
+            let record = storage_iterator.next().await.unwrap().unwrap();
+            assert_eq!(record.id, _id2());
+
+            let query = language::Operator::Not(Box::new(language::Operator::In(
+                language::TagName::PlainTagName(vec![1, 5, 8, 1]),
+                vec![language::TargetValue::Unencrypted(
+                    "Plain value".to_string(),
+                )],
+            )));
+
+            let mut storage_iterator = storage.search(&_type1(), &query, None).await.unwrap();
+            assert!(storage_iterator.next().await.unwrap().is_none());
+
+            let query = language::Operator::Neq(
+                language::TagName::PlainTagName(vec![1, 5, 8, 1]),
+                language::TargetValue::Unencrypted("Other value".to_string()),
+            );
+
+            let mut storage_iterator = storage.search(&_type1(), &query, None).await.unwrap();
+            let record = storage_iterator.next().await.unwrap().unwrap();
+            assert_eq!(record.id, _id1());
+            assert!(storage_iterator.next().await.unwrap().is_none());
+        }
+
+        _cleanup("postgres_storage_search_works").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_update_works() {
+        _cleanup("postgres_storage_update_works").await;
+
+        {
+            let storage = _storage("postgres_storage_update_works").await;
+
+            storage
+                .add(&_type1(), &_id1(), &_value1(), &_tags())
+                .await
+                .unwrap();
+
+            storage
+                .update(&_type1(), &_id1(), &_value2())
+                .await
+                .unwrap();
+
+            let record = storage
+                .get(&_type1(), &_id1(), r##"{"retrieveValue": true}"##)
+                .await
+                .unwrap();
+            assert_eq!(record.value.unwrap(), _value2());
+
+            let res = storage.update(&_type2(), &_id1(), &_value1()).await;
+            assert_kind!(IndyErrorKind::WalletItemNotFound, res);
+        }
+
+        _cleanup("postgres_storage_update_works").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_add_tags_works() {
+        _cleanup("postgres_storage_add_tags_works").await;
+
+        {
+            let storage = _storage("postgres_storage_add_tags_works").await;
+
+            storage
+                .add(&_type1(), &_id1(), &_value1(), &_tags())
+                .await
+                .unwrap();
+
+            storage
+                .add_tags(&_type1(), &_id1(), &_new_tags())
+                .await
+                .unwrap();
+
+            let record = storage
+                .get(&_type1(), &_id1(), r##"{"retrieveTags": true}"##)
+                .await
+                .unwrap();
+
+            let expected_tags = {
+                let mut tags = _tags();
+                tags.extend(_new_tags());
+                _sort(tags)
+            };
+
+            assert_eq!(_sort(record.tags.unwrap()), expected_tags);
+        }
+
+        _cleanup("postgres_storage_add_tags_works").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_update_tags_works() {
+        _cleanup("postgres_storage_update_tags_works").await;
+
+        {
+            let storage = _storage("postgres_storage_update_tags_works").await;
+
+            storage
+                .add(&_type1(), &_id1(), &_value1(), &_tags())
+                .await
+                .unwrap();
+
+            storage
+                .update_tags(&_type1(), &_id1(), &_new_tags())
+                .await
+                .unwrap();
+
+            let record = storage
+                .get(&_type1(), &_id1(), r##"{"retrieveTags": true}"##)
+                .await
+                .unwrap();
+
+            assert_eq!(_sort(record.tags.unwrap()), _sort(_new_tags()));
+        }
+
+        _cleanup("postgres_storage_update_tags_works").await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "postgres")]
+    async fn postgres_storage_delete_tags_works() {
+        _cleanup("postgres_storage_delete_tags_works").await;
+
+        {
+            let storage = _storage("postgres_storage_delete_tags_works").await;
+
+            let tag_name1 = vec![0, 100, 20];
+            let tag_name2 = vec![100, 20, 0];
+            let tag_name3 = vec![20, 0, 100];
+
+            let tags = vec![
+                Tag::Encrypted(tag_name1.clone(), vec![0, 100, 20]),
+                Tag::PlainText(tag_name2.clone(), "value2".to_string()),
+                Tag::Encrypted(tag_name3.clone(), vec![100, 20, 0]),
+            ];
+
+            storage
+                .add(&_type1(), &_id1(), &_value1(), &tags)
+                .await
+                .unwrap();
+
+            let tag_names = vec![
+                TagName::OfEncrypted(tag_name1.clone()),
+                TagName::OfPlain(tag_name2.clone()),
+            ];
+
+            storage
+                .delete_tags(&_type1(), &_id1(), &tag_names)
+                .await
+                .unwrap();
+
+            let record = storage
+                .get(&_type1(), &_id1(), r##"{"retrieveTags": true}"##)
+                .await
+                .unwrap();
+
+            let expected_tags = vec![Tag::Encrypted(tag_name3, vec![100, 20, 0])];
+            assert_eq!(_sort(record.tags.unwrap()), _sort(expected_tags));
+        }
+
+        _cleanup("postgres_storage_delete_tags_works").await;
+    }
+
+    fn _config() -> Option<&'static str> {
+        Some(
+            r#"
+            {
+                "read_host": "127.0.0.1",
+                "write_host": "127.0.0.1",
+                "port": 5432,
+                "db_name": "indy"
+            }
+            "#,
+        )
+    }
+
+    fn _credentials() -> Option<&'static str> {
+        Some(
+            r#"
+            {
+                "user": "postgres",
+                "pass": "pass@word1"
+            }
+            "#,
+        )
+    }
+
+    async fn _cleanup(name: &str) {
+        PostgresStorageType::new()
+            .delete_storage(name, _config(), _credentials())
+            .await
+            .ok();
+    }
+
+    async fn _storage(name: &str) -> Box<dyn WalletStorage> {
+        let storage_type = PostgresStorageType::new();
+
+        storage_type
+            .create_storage(name, _config(), _credentials(), &_metadata())
+            .await
+            .unwrap();
+
+        storage_type
+            .open_storage(name, _config(), _credentials())
+            .await
+            .unwrap()
+    }
+
+    fn _metadata() -> Vec<u8> {
+        return vec![
+            1, 2, 3, 4, 5, 6, 7, 8, 1, 2, 3, 4, 5, 6, 7, 8, 1, 2, 3, 4, 5, 6, 7, 8, 1, 2, 3, 4, 5,
+            6, 7, 8, 1, 2, 3, 4, 5, 6, 7, 8, 1, 2, 3, 4, 5, 6, 7, 8, 1, 2, 3, 4, 5, 6, 7, 8, 1, 2,
+            3, 4, 5, 6, 7, 8,
+        ];
+    }
+
+    fn _type(i: u8) -> Vec<u8> {
+        vec![i, 1 + i, 2 + i]
+    }
+
+    fn _type1() -> Vec<u8> {
+        _type(1)
+    }
+
+    fn _type2() -> Vec<u8> {
+        _type(2)
+    }
+
+    fn _id(i: u8) -> Vec<u8> {
+        vec![3 + i, 4 + i, 5 + i]
+    }
+
+    fn _id1() -> Vec<u8> {
+        _id(1)
+    }
+
+    fn _id2() -> Vec<u8> {
+        _id(2)
+    }
+
+    fn _value(i: u8) -> EncryptedValue {
+        EncryptedValue {
+            data: vec![6 + i, 7 + i, 8 + i],
+            key: [9 + i, 10 + i, 11 + i].repeat(20),
+        }
+    }
+
+    fn _value1() -> EncryptedValue {
+        _value(1)
+    }
+
+    fn _value2() -> EncryptedValue {
+        _value(2)
+    }
+
+    fn _tags() -> Vec<Tag> {
+        vec![
+            Tag::Encrypted(vec![1, 5, 8], vec![3, 5, 6]),
+            Tag::PlainText(vec![1, 5, 8, 1], "Plain value".to_string()),
+        ]
+    }
+
+    fn _new_tags() -> Vec<Tag> {
+        vec![
+            Tag::Encrypted(vec![1, 1, 1], vec![2, 2, 2]),
+            Tag::PlainText(vec![1, 1, 1], String::from("tag_value_3")),
+        ]
+    }
+
+    fn _sort(mut v: Vec<Tag>) -> Vec<Tag> {
+        v.sort();
+        v
+    }
+}
@@ -0,0 +1,208 @@
+use indy_api_types::errors::prelude::*;
+use indy_utils::crypto::base64;
+use serde_json::Value;
+
+use crate::{
+    language::{Operator, TagName, TargetValue},
+    storage::sql::ToPlain,
+    SearchOptions,
+};
+
+pub fn wql_to_sql(
+    wallet_id: i64,
+    type_: &[u8],
+    wql: &Operator,
+    options: &SearchOptions,
+) -> IndyResult<(String, Vec<Value>)> {
+    let mut arguments: Vec<Value> = Vec::new();
+    let query_condition = operator_to_sql(wql, &mut arguments)?;
+
+    let query_string = format!(
+        "SELECT {}, name, {}, {} FROM items WHERE {} type = {} AND wallet_id = {} ORDER BY id",
+        if options.retrieve_type {
+            "type"
+        } else {
+            "NULL::text"
+        },
+        if options.retrieve_value {
+            "value"
+        } else {
+            "NULL::bytea"
+        },
+        if options.retrieve_tags {
+            "tags"
+        } else {
+            "NULL::jsonb"
+        },
+        if !query_condition.is_empty() {
+            query_condition + " AND"
+        } else {
+            "".to_string()
+        },
+        placeholder(base64::encode(type_).into(), &mut arguments),
+        placeholder(wallet_id.into(), &mut arguments),
+    );
+
+    Ok((query_string, arguments))
+}
+
+pub fn wql_to_sql_count(
+    wallet_id: i64,
+    type_: &[u8],
+    wql: &Operator,
+) -> IndyResult<(String, Vec<Value>)> {
+    let mut arguments: Vec<Value> = Vec::new();
+    let query_condition = operator_to_sql(wql, &mut arguments)?;
+
+    let query_string = format!(
+        "SELECT count(*) FROM items i WHERE {} i.type = {} AND i.wallet_id = {}",
+        if !query_condition.is_empty() {
+            query_condition + " AND"
+        } else {
+            "".to_string()
+        },
+        placeholder(base64::encode(type_).into(), &mut arguments),
+        placeholder(wallet_id.into(), &mut arguments),
+    );
+
+    Ok((query_string, arguments))
+}
+
+/// Adds `value` to the query arguments and returns the numbered placeholder referring to it.
+/// Placeholders must be generated in the order they appear in the query text.
+fn placeholder(value: Value, arguments: &mut Vec<Value>) -> String {
+    arguments.push(value);
+    format!("${}", arguments.len())
+}
+
+fn operator_to_sql(op: &Operator, arguments: &mut Vec<Value>) -> IndyResult<String> {
+    match *op {
+        Operator::Eq(ref tag_name, ref target_value) => {
+            Ok(eq_to_sql(tag_name, target_value, arguments))
+        }
+        Operator::Neq(ref tag_name, ref target_value) => {
+            Ok(neq_to_sql(tag_name, target_value, arguments))
+        }
+        Operator::Gt(ref tag_name, ref target_value) => {
+            plain_comparison_to_sql(tag_name, target_value, ">", "$gt", arguments)
+        }
+        Operator::Gte(ref tag_name, ref target_value) => {
+            plain_comparison_to_sql(tag_name, target_value, ">=", "$gte", arguments)
+        }
+        Operator::Lt(ref tag_name, ref target_value) => {
+            plain_comparison_to_sql(tag_name, target_value, "<", "$lt", arguments)
+        }
+        Operator::Lte(ref tag_name, ref target_value) => {
+            plain_comparison_to_sql(tag_name, target_value, "<=", "$lte", arguments)
+        }
+        Operator::Like(ref tag_name, ref target_value) => {
+            plain_comparison_to_sql(tag_name, target_value, "LIKE", "$like", arguments)
+        }
+        Operator::In(ref tag_name, ref target_values) => {
+            Ok(in_to_sql(tag_name, target_values, arguments))
+        }
+        Operator::And(ref suboperators) => and_to_sql(suboperators, arguments),
+        Operator::Or(ref suboperators) => or_to_sql(suboperators, arguments),
+        Operator::Not(ref suboperator) => not_to_sql(suboperator, arguments),
+    }
+}
+
+fn tag_to_sql(tag_name: &TagName, arguments: &mut Vec<Value>) -> String {
+    format!(
+        "(tags ->> {})",
+        placeholder(tag_name.to_plain().into(), arguments)
+    )
+}
+
+// Containment rather than `->>` comparison, so that the GIN index on tags can be used.
+fn eq_to_sql(tag_name: &TagName, tag_value: &TargetValue, arguments: &mut Vec<Value>) -> String {
+    let tag = placeholder(tag_name.to_plain().into(), arguments);
+    let value = placeholder(tag_value.to_plain().into(), arguments);
+    format!(
+        "(tags @> jsonb_build_object({}::text, {}::text))",
+        tag, value
+    )
+}
+
+fn neq_to_sql(tag_name: &TagName, tag_value: &TargetValue, arguments: &mut Vec<Value>) -> String {
+    let tag = tag_to_sql(tag_name, arguments);
+    let value = placeholder(tag_value.to_plain().into(), arguments);
+    format!("({} != {})", tag, value)
+}
+
+fn plain_comparison_to_sql(
+    tag_name: &TagName,
+    tag_value: &TargetValue,
+    sql_operator: &str,
+    wql_operator: &str,
+    arguments: &mut Vec<Value>,
+) -> IndyResult<String> {
+    match (tag_name, tag_value) {
+        (&TagName::PlainTagName(_), &TargetValue::Unencrypted(_)) => {
+            let tag = tag_to_sql(tag_name, arguments);
+            let value = placeholder(tag_value.to_plain().into(), arguments);
+            Ok(format!("({} {} {})", tag, sql_operator, value))
+        }
+        _ => Err(err_msg(
+            IndyErrorKind::WalletQueryError,
+            format!(
+                "Invalid combination of tag name and value for {} operator",
+                wql_operator
+            ),
+        )),
+    }
+}
+
+fn in_to_sql(tag_name: &TagName, tag_values: &[TargetValue], arguments: &mut Vec<Value>) -> String {
+    if tag_values.is_empty() {
+        return "FALSE".to_string();
+    }
+
+    let tag = tag_to_sql(tag_name, arguments);
+
+    let values = tag_values
+        .iter()
+        .map(|tag_value| placeholder(tag_value.to_plain().into(), arguments))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!("({} IN ({}))", tag, values)
+}
+
+fn and_to_sql(suboperators: &[Operator], arguments: &mut Vec<Value>) -> IndyResult<String> {
+    join_operators(suboperators, " AND ", arguments)
+}
+
+fn or_to_sql(suboperators: &[Operator], arguments: &mut Vec<Value>) -> IndyResult<String> {
+    join_operators(suboperators, " OR ", arguments)
+}
+
+fn not_to_sql(suboperator: &Operator, arguments: &mut Vec<Value>) -> IndyResult<String> {
+    let suboperator_string = operator_to_sql(suboperator, arguments)?;
+    Ok("NOT (".to_string() + &suboperator_string + ")")
+}
+
+fn join_operators(
+    operators: &[Operator],
+    join_str: &str,
+    arguments: &mut Vec<Value>,
+) -> IndyResult<String> {
+    let mut s = String::new();
+
+    if !operators.is_empty() {
+        s.push('(');
+        for (index, operator) in operators.iter().enumerate() {
+            let operator_string = operator_to_sql(operator, arguments)?;
+
+            s.push_str(&operator_string);
+
+            if index < operators.len() - 1 {
+                s.push_str(join_str);
+            }
+        }
+
+        s.push(')');
+    }
+
+    Ok(s)
+}
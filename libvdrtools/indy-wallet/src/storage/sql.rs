@@ -0,0 +1,318 @@
+//! Parts of the MySQL and Postgres storages which do not depend on the SQL dialect: binding of
+//! query arguments, streaming of records and the JSON representation of tags.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+use async_stream::stream;
+use async_trait::async_trait;
+use futures::{stream::BoxStream, StreamExt};
+use indy_api_types::errors::prelude::*;
+use indy_utils::crypto::base64;
+use sqlx::{
+    database::HasArguments, ColumnIndex, Database, Decode, Encode, Executor, IntoArguments, Pool,
+    Row, Type,
+};
+
+use crate::{
+    language,
+    storage::{StorageIterator, StorageRecord, Tag, TagName},
+    wallet::EncryptedValue,
+    SearchOptions,
+};
+
+/// Maximum number of records read ahead from the result set of a search.
+pub(super) const FETCH_BATCH_SIZE: usize = 100;
+
+pub(super) type RecordStream = BoxStream<'static, IndyResult<StorageRecord>>;
+
+/// Iterates over the result set of a query as it is streamed from the server, holding at most
+/// `FETCH_BATCH_SIZE` records in memory at a time. The connection the query runs on is kept
+/// until the result set is exhausted or the iterator is dropped.
+pub(super) struct SqlStorageIterator {
+    // Row streams are not `Sync`, the mutex makes the iterator `Sync` as `StorageIterator`
+    // requires. It is only ever accessed through `get_mut`, so it is never locked.
+    records: Option<Mutex<RecordStream>>,
+    batch: VecDeque<IndyResult<StorageRecord>>,
+    total_count: Option<usize>,
+}
+
+impl SqlStorageIterator {
+    pub(super) fn new(
+        records: Option<RecordStream>,
+        total_count: Option<usize>,
+    ) -> IndyResult<SqlStorageIterator> {
+        Ok(SqlStorageIterator {
+            records: records.map(Mutex::new),
+            batch: VecDeque::with_capacity(FETCH_BATCH_SIZE),
+            total_count,
+        })
+    }
+
+    async fn fetch_batch(&mut self) {
+        if let Some(ref mut records) = self.records {
+            let records = match records.get_mut() {
+                Ok(records) => records,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            while self.batch.len() < FETCH_BATCH_SIZE {
+                match records.next().await {
+                    Some(record) => self.batch.push_back(record),
+                    None => {
+                        self.records = None;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StorageIterator for SqlStorageIterator {
+    async fn next(&mut self) -> IndyResult<Option<StorageRecord>> {
+        if self.batch.is_empty() {
+            self.fetch_batch().await;
+        }
+
+        match self.batch.pop_front() {
+            Some(record) => Ok(Some(record?)),
+            None => Ok(None),
+        }
+    }
+
+    fn get_total_count(&self) -> IndyResult<Option<usize>> {
+        Ok(self.total_count.to_owned())
+    }
+}
+
+pub(super) enum SqlArgument {
+    Int(i64),
+    Text(String),
+}
+
+impl SqlArgument {
+    pub(super) fn from_values(args: Vec<serde_json::Value>) -> IndyResult<Vec<SqlArgument>> {
+        args.into_iter()
+            .map(|arg| match arg {
+                serde_json::Value::Number(ref number) if number.is_i64() => {
+                    Ok(SqlArgument::Int(number.as_i64().unwrap()))
+                }
+                serde_json::Value::String(string) => Ok(SqlArgument::Text(string)),
+                _ => Err(err_msg(
+                    IndyErrorKind::InvalidState,
+                    "Unexpected sql parameter type.",
+                )),
+            })
+            .collect()
+    }
+}
+
+/// Streams the records selected by `sql`, which must select the type, name, value and tags
+/// columns in this order. Columns not retrieved according to `options` are left out of the records.
+pub(super) fn stream_records<DB>(
+    pool: Pool<DB>,
+    sql: String,
+    args: Vec<SqlArgument>,
+    options: SearchOptions,
+) -> RecordStream
+where
+    DB: Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'q> i64: Encode<'q, DB> + Type<DB>,
+    for<'q> String: Encode<'q, DB> + Decode<'q, DB> + Type<DB>,
+    for<'r> Vec<u8>: Decode<'r, DB> + Type<DB>,
+    for<'r> serde_json::Value: Decode<'r, DB> + Type<DB>,
+    usize: ColumnIndex<DB::Row>,
+{
+    Box::pin(stream! {
+        let mut query = sqlx::query::<DB>(&sql);
+
+        for arg in args {
+            query = match arg {
+                SqlArgument::Int(value) => query.bind(value),
+                SqlArgument::Text(value) => query.bind(value),
+            };
+        }
+
+        let mut rows = query.fetch(&pool);
+
+        while let Some(row) = rows.next().await {
+            let record: IndyResult<StorageRecord> = row
+                .map_err(IndyError::from)
+                .and_then(|row| _record_from_row(row, &options));
+            yield record;
+        }
+    })
+}
+
+fn _record_from_row<R>(row: R, options: &SearchOptions) -> IndyResult<StorageRecord>
+where
+    R: Row,
+    for<'r> String: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> Vec<u8>: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> serde_json::Value: Decode<'r, R::Database> + Type<R::Database>,
+    usize: ColumnIndex<R>,
+{
+    let type_ = if options.retrieve_type {
+        let type_: String = row.get(0);
+        Some(base64::decode(&type_)?)
+    } else {
+        None
+    };
+
+    let id = {
+        let id: String = row.get(1);
+        base64::decode(&id)?
+    };
+
+    let value = if options.retrieve_value {
+        let value: Vec<u8> = row.get(2);
+        Some(EncryptedValue::from_bytes(&value)?)
+    } else {
+        None
+    };
+
+    let tags = if options.retrieve_tags {
+        let tags: serde_json::Value = row.get(3);
+        Some(_tags_from_json(tags)?)
+    } else {
+        None
+    };
+
+    Ok(StorageRecord::new(id, value, type_, tags))
+}
+
+pub(super) fn _tags_to_plain(tags: &[Tag]) -> HashMap<String, String> {
+    let mut map = HashMap::with_capacity(tags.len());
+
+    for tag in tags {
+        match *tag {
+            Tag::Encrypted(ref name, ref value) => {
+                map.insert(base64::encode(name), base64::encode(value))
+            }
+            Tag::PlainText(ref name, ref value) => {
+                map.insert(format!("~{}", &base64::encode(name)), value.to_string())
+            }
+        };
+    }
+
+    map
+}
+
+pub(super) fn _tags_to_json(tags: &[Tag]) -> IndyResult<String> {
+    serde_json::to_string(&_tags_to_plain(tags)).to_indy(
+        IndyErrorKind::InvalidState,
+        "Unable to serialize tags as json",
+    )
+}
+
+pub(super) fn _tags_from_json(json: serde_json::Value) -> IndyResult<Vec<Tag>> {
+    let string_tags: HashMap<String, String> = serde_json::from_value(json).to_indy(
+        IndyErrorKind::InvalidState,
+        "Unable to deserialize tags from json",
+    )?;
+
+    let mut tags = Vec::with_capacity(string_tags.len());
+
+    for (k, v) in string_tags {
+        if k.starts_with('~') {
+            let mut key = k;
+            key.remove(0);
+            tags.push(Tag::PlainText(
+                base64::decode(&key).to_indy(
+                    IndyErrorKind::InvalidState,
+                    "Unable to decode tag key from base64",
+                )?,
+                v,
+            ));
+        } else {
+            tags.push(Tag::Encrypted(
+                base64::decode(&k).to_indy(
+                    IndyErrorKind::InvalidState,
+                    "Unable to decode tag key from base64",
+                )?,
+                base64::decode(&v).to_indy(
+                    IndyErrorKind::InvalidState,
+                    "Unable to decode tag value from base64",
+                )?,
+            ));
+        }
+    }
+    Ok(tags)
+}
+
+pub(super) fn _tag_names_to_plain(tag_names: &[TagName]) -> Vec<String> {
+    tag_names
+        .iter()
+        .map(|tag_name| match *tag_name {
+            TagName::OfEncrypted(ref tag_name) => base64::encode(tag_name),
+            TagName::OfPlain(ref tag_name) => format!("~{}", base64::encode(tag_name)),
+        })
+        .collect()
+}
+
+/// Tag names and values of WQL queries as they are stored in the tags column. Unlike their
+/// `to_string`, which renders them as JSON strings, the result is not quoted.
+pub(super) trait ToPlain {
+    fn to_plain(&self) -> String;
+}
+
+impl ToPlain for language::TagName {
+    fn to_plain(&self) -> String {
+        match *self {
+            language::TagName::EncryptedTagName(ref v) => base64::encode(v),
+            language::TagName::PlainTagName(ref v) => format!("~{}", base64::encode(v)),
+        }
+    }
+}
+
+impl ToPlain for language::TargetValue {
+    fn to_plain(&self) -> String {
+        match *self {
+            language::TargetValue::Unencrypted(ref s) => s.to_owned(),
+            language::TargetValue::Encrypted(ref v) => base64::encode(v),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_json_round_trip() {
+        let mut tags = vec![
+            Tag::Encrypted(vec![1, 5, 8], vec![3, 5, 6]),
+            Tag::PlainText(vec![1, 5, 8, 1], "Plain value".to_string()),
+        ];
+        let json: serde_json::Value = serde_json::from_str(&_tags_to_json(&tags).unwrap()).unwrap();
+
+        let mut parsed = _tags_from_json(json).unwrap();
+        parsed.sort();
+        tags.sort();
+        assert_eq!(parsed, tags);
+    }
+
+    #[test]
+    fn plain_tag_names_are_prefixed() {
+        let tag_names = vec![
+            TagName::OfEncrypted(vec![1, 2]),
+            TagName::OfPlain(vec![1, 2]),
+        ];
+        assert_eq!(
+            _tag_names_to_plain(&tag_names),
+            vec![
+                base64::encode(&[1, 2]),
+                format!("~{}", base64::encode(&[1, 2]))
+            ]
+        );
+        assert_eq!(
+            language::TagName::PlainTagName(vec![1, 2]).to_plain(),
+            format!("~{}", base64::encode(&[1, 2]))
+        );
+    }
+}
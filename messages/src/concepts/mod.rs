@@ -4,6 +4,7 @@ pub mod attachment;
 pub mod localization;
 pub mod mime_type;
 pub mod problem_report;
+pub mod service_decorator;
 pub mod timing;
 #[macro_use]
 pub mod ack;
@@ -0,0 +1,52 @@
+/// The `~service` decorator, describing where and how to reply to a message which was not
+/// sent over a connection, as defined in [Aries RFC 0056](https://github.com/hyperledger/aries-rfcs/tree/main/features/0056-service-decorator).
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceDecorator {
+    pub recipient_keys: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub routing_keys: Vec<String>,
+    pub service_endpoint: String,
+}
+
+impl ServiceDecorator {
+    pub fn create() -> ServiceDecorator {
+        ServiceDecorator::default()
+    }
+
+    pub fn set_recipient_keys(mut self, recipient_keys: Vec<String>) -> ServiceDecorator {
+        self.recipient_keys = recipient_keys;
+        self
+    }
+
+    pub fn set_routing_keys(mut self, routing_keys: Vec<String>) -> ServiceDecorator {
+        self.routing_keys = routing_keys;
+        self
+    }
+
+    pub fn set_service_endpoint(mut self, service_endpoint: String) -> ServiceDecorator {
+        self.service_endpoint = service_endpoint;
+        self
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "general_test")]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_service_decorator_serialization() {
+        let service = ServiceDecorator::create()
+            .set_recipient_keys(vec!["recipient_key".to_string()])
+            .set_service_endpoint("https://example.org/endpoint".to_string());
+
+        let expected = json!({
+            "recipientKeys": ["recipient_key"],
+            "serviceEndpoint": "https://example.org/endpoint"
+        });
+        assert_eq!(serde_json::to_value(&service).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<ServiceDecorator>(expected).unwrap(), service);
+    }
+}
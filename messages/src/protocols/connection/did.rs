@@ -21,8 +21,11 @@ impl Did {
         lazy_static! {
             static ref REGEX_METHOD_NAME: Regex =
                 Regex::new("^(did:sov:)?[1-9A-HJ-NP-Za-km-z]{21,22}$").expect("unexpected regex error occurred.");
+            static ref REGEX_PEER_DID: Regex =
+                Regex::new("^did:peer:(0z[1-9A-HJ-NP-Za-km-z]+|2(\\.[AEVIDS][0-9A-Za-z_=-]+)+)$")
+                    .expect("unexpected regex error occurred.");
         }
-        REGEX_METHOD_NAME.is_match(did)
+        REGEX_METHOD_NAME.is_match(did) || REGEX_PEER_DID.is_match(did)
     }
 }
 
@@ -52,4 +55,12 @@ pub mod unit_tests {
         assert!(Did::new("did:sov:2hoqvcwupRTUNkXn6ArYzs").is_ok());
         assert!(Did::new("df6Y3iUa6t").is_err());
     }
+
+    #[test]
+    fn test_peer_did_validation() {
+        assert!(Did::new("did:peer:0z6MkpTHR8VNsBxYAAWHut2Geadd9jSwuBV8xRoAnwWsdvktH").is_ok());
+        assert!(Did::new("did:peer:2.Vz6MkqRYqQiSgvZQdnBytw86Qbs2ZWUkGv22od935YF4s8M7V.SeyJ0IjoiZG0iLCJzIjoiaHR0cHM6Ly9leGFtcGxlLmNvbS9lbmRwb2ludCJ9").is_ok());
+        assert!(Did::new("did:peer:1zQmZMygzYqNwU6Uhmewx5Xepf2VLp5S4HLSwwgf2aiKZuwa").is_err());
+        assert!(Did::new("did:peer:2").is_err());
+    }
 }
@@ -1,5 +1,6 @@
 use crate::a2a::{A2AMessage, MessageId};
 use crate::concepts::attachment::{AttachmentId, Attachments};
+use crate::concepts::service_decorator::ServiceDecorator;
 use crate::concepts::thread::Thread;
 use crate::concepts::timing::Timing;
 use crate::errors::error::prelude::*;
@@ -18,6 +19,9 @@ pub struct PresentationRequest {
     #[serde(rename = "~timing")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timing: Option<Timing>,
+    #[serde(rename = "~service")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<ServiceDecorator>,
 }
 
 timing_optional!(PresentationRequest);
@@ -48,6 +52,11 @@ impl PresentationRequest {
         Ok(self)
     }
 
+    pub fn set_service(mut self, service: Option<ServiceDecorator>) -> Self {
+        self.service = service;
+        self
+    }
+
     pub fn get_presentation_request_data(self) -> MessagesResult<String> {
         self.request_presentations_attach.content()
     }
@@ -113,6 +122,7 @@ pub mod test_utils {
             request_presentations_attach: _attachment(),
             thread: None,
             timing: Some(Timing::default()),
+            service: None,
         }
     }
 }
@@ -145,4 +155,18 @@ pub mod unit_tests {
         let out_timestamp: String = presentation_request.timing.unwrap().get_out_time().unwrap().into();
         assert!(was_in_past(&out_timestamp, chrono::Duration::milliseconds(100)).unwrap());
     }
+
+    #[test]
+    fn test_presentation_request_with_service_decorator() {
+        let service = ServiceDecorator::create()
+            .set_recipient_keys(vec!["recipient_key".to_string()])
+            .set_service_endpoint("https://example.org/endpoint".to_string());
+        let presentation_request = _presentation_request().set_service(Some(service.clone()));
+
+        let serialized = serde_json::to_value(&presentation_request).unwrap();
+        assert_eq!(serialized["~service"]["recipientKeys"], json!(["recipient_key"]));
+
+        let deserialized: PresentationRequest = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized.service, Some(service));
+    }
 }
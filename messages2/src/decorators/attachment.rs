@@ -42,9 +42,8 @@ impl Attachment {
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AttachmentData {
-    // There probably is a better type for this???
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub jws: Option<String>,
+    pub jws: Option<AttachmentJws>,
     // Better type for this as well?
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sha256: Option<String>,
@@ -62,6 +61,37 @@ impl AttachmentData {
     }
 }
 
+/// A detached JSON Web Signature over the attachment content, in the flattened JSON serialization
+/// described by the [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/concepts/0017-attachments/README.md#signing-attachments>).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AttachmentJws {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<JwsHeader>,
+    pub protected: String,
+    pub signature: String,
+}
+
+impl AttachmentJws {
+    pub fn new(protected: String, signature: String) -> Self {
+        Self {
+            header: None,
+            protected,
+            signature,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct JwsHeader {
+    pub kid: String,
+}
+
+impl JwsHeader {
+    pub fn new(kid: String) -> Self {
+        Self { kid }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum AttachmentType {
@@ -165,7 +195,8 @@ pub mod tests {
 
     #[test]
     fn test_extended_attach_data() {
-        let jws = "test_jws".to_owned();
+        let mut jws = AttachmentJws::new("test_protected".to_owned(), "test_signature".to_owned());
+        jws.header = Some(JwsHeader::new("test_kid".to_owned()));
         let sha256 = "test_sha256".to_owned();
 
         let data = json!({
@@ -24,10 +24,10 @@ use crate::{
     misc::utils::MSG_TYPE,
     msg_fields::{
         protocols::{
-            basic_message::BasicMessage, connection::Connection, cred_issuance::CredentialIssuance,
-            discover_features::DiscoverFeatures, notification::Ack, out_of_band::OutOfBand,
-            present_proof::PresentProof, report_problem::ProblemReport, revocation::Revocation, routing::Forward,
-            trust_ping::TrustPing,
+            basic_message::BasicMessage, connection::Connection, coordinate_mediation::CoordinateMediation,
+            cred_issuance::CredentialIssuance, did_exchange::DidExchange, discover_features::DiscoverFeatures,
+            notification::Ack, out_of_band::OutOfBand, pickup::Pickup, present_proof::PresentProof,
+            report_problem::ProblemReport, revocation::Revocation, routing::Forward, trust_ping::TrustPing,
         },
         traits::DelayedSerde,
     },
@@ -63,6 +63,9 @@ pub enum AriesMessage {
     BasicMessage(BasicMessage),
     OutOfBand(OutOfBand),
     Notification(Ack),
+    DidExchange(DidExchange),
+    CoordinateMediation(CoordinateMediation),
+    Pickup(Pickup),
 }
 
 impl DelayedSerde for AriesMessage {
@@ -139,6 +142,15 @@ impl DelayedSerde for AriesMessage {
                     NotificationTypeV1_0::Ack => Ack::deserialize(deserializer).map(From::from),
                 }
             }
+            Protocol::DidExchangeType(msg_type) => {
+                DidExchange::delayed_deserialize((msg_type, kind_str), deserializer).map(From::from)
+            }
+            Protocol::CoordinateMediationType(msg_type) => {
+                CoordinateMediation::delayed_deserialize((msg_type, kind_str), deserializer).map(From::from)
+            }
+            Protocol::PickupType(msg_type) => {
+                Pickup::delayed_deserialize((msg_type, kind_str), deserializer).map(From::from)
+            }
         }
     }
 
@@ -158,6 +170,9 @@ impl DelayedSerde for AriesMessage {
             Self::BasicMessage(v) => MsgWithType::from(v).serialize(serializer),
             Self::OutOfBand(v) => v.delayed_serialize(serializer),
             Self::Notification(v) => MsgWithType::from(v).serialize(serializer),
+            Self::DidExchange(v) => v.delayed_serialize(serializer),
+            Self::CoordinateMediation(v) => v.delayed_serialize(serializer),
+            Self::Pickup(v) => v.delayed_serialize(serializer),
         }
     }
 }
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use crate::maybe_known::MaybeKnown;
+
+/// Links an attachment of a message to the format of its content, as used by the `formats`
+/// field of protocols supporting multiple attachment formats (e.g: issue-credential/2.0).
+///
+/// The format type is protocol and message specific, hence the generic parameter.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AttachmentFormatSpecifier<F> {
+    pub attach_id: String,
+    pub format: MaybeKnown<F>,
+}
+
+impl<F> AttachmentFormatSpecifier<F> {
+    pub fn new(attach_id: String, format: MaybeKnown<F>) -> Self {
+        Self { attach_id, format }
+    }
+}
@@ -0,0 +1,3 @@
+//! Module containing types shared between the messages of multiple protocols.
+
+pub mod attachment_format_id;
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use super::CoordinateMediationDecorators;
+use crate::msg_parts::MsgParts;
+
+pub type Keylist = MsgParts<KeylistContent, CoordinateMediationDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistContent {
+    pub keys: Vec<KeylistItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<KeylistPagination>,
+}
+
+impl KeylistContent {
+    pub fn new(keys: Vec<KeylistItem>) -> Self {
+        Self { keys, pagination: None }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistItem {
+    pub recipient_key: String,
+}
+
+impl KeylistItem {
+    pub fn new(recipient_key: String) -> Self {
+        Self { recipient_key }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistPagination {
+    pub count: u32,
+    pub offset: u32,
+    pub remaining: u32,
+}
+
+impl KeylistPagination {
+    pub fn new(count: u32, offset: u32, remaining: u32) -> Self {
+        Self {
+            count,
+            offset,
+            remaining,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::thread::tests::make_extended_thread, misc::test_utils,
+        msg_types::coordinate_mediation::CoordinateMediationTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_keylist() {
+        let content = KeylistContent::new(vec![KeylistItem::new("key_1".to_owned())]);
+        let decorators = CoordinateMediationDecorators::default();
+
+        let expected = json!({
+            "keys": [{ "recipient_key": "key_1" }]
+        });
+
+        test_utils::test_msg(content, decorators, CoordinateMediationTypeV1_0::Keylist, expected);
+    }
+
+    #[test]
+    fn test_extended_keylist() {
+        let mut content = KeylistContent::new(vec![KeylistItem::new("key_1".to_owned())]);
+        content.pagination = Some(KeylistPagination::new(1, 0, 0));
+        let mut decorators = CoordinateMediationDecorators::default();
+        decorators.thread = Some(make_extended_thread());
+
+        let expected = json!({
+            "keys": [{ "recipient_key": "key_1" }],
+            "pagination": { "count": 1, "offset": 0, "remaining": 0 },
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(content, decorators, CoordinateMediationTypeV1_0::Keylist, expected);
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use super::CoordinateMediationDecorators;
+use crate::msg_parts::MsgParts;
+
+pub type KeylistQuery = MsgParts<KeylistQueryContent, CoordinateMediationDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
+pub struct KeylistQueryContent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paginate: Option<KeylistQueryPaginate>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistQueryPaginate {
+    pub limit: u32,
+    pub offset: u32,
+}
+
+impl KeylistQueryPaginate {
+    pub fn new(limit: u32, offset: u32) -> Self {
+        Self { limit, offset }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{misc::test_utils, msg_types::coordinate_mediation::CoordinateMediationTypeV1_0};
+
+    #[test]
+    fn test_minimal_keylist_query() {
+        let content = KeylistQueryContent::default();
+        let decorators = CoordinateMediationDecorators::default();
+
+        let expected = json!({});
+
+        test_utils::test_msg(content, decorators, CoordinateMediationTypeV1_0::KeylistQuery, expected);
+    }
+
+    #[test]
+    fn test_extended_keylist_query() {
+        let mut content = KeylistQueryContent::default();
+        content.paginate = Some(KeylistQueryPaginate::new(30, 0));
+        let decorators = CoordinateMediationDecorators::default();
+
+        let expected = json!({
+            "paginate": { "limit": 30, "offset": 0 }
+        });
+
+        test_utils::test_msg(content, decorators, CoordinateMediationTypeV1_0::KeylistQuery, expected);
+    }
+}
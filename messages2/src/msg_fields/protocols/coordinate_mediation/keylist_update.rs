@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use super::CoordinateMediationDecorators;
+use crate::msg_parts::MsgParts;
+
+pub type KeylistUpdate = MsgParts<KeylistUpdateContent, CoordinateMediationDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistUpdateContent {
+    pub updates: Vec<KeylistUpdateItem>,
+}
+
+impl KeylistUpdateContent {
+    pub fn new(updates: Vec<KeylistUpdateItem>) -> Self {
+        Self { updates }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistUpdateItem {
+    pub recipient_key: String,
+    pub action: KeylistUpdateAction,
+}
+
+impl KeylistUpdateItem {
+    pub fn new(recipient_key: String, action: KeylistUpdateAction) -> Self {
+        Self { recipient_key, action }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeylistUpdateAction {
+    Add,
+    Remove,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{misc::test_utils, msg_types::coordinate_mediation::CoordinateMediationTypeV1_0};
+
+    #[test]
+    fn test_keylist_update() {
+        let content = KeylistUpdateContent::new(vec![
+            KeylistUpdateItem::new("key_1".to_owned(), KeylistUpdateAction::Add),
+            KeylistUpdateItem::new("key_2".to_owned(), KeylistUpdateAction::Remove),
+        ]);
+        let decorators = CoordinateMediationDecorators::default();
+
+        let expected = json!({
+            "updates": [
+                { "recipient_key": "key_1", "action": "add" },
+                { "recipient_key": "key_2", "action": "remove" }
+            ]
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            CoordinateMediationTypeV1_0::KeylistUpdate,
+            expected,
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use super::{keylist_update::KeylistUpdateAction, CoordinateMediationDecorators};
+use crate::msg_parts::MsgParts;
+
+pub type KeylistUpdateResponse = MsgParts<KeylistUpdateResponseContent, CoordinateMediationDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistUpdateResponseContent {
+    pub updated: Vec<KeylistUpdateResponseItem>,
+}
+
+impl KeylistUpdateResponseContent {
+    pub fn new(updated: Vec<KeylistUpdateResponseItem>) -> Self {
+        Self { updated }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistUpdateResponseItem {
+    pub recipient_key: String,
+    pub action: KeylistUpdateAction,
+    pub result: KeylistUpdateResult,
+}
+
+impl KeylistUpdateResponseItem {
+    pub fn new(recipient_key: String, action: KeylistUpdateAction, result: KeylistUpdateResult) -> Self {
+        Self {
+            recipient_key,
+            action,
+            result,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeylistUpdateResult {
+    ClientError,
+    ServerError,
+    NoChange,
+    Success,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::thread::tests::make_extended_thread, misc::test_utils,
+        msg_types::coordinate_mediation::CoordinateMediationTypeV1_0,
+    };
+
+    #[test]
+    fn test_keylist_update_response() {
+        let content = KeylistUpdateResponseContent::new(vec![
+            KeylistUpdateResponseItem::new(
+                "key_1".to_owned(),
+                KeylistUpdateAction::Add,
+                KeylistUpdateResult::Success,
+            ),
+            KeylistUpdateResponseItem::new(
+                "key_2".to_owned(),
+                KeylistUpdateAction::Remove,
+                KeylistUpdateResult::NoChange,
+            ),
+        ]);
+        let mut decorators = CoordinateMediationDecorators::default();
+        decorators.thread = Some(make_extended_thread());
+
+        let expected = json!({
+            "updated": [
+                { "recipient_key": "key_1", "action": "add", "result": "success" },
+                { "recipient_key": "key_2", "action": "remove", "result": "no_change" }
+            ],
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            CoordinateMediationTypeV1_0::KeylistUpdateResponse,
+            expected,
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use super::CoordinateMediationDecorators;
+use crate::{misc::NoDecorators, msg_parts::MsgParts};
+
+pub type MediateDeny = MsgParts<MediateDenyContent, CoordinateMediationDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
+#[serde(transparent)]
+pub struct MediateDenyContent(NoDecorators);
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::thread::tests::make_extended_thread, misc::test_utils,
+        msg_types::coordinate_mediation::CoordinateMediationTypeV1_0,
+    };
+
+    #[test]
+    fn test_mediate_deny() {
+        let content = MediateDenyContent::default();
+        let mut decorators = CoordinateMediationDecorators::default();
+        decorators.thread = Some(make_extended_thread());
+
+        let expected = json!({
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(content, decorators, CoordinateMediationTypeV1_0::MediateDeny, expected);
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use super::CoordinateMediationDecorators;
+use crate::msg_parts::MsgParts;
+
+pub type MediateGrant = MsgParts<MediateGrantContent, CoordinateMediationDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MediateGrantContent {
+    pub endpoint: String,
+    pub routing_keys: Vec<String>,
+}
+
+impl MediateGrantContent {
+    pub fn new(endpoint: String, routing_keys: Vec<String>) -> Self {
+        Self { endpoint, routing_keys }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::thread::tests::make_extended_thread, misc::test_utils,
+        msg_types::coordinate_mediation::CoordinateMediationTypeV1_0,
+    };
+
+    #[test]
+    fn test_mediate_grant() {
+        let content = MediateGrantContent::new(
+            "http://mediator.example.org/endpoint".to_owned(),
+            vec!["did:key:z6MkpTHR8VNsBxYAAWHut2Geadd9jSwuBV8xRoAnwWsdvktH".to_owned()],
+        );
+        let mut decorators = CoordinateMediationDecorators::default();
+        decorators.thread = Some(make_extended_thread());
+
+        let expected = json!({
+            "endpoint": content.endpoint,
+            "routing_keys": content.routing_keys,
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(content, decorators, CoordinateMediationTypeV1_0::MediateGrant, expected);
+    }
+}
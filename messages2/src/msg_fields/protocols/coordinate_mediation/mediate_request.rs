@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use super::CoordinateMediationDecorators;
+use crate::{misc::NoDecorators, msg_parts::MsgParts};
+
+pub type MediateRequest = MsgParts<MediateRequestContent, CoordinateMediationDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
+#[serde(transparent)]
+pub struct MediateRequestContent(NoDecorators);
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::timing::tests::make_extended_timing, misc::test_utils,
+        msg_types::coordinate_mediation::CoordinateMediationTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_mediate_request() {
+        let content = MediateRequestContent::default();
+        let decorators = CoordinateMediationDecorators::default();
+
+        let expected = json!({});
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            CoordinateMediationTypeV1_0::MediateRequest,
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_extended_mediate_request() {
+        let content = MediateRequestContent::default();
+        let mut decorators = CoordinateMediationDecorators::default();
+        decorators.timing = Some(make_extended_timing());
+
+        let expected = json!({
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            CoordinateMediationTypeV1_0::MediateRequest,
+            expected,
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Module containing the `coordinate mediation` protocol messages, as defined in the [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0211-route-coordination/README.md>).
+
+pub mod keylist;
+pub mod keylist_query;
+pub mod keylist_update;
+pub mod keylist_update_response;
+pub mod mediate_deny;
+pub mod mediate_grant;
+pub mod mediate_request;
+
+use derive_more::From;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use self::{
+    keylist::{Keylist, KeylistContent},
+    keylist_query::{KeylistQuery, KeylistQueryContent},
+    keylist_update::{KeylistUpdate, KeylistUpdateContent},
+    keylist_update_response::{KeylistUpdateResponse, KeylistUpdateResponseContent},
+    mediate_deny::{MediateDeny, MediateDenyContent},
+    mediate_grant::{MediateGrant, MediateGrantContent},
+    mediate_request::{MediateRequest, MediateRequestContent},
+};
+use crate::{
+    decorators::{thread::Thread, timing::Timing, transport::Transport},
+    misc::utils::{into_msg_with_type, transit_to_aries_msg},
+    msg_fields::traits::DelayedSerde,
+    msg_types::{
+        protocols::coordinate_mediation::{
+            CoordinateMediationType as CoordinateMediationKind, CoordinateMediationTypeV1, CoordinateMediationTypeV1_0,
+        },
+        MsgWithType,
+    },
+};
+
+#[derive(Clone, Debug, From, PartialEq)]
+pub enum CoordinateMediation {
+    MediateRequest(MediateRequest),
+    MediateGrant(MediateGrant),
+    MediateDeny(MediateDeny),
+    KeylistUpdate(KeylistUpdate),
+    KeylistUpdateResponse(KeylistUpdateResponse),
+    KeylistQuery(KeylistQuery),
+    Keylist(Keylist),
+}
+
+impl DelayedSerde for CoordinateMediation {
+    type MsgType<'a> = (CoordinateMediationKind, &'a str);
+
+    fn delayed_deserialize<'de, D>(msg_type: Self::MsgType<'de>, deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (protocol, kind_str) = msg_type;
+
+        let kind = match protocol {
+            CoordinateMediationKind::V1(CoordinateMediationTypeV1::V1_0(kind)) => kind.kind_from_str(kind_str),
+        };
+
+        match kind.map_err(D::Error::custom)? {
+            CoordinateMediationTypeV1_0::MediateRequest => MediateRequest::deserialize(deserializer).map(From::from),
+            CoordinateMediationTypeV1_0::MediateGrant => MediateGrant::deserialize(deserializer).map(From::from),
+            CoordinateMediationTypeV1_0::MediateDeny => MediateDeny::deserialize(deserializer).map(From::from),
+            CoordinateMediationTypeV1_0::KeylistUpdate => KeylistUpdate::deserialize(deserializer).map(From::from),
+            CoordinateMediationTypeV1_0::KeylistUpdateResponse => {
+                KeylistUpdateResponse::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV1_0::KeylistQuery => KeylistQuery::deserialize(deserializer).map(From::from),
+            CoordinateMediationTypeV1_0::Keylist => Keylist::deserialize(deserializer).map(From::from),
+        }
+    }
+
+    fn delayed_serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::MediateRequest(v) => MsgWithType::from(v).serialize(serializer),
+            Self::MediateGrant(v) => MsgWithType::from(v).serialize(serializer),
+            Self::MediateDeny(v) => MsgWithType::from(v).serialize(serializer),
+            Self::KeylistUpdate(v) => MsgWithType::from(v).serialize(serializer),
+            Self::KeylistUpdateResponse(v) => MsgWithType::from(v).serialize(serializer),
+            Self::KeylistQuery(v) => MsgWithType::from(v).serialize(serializer),
+            Self::Keylist(v) => MsgWithType::from(v).serialize(serializer),
+        }
+    }
+}
+
+/// Decorators shared by all coordinate mediation messages. Responses are threaded to their requests, and
+/// requests may ask for their response on the same connection through `~transport`.
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
+pub struct CoordinateMediationDecorators {
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[serde(rename = "~transport")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<Transport>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+transit_to_aries_msg!(MediateRequestContent: CoordinateMediationDecorators, CoordinateMediation);
+transit_to_aries_msg!(MediateGrantContent: CoordinateMediationDecorators, CoordinateMediation);
+transit_to_aries_msg!(MediateDenyContent: CoordinateMediationDecorators, CoordinateMediation);
+transit_to_aries_msg!(KeylistUpdateContent: CoordinateMediationDecorators, CoordinateMediation);
+transit_to_aries_msg!(KeylistUpdateResponseContent: CoordinateMediationDecorators, CoordinateMediation);
+transit_to_aries_msg!(KeylistQueryContent: CoordinateMediationDecorators, CoordinateMediation);
+transit_to_aries_msg!(KeylistContent: CoordinateMediationDecorators, CoordinateMediation);
+
+into_msg_with_type!(MediateRequest, CoordinateMediationTypeV1_0, MediateRequest);
+into_msg_with_type!(MediateGrant, CoordinateMediationTypeV1_0, MediateGrant);
+into_msg_with_type!(MediateDeny, CoordinateMediationTypeV1_0, MediateDeny);
+into_msg_with_type!(KeylistUpdate, CoordinateMediationTypeV1_0, KeylistUpdate);
+into_msg_with_type!(
+    KeylistUpdateResponse,
+    CoordinateMediationTypeV1_0,
+    KeylistUpdateResponse
+);
+into_msg_with_type!(KeylistQuery, CoordinateMediationTypeV1_0, KeylistQuery);
+into_msg_with_type!(Keylist, CoordinateMediationTypeV1_0, Keylist);
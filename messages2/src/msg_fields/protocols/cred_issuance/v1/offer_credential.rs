@@ -50,7 +50,7 @@ mod tests {
             timing::tests::make_extended_timing,
         },
         misc::test_utils,
-        msg_fields::protocols::cred_issuance::CredentialAttr,
+        msg_fields::protocols::cred_issuance::v1::CredentialAttr,
         msg_types::cred_issuance::CredentialIssuanceTypeV1_0,
     };
 
@@ -48,7 +48,7 @@ mod tests {
     use crate::{
         decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
         misc::test_utils,
-        msg_fields::protocols::cred_issuance::CredentialAttr,
+        msg_fields::protocols::cred_issuance::v1::CredentialAttr,
         msg_types::cred_issuance::CredentialIssuanceTypeV1_0,
     };
 
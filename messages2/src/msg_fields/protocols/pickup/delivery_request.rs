@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+use super::PickupDecorators;
+use crate::msg_parts::MsgParts;
+
+pub type DeliveryRequest = MsgParts<DeliveryRequestContent, PickupDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DeliveryRequestContent {
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient_key: Option<String>,
+}
+
+impl DeliveryRequestContent {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            recipient_key: None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::transport::tests::make_minimal_transport, misc::test_utils, msg_types::pickup::PickupTypeV2_0,
+    };
+
+    #[test]
+    fn test_minimal_delivery_request() {
+        let content = DeliveryRequestContent::new(10);
+        let decorators = PickupDecorators::default();
+
+        let expected = json!({
+            "limit": 10
+        });
+
+        test_utils::test_msg(content, decorators, PickupTypeV2_0::DeliveryRequest, expected);
+    }
+
+    #[test]
+    fn test_extended_delivery_request() {
+        let mut content = DeliveryRequestContent::new(10);
+        content.recipient_key = Some("key".to_owned());
+        let mut decorators = PickupDecorators::default();
+        decorators.transport = Some(make_minimal_transport());
+
+        let expected = json!({
+            "limit": 10,
+            "recipient_key": content.recipient_key,
+            "~transport": decorators.transport
+        });
+
+        test_utils::test_msg(content, decorators, PickupTypeV2_0::DeliveryRequest, expected);
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use super::PickupDecorators;
+use crate::msg_parts::MsgParts;
+
+pub type LiveDeliveryChange = MsgParts<LiveDeliveryChangeContent, PickupDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct LiveDeliveryChangeContent {
+    pub live_delivery: bool,
+}
+
+impl LiveDeliveryChangeContent {
+    pub fn new(live_delivery: bool) -> Self {
+        Self { live_delivery }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::transport::tests::make_minimal_transport, misc::test_utils, msg_types::pickup::PickupTypeV2_0,
+    };
+
+    #[test]
+    fn test_live_delivery_change() {
+        let content = LiveDeliveryChangeContent::new(true);
+        let mut decorators = PickupDecorators::default();
+        decorators.transport = Some(make_minimal_transport());
+
+        let expected = json!({
+            "live_delivery": true,
+            "~transport": decorators.transport
+        });
+
+        test_utils::test_msg(content, decorators, PickupTypeV2_0::LiveDeliveryChange, expected);
+    }
+}
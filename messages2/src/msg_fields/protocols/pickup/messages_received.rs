@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use super::PickupDecorators;
+use crate::msg_parts::MsgParts;
+
+pub type MessagesReceived = MsgParts<MessagesReceivedContent, PickupDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MessagesReceivedContent {
+    pub message_id_list: Vec<String>,
+}
+
+impl MessagesReceivedContent {
+    pub fn new(message_id_list: Vec<String>) -> Self {
+        Self { message_id_list }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{misc::test_utils, msg_types::pickup::PickupTypeV2_0};
+
+    #[test]
+    fn test_messages_received() {
+        let content = MessagesReceivedContent::new(vec!["id_1".to_owned(), "id_2".to_owned()]);
+        let decorators = PickupDecorators::default();
+
+        let expected = json!({
+            "message_id_list": content.message_id_list
+        });
+
+        test_utils::test_msg(content, decorators, PickupTypeV2_0::MessagesReceived, expected);
+    }
+}
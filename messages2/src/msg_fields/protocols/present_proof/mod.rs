@@ -1,35 +1,20 @@
-//! Module containing the `present proof` protocol messages, as defined in the [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0037-present-proof/README.md>).
+//! Module containing the `present proof` protocol messages, as defined in the
+//! [v1 RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0037-present-proof/README.md>)
+//! and the [v2 RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0454-present-proof-v2/README.md>).
 
-pub mod ack;
-pub mod present;
-pub mod propose;
-pub mod request;
+pub mod v1;
+pub mod v2;
 
 use derive_more::From;
-use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use serde::{Deserializer, Serializer};
 
-use self::{
-    ack::{AckPresentation, AckPresentationContent},
-    present::{Presentation, PresentationContent, PresentationDecorators},
-    propose::{ProposePresentation, ProposePresentationContent, ProposePresentationDecorators},
-    request::{RequestPresentation, RequestPresentationContent, RequestPresentationDecorators},
-};
-use super::notification::AckDecorators;
-use crate::{
-    misc::utils::{self, into_msg_with_type, transit_to_aries_msg},
-    msg_fields::traits::DelayedSerde,
-    msg_types::{
-        protocols::present_proof::{PresentProofType, PresentProofTypeV1, PresentProofTypeV1_0},
-        MsgWithType,
-    },
-};
+use self::{v1::PresentProofV1, v2::PresentProofV2};
+use crate::{msg_fields::traits::DelayedSerde, msg_types::protocols::present_proof::PresentProofType};
 
 #[derive(Clone, Debug, From, PartialEq)]
 pub enum PresentProof {
-    ProposePresentation(ProposePresentation),
-    RequestPresentation(RequestPresentation),
-    Presentation(Presentation),
-    Ack(AckPresentation),
+    V1(PresentProofV1),
+    V2(PresentProofV2),
 }
 
 impl DelayedSerde for PresentProof {
@@ -41,16 +26,13 @@ impl DelayedSerde for PresentProof {
     {
         let (protocol, kind_str) = msg_type;
 
-        let kind = match protocol {
-            PresentProofType::V1(PresentProofTypeV1::V1_0(kind)) => kind.kind_from_str(kind_str),
-        };
-
-        match kind.map_err(D::Error::custom)? {
-            PresentProofTypeV1_0::ProposePresentation => ProposePresentation::deserialize(deserializer).map(From::from),
-            PresentProofTypeV1_0::RequestPresentation => RequestPresentation::deserialize(deserializer).map(From::from),
-            PresentProofTypeV1_0::Presentation => Presentation::deserialize(deserializer).map(From::from),
-            PresentProofTypeV1_0::Ack => AckPresentation::deserialize(deserializer).map(From::from),
-            PresentProofTypeV1_0::PresentationPreview => Err(utils::not_standalone_msg::<D>(kind_str)),
+        match protocol {
+            PresentProofType::V1(msg_type) => {
+                PresentProofV1::delayed_deserialize((msg_type, kind_str), deserializer).map(From::from)
+            }
+            PresentProofType::V2(msg_type) => {
+                PresentProofV2::delayed_deserialize((msg_type, kind_str), deserializer).map(From::from)
+            }
         }
     }
 
@@ -59,20 +41,8 @@ impl DelayedSerde for PresentProof {
         S: Serializer,
     {
         match self {
-            Self::ProposePresentation(v) => MsgWithType::from(v).serialize(serializer),
-            Self::RequestPresentation(v) => MsgWithType::from(v).serialize(serializer),
-            Self::Presentation(v) => MsgWithType::from(v).serialize(serializer),
-            Self::Ack(v) => MsgWithType::from(v).serialize(serializer),
+            Self::V1(v) => v.delayed_serialize(serializer),
+            Self::V2(v) => v.delayed_serialize(serializer),
         }
     }
 }
-
-transit_to_aries_msg!(ProposePresentationContent: ProposePresentationDecorators, PresentProof);
-transit_to_aries_msg!(RequestPresentationContent: RequestPresentationDecorators, PresentProof);
-transit_to_aries_msg!(PresentationContent: PresentationDecorators, PresentProof);
-transit_to_aries_msg!(AckPresentationContent: AckDecorators, PresentProof);
-
-into_msg_with_type!(ProposePresentation, PresentProofTypeV1_0, ProposePresentation);
-into_msg_with_type!(RequestPresentation, PresentProofTypeV1_0, RequestPresentation);
-into_msg_with_type!(Presentation, PresentProofTypeV1_0, Presentation);
-into_msg_with_type!(AckPresentation, PresentProofTypeV1_0, Ack);
@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decorators::{attachment::Attachment, please_ack::PleaseAck, thread::Thread, timing::Timing},
+    msg_fields::protocols::common::attachment_format_id::AttachmentFormatSpecifier,
+    msg_parts::MsgParts,
+};
+
+pub type PresentationV2 = MsgParts<PresentationV2Content, PresentationV2Decorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PresentationV2Content {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_presentation: Option<bool>,
+    pub formats: Vec<AttachmentFormatSpecifier<PresentationAttachmentFormatType>>,
+    #[serde(rename = "presentations~attach")]
+    pub presentations_attach: Vec<Attachment>,
+}
+
+impl PresentationV2Content {
+    pub fn new(
+        formats: Vec<AttachmentFormatSpecifier<PresentationAttachmentFormatType>>,
+        presentations_attach: Vec<Attachment>,
+    ) -> Self {
+        Self {
+            goal_code: None,
+            comment: None,
+            last_presentation: None,
+            formats,
+            presentations_attach,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PresentationV2Decorators {
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[serde(rename = "~please_ack")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub please_ack: Option<PleaseAck>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl PresentationV2Decorators {
+    pub fn new(thread: Thread) -> Self {
+        Self {
+            thread,
+            please_ack: None,
+            timing: None,
+        }
+    }
+}
+
+/// Attachment formats a `presentation` message can carry.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum PresentationAttachmentFormatType {
+    #[serde(rename = "hlindy/proof@v2.0")]
+    HyperledgerIndyProof2_0,
+    #[serde(rename = "dif/presentation-exchange/submission@v1.0")]
+    DifPresentationExchangeSubmission1_0,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{
+            attachment::tests::make_extended_attachment, please_ack::tests::make_minimal_please_ack,
+            thread::tests::make_extended_thread, timing::tests::make_extended_timing,
+        },
+        maybe_known::MaybeKnown,
+        misc::test_utils,
+        msg_types::present_proof::PresentProofTypeV2_0,
+    };
+
+    fn make_presentation_content() -> PresentationV2Content {
+        let format = AttachmentFormatSpecifier::new(
+            "test_id".to_owned(),
+            MaybeKnown::Known(PresentationAttachmentFormatType::HyperledgerIndyProof2_0),
+        );
+        PresentationV2Content::new(vec![format], vec![make_extended_attachment()])
+    }
+
+    #[test]
+    fn test_minimal_present_proof_v2() {
+        let content = make_presentation_content();
+
+        let decorators = PresentationV2Decorators::new(make_extended_thread());
+
+        let expected = json!({
+            "formats": content.formats,
+            "presentations~attach": content.presentations_attach,
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(content, decorators, PresentProofTypeV2_0::Presentation, expected);
+    }
+
+    #[test]
+    fn test_extended_present_proof_v2() {
+        let mut content = make_presentation_content();
+        content.goal_code = Some("test_goal_code".to_owned());
+        content.comment = Some("test_comment".to_owned());
+        content.last_presentation = Some(true);
+
+        let mut decorators = PresentationV2Decorators::new(make_extended_thread());
+        decorators.timing = Some(make_extended_timing());
+        decorators.please_ack = Some(make_minimal_please_ack());
+
+        let expected = json!({
+            "formats": content.formats,
+            "presentations~attach": content.presentations_attach,
+            "goal_code": content.goal_code,
+            "comment": content.comment,
+            "last_presentation": content.last_presentation,
+            "~thread": decorators.thread,
+            "~timing": decorators.timing,
+            "~please_ack": decorators.please_ack
+        });
+
+        test_utils::test_msg(content, decorators, PresentProofTypeV2_0::Presentation, expected);
+    }
+}
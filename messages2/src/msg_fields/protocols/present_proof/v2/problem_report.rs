@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    msg_fields::protocols::report_problem::{ProblemReportContent, ProblemReportDecorators},
+    msg_parts::MsgParts,
+};
+
+pub type PresentProofProblemReportV2 = MsgParts<PresentProofProblemReportV2Content, ProblemReportDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
+#[serde(transparent)]
+pub struct PresentProofProblemReportV2Content(pub ProblemReportContent);
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_types::present_proof::PresentProofTypeV2_0,
+    };
+
+    #[test]
+    fn test_minimal_problem_report_proof_v2() {
+        let content = PresentProofProblemReportV2Content::default();
+        let decorators = ProblemReportDecorators::default();
+
+        let expected = json!({});
+
+        test_utils::test_msg(content, decorators, PresentProofTypeV2_0::ProblemReport, expected);
+    }
+
+    #[test]
+    fn test_extended_problem_report_proof_v2() {
+        let mut content = PresentProofProblemReportV2Content::default();
+        content.0.description = Some("abandoned".to_owned());
+
+        let mut decorators = ProblemReportDecorators::default();
+        decorators.thread = Some(make_extended_thread());
+        decorators.timing = Some(make_extended_timing());
+
+        let expected = json!({
+            "description": content.0.description,
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, PresentProofTypeV2_0::ProblemReport, expected);
+    }
+}
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decorators::{attachment::Attachment, thread::Thread, timing::Timing},
+    msg_fields::protocols::common::attachment_format_id::AttachmentFormatSpecifier,
+    msg_parts::MsgParts,
+};
+
+pub type ProposePresentationV2 = MsgParts<ProposePresentationV2Content, ProposePresentationV2Decorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ProposePresentationV2Content {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    pub formats: Vec<AttachmentFormatSpecifier<ProposePresentationAttachmentFormatType>>,
+    #[serde(rename = "proposals~attach")]
+    pub proposals_attach: Vec<Attachment>,
+}
+
+impl ProposePresentationV2Content {
+    pub fn new(
+        formats: Vec<AttachmentFormatSpecifier<ProposePresentationAttachmentFormatType>>,
+        proposals_attach: Vec<Attachment>,
+    ) -> Self {
+        Self {
+            goal_code: None,
+            comment: None,
+            formats,
+            proposals_attach,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
+pub struct ProposePresentationV2Decorators {
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+/// Attachment formats a `propose-presentation` message can carry.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum ProposePresentationAttachmentFormatType {
+    #[serde(rename = "hlindy/proof-req@v2.0")]
+    HyperledgerIndyProofRequest2_0,
+    #[serde(rename = "dif/presentation-exchange/definitions@v1.0")]
+    DifPresentationExchangeDefinitions1_0,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{
+            attachment::tests::make_extended_attachment, thread::tests::make_extended_thread,
+            timing::tests::make_extended_timing,
+        },
+        maybe_known::MaybeKnown,
+        misc::test_utils,
+        msg_types::present_proof::PresentProofTypeV2_0,
+    };
+
+    fn make_propose_content() -> ProposePresentationV2Content {
+        let format = AttachmentFormatSpecifier::new(
+            "test_id".to_owned(),
+            MaybeKnown::Known(ProposePresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0),
+        );
+        ProposePresentationV2Content::new(vec![format], vec![make_extended_attachment()])
+    }
+
+    #[test]
+    fn test_minimal_propose_proof_v2() {
+        let content = make_propose_content();
+
+        let decorators = ProposePresentationV2Decorators::default();
+
+        let expected = json!({
+            "formats": content.formats,
+            "proposals~attach": content.proposals_attach,
+        });
+
+        test_utils::test_msg(content, decorators, PresentProofTypeV2_0::ProposePresentation, expected);
+    }
+
+    #[test]
+    fn test_extended_propose_proof_v2() {
+        let mut content = make_propose_content();
+        content.goal_code = Some("test_goal_code".to_owned());
+        content.comment = Some("test_comment".to_owned());
+
+        let mut decorators = ProposePresentationV2Decorators::default();
+        decorators.thread = Some(make_extended_thread());
+        decorators.timing = Some(make_extended_timing());
+
+        let expected = json!({
+            "formats": content.formats,
+            "proposals~attach": content.proposals_attach,
+            "goal_code": content.goal_code,
+            "comment": content.comment,
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, PresentProofTypeV2_0::ProposePresentation, expected);
+    }
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decorators::{attachment::Attachment, thread::Thread, timing::Timing},
+    msg_fields::protocols::common::attachment_format_id::AttachmentFormatSpecifier,
+    msg_parts::MsgParts,
+};
+
+pub type RequestPresentationV2 = MsgParts<RequestPresentationV2Content, RequestPresentationV2Decorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RequestPresentationV2Content {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub will_confirm: bool,
+    pub formats: Vec<AttachmentFormatSpecifier<RequestPresentationAttachmentFormatType>>,
+    #[serde(rename = "request_presentations~attach")]
+    pub request_presentations_attach: Vec<Attachment>,
+}
+
+impl RequestPresentationV2Content {
+    pub fn new(
+        formats: Vec<AttachmentFormatSpecifier<RequestPresentationAttachmentFormatType>>,
+        request_presentations_attach: Vec<Attachment>,
+    ) -> Self {
+        Self {
+            goal_code: None,
+            comment: None,
+            will_confirm: false,
+            formats,
+            request_presentations_attach,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
+pub struct RequestPresentationV2Decorators {
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+/// Attachment formats a `request-presentation` message can carry.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum RequestPresentationAttachmentFormatType {
+    #[serde(rename = "hlindy/proof-req@v2.0")]
+    HyperledgerIndyProofRequest2_0,
+    #[serde(rename = "dif/presentation-exchange/definitions@v1.0")]
+    DifPresentationExchangeDefinitions1_0,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{
+            attachment::tests::make_extended_attachment, thread::tests::make_extended_thread,
+            timing::tests::make_extended_timing,
+        },
+        maybe_known::MaybeKnown,
+        misc::test_utils,
+        msg_types::present_proof::PresentProofTypeV2_0,
+    };
+
+    fn make_request_content() -> RequestPresentationV2Content {
+        let format = AttachmentFormatSpecifier::new(
+            "test_id".to_owned(),
+            MaybeKnown::Known(RequestPresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0),
+        );
+        RequestPresentationV2Content::new(vec![format], vec![make_extended_attachment()])
+    }
+
+    #[test]
+    fn test_minimal_request_proof_v2() {
+        let content = make_request_content();
+
+        let decorators = RequestPresentationV2Decorators::default();
+
+        let expected = json!({
+            "will_confirm": false,
+            "formats": content.formats,
+            "request_presentations~attach": content.request_presentations_attach,
+        });
+
+        test_utils::test_msg(content, decorators, PresentProofTypeV2_0::RequestPresentation, expected);
+    }
+
+    #[test]
+    fn test_extended_request_proof_v2() {
+        let mut content = make_request_content();
+        content.goal_code = Some("test_goal_code".to_owned());
+        content.comment = Some("test_comment".to_owned());
+        content.will_confirm = true;
+
+        let mut decorators = RequestPresentationV2Decorators::default();
+        decorators.thread = Some(make_extended_thread());
+        decorators.timing = Some(make_extended_timing());
+
+        let expected = json!({
+            "will_confirm": true,
+            "formats": content.formats,
+            "request_presentations~attach": content.request_presentations_attach,
+            "goal_code": content.goal_code,
+            "comment": content.comment,
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, PresentProofTypeV2_0::RequestPresentation, expected);
+    }
+}
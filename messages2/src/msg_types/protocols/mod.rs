@@ -4,10 +4,10 @@ use derive_more::{From, TryInto};
 use serde::{Deserialize, Serialize};
 
 use self::{
-    basic_message::BasicMessageType, connection::ConnectionType, cred_issuance::CredentialIssuanceType,
-    discover_features::DiscoverFeaturesType, notification::NotificationType, out_of_band::OutOfBandType,
-    present_proof::PresentProofType, report_problem::ReportProblemType, revocation::RevocationType,
-    routing::RoutingType, trust_ping::TrustPingType,
+    basic_message::BasicMessageType, connection::ConnectionType, coordinate_mediation::CoordinateMediationType,
+    cred_issuance::CredentialIssuanceType, did_exchange::DidExchangeType, discover_features::DiscoverFeaturesType,
+    notification::NotificationType, out_of_band::OutOfBandType, pickup::PickupType, present_proof::PresentProofType,
+    report_problem::ReportProblemType, revocation::RevocationType, routing::RoutingType, trust_ping::TrustPingType,
 };
 use crate::{
     error::{MsgTypeError, MsgTypeResult},
@@ -16,10 +16,13 @@ use crate::{
 
 pub mod basic_message;
 pub mod connection;
+pub mod coordinate_mediation;
 pub mod cred_issuance;
+pub mod did_exchange;
 pub mod discover_features;
 pub mod notification;
 pub mod out_of_band;
+pub mod pickup;
 pub mod present_proof;
 pub mod report_problem;
 pub mod revocation;
@@ -54,6 +57,9 @@ pub enum Protocol {
     BasicMessageType(BasicMessageType),
     OutOfBandType(OutOfBandType),
     NotificationType(NotificationType),
+    DidExchangeType(DidExchangeType),
+    CoordinateMediationType(CoordinateMediationType),
+    PickupType(PickupType),
 }
 
 /// Utility macro to avoid harder to read and error prone calling
@@ -88,6 +94,9 @@ impl Protocol {
         match_protocol!(BasicMessageType, protocol, major, minor);
         match_protocol!(OutOfBandType, protocol, major, minor);
         match_protocol!(NotificationType, protocol, major, minor);
+        match_protocol!(DidExchangeType, protocol, major, minor);
+        match_protocol!(CoordinateMediationType, protocol, major, minor);
+        match_protocol!(PickupType, protocol, major, minor);
 
         Err(MsgTypeError::unknown_protocol(protocol.to_owned()))
     }
@@ -106,6 +115,9 @@ impl Protocol {
             Self::BasicMessageType(v) => v.as_protocol_parts(),
             Self::OutOfBandType(v) => v.as_protocol_parts(),
             Self::NotificationType(v) => v.as_protocol_parts(),
+            Self::DidExchangeType(v) => v.as_protocol_parts(),
+            Self::CoordinateMediationType(v) => v.as_protocol_parts(),
+            Self::PickupType(v) => v.as_protocol_parts(),
         }
     }
 